@@ -0,0 +1,66 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use milhouse::{List, UpdateMap, VecUpdateMap};
+use typenum::U1099511627776;
+
+type C = U1099511627776;
+const N: u64 = 800_000;
+const UPDATES: usize = 10_000;
+
+/// Scattered indices, in the unsorted order in which e.g. rewards are computed.
+fn updates() -> Vec<(usize, u64)> {
+    (0..UPDATES)
+        .map(|i| ((i * 7919 * 104_729) % N as usize, i as u64))
+        .collect()
+}
+
+fn get_mut_each<U: UpdateMap<u64>>(list: &List<u64, C, U>, updates: &[(usize, u64)]) {
+    let mut list = list.clone();
+    for &(index, value) in updates {
+        *list.get_mut(index).unwrap() = value;
+    }
+    list.apply_updates().unwrap();
+}
+
+fn update_many<U: UpdateMap<u64>>(list: &List<u64, C, U>, updates: &[(usize, u64)]) {
+    let mut list = list.clone();
+    list.update_many(updates.iter().copied()).unwrap();
+    list.apply_updates().unwrap();
+}
+
+pub fn update_many_u64(c: &mut Criterion) {
+    let updates = updates();
+    let list = List::<u64, C>::try_from_iter(0..N).unwrap();
+    let sorted_list = List::<u64, C, VecUpdateMap<u64>>::new(list.to_vec()).unwrap();
+
+    c.bench_with_input(
+        BenchmarkId::new("get_mut_each", UPDATES),
+        &list,
+        |b, list| {
+            b.iter(|| get_mut_each(list, &updates));
+        },
+    );
+    c.bench_with_input(
+        BenchmarkId::new("update_many", UPDATES),
+        &list,
+        |b, list| {
+            b.iter(|| update_many(list, &updates));
+        },
+    );
+    c.bench_with_input(
+        BenchmarkId::new("get_mut_each_vec_update_map", UPDATES),
+        &sorted_list,
+        |b, list| {
+            b.iter(|| get_mut_each(list, &updates));
+        },
+    );
+    c.bench_with_input(
+        BenchmarkId::new("update_many_vec_update_map", UPDATES),
+        &sorted_list,
+        |b, list| {
+            b.iter(|| update_many(list, &updates));
+        },
+    );
+}
+
+criterion_group!(benches, update_many_u64);
+criterion_main!(benches);
@@ -0,0 +1,4 @@
+fn main() {
+    // Switch the shared source to `Rc` node pointers, see `milhouse::Arc`.
+    println!("cargo:rustc-cfg=milhouse_rc");
+}
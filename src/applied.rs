@@ -0,0 +1,115 @@
+use crate::{Error, List, UpdateMap, Value, Vector};
+use ssz::Encode;
+use std::ops::Deref;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Lists and vectors which buffer modifications as pending updates.
+pub trait PendingUpdates {
+    fn has_pending_updates(&self) -> bool;
+
+    fn apply_updates(&mut self) -> Result<(), Error>;
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> PendingUpdates for List<T, N, U> {
+    fn has_pending_updates(&self) -> bool {
+        List::has_pending_updates(self)
+    }
+
+    fn apply_updates(&mut self) -> Result<(), Error> {
+        List::apply_updates(self)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> PendingUpdates for Vector<T, N, U> {
+    fn has_pending_updates(&self) -> bool {
+        Vector::has_pending_updates(self)
+    }
+
+    fn apply_updates(&mut self) -> Result<(), Error> {
+        Vector::apply_updates(self)
+    }
+}
+
+/// List or vector in the applied state, whose tree includes all of its modifications.
+///
+/// A plain `List` or `Vector` may have pending updates. Wrapping it applies them, and only
+/// read access is given out, so the values seen through `Deref` and the tree hash computed by
+/// `tree_hash_root` always come straight from the tree. To modify the list, take it back out with
+/// `into_inner` and wrap it again afterwards. APIs which must not observe pending updates can take
+/// an `Applied` to enforce this at compile time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Applied<L> {
+    inner: L,
+}
+
+impl<L: PendingUpdates> Applied<L> {
+    /// Apply the pending updates of `inner` and wrap it.
+    pub fn new(mut inner: L) -> Result<Self, Error> {
+        inner.apply_updates()?;
+        Ok(Self { inner })
+    }
+
+    /// Return the list, so that it can be modified.
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Apply any pending updates and convert to the applied state.
+    pub fn into_applied(self) -> Result<Applied<Self>, Error> {
+        Applied::new(self)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Apply any pending updates and convert to the applied state.
+    pub fn into_applied(self) -> Result<Applied<Self>, Error> {
+        Applied::new(self)
+    }
+}
+
+impl<L> Deref for Applied<L> {
+    type Target = L;
+
+    fn deref(&self) -> &L {
+        &self.inner
+    }
+}
+
+impl<L: TreeHash> TreeHash for Applied<L> {
+    fn tree_hash_type() -> TreeHashType {
+        L::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.inner.tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        L::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.inner.tree_hash_root()
+    }
+}
+
+impl<L: Encode> Encode for Applied<L> {
+    fn is_ssz_fixed_len() -> bool {
+        L::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        L::ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.inner.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.inner.ssz_append(buf)
+    }
+}
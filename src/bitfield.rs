@@ -0,0 +1,447 @@
+use crate::builder::Builder;
+use crate::iter::Iter;
+#[cfg(feature = "serde")]
+use crate::serde::{encode_hex, HexVisitor, SszBytesVisitor};
+use crate::utils::{int_log, opt_packing_depth, Length};
+use crate::{Arc, Error, Tree};
+use derivative::Derivative;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::{Decode, DecodeError, Encode};
+use std::marker::PhantomData;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Persistent tree of bits, packed 8 to a byte and 256 to a chunk.
+#[derive(Debug, Clone, PartialEq)]
+struct Bits {
+    tree: Arc<Tree<u8>>,
+    depth: usize,
+    /// Number of bits stored.
+    length: Length,
+}
+
+impl Bits {
+    fn depth(max_bits: usize) -> usize {
+        let packing_depth = opt_packing_depth::<u8>().unwrap_or(0);
+        int_log(max_bits.div_ceil(8)).saturating_sub(packing_depth)
+    }
+
+    fn from_bytes(bytes: &[u8], length: usize, max_bits: usize) -> Result<Self, Error> {
+        let mut builder = Builder::new(Self::depth(max_bits), 0);
+        for byte in bytes {
+            builder.push(*byte)?;
+        }
+        let (tree, depth, _) = builder.finish()?;
+        Ok(Self {
+            tree,
+            depth,
+            length: Length(length),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.length.as_usize()
+    }
+
+    fn num_bytes(&self) -> usize {
+        self.len().div_ceil(8)
+    }
+
+    fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len() {
+            return None;
+        }
+        let packing_depth = opt_packing_depth::<u8>().unwrap_or(0);
+        let byte = self
+            .tree
+            .get_recursive(index / 8, self.depth, packing_depth)?;
+        Some((byte >> (index % 8)) & 1 == 1)
+    }
+
+    /// Set the bit at `index`, which may be at most equal to the current length.
+    fn set(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        if index > self.len() {
+            return Err(Error::OutOfBoundsUpdate {
+                index,
+                len: self.len(),
+            });
+        }
+
+        let byte_index = index / 8;
+        let packing_depth = opt_packing_depth::<u8>().unwrap_or(0);
+        let byte = self
+            .tree
+            .get_recursive(byte_index, self.depth, packing_depth)
+            .copied()
+            .unwrap_or(0);
+        let mask = 1 << (index % 8);
+        let new_byte = if value { byte | mask } else { byte & !mask };
+
+        self.tree = self
+            .tree
+            .with_updated_leaf(byte_index, new_byte, self.depth)?;
+        if index == self.len() {
+            *self.length.as_mut() += 1;
+        }
+        Ok(())
+    }
+
+    fn bytes(&self) -> Iter<'_, u8> {
+        Iter::from_index(0, &self.tree, self.depth, Length(self.num_bytes()))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bytes()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .take(self.len())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.bytes().copied().collect()
+    }
+}
+
+/// Check that all bits of `bytes` beyond the first `length` are zero.
+fn check_padding(bytes: &[u8], length: usize) -> Result<(), DecodeError> {
+    let padding_bits = bytes.len() * 8 - length;
+    if padding_bits >= 8 {
+        return Err(DecodeError::BytesInvalid(format!(
+            "Bitfield of {} bits has {} bytes",
+            length,
+            bytes.len()
+        )));
+    }
+    match bytes.last() {
+        Some(last) if padding_bits > 0 && last >> (8 - padding_bits) != 0 => Err(
+            DecodeError::BytesInvalid("Bitfield has non-zero padding bits".into()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Persistent SSZ bitlist with a maximum length of `N` bits.
+#[derive(Derivative)]
+#[derivative(Debug, Clone, PartialEq)]
+pub struct Bitlist<N: Unsigned> {
+    bits: Bits,
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Unsigned> Bitlist<N> {
+    /// Create a bitlist of `length` bits, all set to zero.
+    pub fn with_capacity(length: usize) -> Result<Self, Error> {
+        if length > N::to_usize() {
+            return Err(Error::ListFull {
+                len: length,
+                max_len: N::to_usize(),
+            });
+        }
+        Self::from_parts(&vec![0; length.div_ceil(8)], length)
+    }
+
+    fn from_parts(bytes: &[u8], length: usize) -> Result<Self, Error> {
+        Ok(Self {
+            bits: Bits::from_bytes(bytes, length, N::to_usize())?,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index)
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsUpdate {
+                index,
+                len: self.len(),
+            });
+        }
+        self.bits.set(index, value)
+    }
+
+    pub fn push(&mut self, value: bool) -> Result<(), Error> {
+        if self.len() == N::to_usize() {
+            return Err(Error::ListFull {
+                len: self.len(),
+                max_len: N::to_usize(),
+            });
+        }
+        self.bits.set(self.len(), value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter()
+    }
+
+    /// Return the bits packed into bytes, without the SSZ length delimiter.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bits.to_bytes()
+    }
+}
+
+impl<N: Unsigned> Encode for Bitlist<N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.len() / 8 + 1
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        let mut bytes = self.to_bytes();
+        let delimiter = 1 << (self.len() % 8);
+        if self.len().is_multiple_of(8) {
+            bytes.push(delimiter);
+        } else if let Some(last) = bytes.last_mut() {
+            *last |= delimiter;
+        }
+        buf.extend_from_slice(&bytes);
+    }
+}
+
+impl<N: Unsigned> Decode for Bitlist<N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let last = match bytes.last() {
+            Some(last) if *last != 0 => *last,
+            _ => {
+                return Err(DecodeError::BytesInvalid(
+                    "Bitlist is missing its length delimiter".into(),
+                ))
+            }
+        };
+        let delimiter_index = 7 - last.leading_zeros() as usize;
+        let length = (bytes.len() - 1) * 8 + delimiter_index;
+
+        if length > N::to_usize() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Bitlist of {} bits exceeds maximum of {}",
+                length,
+                N::to_usize()
+            )));
+        }
+
+        // Strip the delimiter, and the whole last byte if it contained nothing else.
+        let mut bytes = bytes.to_vec();
+        if delimiter_index == 0 {
+            bytes.pop();
+        } else if let Some(last) = bytes.last_mut() {
+            *last ^= 1 << delimiter_index;
+        }
+
+        Self::from_parts(&bytes, length)
+            .map_err(|e| DecodeError::BytesInvalid(format!("Error building Bitlist: {:?}", e)))
+    }
+}
+
+impl<N: Unsigned> TreeHash for Bitlist<N> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("Bitlist should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Bitlist should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let root = self.bits.tree.tree_hash();
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N: Unsigned> Serialize for Bitlist<N> {
+    /// Human-readable formats get the SSZ encoding as a 0x-prefixed hex string, while binary
+    /// formats get it as a byte string.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode_hex(&self.as_ssz_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_ssz_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N: Unsigned> Deserialize<'de> for Bitlist<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor::default())
+        } else {
+            deserializer.deserialize_bytes(SszBytesVisitor::default())
+        }
+    }
+}
+
+/// Persistent SSZ bitvector of exactly `N` bits.
+#[derive(Derivative)]
+#[derivative(Debug, Clone, PartialEq)]
+pub struct Bitvector<N: Unsigned> {
+    bits: Bits,
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Unsigned> Bitvector<N> {
+    /// Create a bitvector with all bits set to zero.
+    pub fn new() -> Self {
+        Self::from_parts(&vec![0; N::to_usize().div_ceil(8)]).unwrap_or_else(|e| {
+            panic!(
+                "Bitvector::new failed for length {}: {:?}",
+                N::to_usize(),
+                e
+            )
+        })
+    }
+
+    fn from_parts(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            bits: Bits::from_bytes(bytes, N::to_usize(), N::to_usize())?,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        N::to_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index)
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), Error> {
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsUpdate {
+                index,
+                len: self.len(),
+            });
+        }
+        self.bits.set(index, value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter()
+    }
+
+    /// Return the bits packed into bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bits.to_bytes()
+    }
+}
+
+impl<N: Unsigned> Default for Bitvector<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Unsigned> Encode for Bitvector<N> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        N::to_usize().div_ceil(8)
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        <Self as Encode>::ssz_fixed_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend(self.bits.bytes());
+    }
+}
+
+impl<N: Unsigned> Decode for Bitvector<N> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        N::to_usize().div_ceil(8)
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let expected = <Self as Decode>::ssz_fixed_len();
+        if bytes.len() != expected {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected,
+            });
+        }
+        check_padding(bytes, N::to_usize())?;
+
+        Self::from_parts(bytes)
+            .map_err(|e| DecodeError::BytesInvalid(format!("Error building Bitvector: {:?}", e)))
+    }
+}
+
+impl<N: Unsigned> TreeHash for Bitvector<N> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Vector
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("Bitvector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Bitvector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.bits.tree.tree_hash()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N: Unsigned> Serialize for Bitvector<N> {
+    /// Human-readable formats get the SSZ encoding as a 0x-prefixed hex string, while binary
+    /// formats get it as a byte string.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode_hex(&self.as_ssz_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_ssz_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N: Unsigned> Deserialize<'de> for Bitvector<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor::default())
+        } else {
+            deserializer.deserialize_bytes(SszBytesVisitor::default())
+        }
+    }
+}
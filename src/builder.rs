@@ -6,6 +6,8 @@ pub struct Builder<T: Value> {
     depth: usize,
     level: usize,
     length: Length,
+    /// Number of values which will be pushed, if known.
+    expected_len: Option<usize>,
     /// Cached value of `opt_packing_factor`.
     packing_factor: Option<usize>,
     /// Cached value of `opt_packing_depth`.
@@ -15,26 +17,52 @@ pub struct Builder<T: Value> {
 impl<T: Value> Builder<T> {
     pub fn new(depth: usize, level: usize) -> Self {
         Self {
-            stack: Vec::with_capacity(depth),
+            // The stack holds at most one node per level, plus the node being merged.
+            stack: Vec::with_capacity(depth + 1),
             depth,
             level,
             length: Length(0),
+            expected_len: None,
             packing_factor: opt_packing_factor::<T>(),
             packing_depth: opt_packing_depth::<T>().unwrap_or(0),
         }
     }
 
+    /// Create a builder for exactly `len` values.
+    ///
+    /// The final packed leaf is allocated with space for just the values it will contain, rather
+    /// than a whole chunk. Pushing more or fewer values is still valid, but may reallocate.
+    pub fn with_capacity(depth: usize, level: usize, len: usize) -> Self {
+        Self {
+            expected_len: Some(len),
+            ..Self::new(depth, level)
+        }
+    }
+
     pub fn push(&mut self, value: T) -> Result<(), Error> {
         let index = self.length.as_usize();
         let next_index = index + 1;
 
         // Fold the nodes on the left of this node into it, and then push that node to the stack.
         let mut new_stack_top = if let Some(packing_factor) = self.packing_factor {
+            if index == 0 {
+                PackedLeaf::<T>::check_layout()?;
+            }
             if index % packing_factor == 0 {
-                MaybeArced::Unarced(Tree::PackedLeaf(PackedLeaf::single(value)))
-            } else if let Some(MaybeArced::Unarced(Tree::PackedLeaf(mut leaf))) = self.stack.pop() {
-                leaf.push(value)?;
-                MaybeArced::Unarced(Tree::PackedLeaf(leaf))
+                let capacity = match self.expected_len {
+                    Some(len) if len > index => std::cmp::min(packing_factor, len - index),
+                    _ => packing_factor,
+                };
+                MaybeArced::Unarced(Tree::packed_leaf_unboxed(PackedLeaf::single_with_capacity(
+                    value, capacity,
+                )))
+            } else if let Some(mut top @ MaybeArced::Unarced(Tree::PackedLeaf(_))) =
+                self.stack.pop()
+            {
+                if let MaybeArced::Unarced(Tree::PackedLeaf(leaf)) = &mut top {
+                    leaf.push(value)?;
+                }
+                top
             } else {
                 return Err(Error::BuilderExpectedLeaf);
             }
@@ -0,0 +1,353 @@
+use crate::chunk_iter::ChunkIter;
+#[cfg(feature = "serde")]
+use crate::serde::{encode_hex, HexVisitor, SszBytesVisitor};
+use crate::{Error, List, Vector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::{Decode, DecodeError, Encode};
+use std::ops::{Deref, DerefMut};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// List of up to `N` bytes.
+///
+/// This is a `List<u8, N>` which is serialized by serde as a 0x-prefixed hex string in
+/// human-readable formats, as the beacon API expects. To serialize a `ByteList` as an array of
+/// numbers instead, use `#[serde(with = "milhouse::serde::as_seq")]`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ByteList<N: Unsigned> {
+    list: List<u8, N>,
+}
+
+/// Vector of exactly `N` bytes.
+///
+/// Like `ByteList`, this is serialized as a hex string in human-readable formats.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ByteVector<N: Unsigned> {
+    vector: Vector<u8, N>,
+}
+
+impl<N: Unsigned> ByteList<N> {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, Error> {
+        List::new(bytes).map(Self::from)
+    }
+
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn into_inner(self) -> List<u8, N> {
+        self.list
+    }
+
+    /// Iterate over the bytes as contiguous slices of up to 32 bytes, one per leaf.
+    ///
+    /// Errors if there are pending updates.
+    pub fn slices(&self) -> Result<ChunkIter<'_, u8>, Error> {
+        self.list.iter_chunks()
+    }
+
+    /// Copy the bytes into a contiguous `Vec`, including any pending updates.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.slices() {
+            Ok(slices) => concat(slices, self.len()),
+            Err(_) => self.list.to_vec(),
+        }
+    }
+
+    /// Copy `dst.len()` bytes starting at `start` into `dst`.
+    pub fn copy_to_slice(&self, start: usize, dst: &mut [u8]) -> Result<(), Error> {
+        check_slice(start, dst.len(), self.len())?;
+        for (dst, src) in dst.iter_mut().zip(self.list.iter_from(start)?) {
+            *dst = *src;
+        }
+        Ok(())
+    }
+
+    /// Overwrite the bytes starting at `start` with `src`, extending the list if necessary.
+    ///
+    /// The writes are applied to the tree immediately, along with any other pending updates.
+    /// Errors without modifying the list if `start` is greater than the length, or if the list
+    /// would exceed its maximum length.
+    pub fn copy_from_slice(&mut self, start: usize, src: &[u8]) -> Result<(), Error> {
+        let len = self.len();
+        if start > len {
+            return Err(Error::OutOfBoundsUpdate { index: start, len });
+        }
+        if start + src.len() > N::to_usize() {
+            return Err(Error::ListFull {
+                len,
+                max_len: N::to_usize(),
+            });
+        }
+        let (overwrite, extend) = src.split_at(std::cmp::min(src.len(), len - start));
+        for (index, byte) in (start..).zip(overwrite) {
+            *self
+                .list
+                .get_mut(index)
+                .ok_or(Error::OutOfBoundsUpdate { index, len })? = *byte;
+        }
+        for byte in extend {
+            self.list.push(*byte)?;
+        }
+        self.list.apply_updates()
+    }
+}
+
+impl<N: Unsigned> ByteVector<N> {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, Error> {
+        Vector::new(bytes).map(Self::from)
+    }
+
+    pub fn into_inner(self) -> Vector<u8, N> {
+        self.vector
+    }
+
+    /// Iterate over the bytes as contiguous slices of up to 32 bytes, one per leaf.
+    ///
+    /// Errors if there are pending updates.
+    pub fn slices(&self) -> Result<ChunkIter<'_, u8>, Error> {
+        self.vector.iter_chunks()
+    }
+
+    /// Copy the bytes into a contiguous `Vec`, including any pending updates.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.slices() {
+            Ok(slices) => concat(slices, self.len()),
+            Err(_) => self.vector.to_vec(),
+        }
+    }
+
+    /// Copy `dst.len()` bytes starting at `start` into `dst`.
+    pub fn copy_to_slice(&self, start: usize, dst: &mut [u8]) -> Result<(), Error> {
+        check_slice(start, dst.len(), self.len())?;
+        for (dst, src) in dst.iter_mut().zip(self.vector.iter_from(start)?) {
+            *dst = *src;
+        }
+        Ok(())
+    }
+
+    /// Overwrite the bytes starting at `start` with `src`.
+    ///
+    /// The writes are applied to the tree immediately, along with any other pending updates.
+    /// Errors without modifying the vector if the bytes would extend past the end.
+    pub fn copy_from_slice(&mut self, start: usize, src: &[u8]) -> Result<(), Error> {
+        let len = self.len();
+        check_slice(start, src.len(), len)?;
+        for (index, byte) in (start..).zip(src) {
+            *self
+                .vector
+                .get_mut(index)
+                .ok_or(Error::OutOfBoundsUpdate { index, len })? = *byte;
+        }
+        self.vector.apply_updates()
+    }
+}
+
+fn check_slice(start: usize, len: usize, total_len: usize) -> Result<(), Error> {
+    let end = start.saturating_add(len);
+    if end > total_len {
+        return Err(Error::OutOfBoundsSlice {
+            start,
+            end,
+            len: total_len,
+        });
+    }
+    Ok(())
+}
+
+fn concat<'a>(slices: impl Iterator<Item = &'a [u8]>, len: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(len);
+    for slice in slices {
+        bytes.extend_from_slice(slice);
+    }
+    bytes
+}
+
+impl<N: Unsigned> From<List<u8, N>> for ByteList<N> {
+    fn from(list: List<u8, N>) -> Self {
+        Self { list }
+    }
+}
+
+impl<N: Unsigned> From<ByteList<N>> for List<u8, N> {
+    fn from(bytes: ByteList<N>) -> Self {
+        bytes.list
+    }
+}
+
+impl<N: Unsigned> From<Vector<u8, N>> for ByteVector<N> {
+    fn from(vector: Vector<u8, N>) -> Self {
+        Self { vector }
+    }
+}
+
+impl<N: Unsigned> From<ByteVector<N>> for Vector<u8, N> {
+    fn from(bytes: ByteVector<N>) -> Self {
+        bytes.vector
+    }
+}
+
+impl<N: Unsigned> Deref for ByteList<N> {
+    type Target = List<u8, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<N: Unsigned> DerefMut for ByteList<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.list
+    }
+}
+
+impl<N: Unsigned> Deref for ByteVector<N> {
+    type Target = Vector<u8, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.vector
+    }
+}
+
+impl<N: Unsigned> DerefMut for ByteVector<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vector
+    }
+}
+
+impl<N: Unsigned> TreeHash for ByteList<N> {
+    fn tree_hash_type() -> TreeHashType {
+        List::<u8, N>::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.list.tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        List::<u8, N>::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.list.tree_hash_root()
+    }
+}
+
+impl<N: Unsigned> TreeHash for ByteVector<N> {
+    fn tree_hash_type() -> TreeHashType {
+        Vector::<u8, N>::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.vector.tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        Vector::<u8, N>::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.vector.tree_hash_root()
+    }
+}
+
+impl<N: Unsigned> Encode for ByteList<N> {
+    fn is_ssz_fixed_len() -> bool {
+        <List<u8, N> as Encode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.list.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.list.ssz_append(buf)
+    }
+}
+
+impl<N: Unsigned> Encode for ByteVector<N> {
+    fn is_ssz_fixed_len() -> bool {
+        <Vector<u8, N> as Encode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <Vector<u8, N> as Encode>::ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.vector.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.vector.ssz_append(buf)
+    }
+}
+
+impl<N: Unsigned> Decode for ByteList<N> {
+    fn is_ssz_fixed_len() -> bool {
+        <List<u8, N> as Decode>::is_ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        List::from_ssz_bytes(bytes).map(Self::from)
+    }
+}
+
+impl<N: Unsigned> Decode for ByteVector<N> {
+    fn is_ssz_fixed_len() -> bool {
+        <Vector<u8, N> as Decode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <Vector<u8, N> as Decode>::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Vector::from_ssz_bytes(bytes).map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N: Unsigned> Serialize for ByteList<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode_hex(&self.as_ssz_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_ssz_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N: Unsigned> Deserialize<'de> for ByteList<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor::default())
+        } else {
+            deserializer.deserialize_bytes(SszBytesVisitor::default())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N: Unsigned> Serialize for ByteVector<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode_hex(&self.as_ssz_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_ssz_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N: Unsigned> Deserialize<'de> for ByteVector<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor::default())
+        } else {
+            deserializer.deserialize_bytes(SszBytesVisitor::default())
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{List, Tree, UpdateMap, Value, Vector};
+use std::cell::Cell;
+use typenum::Unsigned;
+
+/// Random access to a list or vector which remembers the last leaf it read from.
+///
+/// Every `List::get` descends from the root to a leaf. A reader skips the descent when the
+/// element is in the same leaf as the previous one, which makes sequential scans via `get` cost
+/// one descent per leaf rather than one per element. It borrows the list, so the cached leaf can
+/// never go stale. Pending updates are checked before the cache, as for `List::get`.
+///
+/// The reader also counts cache hits and misses, to help decide whether a loop would be better
+/// written with `iter` or `iter_chunks`.
+#[derive(Debug)]
+pub struct CachedReader<'a, T: Value, U> {
+    tree: &'a Tree<T>,
+    updates: &'a U,
+    depth: usize,
+    packing_depth: usize,
+    len: usize,
+    /// Index of the first value of the cached leaf, and its values.
+    leaf: Cell<Option<(usize, &'a [T])>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> CachedReader<'a, T, U> {
+    fn new(
+        tree: &'a Tree<T>,
+        updates: &'a U,
+        depth: usize,
+        packing_depth: usize,
+        len: usize,
+    ) -> Self {
+        Self {
+            tree,
+            updates,
+            depth,
+            packing_depth,
+            len,
+            leaf: Cell::new(None),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        if index >= self.len {
+            return None;
+        }
+        if let Some(value) = self.updates.get(index) {
+            return Some(value);
+        }
+        if let Some((start, values)) = self.leaf.get() {
+            if let Some(value) = index.checked_sub(start).and_then(|i| values.get(i)) {
+                self.hits.set(self.hits.get() + 1);
+                return Some(value);
+            }
+        }
+        self.misses.set(self.misses.get() + 1);
+        let (start, values) =
+            self.tree
+                .get_leaf_recursive(index, self.depth, self.packing_depth)?;
+        self.leaf.set(Some((start, values)));
+        values.get(index - start)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of reads from the tree which were served by the cached leaf.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Number of reads from the tree which had to descend from the root.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Create a reader for repeated `get`s, which caches the most recently read leaf.
+    pub fn cached_reader(&self) -> CachedReader<'_, T, U> {
+        let backing = &self.interface.backing;
+        CachedReader::new(
+            &backing.tree,
+            &self.interface.updates,
+            backing.depth,
+            backing.packing_depth,
+            self.len(),
+        )
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Create a reader for repeated `get`s, which caches the most recently read leaf.
+    pub fn cached_reader(&self) -> CachedReader<'_, T, U> {
+        let backing = &self.interface.backing;
+        CachedReader::new(
+            &backing.tree,
+            &self.interface.updates,
+            backing.depth,
+            backing.packing_depth,
+            self.len(),
+        )
+    }
+}
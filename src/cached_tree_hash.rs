@@ -0,0 +1,170 @@
+use crate::{Error, List, MaybeSync, Value, Vector};
+use parking_lot::RwLock;
+use ssz::{Decode, DecodeError, Encode};
+use std::ops::{Deref, DerefMut};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Types whose tree hash root can be served from a cache once they're prepared.
+///
+/// Lists and vectors already cache the hashes of their nodes, so a container derived with
+/// `tree_hash_derive` only hashes its own fields' roots, without re-merkleizing their contents,
+/// as long as none of them has pending updates. Pending updates are hashed in a temporary copy of
+/// the tree on every call, because `tree_hash_root` only has `&self`. Calling `prepare_tree_hash`
+/// on each field before hashing the container applies them, so the container root reuses every
+/// field's cached root. A container can implement this trait by calling `prepare_tree_hash` on
+/// each of its fields in turn.
+pub trait CachedTreeHash: TreeHash {
+    /// Update caches so that the next `tree_hash_root` doesn't need to rehash unchanged data.
+    fn prepare_tree_hash(&mut self) -> Result<(), Error>;
+
+    /// Prepare the caches and return the tree hash root.
+    fn cached_tree_hash_root(&mut self) -> Result<Hash256, Error> {
+        self.prepare_tree_hash()?;
+        Ok(self.tree_hash_root())
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> CachedTreeHash for List<T, N> {
+    /// Apply pending updates, so that they are hashed into the list's tree.
+    fn prepare_tree_hash(&mut self) -> Result<(), Error> {
+        self.apply_updates()
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> CachedTreeHash for Vector<T, N> {
+    /// Apply pending updates, so that they are hashed into the vector's tree.
+    fn prepare_tree_hash(&mut self) -> Result<(), Error> {
+        self.apply_updates()
+    }
+}
+
+/// Wrapper which caches the tree hash root of a value that doesn't cache its own.
+///
+/// This is for container fields such as an `ssz_types::VariableList`, which would otherwise be
+/// re-merkleized every time the container is hashed. The cached root is cleared by any mutable
+/// access through `DerefMut`. The wrapper is transparent to SSZ and tree hashing, so it can be
+/// used in place of the value in structs deriving `Encode`, `Decode` and `TreeHash`.
+#[derive(Debug, Default)]
+pub struct CachedRoot<T> {
+    value: T,
+    root: RwLock<Option<Hash256>>,
+}
+
+impl<T> CachedRoot<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            root: RwLock::new(None),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Return `true` if the root has been computed since the value was last modified.
+    pub fn is_cached(&self) -> bool {
+        self.root.read().is_some()
+    }
+}
+
+impl<T> From<T> for CachedRoot<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for CachedRoot<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachedRoot<T> {
+    /// Borrow the value mutably, clearing the cached root.
+    fn deref_mut(&mut self) -> &mut T {
+        *self.root.get_mut() = None;
+        &mut self.value
+    }
+}
+
+impl<T: Clone> Clone for CachedRoot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            root: RwLock::new(*self.root.read()),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for CachedRoot<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: TreeHash> TreeHash for CachedRoot<T> {
+    fn tree_hash_type() -> TreeHashType {
+        T::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.value.tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        T::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        if let Some(root) = *self.root.read() {
+            return root;
+        }
+        let root = self.value.tree_hash_root();
+        *self.root.write() = Some(root);
+        root
+    }
+}
+
+impl<T: TreeHash> CachedTreeHash for CachedRoot<T> {
+    /// Compute and cache the root of the value, if it isn't already cached.
+    fn prepare_tree_hash(&mut self) -> Result<(), Error> {
+        self.tree_hash_root();
+        Ok(())
+    }
+}
+
+impl<T: Encode> Encode for CachedRoot<T> {
+    fn is_ssz_fixed_len() -> bool {
+        T::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        T::ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.value.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.value.ssz_append(buf)
+    }
+}
+
+impl<T: Decode> Decode for CachedRoot<T> {
+    fn is_ssz_fixed_len() -> bool {
+        T::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        T::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        T::from_ssz_bytes(bytes).map(Self::new)
+    }
+}
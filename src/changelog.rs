@@ -0,0 +1,56 @@
+/// Summary of the changes made to a tree by `apply_updates_with_changelog`.
+///
+/// Indices are recorded for every pending update, including values which were accessed mutably
+/// but left unchanged, as the tree is rewritten for them all the same.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changelog {
+    /// Indices of the elements which were written, in ascending order.
+    pub indices: Vec<usize>,
+    /// Length before the updates were applied.
+    pub prev_len: usize,
+    /// Length after the updates were applied.
+    pub len: usize,
+    /// Number of nodes replaced in the tree, including leaves.
+    ///
+    /// Each of these nodes has its hash recomputed by the next call to `tree_hash_root`.
+    pub nodes_rewritten: usize,
+}
+
+impl Changelog {
+    pub(crate) fn new(
+        indices: Vec<usize>,
+        prev_len: usize,
+        len: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) -> Self {
+        // Count the distinct ancestors of the modified chunks at each level of the tree.
+        let mut prefixes = indices
+            .iter()
+            .map(|index| index >> packing_depth)
+            .collect::<Vec<_>>();
+        prefixes.dedup();
+        let mut nodes_rewritten = 0;
+        for _ in 0..=depth {
+            nodes_rewritten += prefixes.len();
+            prefixes.iter_mut().for_each(|prefix| *prefix >>= 1);
+            prefixes.dedup();
+        }
+
+        Self {
+            indices,
+            prev_len,
+            len,
+            nodes_rewritten,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Return `true` if the element at `index` was written.
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.binary_search(&index).is_ok()
+    }
+}
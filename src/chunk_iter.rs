@@ -0,0 +1,120 @@
+use crate::iter::Iter;
+use crate::utils::Length;
+use crate::{Leaf, PackedLeaf, Tree, Value};
+use ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
+use std::slice;
+
+/// Number of offsets encoded into each chunk by `SszChunks`.
+const OFFSETS_PER_CHUNK: usize = 1024;
+
+/// Iterator over the leaves of a tree, yielding their values as slices.
+///
+/// Each `PackedLeaf` is yielded as a single slice of its values, while each `Leaf` is yielded as
+/// a slice of length 1.
+#[derive(Debug)]
+pub struct ChunkIter<'a, T: Value> {
+    /// Stack of subtrees still to be visited, with the next subtree on top.
+    stack: Vec<&'a Tree<T>>,
+    /// Number of elements remaining to be yielded.
+    remaining: usize,
+}
+
+impl<'a, T: Value> ChunkIter<'a, T> {
+    pub fn new(root: &'a Tree<T>, depth: usize, length: usize) -> Self {
+        let mut stack = Vec::with_capacity(depth + 1);
+        stack.push(root);
+        Self {
+            stack,
+            remaining: length,
+        }
+    }
+}
+
+impl<'a, T: Value> Iterator for ChunkIter<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            match self.stack.pop()? {
+                Tree::Node { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+                Tree::Leaf(Leaf { value, .. }) => {
+                    self.remaining -= 1;
+                    return Some(slice::from_ref(&**value));
+                }
+                Tree::PackedLeaf(PackedLeaf { values, .. }) => {
+                    let len = values.len().min(self.remaining);
+                    self.remaining -= len;
+                    return Some(&values[..len]);
+                }
+                Tree::Zero(_) => return None,
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the SSZ encoding of a list or vector, in chunks of whole leaves.
+///
+/// Concatenating the chunks gives the same bytes as `as_ssz_bytes`, but only one chunk needs to
+/// be held in memory at a time, which suits vectored writes and incremental compression.
+/// Consecutive leaves are encoded into the same chunk until it holds at least `min_chunk_len`
+/// bytes, so with a `min_chunk_len` of 0 there is one chunk per leaf. For variable-length items,
+/// the offsets come first, in chunks of up to 1024 offsets.
+#[derive(Debug)]
+pub struct SszChunks<'a, T: Value> {
+    leaves: ChunkIter<'a, T>,
+    /// Iterator over the items for computing offsets, for variable-length items only.
+    offsets: Option<Iter<'a, T>>,
+    next_offset: usize,
+    min_chunk_len: usize,
+}
+
+impl<'a, T: Value> SszChunks<'a, T> {
+    pub(crate) fn new(
+        root: &'a Tree<T>,
+        depth: usize,
+        length: usize,
+        min_chunk_len: usize,
+    ) -> Self {
+        let variable = !<T as Encode>::is_ssz_fixed_len();
+        Self {
+            leaves: ChunkIter::new(root, depth, length),
+            offsets: variable.then(|| Iter::from_index(0, root, depth, Length(length))),
+            next_offset: length * BYTES_PER_LENGTH_OFFSET,
+            min_chunk_len,
+        }
+    }
+}
+
+impl<T: Value> Iterator for SszChunks<'_, T> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(items) = &mut self.offsets {
+            let mut chunk = vec![];
+            for item in items.by_ref().take(OFFSETS_PER_CHUNK) {
+                chunk.extend_from_slice(&ssz::encode_length(self.next_offset));
+                self.next_offset += item.ssz_bytes_len();
+            }
+            if !chunk.is_empty() {
+                return Some(chunk);
+            }
+            self.offsets = None;
+        }
+
+        let mut chunk = vec![];
+        for values in self.leaves.by_ref() {
+            for value in values {
+                value.ssz_append(&mut chunk);
+            }
+            // Items may encode to nothing, so keep going until the chunk is non-empty.
+            if !chunk.is_empty() && chunk.len() >= self.min_chunk_len {
+                break;
+            }
+        }
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
@@ -5,6 +5,7 @@ use std::ops::Deref;
 pub enum Cow<'a, T: Clone> {
     BTree(BTreeCow<'a, T>),
     Vec(VecCow<'a, T>),
+    SortedVec(SortedVecCow<'a, T>),
 }
 
 impl<'a, T: Clone> Deref for Cow<'a, T> {
@@ -14,6 +15,7 @@ impl<'a, T: Clone> Deref for Cow<'a, T> {
         match self {
             Self::BTree(cow) => cow.deref(),
             Self::Vec(cow) => cow.deref(),
+            Self::SortedVec(cow) => cow.deref(),
         }
     }
 }
@@ -23,6 +25,7 @@ impl<'a, T: Clone> Cow<'a, T> {
         match self {
             Self::BTree(cow) => cow.into_mut(),
             Self::Vec(cow) => cow.into_mut(),
+            Self::SortedVec(cow) => cow.into_mut(),
         }
     }
 
@@ -30,6 +33,7 @@ impl<'a, T: Clone> Cow<'a, T> {
         match self {
             Self::BTree(cow) => cow.make_mut(),
             Self::Vec(cow) => cow.make_mut(),
+            Self::SortedVec(cow) => cow.make_mut(),
         }
     }
 }
@@ -135,3 +139,65 @@ impl<'a, T: Clone> Deref for VecCow<'a, T> {
         }
     }
 }
+
+/// Position in a `VecUpdateMap` at which a missing key would be inserted.
+pub struct SortedVecVacantEntry<'a, T> {
+    pub(crate) entries: &'a mut Vec<(usize, T)>,
+    pub(crate) position: usize,
+    pub(crate) key: usize,
+}
+
+impl<'a, T> SortedVecVacantEntry<'a, T> {
+    pub fn insert(self, value: T) -> &'a mut T {
+        self.entries.insert(self.position, (self.key, value));
+        &mut self.entries[self.position].1
+    }
+}
+
+pub enum SortedVecCow<'a, T: Clone> {
+    Immutable {
+        value: &'a T,
+        entry: Option<SortedVecVacantEntry<'a, T>>,
+    },
+    Mutable {
+        value: &'a mut T,
+    },
+}
+
+impl<'a, T: Clone> CowTrait<'a, T> for SortedVecCow<'a, T> {
+    fn into_mut(self) -> Result<&'a mut T, Error> {
+        match self {
+            Self::Immutable { value, entry } => entry
+                .ok_or(Error::CowMissingEntry)
+                .map(|e| e.insert(value.clone())),
+            Self::Mutable { value } => Ok(value),
+        }
+    }
+
+    fn make_mut(&mut self) -> Result<&mut T, Error> {
+        match self {
+            Self::Mutable { value } => Ok(value),
+            Self::Immutable { entry, value } => {
+                let value_mut_ref = entry
+                    .take()
+                    .ok_or(Error::CowMissingEntry)?
+                    .insert(value.clone());
+                *self = Self::Mutable {
+                    value: value_mut_ref,
+                };
+                self.make_mut()
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone> Deref for SortedVecCow<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Immutable { value, .. } => value,
+            Self::Mutable { value } => value,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::iter::Iter;
+use crate::utils::Length;
+use crate::{Arc, Error, List, Tree, UpdateMap, Value};
+use derivative::Derivative;
+use ssz::{DecodeError, Encode, SszDecoderBuilder, SszEncoder, BYTES_PER_LENGTH_OFFSET};
+use std::marker::PhantomData;
+use typenum::Unsigned;
+
+/// Version of the `ListDiff` binary encoding produced by `ListDiff::as_bytes`.
+pub const DIFF_VERSION: u8 = 1;
+
+/// Difference between two snapshots of a `List`.
+///
+/// Only the elements which changed are stored, so computing and applying a diff between lists
+/// which share most of their structure is cheap.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(PartialEq(bound = "T: Value"))]
+pub struct ListDiff<T: Value, N: Unsigned> {
+    /// Length of the list after applying the diff.
+    pub(crate) length: usize,
+    /// New values for elements that were changed or added, sorted by index.
+    pub(crate) updates: Vec<(usize, T)>,
+    #[derivative(PartialEq = "ignore")]
+    _phantom: PhantomData<N>,
+}
+
+impl<T: Value, N: Unsigned> ListDiff<T, N> {
+    /// Compute the diff which transforms `base` into `new`.
+    ///
+    /// Subtrees which are pointer-equal are skipped entirely, so this is fastest when `new` was
+    /// derived from `base` (or rebased onto it).
+    pub fn compute<U: UpdateMap<T>>(
+        base: &List<T, N, U>,
+        new: &List<T, N, U>,
+    ) -> Result<Self, Error> {
+        if base.has_pending_updates() || new.has_pending_updates() {
+            return Err(Error::DiffPendingUpdates);
+        }
+
+        let base_backing = &base.interface.backing;
+        let new_backing = &new.interface.backing;
+        let mut updates = vec![];
+        compute_recursive(
+            &base_backing.tree,
+            &new_backing.tree,
+            0,
+            new_backing.depth,
+            new_backing.packing_depth,
+            &mut updates,
+        )?;
+
+        Ok(Self::from_parts(new.len(), updates))
+    }
+
+    pub(crate) fn from_parts(length: usize, updates: Vec<(usize, T)>) -> Self {
+        Self {
+            length,
+            updates,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Apply this diff to `base`, transforming it into the list the diff was computed from.
+    ///
+    /// Any pending updates on `base` are applied first.
+    pub fn apply<U: UpdateMap<T>>(&self, base: &mut List<T, N, U>) -> Result<(), Error> {
+        base.truncate(self.length)?;
+        base.apply_updates()?;
+
+        for (index, value) in &self.updates {
+            let len = base.len();
+            if *index < len {
+                *base
+                    .get_mut(*index)
+                    .ok_or(Error::DiffInvalidIndex { index: *index, len })? = value.clone();
+            } else if *index == len {
+                base.push(value.clone())?;
+            } else {
+                return Err(Error::DiffInvalidIndex { index: *index, len });
+            }
+        }
+        base.apply_updates()?;
+
+        if base.len() != self.length {
+            return Err(Error::DiffWrongLength {
+                len: base.len(),
+                expected: self.length,
+            });
+        }
+        Ok(())
+    }
+
+    /// Length of the list after applying the diff.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Return `true` if the diff contains no changed elements.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Changed and added elements, sorted by index.
+    pub fn updates(&self) -> &[(usize, T)] {
+        &self.updates
+    }
+
+    /// Encode this diff as bytes suitable for persistent storage.
+    ///
+    /// The encoding is a single version byte, followed by the SSZ encoding of a container
+    /// holding the new length, the changed indices and their values.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let indices = self
+            .updates
+            .iter()
+            .map(|(index, _)| *index as u64)
+            .collect::<Vec<_>>();
+        let values = self
+            .updates
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect::<Vec<_>>();
+
+        let mut bytes = vec![DIFF_VERSION];
+        let fixed_len = <u64 as Encode>::ssz_fixed_len() + 2 * BYTES_PER_LENGTH_OFFSET;
+        let mut encoder = SszEncoder::container(&mut bytes, fixed_len);
+        encoder.append(&(self.length as u64));
+        encoder.append(&indices);
+        encoder.append(&values);
+        encoder.finalize();
+        bytes
+    }
+
+    /// Decode a diff from bytes produced by `as_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (version, ssz_bytes) = bytes.split_first().ok_or(DecodeError::InvalidByteLength {
+            len: 0,
+            expected: 1,
+        })?;
+        if *version != DIFF_VERSION {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Unsupported ListDiff version {}",
+                version
+            )));
+        }
+
+        let mut builder = SszDecoderBuilder::new(ssz_bytes);
+        builder.register_type::<u64>()?;
+        builder.register_type::<Vec<u64>>()?;
+        builder.register_type::<Vec<T>>()?;
+        let mut decoder = builder.build()?;
+        let length = decoder.decode_next::<u64>()? as usize;
+        let indices = decoder.decode_next::<Vec<u64>>()?;
+        let values = decoder.decode_next::<Vec<T>>()?;
+
+        if length > N::to_usize() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "ListDiff length {} exceeds maximum of {}",
+                length,
+                N::to_usize()
+            )));
+        }
+        if indices.len() != values.len() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "ListDiff has {} indices but {} values",
+                indices.len(),
+                values.len()
+            )));
+        }
+        if indices.windows(2).any(|pair| pair[0] >= pair[1])
+            || indices
+                .last()
+                .is_some_and(|index| *index as usize >= length)
+        {
+            return Err(DecodeError::BytesInvalid(
+                "ListDiff indices are unsorted or out of bounds".into(),
+            ));
+        }
+
+        let updates = indices
+            .into_iter()
+            .map(|index| index as usize)
+            .zip(values)
+            .collect();
+        Ok(Self::from_parts(length, updates))
+    }
+}
+
+fn compute_recursive<T: Value>(
+    base: &Arc<Tree<T>>,
+    new: &Arc<Tree<T>>,
+    prefix: usize,
+    depth: usize,
+    packing_depth: usize,
+    updates: &mut Vec<(usize, T)>,
+) -> Result<(), Error> {
+    if Arc::ptr_eq(base, new) {
+        return Ok(());
+    }
+
+    match (&**base, &**new) {
+        (_, Tree::Zero(_)) => Ok(()),
+        (Tree::Leaf(base_leaf), Tree::Leaf(new_leaf)) => {
+            if base_leaf.value != new_leaf.value {
+                updates.push((prefix, (*new_leaf.value).clone()));
+            }
+            Ok(())
+        }
+        (Tree::PackedLeaf(base_leaf), Tree::PackedLeaf(new_leaf)) => {
+            for (i, value) in new_leaf.values.iter().enumerate() {
+                if base_leaf.values.get(i) != Some(value) {
+                    updates.push((prefix + i, value.clone()));
+                }
+            }
+            Ok(())
+        }
+        (
+            Tree::Node {
+                left: base_left,
+                right: base_right,
+                ..
+            },
+            Tree::Node {
+                left: new_left,
+                right: new_right,
+                ..
+            },
+        ) if depth > 0 => {
+            let new_depth = depth - 1;
+            let right_prefix = prefix | (1 << (new_depth + packing_depth));
+            compute_recursive(
+                base_left,
+                new_left,
+                prefix,
+                new_depth,
+                packing_depth,
+                updates,
+            )?;
+            compute_recursive(
+                base_right,
+                new_right,
+                right_prefix,
+                new_depth,
+                packing_depth,
+                updates,
+            )
+        }
+        (Tree::Zero(_), _) => {
+            // Every element of the new subtree was added.
+            let length = Length(new.compute_len());
+            updates.extend(
+                Iter::from_index(0, new, depth, length)
+                    .enumerate()
+                    .map(|(i, value)| (prefix + i, value.clone())),
+            );
+            Ok(())
+        }
+        _ => Err(Error::InvalidDiffNode),
+    }
+}
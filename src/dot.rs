@@ -0,0 +1,91 @@
+use crate::{Arc, Tree, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use tree_hash::Hash256;
+
+/// Number of hex characters of each hash to include in node labels.
+const HASH_PREFIX_LEN: usize = 8;
+
+fn short_hash(hash: Hash256) -> String {
+    if hash.is_zero() {
+        "unhashed".to_string()
+    } else {
+        let hex = hash
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        format!("0x{}", &hex[..HASH_PREFIX_LEN])
+    }
+}
+
+impl<T: Value> Tree<T> {
+    /// Render `tree` in Graphviz DOT format.
+    ///
+    /// Each distinct node is drawn once, so subtrees shared via `Arc` show up as nodes with
+    /// multiple incoming edges. Labels show the kind of each node and a prefix of its cached
+    /// hash, if it has been computed.
+    pub fn to_dot(tree: &Arc<Self>) -> String {
+        Self::to_dot_many(&[tree])
+    }
+
+    /// Render several trees in a single DOT graph, to show the nodes they share.
+    ///
+    /// Roots are labelled by their position in `trees`.
+    pub fn to_dot_many(trees: &[&Arc<Self>]) -> String {
+        let mut ids = HashMap::new();
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph tree {{");
+        let _ = writeln!(out, "  node [shape=box, fontname=\"monospace\"];");
+
+        for (i, root) in trees.iter().enumerate() {
+            let _ = writeln!(out, "  root{i} [shape=plaintext, label=\"root {i}\"];");
+            let id = Self::write_dot_node(root, &mut ids, &mut out);
+            let _ = writeln!(out, "  root{i} -> n{id};");
+        }
+
+        let _ = writeln!(out, "}}");
+        out
+    }
+
+    /// Write `node` and its descendants to `out` if they haven't been already, returning its ID.
+    fn write_dot_node(
+        node: &Arc<Self>,
+        ids: &mut HashMap<*const Self, usize>,
+        out: &mut String,
+    ) -> usize {
+        let ptr = Arc::as_ptr(node);
+        if let Some(id) = ids.get(&ptr) {
+            return *id;
+        }
+        let id = ids.len();
+        ids.insert(ptr, id);
+
+        match &**node {
+            Self::Leaf(leaf) => {
+                let hash = short_hash(*leaf.hash.read());
+                let _ = writeln!(out, "  n{id} [label=\"Leaf\\n{hash}\"];");
+            }
+            Self::PackedLeaf(packed) => {
+                let hash = short_hash(*packed.hash.read());
+                let len = packed.values.len();
+                let _ = writeln!(
+                    out,
+                    "  n{id} [label=\"PackedLeaf ({len} values)\\n{hash}\"];"
+                );
+            }
+            Self::Zero(depth) => {
+                let _ = writeln!(out, "  n{id} [label=\"Zero({depth})\", style=dashed];");
+            }
+            Self::Node { hash, left, right } => {
+                let hash = short_hash(*hash.read());
+                let _ = writeln!(out, "  n{id} [label=\"Node\\n{hash}\"];");
+                let left_id = Self::write_dot_node(left, ids, out);
+                let right_id = Self::write_dot_node(right, ids, out);
+                let _ = writeln!(out, "  n{id} -> n{left_id} [label=\"0\"];");
+                let _ = writeln!(out, "  n{id} -> n{right_id} [label=\"1\"];");
+            }
+        }
+        id
+    }
+}
@@ -0,0 +1,331 @@
+use crate::builder::Builder;
+use crate::interface::{ImmList, Interface, MutList};
+use crate::interface_iter::InterfaceIter;
+use crate::iter::Iter;
+use crate::level_iter::LevelIter;
+use crate::update_map::MaxMap;
+use crate::utils::{int_log, opt_packing_depth, ssz_append_variable, updated_length, Length};
+use crate::{Arc, Error, List, MaybeSync, Tree, UpdateMap, Value};
+use derivative::Derivative;
+use itertools::process_results;
+use ssz::{Decode, Encode, BYTES_PER_LENGTH_OFFSET};
+use std::collections::BTreeMap;
+use tree_hash::{Hash256, PackedEncoding, TreeHash};
+use typenum::Unsigned;
+use vec_map::VecMap;
+
+/// Persistent list whose maximum length is chosen at runtime rather than by a `typenum`.
+///
+/// The depth of the tree is computed from the maximum length when the list is constructed, so a
+/// single monomorphization can serve lists of every capacity. Hashing and encoding are identical
+/// to a `List<T, N>` with `N` equal to `max_len`.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(PartialEq(bound = "T: Value, U: UpdateMap<T> + PartialEq"))]
+pub struct DynList<T: Value, U: UpdateMap<T> = MaxMap<VecMap<T>>> {
+    pub(crate) interface: Interface<T, DynListInner<T>, U>,
+}
+
+#[derive(Debug, Clone, Derivative)]
+#[derivative(PartialEq(bound = "T: Value"))]
+pub struct DynListInner<T: Value> {
+    pub(crate) tree: Arc<Tree<T>>,
+    pub(crate) length: Length,
+    pub(crate) depth: usize,
+    pub(crate) packing_depth: usize,
+    pub(crate) max_len: usize,
+}
+
+/// Depth of the tree for a list of up to `max_len` elements of type `T`.
+fn dyn_list_depth<T: Value>(max_len: usize) -> usize {
+    int_log(max_len).saturating_sub(opt_packing_depth::<T>().unwrap_or(0))
+}
+
+impl<T: Value, U: UpdateMap<T>> DynList<T, U> {
+    pub fn new(vec: Vec<T>, max_len: usize) -> Result<Self, Error> {
+        Self::try_from_iter(vec, max_len)
+    }
+
+    fn from_parts(tree: Arc<Tree<T>>, depth: usize, length: Length, max_len: usize) -> Self {
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        Self {
+            interface: Interface::new(DynListInner {
+                tree,
+                length,
+                depth,
+                packing_depth,
+                max_len,
+            }),
+        }
+    }
+
+    pub fn empty(max_len: usize) -> Self {
+        let depth = dyn_list_depth::<T>(max_len);
+        Self::from_parts(Tree::empty(depth), depth, Length(0), max_len)
+    }
+
+    pub fn try_from_iter(iter: impl IntoIterator<Item = T>, max_len: usize) -> Result<Self, Error> {
+        let mut builder = Builder::new(dyn_list_depth::<T>(max_len), 0);
+
+        for (index, item) in iter.into_iter().enumerate() {
+            if index == max_len {
+                return Err(Error::ListFull {
+                    len: index,
+                    max_len,
+                });
+            }
+            builder.push(item)?;
+        }
+
+        let (tree, depth, length) = builder.finish()?;
+
+        Ok(Self::from_parts(tree, depth, length, max_len))
+    }
+
+    /// The maximum number of elements this list can hold.
+    pub fn max_len(&self) -> usize {
+        self.interface.backing.max_len
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    pub fn iter(&self) -> InterfaceIter<'_, T, U> {
+        self.interface.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.interface.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.interface.get_mut(index)
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        self.interface.push(value)
+    }
+
+    /// Push `value` if the list has space for it, otherwise return it.
+    pub fn push_within_capacity(&mut self, value: T) -> Result<(), T> {
+        self.interface.push_within_capacity(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.interface.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interface.is_empty()
+    }
+
+    pub fn has_pending_updates(&self) -> bool {
+        self.interface.has_pending_updates()
+    }
+
+    pub fn apply_updates(&mut self) -> Result<(), Error> {
+        self.interface.apply_updates()
+    }
+
+    /// Convert to a `List` with a static maximum length, which must equal `self.max_len()`.
+    ///
+    /// The tree and any pending updates are moved across without copying.
+    pub fn try_into_list<N: Unsigned>(self) -> Result<List<T, N, U>, Error> {
+        if N::to_usize() != self.max_len() {
+            return Err(Error::WrongListMaxLength {
+                max_len: self.max_len(),
+                expected: N::to_usize(),
+            });
+        }
+        let Interface {
+            backing, updates, ..
+        } = self.interface;
+        let mut list = List::from_parts(backing.tree, backing.depth, backing.length);
+        list.interface.updates = updates;
+        Ok(list)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<List<T, N, U>> for DynList<T, U> {
+    fn from(list: List<T, N, U>) -> Self {
+        let Interface {
+            backing, updates, ..
+        } = list.interface;
+        let mut dyn_list =
+            Self::from_parts(backing.tree, backing.depth, backing.length, N::to_usize());
+        dyn_list.interface.updates = updates;
+        dyn_list
+    }
+}
+
+impl<T: Value> ImmList<T> for DynListInner<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len().as_usize() {
+            self.tree
+                .get_recursive(index, self.depth, self.packing_depth)
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> Length {
+        self.length
+    }
+
+    fn iter_from(&self, index: usize) -> Iter<'_, T> {
+        Iter::from_index(index, &self.tree, self.depth, self.length)
+    }
+
+    fn level_iter_from(&self, index: usize) -> LevelIter<'_, T> {
+        LevelIter::from_index(index, &self.tree, self.depth, self.length)
+    }
+}
+
+impl<T: Value> MutList<T> for DynListInner<T> {
+    fn validate_push(&self, current_len: usize) -> Result<(), Error> {
+        if current_len == self.max_len {
+            Err(Error::ListFull {
+                len: current_len,
+                max_len: self.max_len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn replace(&mut self, index: usize, value: T) -> Result<(), Error> {
+        if index > self.len().as_usize() {
+            return Err(Error::OutOfBoundsUpdate {
+                index,
+                len: self.len().as_usize(),
+            });
+        }
+
+        self.tree = self.tree.with_updated_leaf(index, value, self.depth)?;
+        if index == self.length.as_usize() {
+            *self.length.as_mut() += 1;
+        }
+        Ok(())
+    }
+
+    fn update<U: UpdateMap<T>>(
+        &mut self,
+        updates: U,
+        hash_updates: Option<BTreeMap<(usize, usize), Hash256>>,
+    ) -> Result<(), Error> {
+        if let Some(max_index) = updates.max_index() {
+            if max_index >= self.max_len {
+                return Err(Error::InvalidListUpdate);
+            }
+        } else {
+            return Ok(());
+        }
+        let length = updated_length(self.length, &updates);
+        Tree::update_leaves_in_place(
+            &mut self.tree,
+            &updates,
+            0,
+            self.depth,
+            hash_updates.as_ref(),
+        )?;
+        self.length = length;
+        Ok(())
+    }
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> IntoIterator for &'a DynList<T, U> {
+    type Item = &'a T;
+    type IntoIter = InterfaceIter<'a, T, U>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Value + MaybeSync> TreeHash for DynList<T> {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    /// Compute the tree hash root, including any pending updates, as for `List`.
+    fn tree_hash_root(&self) -> Hash256 {
+        let backing = self
+            .interface
+            .applied_backing()
+            .expect("pending updates should be valid");
+        tree_hash::mix_in_length(&backing.tree.tree_hash(), self.len())
+    }
+}
+
+impl<T: Value> Encode for DynList<T> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if <T as Encode>::is_ssz_fixed_len() {
+            <T as Encode>::ssz_fixed_len() * self.len()
+        } else {
+            let mut len = self.iter().map(|item| item.ssz_bytes_len()).sum();
+            len += BYTES_PER_LENGTH_OFFSET * self.len();
+            len
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if <T as Encode>::is_ssz_fixed_len() {
+            buf.reserve(<T as Encode>::ssz_fixed_len() * self.len());
+
+            for item in self {
+                item.ssz_append(buf);
+            }
+        } else {
+            ssz_append_variable(|| self.iter(), self.len(), buf);
+        }
+    }
+}
+
+impl<T: Value> DynList<T> {
+    /// Decode a list of at most `max_len` elements from SSZ bytes.
+    ///
+    /// This can't be an implementation of `Decode`, as the maximum length is not known
+    /// statically.
+    pub fn from_ssz_bytes(bytes: &[u8], max_len: usize) -> Result<Self, ssz::DecodeError> {
+        let build_error =
+            |e: Error| ssz::DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e));
+
+        if bytes.is_empty() {
+            Ok(Self::empty(max_len))
+        } else if <T as Decode>::is_ssz_fixed_len() {
+            let num_items = bytes
+                .len()
+                .checked_div(<T as Decode>::ssz_fixed_len())
+                .ok_or(ssz::DecodeError::ZeroLengthItem)?;
+
+            if num_items > max_len {
+                return Err(ssz::DecodeError::BytesInvalid(format!(
+                    "List of {} items exceeds maximum of {}",
+                    num_items, max_len
+                )));
+            }
+
+            process_results(
+                bytes
+                    .chunks(<T as Decode>::ssz_fixed_len())
+                    .map(T::from_ssz_bytes),
+                |iter| Self::try_from_iter(iter, max_len).map_err(build_error),
+            )?
+        } else {
+            let items: Vec<T> = ssz::decode_list_of_variable_length_items(bytes, Some(max_len))?;
+            Self::new(items, max_len).map_err(build_error)
+        }
+    }
+}
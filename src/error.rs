@@ -1,22 +1,77 @@
+use ssz::DecodeError;
 use std::fmt::{Display, Error as FmtError, Formatter};
+use tree_hash::Hash256;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
-    OutOfBoundsUpdate { index: usize, len: usize },
-    OutOfBoundsIterFrom { index: usize, len: usize },
-    ListFull { len: usize },
-    PackedLeafFull { len: usize },
-    LeafUpdateMissing { index: usize },
-    PackedLeafOutOfBounds { sub_index: usize, len: usize },
-    NodeUpdatesMissing { prefix: usize },
+    OutOfBoundsUpdate {
+        index: usize,
+        len: usize,
+    },
+    OutOfBoundsIterFrom {
+        index: usize,
+        len: usize,
+    },
+    ListFull {
+        len: usize,
+        max_len: usize,
+    },
+    PackedLeafFull {
+        len: usize,
+    },
+    LeafUpdateMissing {
+        index: usize,
+    },
+    InvalidPackedLayout {
+        packing_factor: usize,
+        ssz_len: usize,
+    },
+    PackedLeafOutOfBounds {
+        sub_index: usize,
+        len: usize,
+    },
+    NodeUpdatesMissing {
+        prefix: usize,
+    },
     InvalidListUpdate,
     InvalidVectorUpdate,
-    WrongVectorLength { len: usize, expected: usize },
+    WrongVectorLength {
+        len: usize,
+        expected: usize,
+    },
+    WrongIteratorLength {
+        len: usize,
+        expected: usize,
+    },
+    WrongListMaxLength {
+        max_len: usize,
+        expected: usize,
+    },
     PushNotSupported,
     UpdateLeafError,
     UpdateLeavesError,
     InvalidRebaseNode,
     InvalidRebaseLeaf,
+    InvalidTruncateNode,
+    InvalidPermuteNode,
+    InvalidDiffNode,
+    DiffPendingUpdates,
+    DiffInvalidIndex {
+        index: usize,
+        len: usize,
+    },
+    DiffWrongLength {
+        len: usize,
+        expected: usize,
+    },
+    InvalidPermutation {
+        index: usize,
+    },
+    ZipPendingUpdates,
+    ZipLengthMismatch {
+        len: usize,
+        other_len: usize,
+    },
     BuilderExpectedLeaf,
     BuilderStackEmptyMerge,
     BuilderStackEmptyMergeLeft,
@@ -29,10 +84,185 @@ pub enum Error {
     BulkUpdateUnclean,
     CowMissingEntry,
     LevelIterPendingUpdates,
+    ChunkIterPendingUpdates,
+    ProofPendingUpdates,
+    StorePendingUpdates,
+    MissingStoredNode {
+        depth: usize,
+        hash: Hash256,
+    },
+    InvalidStoredNode {
+        depth: usize,
+        hash: Hash256,
+    },
+    /// Error from the database behind a `NodeStore`.
+    NodeStore {
+        message: String,
+    },
+    OutOfBoundsProof {
+        index: usize,
+        len: usize,
+    },
+    OutOfBoundsSubtree {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    SubtreePendingUpdates,
+    SnapshotPendingUpdates,
+    OverlayPendingUpdates,
+    OutOfBoundsSlice {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    InvalidProofNode,
+    InvalidGeneralizedIndex {
+        gindex: usize,
+    },
+    GeneralizedIndexPendingUpdates,
+    InvalidMultiProof,
+    UnprovenIndex {
+        index: usize,
+    },
+    PartialValueUnavailable {
+        index: usize,
+    },
+    MultiProofMissingNode {
+        gindex: usize,
+    },
+    InvalidMapNode {
+        depth: usize,
+    },
+    InvalidTreeLength {
+        len: usize,
+        max_len: usize,
+    },
+    InvalidTreeDepth {
+        index: usize,
+        depth: usize,
+    },
+    InvalidPackedLeafLength {
+        index: usize,
+        len: usize,
+        expected: usize,
+    },
+    InvalidZeroSubtree {
+        index: usize,
+        depth: usize,
+    },
+    NonZeroPadding {
+        index: usize,
+        depth: usize,
+    },
+    InvalidCachedHash {
+        index: usize,
+        depth: usize,
+    },
+    StableContainerInvalidField {
+        index: usize,
+    },
+    /// SSZ bytes for a list contained more than `max_len` items.
+    DecodeListTooLong {
+        len: usize,
+        max_len: usize,
+    },
+    /// SSZ bytes for a vector contained `len` items rather than exactly `expected`.
+    DecodeWrongVectorLength {
+        len: usize,
+        expected: usize,
+    },
+    /// Any other error from decoding SSZ bytes.
+    SszDecode(DecodeError),
+    /// An error which occurred while updating the subtree at the given position.
+    ///
+    /// `depth` is the height of the subtree above its leaves, `prefix` is the index of its first
+    /// element, and `gindex` is its generalized index relative to the root of the tree being
+    /// updated.
+    InTree {
+        depth: usize,
+        prefix: usize,
+        gindex: usize,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Wrap this error with the position in the tree at which it occurred.
+    ///
+    /// Errors which already have a position are returned unchanged, so that the innermost
+    /// position is kept.
+    pub(crate) fn in_tree(self, depth: usize, prefix: usize, gindex: usize) -> Self {
+        match self {
+            Error::InTree { .. } => self,
+            _ => Error::InTree {
+                depth,
+                prefix,
+                gindex,
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// Return the underlying error, without any context about where it occurred.
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::InTree { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
-        write!(f, "{:?}", self)
+        match self {
+            Error::InTree {
+                depth,
+                prefix,
+                gindex,
+                ..
+            } => write!(
+                f,
+                "error in subtree at generalized index {gindex} (depth {depth}, prefix {prefix})"
+            ),
+            Error::WrongVectorLength { len, expected } => {
+                write!(f, "wrong vector length: expected {expected}, got {len}")
+            }
+            Error::DecodeListTooLong { len, max_len } => {
+                write!(f, "List of {len} items exceeds maximum of {max_len}")
+            }
+            Error::DecodeWrongVectorLength { len, expected } => {
+                write!(f, "Vector of {len} items, expected {expected}")
+            }
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InTree { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Error::SszDecode(e)
+    }
+}
+
+/// Convert back to an `ssz::DecodeError` for use in `Decode` impls.
+///
+/// Decoding errors are returned unchanged, and any other error becomes `BytesInvalid` with its
+/// message.
+impl From<Error> for DecodeError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::SszDecode(e) => e,
+            e => DecodeError::BytesInvalid(e.to_string()),
+        }
     }
 }
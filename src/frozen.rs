@@ -0,0 +1,147 @@
+use crate::iter::Iter;
+use crate::utils::{opt_packing_depth, ssz_append_variable, Length};
+use crate::{Arc, Error, List, MaybeSync, MemoryUsage, Tree, UpdateMap, Value};
+use ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
+use std::marker::PhantomData;
+use tree_hash::{Hash256, PackedEncoding, TreeHash};
+use typenum::Unsigned;
+
+/// Immutable list, without any of the machinery for pending updates.
+///
+/// A frozen list is just a pointer to its tree and a length, so it is smaller than a `List` and
+/// cloning it is a single reference count increment. Use `thaw` to get a mutable `List` back,
+/// which shares the same tree.
+#[derive(Debug)]
+pub struct FrozenList<T: Value, N: Unsigned> {
+    tree: Arc<Tree<T>>,
+    length: Length,
+    _phantom: PhantomData<N>,
+}
+
+impl<T: Value, N: Unsigned> Clone for FrozenList<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            length: self.length,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Value, N: Unsigned> PartialEq for FrozenList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length
+            && (Arc::ptr_eq(&self.tree, &other.tree) || self.tree == other.tree)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Apply any pending updates and convert to an immutable `FrozenList`.
+    pub fn freeze(mut self) -> Result<FrozenList<T, N>, Error> {
+        self.apply_updates()?;
+        let backing = self.interface.backing;
+        Ok(FrozenList {
+            tree: backing.tree,
+            length: backing.length,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: Value, N: Unsigned> FrozenList<T, N> {
+    /// Convert back into a mutable `List` sharing the same tree.
+    pub fn thaw<U: UpdateMap<T>>(&self) -> List<T, N, U> {
+        List::from_parts(self.tree.clone(), Self::depth(), self.length)
+    }
+
+    fn depth() -> usize {
+        List::<T, N>::depth()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length.as_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() {
+            let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+            self.tree.get_recursive(index, Self::depth(), packing_depth)
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::from_index(0, &self.tree, Self::depth(), self.length)
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    /// Compute the memory used by the tree backing this list.
+    ///
+    /// See `Tree::mem_usage`.
+    pub fn mem_usage(&self) -> MemoryUsage {
+        Tree::mem_usage(&self.tree)
+    }
+}
+
+impl<'a, T: Value, N: Unsigned> IntoIterator for &'a FrozenList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> TreeHash for FrozenList<T, N> {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        tree_hash::mix_in_length(&self.tree.tree_hash(), self.len())
+    }
+}
+
+impl<T: Value, N: Unsigned> Encode for FrozenList<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if <T as Encode>::is_ssz_fixed_len() {
+            <T as Encode>::ssz_fixed_len() * self.len()
+        } else {
+            let mut len = self.iter().map(|item| item.ssz_bytes_len()).sum();
+            len += BYTES_PER_LENGTH_OFFSET * self.len();
+            len
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if <T as Encode>::is_ssz_fixed_len() {
+            buf.reserve(<T as Encode>::ssz_fixed_len() * self.len());
+
+            for item in self {
+                item.ssz_append(buf);
+            }
+        } else {
+            ssz_append_variable(|| self.iter(), self.len(), buf);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Fuzzing harnesses, shared by the targets in `fuzz/` and available to downstream crates.
+//!
+//! Each harness takes the raw bytes provided by the fuzzer and panics if it finds a discrepancy.
+//! Inputs which run out of bytes part way through end the run early rather than failing, so the
+//! harnesses can be driven by any byte source, e.g. `cargo fuzz` or a seeded RNG in a test.
+use crate::update_map::MaxMap;
+use crate::{BitmapUpdateMap, List, MaybeSync, Sha256Hasher, UpdateMap, Value, VecUpdateMap};
+use arbitrary::{Arbitrary, Unstructured};
+use ssz::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{Unsigned, U1024, U130, U255, U33, U64};
+use vec_map::VecMap;
+
+/// Operations applied by `ops_model`.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum Op<T> {
+    Get(usize),
+    Set(usize, T),
+    Push(T),
+    Pop,
+    Truncate(usize),
+    PopFront(usize),
+    Swap(usize, usize),
+    ApplyUpdates,
+    TreeHash,
+    Iter,
+    IterFrom(usize),
+    /// Clone the list and continue with the clone, so later updates must copy shared nodes.
+    Clone,
+}
+
+/// Decode the input as lists of several element types, checking that decoding agrees with
+/// `Vec` and that re-encoding reproduces the input exactly.
+pub fn ssz_round_trip(data: &[u8]) {
+    check_round_trip::<u8, U255>(data);
+    check_round_trip::<u16, U130>(data);
+    check_round_trip::<u64, U33>(data);
+    check_round_trip::<Hash256, U64>(data);
+    check_round_trip::<List<u16, U33>, U33>(data);
+}
+
+fn check_round_trip<T, N>(data: &[u8])
+where
+    T: Value + MaybeSync + Debug,
+    N: Unsigned,
+{
+    let expected = Vec::<T>::from_ssz_bytes(data)
+        .ok()
+        .filter(|values| values.len() <= N::to_usize());
+    match List::<T, N>::from_ssz_bytes(data) {
+        Ok(list) => {
+            assert_eq!(Some(list.to_vec()), expected);
+            assert_eq!(list.as_ssz_bytes(), data);
+            list.validate().expect("decoded list is valid");
+            let rebuilt = List::<T, N>::new(list.to_vec()).expect("length was checked");
+            assert_eq!(list.tree_hash_root(), rebuilt.tree_hash_root());
+        }
+        Err(_) => assert_eq!(expected, None),
+    }
+}
+
+/// Apply a random sequence of operations to a list and to a `Vec`, checking that they agree
+/// after every operation.
+///
+/// The first byte selects the element type and maximum length, which are chosen so that the
+/// lists have partially filled packed leaves and non-power-of-two lengths.
+pub fn ops_model(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let Ok(kind) = u.int_in_range(0..=2u8) else {
+        return;
+    };
+    let _ = match kind {
+        0 => run_ops::<u8, U255, MaxMap<VecMap<u8>>>(&mut u),
+        1 => run_ops::<u64, U130, MaxMap<VecMap<u64>>>(&mut u),
+        _ => run_ops::<Hash256, U33, MaxMap<VecMap<Hash256>>>(&mut u),
+    };
+}
+
+/// Apply the same operations to lists with each kind of update map, checking that they always
+/// agree with each other and with a `Vec`.
+pub fn update_map_equivalence(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let Ok(ops) = Vec::<Op<u64>>::arbitrary(&mut u) else {
+        return;
+    };
+    let Ok(initial) = u.int_in_range(0..=130usize) else {
+        return;
+    };
+    let values = (0..initial as u64).collect::<Vec<_>>();
+
+    let roots = [
+        apply_ops::<u64, U130, MaxMap<VecMap<u64>>>(&values, &ops),
+        apply_ops::<u64, U130, VecMap<u64>>(&values, &ops),
+        apply_ops::<u64, U130, BTreeMap<usize, u64>>(&values, &ops),
+        apply_ops::<u64, U130, VecUpdateMap<u64>>(&values, &ops),
+        apply_ops::<u64, U130, BitmapUpdateMap<u64>>(&values, &ops),
+    ];
+    assert!(roots.iter().all(|root| *root == roots[0]));
+}
+
+/// Build lists whose lengths are within one of a multiple of the packing factor, and push and
+/// pop across the leaf boundary, checking hashes against lists built from scratch.
+pub fn packed_leaf_boundaries(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let _ = check_boundaries::<u8, U1024>(&mut u);
+    let _ = check_boundaries::<u16, U255>(&mut u);
+    let _ = check_boundaries::<u64, U130>(&mut u);
+}
+
+fn check_boundaries<T, N>(u: &mut Unstructured) -> arbitrary::Result<()>
+where
+    T: Value + MaybeSync + Debug + for<'a> Arbitrary<'a>,
+    N: Unsigned,
+{
+    let factor = T::tree_hash_packing_factor();
+    let leaves = u.int_in_range(0..=N::to_usize() / factor)?;
+    let len = (leaves * factor + u.int_in_range(0..=2)?)
+        .saturating_sub(1)
+        .min(N::to_usize());
+    let mut model = (0..len)
+        .map(|_| T::arbitrary(u))
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    let mut list = List::<T, N>::new(model.clone()).expect("length is within bounds");
+
+    for _ in 0..u.int_in_range(0..=4)? {
+        if bool::arbitrary(u)? {
+            let value = T::arbitrary(u)?;
+            let result = list.push(value.clone());
+            if model.len() < N::to_usize() {
+                result.expect("list is not full");
+                model.push(value);
+            } else {
+                assert!(result.is_err());
+            }
+        } else {
+            assert_eq!(list.pop().expect("pop succeeds"), model.pop());
+        }
+        if bool::arbitrary(u)? {
+            list.apply_updates().expect("updates apply");
+        }
+        let expected = List::<T, N>::new(model.clone()).expect("length is within bounds");
+        assert_eq!(list.to_vec(), model);
+        assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+    }
+    list.apply_updates().expect("updates apply");
+    list.validate().expect("list is valid");
+    Ok(())
+}
+
+fn run_ops<T, N, U>(u: &mut Unstructured) -> arbitrary::Result<()>
+where
+    T: Value + MaybeSync + Debug + for<'a> Arbitrary<'a>,
+    N: Unsigned,
+    U: UpdateMap<T>,
+{
+    let len = u.int_in_range(0..=N::to_usize())?;
+    let values = (0..len)
+        .map(|_| T::arbitrary(u))
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    let ops = Vec::<Op<T>>::arbitrary(u)?;
+    apply_ops::<T, N, U>(&values, &ops);
+    Ok(())
+}
+
+/// Apply `ops` to a list initialised with `values`, returning its final tree hash root.
+fn apply_ops<T, N, U>(values: &[T], ops: &[Op<T>]) -> Hash256
+where
+    T: Value + MaybeSync + Debug,
+    N: Unsigned,
+    U: UpdateMap<T>,
+{
+    let mut model = values.to_vec();
+    let mut list = List::<T, N, U>::new(model.clone()).expect("length is within bounds");
+
+    for op in ops {
+        match op {
+            Op::Get(index) => {
+                assert_eq!(list.get(*index), model.get(*index));
+            }
+            Op::Set(index, value) => match list.get_mut(*index) {
+                Some(elem) => {
+                    *elem = value.clone();
+                    model[*index] = value.clone();
+                }
+                None => assert!(*index >= model.len()),
+            },
+            Op::Push(value) => {
+                let result = list.push(value.clone());
+                if model.len() < N::to_usize() {
+                    result.expect("list is not full");
+                    model.push(value.clone());
+                } else {
+                    assert!(result.is_err());
+                }
+            }
+            Op::Pop => {
+                assert_eq!(list.pop().expect("pop succeeds"), model.pop());
+            }
+            Op::Truncate(len) => {
+                let len = len % (model.len() + 1);
+                list.truncate(len).expect("truncate succeeds");
+                model.truncate(len);
+            }
+            Op::PopFront(n) => {
+                let n = n % (model.len() + 1);
+                list.pop_front(n).expect("pop_front succeeds");
+                model.drain(..n);
+            }
+            Op::Swap(i, j) => {
+                let result = list.swap(*i, *j);
+                if *i < model.len() && *j < model.len() {
+                    result.expect("indices are in bounds");
+                    model.swap(*i, *j);
+                } else {
+                    assert!(result.is_err());
+                }
+            }
+            Op::ApplyUpdates => {
+                list.apply_updates().expect("updates apply");
+                assert!(!list.has_pending_updates());
+            }
+            Op::TreeHash => {
+                let expected = List::<T, N>::new(model.clone()).expect("length is within bounds");
+                assert_eq!(
+                    list.tree_hash_root_with::<Sha256Hasher>()
+                        .expect("hash succeeds"),
+                    expected.tree_hash_root()
+                );
+            }
+            Op::Iter => {
+                assert!(list.iter().eq(model.iter()));
+            }
+            Op::IterFrom(index) => match list.iter_from(*index) {
+                Ok(iter) => assert!(iter.eq(model[*index..].iter())),
+                Err(_) => assert!(*index > model.len()),
+            },
+            Op::Clone => {
+                list = list.clone();
+            }
+        }
+        assert_eq!(list.len(), model.len());
+    }
+
+    assert_eq!(list.to_vec(), model);
+    list.apply_updates().expect("updates apply");
+    list.validate().expect("list is valid");
+    list.tree_hash_root_with::<Sha256Hasher>()
+        .expect("hash succeeds")
+}
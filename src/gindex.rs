@@ -0,0 +1,162 @@
+//! Generalized indices, as used by the consensus specs to identify nodes in a Merkle tree.
+//!
+//! The root of a tree has generalized index 1, and the children of the node with generalized
+//! index `g` are `2 * g` and `2 * g + 1`. For a `List`, the root mixes in the length: the root of
+//! the data tree is at 2 and the length chunk is at 3.
+use crate::proof::length_chunk;
+use crate::utils::opt_packing_depth;
+use crate::{Arc, Error, List, MaybeSync, Sha256Hasher, Tree, UpdateMap, Value, Vector};
+use tree_hash::Hash256;
+use typenum::Unsigned;
+
+/// Generalized index of the root of a list's data tree.
+pub const LIST_DATA_GINDEX: usize = 2;
+
+/// Generalized index of a list's length chunk.
+pub const LIST_LENGTH_GINDEX: usize = 3;
+
+/// Depth of the node at `gindex` below the root, or `None` for the invalid index 0.
+pub fn gindex_depth(gindex: usize) -> Option<usize> {
+    gindex.checked_ilog2().map(|depth| depth as usize)
+}
+
+pub fn gindex_parent(gindex: usize) -> usize {
+    gindex / 2
+}
+
+pub fn gindex_sibling(gindex: usize) -> usize {
+    gindex ^ 1
+}
+
+/// Generalized index of the left (`right == false`) or right child of `gindex`.
+pub fn gindex_child(gindex: usize, right: bool) -> usize {
+    2 * gindex + usize::from(right)
+}
+
+/// Generalized index of the node at `inner` within the subtree rooted at `outer`.
+///
+/// Corresponds to `concat_generalized_indices` from the consensus specs.
+pub fn concat_gindices(outer: usize, inner: usize) -> Option<usize> {
+    let inner_depth = gindex_depth(inner)?;
+    let shifted = outer.checked_shl(inner_depth as u32)?;
+    if shifted >> inner_depth != outer {
+        return None;
+    }
+    Some(shifted | (inner ^ (1 << inner_depth)))
+}
+
+/// Generalized index of the `index`th node at `depth` below the root.
+pub fn gindex_at_depth(depth: usize, index: usize) -> Option<usize> {
+    let first = 1usize.checked_shl(depth as u32)?;
+    (index < first).then(|| first + index)
+}
+
+impl<T: Value> Tree<T> {
+    /// Return the node at `gindex` relative to `tree`, which has the given `depth`.
+    ///
+    /// Nodes within zero subtrees are returned as new zero nodes. Errors if `gindex` lies below
+    /// the leaves of the tree.
+    pub fn node_at(tree: &Arc<Self>, gindex: usize, depth: usize) -> Result<Arc<Self>, Error> {
+        let node_depth = gindex_depth(gindex)
+            .filter(|node_depth| *node_depth <= depth)
+            .ok_or(Error::InvalidGeneralizedIndex { gindex })?;
+
+        let mut node = tree.clone();
+        for level in (0..node_depth).rev() {
+            let right = (gindex >> level) & 1 == 1;
+            node = match &*node {
+                Self::Node { left, right: r, .. } => {
+                    if right {
+                        r.clone()
+                    } else {
+                        left.clone()
+                    }
+                }
+                Self::Zero(zero_depth) if *zero_depth > 0 => Self::zero(zero_depth - 1),
+                _ => return Err(Error::InvalidGeneralizedIndex { gindex }),
+            };
+        }
+        Ok(node)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Generalized index of the chunk containing the `index`th element.
+    ///
+    /// Returns `None` if `index` is greater than or equal to the maximum length `N`, but doesn't
+    /// check the current length of the list.
+    pub fn generalized_index(index: usize) -> Option<usize> {
+        if index >= N::to_usize() {
+            return None;
+        }
+        let chunk_index = index >> opt_packing_depth::<T>().unwrap_or(0);
+        concat_gindices(
+            LIST_DATA_GINDEX,
+            gindex_at_depth(Self::depth(), chunk_index)?,
+        )
+    }
+
+    /// Return the node of the data tree at `gindex`, which is relative to the root of the list.
+    ///
+    /// Errors if `gindex` doesn't identify a node of the data tree, or if there are pending
+    /// updates.
+    pub fn node_at(&self, gindex: usize) -> Result<Arc<Tree<T>>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::GeneralizedIndexPendingUpdates);
+        }
+        let depth = gindex_depth(gindex).ok_or(Error::InvalidGeneralizedIndex { gindex })?;
+        // The data tree is the left subtree of the root.
+        if depth == 0 || (gindex >> (depth - 1)) != LIST_DATA_GINDEX {
+            return Err(Error::InvalidGeneralizedIndex { gindex });
+        }
+        let relative = gindex - (1 << depth) + (1 << (depth - 1));
+        let backing = &self.interface.backing;
+        Tree::node_at(&backing.tree, relative, backing.depth)
+            .map_err(|_| Error::InvalidGeneralizedIndex { gindex })
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Return the hash of the node at `gindex`, including the root and the length chunk.
+    pub fn hash_at(&self, gindex: usize) -> Result<Hash256, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::GeneralizedIndexPendingUpdates);
+        }
+        match gindex {
+            1 => self.tree_hash_root_with::<Sha256Hasher>(),
+            LIST_LENGTH_GINDEX => Ok(length_chunk(self.len())),
+            _ => self.node_at(gindex).map(|node| node.tree_hash()),
+        }
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Generalized index of the chunk containing the `index`th element.
+    ///
+    /// Returns `None` if `index` is greater than or equal to the length `N`.
+    pub fn generalized_index(index: usize) -> Option<usize> {
+        if index >= N::to_usize() {
+            return None;
+        }
+        let chunk_index = index >> opt_packing_depth::<T>().unwrap_or(0);
+        gindex_at_depth(List::<T, N, U>::depth(), chunk_index)
+    }
+
+    /// Return the node at `gindex`.
+    ///
+    /// Errors if `gindex` lies below the leaves of the tree, or if there are pending updates.
+    pub fn node_at(&self, gindex: usize) -> Result<Arc<Tree<T>>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::GeneralizedIndexPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Tree::node_at(&backing.tree, gindex, backing.depth)
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Return the hash of the node at `gindex`.
+    pub fn hash_at(&self, gindex: usize) -> Result<Hash256, Error> {
+        self.node_at(gindex).map(|node| node.tree_hash())
+    }
+}
@@ -0,0 +1,85 @@
+use crate::interface::MutList;
+use crate::{UpdateMap, Value};
+use std::ops::{Deref, DerefMut};
+
+/// Mutable reference to an element of a list or vector, which is written back on drop.
+///
+/// The guard works on a copy of the element, and only records it as a pending update when it is
+/// dropped, and only if its value has changed. Unlike `get_mut`, reading an element through a
+/// guard without modifying it does not cause any part of the tree to be re-hashed.
+pub struct MutGuard<'a, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    pub(crate) backing: &'a B,
+    pub(crate) updates: &'a mut U,
+    pub(crate) index: usize,
+    /// Always `Some` until the guard is dropped or discarded.
+    pub(crate) value: Option<T>,
+}
+
+impl<T, B, U> MutGuard<'_, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Drop the guard without writing back any changes.
+    pub fn discard(mut self) {
+        self.value = None;
+    }
+}
+
+impl<T, B, U> Deref for MutGuard<'_, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+            .as_ref()
+            .expect("value is only taken when the guard is dropped")
+    }
+}
+
+impl<T, B, U> DerefMut for MutGuard<'_, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+            .as_mut()
+            .expect("value is only taken when the guard is dropped")
+    }
+}
+
+impl<T, B, U> Drop for MutGuard<'_, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    fn drop(&mut self) {
+        let Some(value) = self.value.take() else {
+            return;
+        };
+        // Values already in the update map are overwritten regardless, as they may be the result
+        // of a previous modification.
+        let unchanged =
+            self.updates.get(self.index).is_none() && self.backing.get(self.index) == Some(&value);
+        if !unchanged {
+            self.updates.insert(self.index, value);
+        }
+    }
+}
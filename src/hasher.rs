@@ -0,0 +1,37 @@
+use ethereum_hashing::{hash32_concat, ZERO_HASHES};
+use tree_hash::Hash256;
+
+/// Hash function used to compute the internal nodes of a tree.
+///
+/// Only the internal nodes of the tree are hashed using this trait. The hashes of unpacked leaf
+/// values are still computed by their `TreeHash` implementations, and packed leaves are not
+/// hashed at all.
+///
+/// The hashes cached in a tree are always SHA256 hashes, so hashers other than `Sha256Hasher`
+/// neither use nor update them, see `Tree::tree_hash_with`.
+pub trait MerkleHasher: 'static {
+    /// Hash two sibling nodes to produce their parent.
+    fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256;
+
+    /// Hash of an empty subtree of the given `depth`.
+    ///
+    /// The default implementation hashes up from a zero leaf, implementations should override it
+    /// with a lookup table where possible.
+    fn zero_hash(depth: usize) -> Hash256 {
+        (0..depth).fold(Hash256::zero(), |hash, _| Self::hash_pair(&hash, &hash))
+    }
+}
+
+/// SHA256 hasher, as used by SSZ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Sha256Hasher;
+
+impl MerkleHasher for Sha256Hasher {
+    fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+        Hash256::from(hash32_concat(left.as_bytes(), right.as_bytes()))
+    }
+
+    fn zero_hash(depth: usize) -> Hash256 {
+        Hash256::from_slice(&ZERO_HASHES[depth])
+    }
+}
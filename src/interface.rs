@@ -1,4 +1,9 @@
+use crate::guard::MutGuard;
 use crate::level_iter::LevelIter;
+use crate::metrics;
+use crate::sharded_updates::ShardedUpdates;
+use crate::trace::op_span;
+use crate::transaction::Transaction;
 use crate::update_map::UpdateMap;
 use crate::utils::{updated_length, Length};
 use crate::{
@@ -8,7 +13,9 @@ use crate::{
 };
 use arbitrary::Arbitrary;
 use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::marker::PhantomData;
+use std::ops::ControlFlow;
 use tree_hash::Hash256;
 
 pub trait ImmList<T: Value> {
@@ -26,7 +33,7 @@ pub trait ImmList<T: Value> {
 }
 
 pub trait MutList<T: Value>: ImmList<T> {
-    fn validate_push(current_len: usize) -> Result<(), Error>;
+    fn validate_push(&self, current_len: usize) -> Result<(), Error>;
     fn replace(&mut self, index: usize, value: T) -> Result<(), Error>;
     fn update<U: UpdateMap<T>>(
         &mut self,
@@ -47,6 +54,31 @@ where
     pub(crate) _phantom: PhantomData<T>,
 }
 
+/// When the pending updates of a list or vector are applied to its tree.
+///
+/// The policy is determined by the update map type, see `UpdateMap::APPLY_POLICY`. Wrapping an
+/// update map in `AutoApply` selects `Auto`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyPolicy {
+    /// Updates are only applied by explicit calls to `apply_updates`.
+    #[default]
+    Explicit,
+    /// The updates left by each modification are applied before the next one.
+    ///
+    /// Reads like `tree_hash_root`, `as_ssz_bytes` and `iter` only borrow the list immutably, so
+    /// they can't apply updates themselves. They include pending updates regardless, and with
+    /// this policy at most the most recent modification is pending when they are called, so
+    /// the update map stays small without any calls to `apply_updates`.
+    Auto,
+}
+
+/// State of a list or vector recorded by `checkpoint`, which can be restored by `revert_to`.
+#[derive(Debug, Clone)]
+pub struct Checkpoint<B, U> {
+    backing: B,
+    updates: U,
+}
+
 impl<T, B, U> Interface<T, B, U>
 where
     T: Value,
@@ -61,37 +93,186 @@ where
         }
     }
 
+    pub fn apply_policy(&self) -> ApplyPolicy {
+        U::APPLY_POLICY
+    }
+
+    /// Apply the updates left pending by the previous modification, if the policy is `Auto`.
+    fn auto_apply(&mut self) {
+        if U::APPLY_POLICY == ApplyPolicy::Auto {
+            self.apply_updates()
+                .expect("pending updates should be valid");
+        }
+    }
+
     pub fn get(&self, idx: usize) -> Option<&T> {
         self.updates.get(idx).or_else(|| self.backing.get(idx))
     }
 
     pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.auto_apply();
         self.updates
             .get_mut_with(idx, |idx| self.backing.get(idx).cloned())
     }
 
+    pub fn get_mut_guard(&mut self, index: usize) -> Option<MutGuard<'_, T, B, U>> {
+        self.auto_apply();
+        let value = self.get(index)?.clone();
+        Some(MutGuard {
+            backing: &self.backing,
+            updates: &mut self.updates,
+            index,
+            value: Some(value),
+        })
+    }
+
     pub fn get_cow(&mut self, index: usize) -> Option<Cow<T>> {
+        self.auto_apply();
         self.updates
             .get_cow_with(index, |idx| self.backing.get(idx))
     }
 
+    /// Swap the elements at indices `i` and `j`, recording both as pending updates.
+    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), Error> {
+        self.auto_apply();
+        let len = self.len();
+        let out_of_bounds = |index| Error::OutOfBoundsUpdate { index, len };
+        let value_i = self.get(i).ok_or_else(|| out_of_bounds(i))?.clone();
+        let value_j = self.get(j).ok_or_else(|| out_of_bounds(j))?.clone();
+        if i != j {
+            self.updates.insert(i, value_j);
+            self.updates.insert(j, value_i);
+        }
+        Ok(())
+    }
+
+    /// Stage the `(index, value)` pairs in `updates` as pending updates.
+    ///
+    /// Unlike `get_mut`, the values being replaced are never read, so no copy of them is made
+    /// from the tree before they are overwritten. Every index is checked before any update is
+    /// staged, so on error nothing is changed. If an index appears more than once, its last value
+    /// is kept.
+    pub fn update_many(
+        &mut self,
+        updates: impl IntoIterator<Item = (usize, T)>,
+    ) -> Result<(), Error> {
+        self.auto_apply();
+        let len = self.len();
+        let updates = updates.into_iter().collect::<Vec<_>>();
+        if let Some(&(index, _)) = updates.iter().find(|(index, _)| *index >= len) {
+            return Err(Error::OutOfBoundsUpdate { index, len });
+        }
+        for (index, value) in updates {
+            self.updates.insert(index, value);
+        }
+        Ok(())
+    }
+
     pub fn push(&mut self, value: T) -> Result<(), Error> {
+        self.auto_apply();
+        let index = self.len();
+        self.backing.validate_push(index)?;
+        self.updates.insert(index, value);
+
+        Ok(())
+    }
+
+    pub fn push_within_capacity(&mut self, value: T) -> Result<(), T> {
+        self.auto_apply();
         let index = self.len();
-        B::validate_push(index)?;
+        if self.backing.validate_push(index).is_err() {
+            return Err(value);
+        }
         self.updates.insert(index, value);
 
         Ok(())
     }
 
+    /// Call `f` on every element, recording each value it returns as an update.
+    pub fn map_in_place<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &T) -> Option<T>,
+    {
+        self.auto_apply();
+        let changes = self
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((index, f(index, value)?)))
+            .collect::<Vec<_>>();
+        for (index, value) in changes {
+            self.updates.insert(index, value);
+        }
+    }
+
+    /// Call `f` with mutable access to each element in turn, stopping early if it breaks.
+    ///
+    /// Modified values are recorded as updates, including those modified before breaking.
+    pub fn try_for_each_mut<R, F>(&mut self, mut f: F) -> ControlFlow<R>
+    where
+        F: FnMut(usize, &mut T) -> ControlFlow<R>,
+    {
+        self.auto_apply();
+        let len = self.len();
+        let mut tree_iter = self.backing.iter_from(0);
+        for index in 0..len {
+            // Advance the tree iterator so that it moves in step with the index.
+            let backing_value = tree_iter.next();
+
+            let flow = if let Some(value) = self.updates.get_mut_with(index, |_| None) {
+                f(index, value)
+            } else if let Some(original) = backing_value {
+                let mut value = original.clone();
+                let flow = f(index, &mut value);
+                if value != *original {
+                    self.updates.insert(index, value);
+                }
+                flow
+            } else {
+                break;
+            };
+            flow?;
+        }
+        ControlFlow::Continue(())
+    }
+
     pub fn apply_updates(&mut self) -> Result<(), Error> {
         if !self.updates.is_empty() {
             let updates = std::mem::take(&mut self.updates);
+            metrics::record(|m| m.update_map_size(updates.len()));
+            let span = op_span!("apply_updates");
+            span.record_elements(updates.len());
             self.backing.update(updates, None)
         } else {
             Ok(())
         }
     }
 
+    /// Return a copy of the backing with the pending updates applied, leaving `self` unchanged.
+    ///
+    /// The copy shares all of the unmodified subtrees with `self`.
+    pub(crate) fn applied_backing(&self) -> Result<B, Error>
+    where
+        B: Clone,
+    {
+        let mut backing = self.backing.clone();
+        if !self.updates.is_empty() {
+            backing.update(self.updates.clone(), None)?;
+        }
+        Ok(backing)
+    }
+
+    /// Return the indices of all pending updates, in ascending order.
+    pub(crate) fn pending_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::with_capacity(self.updates.len());
+        let _ = self
+            .updates
+            .for_each_range::<_, Infallible>(0, self.len(), |index, _| {
+                indices.push(index);
+                ControlFlow::Continue(Ok(()))
+            });
+        indices
+    }
+
     pub fn has_pending_updates(&self) -> bool {
         !self.updates.is_empty()
     }
@@ -110,6 +291,7 @@ where
     }
 
     pub fn iter_cow(&mut self) -> InterfaceIterCow<T, U> {
+        self.auto_apply();
         let index = 0;
         InterfaceIterCow {
             tree_iter: self.backing.iter_from(index),
@@ -134,6 +316,52 @@ where
         self.len() == 0
     }
 
+    /// Start a transaction whose modifications can be rolled back without affecting `self`.
+    pub fn begin_transaction(&mut self) -> Transaction<'_, T, B, U> {
+        self.auto_apply();
+        Transaction::new(self)
+    }
+
+    /// Record the current state, including pending updates, so that it can be restored later by
+    /// `revert_to`.
+    ///
+    /// Checkpoints can be nested: reverting to a checkpoint discards only the modifications made
+    /// since it was taken. The tree is shared with the checkpoint rather than copied, but the
+    /// pending updates are cloned, so this is O(n) in the number of pending updates.
+    pub fn checkpoint(&self) -> Checkpoint<B, U>
+    where
+        B: Clone,
+    {
+        Checkpoint {
+            backing: self.backing.clone(),
+            updates: self.updates.clone(),
+        }
+    }
+
+    /// Restore the state recorded by `checkpoint`, discarding every modification made since.
+    ///
+    /// This includes updates applied since the checkpoint was taken.
+    pub fn revert_to(&mut self, checkpoint: Checkpoint<B, U>) {
+        self.backing = checkpoint.backing;
+        self.updates = checkpoint.updates;
+    }
+
+    /// Add updates staged by other threads to the pending updates.
+    ///
+    /// Staged updates take precedence over existing pending updates to the same index. Errors
+    /// without adding any updates if an index is out of bounds.
+    pub fn merge_updates(&mut self, updates: ShardedUpdates<T>) -> Result<(), Error> {
+        let updates = updates.into_sorted_vec();
+        let len = self.len();
+        if let Some((index, _)) = updates.iter().find(|(index, _)| *index >= len) {
+            return Err(Error::OutOfBoundsUpdate { index: *index, len });
+        }
+        for (index, value) in updates {
+            self.updates.insert(index, value);
+        }
+        Ok(())
+    }
+
     pub fn bulk_update(&mut self, updates: U) -> Result<(), Error> {
         if !self.updates.is_empty() {
             return Err(Error::BulkUpdateUnclean);
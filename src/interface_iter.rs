@@ -13,6 +13,9 @@ impl<'a, T: Value, U: UpdateMap<T>> Iterator for InterfaceIter<'a, T, U> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
+        if self.index >= self.length {
+            return None;
+        }
         let index = self.index;
         self.index += 1;
 
@@ -27,6 +30,35 @@ impl<'a, T: Value, U: UpdateMap<T>> Iterator for InterfaceIter<'a, T, U> {
         let remaining = self.length.saturating_sub(self.index);
         (remaining, Some(remaining))
     }
+
+    /// Skip `n` elements, re-descending only from the nearest ancestor of the next element.
+    ///
+    /// This makes `step_by` stride through the tree without visiting the skipped elements.
+    fn nth(&mut self, n: usize) -> Option<&'a T> {
+        self.index = self.index.saturating_add(n).min(self.length);
+        self.tree_iter.seek(self.index);
+        self.next()
+    }
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> DoubleEndedIterator for InterfaceIter<'a, T, U> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.index >= self.length {
+            return None;
+        }
+        self.length -= 1;
+        let index = self.length;
+
+        // Pending pushes extend beyond the end of the tree, so only step the tree iterator back
+        // once it is in step with this iterator.
+        let backing_value = if index < self.tree_iter.end() {
+            self.tree_iter.next_back()
+        } else {
+            None
+        };
+
+        self.updates.get(index).or(backing_value)
+    }
 }
 
 impl<'a, T: Value, U: UpdateMap<T>> ExactSizeIterator for InterfaceIter<'a, T, U> {}
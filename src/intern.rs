@@ -0,0 +1,174 @@
+use crate::utils::ref_count;
+use crate::{Arc, MaybeSync, Tree, Value};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use tree_hash::Hash256;
+
+/// Pool of canonical tree nodes, used to share identical subtrees between different trees.
+///
+/// Nodes are looked up by a `NodeKey`. Different subtrees can have the same key, because zero
+/// padding doesn't change a hash and so e.g. `[0, 0, 0]` and `[0, 0, 0, 0]` hash the same when
+/// packed. `Tree::intern` therefore compares a candidate with the subtree it would replace before
+/// substituting it.
+pub trait NodeInterner<T: Value> {
+    /// Return the canonical node with the given `key`, if one is known.
+    fn get(&self, key: &NodeKey) -> Option<Arc<Tree<T>>>;
+
+    /// Record `node` as the canonical node with the given `key`.
+    fn insert(&self, key: NodeKey, node: Arc<Tree<T>>);
+}
+
+/// Key identifying a node by its depth, kind and tree hash.
+///
+/// The kind separates leaves from internal nodes and zero subtrees, and packed leaves of
+/// different lengths from each other, none of which can be told apart by hash alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeKey {
+    pub depth: usize,
+    pub kind: NodeKind,
+    pub hash: Hash256,
+}
+
+/// Kind of a tree node, with the number of values for packed leaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Leaf,
+    PackedLeaf(usize),
+    Node,
+    Zero,
+}
+
+impl NodeKey {
+    pub fn new<T: Value + MaybeSync>(tree: &Tree<T>, depth: usize) -> Self {
+        let kind = match tree {
+            Tree::Leaf(_) => NodeKind::Leaf,
+            Tree::PackedLeaf(leaf) => NodeKind::PackedLeaf(leaf.values.len()),
+            Tree::Node { .. } => NodeKind::Node,
+            Tree::Zero(_) => NodeKind::Zero,
+        };
+        Self {
+            depth,
+            kind,
+            hash: tree.tree_hash(),
+        }
+    }
+}
+
+/// Simple `NodeInterner` backed by a hash map.
+///
+/// The pool keeps every node inserted into it alive until it is pruned or cleared.
+#[derive(Debug)]
+pub struct InternPool<T: Value> {
+    nodes: RwLock<HashMap<NodeKey, Arc<Tree<T>>>>,
+}
+
+impl<T: Value> Default for InternPool<T> {
+    fn default() -> Self {
+        Self {
+            nodes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Value> InternPool<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nodes in the pool.
+    pub fn len(&self) -> usize {
+        self.nodes.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all nodes from the pool.
+    pub fn clear(&self) {
+        self.nodes.write().clear();
+    }
+
+    /// Remove nodes which are no longer referenced by any tree outside the pool.
+    ///
+    /// Removing a node may leave its children unreferenced, so this repeats until no more nodes
+    /// can be removed.
+    pub fn prune(&self) {
+        let mut nodes = self.nodes.write();
+        loop {
+            let len = nodes.len();
+            nodes.retain(|_, node| ref_count(node) > 1);
+            if nodes.len() == len {
+                break;
+            }
+        }
+    }
+}
+
+impl<T: Value> NodeInterner<T> for InternPool<T> {
+    fn get(&self, key: &NodeKey) -> Option<Arc<Tree<T>>> {
+        self.nodes.read().get(key).cloned()
+    }
+
+    fn insert(&self, key: NodeKey, node: Arc<Tree<T>>) {
+        self.nodes.write().entry(key).or_insert(node);
+    }
+}
+
+impl<T: Value + MaybeSync> Tree<T> {
+    /// Replace every subtree of `tree` with its canonical version from `interner`.
+    ///
+    /// Subtrees not yet known to the interner are added to it, so that later trees containing
+    /// the same subtrees will share them. A canonical node is only substituted if it has the same
+    /// contents as the subtree it replaces. If it doesn't, the subtree's children are interned
+    /// instead, and the pool keeps the node it already had for that key.
+    pub fn intern<I: NodeInterner<T>>(tree: &Arc<Self>, depth: usize, interner: &I) -> Arc<Self> {
+        let key = NodeKey::new(tree, depth);
+        if let Some(existing) = interner.get(&key) {
+            if Self::same_contents(tree, &existing) {
+                return existing;
+            }
+        }
+        let hash = key.hash;
+
+        let node = match &**tree {
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_left = Self::intern(left, depth - 1, interner);
+                let new_right = Self::intern(right, depth - 1, interner);
+                if Arc::ptr_eq(left, &new_left) && Arc::ptr_eq(right, &new_right) {
+                    tree.clone()
+                } else {
+                    Self::node(new_left, new_right, hash)
+                }
+            }
+            _ => tree.clone(),
+        };
+        interner.insert(key, node.clone());
+        node
+    }
+
+    /// Compare two subtrees by their values and shape, without relying on their hashes.
+    fn same_contents(a: &Arc<Self>, b: &Arc<Self>) -> bool {
+        if Arc::ptr_eq(a, b) {
+            return true;
+        }
+        match (&**a, &**b) {
+            (Self::Leaf(l1), Self::Leaf(l2)) => l1 == l2,
+            (Self::PackedLeaf(l1), Self::PackedLeaf(l2)) => l1 == l2,
+            (
+                Self::Node {
+                    left: l1,
+                    right: r1,
+                    ..
+                },
+                Self::Node {
+                    left: l2,
+                    right: r2,
+                    ..
+                },
+            ) => Self::same_contents(l1, l2) && Self::same_contents(r1, r2),
+            (Self::Zero(d1), Self::Zero(d2)) => d1 == d2,
+            _ => false,
+        }
+    }
+}
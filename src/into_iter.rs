@@ -0,0 +1,105 @@
+use crate::{Arc, Leaf, PackedLeaf, Tree, UpdateMap, Value};
+use std::vec;
+
+/// Owning iterator over the elements of a `List` or `Vector`.
+///
+/// Values are moved out of any parts of the tree which are uniquely owned by the iterator, and
+/// only cloned from subtrees which are shared with other trees.
+#[derive(Debug)]
+pub struct IntoIter<T: Value, U: UpdateMap<T>> {
+    /// Stack of subtrees still to be visited, with the next subtree on top.
+    stack: Vec<Arc<Tree<T>>>,
+    /// Remaining values from the current packed leaf.
+    packed: vec::IntoIter<T>,
+    /// Pending updates, which take priority over values from the tree.
+    updates: U,
+    /// Index of the next element to be yielded.
+    index: usize,
+    /// Total number of elements to be yielded.
+    length: usize,
+}
+
+impl<T: Value, U: UpdateMap<T>> IntoIter<T, U> {
+    pub(crate) fn new(tree: Arc<Tree<T>>, updates: U, length: usize) -> Self {
+        Self {
+            stack: vec![tree],
+            packed: Vec::new().into_iter(),
+            updates,
+            index: 0,
+            length,
+        }
+    }
+
+    /// Take the next value from the tree, or return `None` if the tree has been exhausted.
+    fn next_tree_value(&mut self) -> Option<T> {
+        loop {
+            if let Some(value) = self.packed.next() {
+                return Some(value);
+            }
+
+            let node = self.stack.pop()?;
+            match Arc::try_unwrap(node) {
+                // Fields are taken by reference, as `Tree` implements `Drop` when nodes are
+                // counted. Children are cloned out of the node and are uniquely owned again once
+                // the node is dropped.
+                Ok(mut tree) => match &mut tree {
+                    Tree::Node { left, right, .. } => {
+                        let (left, right) = (left.clone(), right.clone());
+                        drop(tree);
+                        self.stack.push(right);
+                        self.stack.push(left);
+                    }
+                    Tree::Leaf(Leaf { value, .. }) => {
+                        let value = value.clone();
+                        drop(tree);
+                        return Some(Arc::unwrap_or_clone(value));
+                    }
+                    Tree::PackedLeaf(PackedLeaf { values, .. }) => {
+                        self.packed = std::mem::take(values).into_iter()
+                    }
+                    Tree::Zero(_) => {
+                        self.stack.clear();
+                        return None;
+                    }
+                },
+                Err(shared) => match &*shared {
+                    Tree::Node { left, right, .. } => {
+                        self.stack.push(right.clone());
+                        self.stack.push(left.clone());
+                    }
+                    Tree::Leaf(Leaf { value, .. }) => return Some((**value).clone()),
+                    Tree::PackedLeaf(PackedLeaf { values, .. }) => {
+                        self.packed = values.clone().into_iter()
+                    }
+                    Tree::Zero(_) => {
+                        self.stack.clear();
+                        return None;
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl<T: Value, U: UpdateMap<T>> Iterator for IntoIter<T, U> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index >= self.length {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+
+        // Advance through the tree in step with the index, even if the value is overridden.
+        let tree_value = self.next_tree_value();
+        self.updates.get(index).cloned().or(tree_value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Value, U: UpdateMap<T>> ExactSizeIterator for IntoIter<T, U> {}
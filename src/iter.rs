@@ -17,8 +17,10 @@ pub struct Iter<'a, T: Value> {
     packing_factor: usize,
     /// Cached packing depth to avoid re-calculating `opt_packing_depth`.
     packing_depth: usize,
-    /// Number of items that will be yielded by the iterator.
+    /// Index one past the last element to be yielded, which is decremented by `next_back`.
     length: Length,
+    /// Stack of tree nodes corresponding to the position of `next_back`, mirroring `stack`.
+    back_stack: Vec<&'a Tree<T>>,
 }
 
 impl<'a, T: Value> Iter<'a, T> {
@@ -27,6 +29,7 @@ impl<'a, T: Value> Iter<'a, T> {
         stack.push(root);
 
         Iter {
+            back_stack: stack.clone(),
             stack,
             index,
             full_depth: depth,
@@ -35,6 +38,34 @@ impl<'a, T: Value> Iter<'a, T> {
             length,
         }
     }
+
+    /// Move forward to `index`, so that it is the next element yielded.
+    ///
+    /// Only the nodes of the stack which don't contain `index` are popped, so skipping a few
+    /// elements ahead stays within the current leaf or its nearest ancestors.
+    pub(crate) fn seek(&mut self, index: usize) {
+        if index <= self.index {
+            return;
+        }
+        // The node at position `i` of the stack has height `full_depth - i`, and contains the
+        // indices which agree with its prefix on the bits above its height.
+        let keep = self
+            .stack
+            .iter()
+            .enumerate()
+            .take_while(|(i, _)| {
+                let shift = (self.full_depth - i + self.packing_depth) as u32;
+                self.index.checked_shr(shift).unwrap_or(0) == index.checked_shr(shift).unwrap_or(0)
+            })
+            .count();
+        self.stack.truncate(keep);
+        self.index = index;
+    }
+
+    /// Index one past the last element which remains to be yielded.
+    pub(crate) fn end(&self) -> usize {
+        self.length.as_usize()
+    }
 }
 
 impl<'a, T: Value> Iterator for Iter<'a, T> {
@@ -102,6 +133,77 @@ impl<'a, T: Value> Iterator for Iter<'a, T> {
         let remaining = self.length.as_usize().saturating_sub(self.index);
         (remaining, Some(remaining))
     }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.index.saturating_add(n).min(self.length.as_usize());
+        self.seek(index);
+        self.next()
+    }
+}
+
+impl<'a, T: Value> DoubleEndedIterator for Iter<'a, T> {
+    /// Yield the last remaining element, descending along the right edge of the remaining
+    /// elements.
+    ///
+    /// This mirrors `next`: after yielding the element at `index`, the nodes above the subtree
+    /// containing `index - 1` are popped, which are those below the lowest set bit of `index`.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.length.as_usize() {
+            return None;
+        }
+        let index = self.length.as_usize() - 1;
+
+        match self.back_stack.last() {
+            None | Some(Tree::Zero(_)) => None,
+            Some(Tree::Leaf(Leaf { value, .. })) => {
+                let result = Some(value.as_ref());
+
+                *self.length.as_mut() = index;
+
+                // Backtrack to the parent node of the previous subtree
+                for _ in 0..=index.trailing_zeros() {
+                    self.back_stack.pop();
+                }
+
+                result
+            }
+            Some(Tree::PackedLeaf(PackedLeaf { values, .. })) => {
+                let sub_index = index % self.packing_factor;
+
+                let result = values.get(sub_index);
+
+                *self.length.as_mut() = index;
+
+                // Reached start of chunk
+                if sub_index == 0 {
+                    let to_pop = index
+                        .trailing_zeros()
+                        .checked_sub(self.packing_depth as u32)
+                        .expect("index should have at least `packing_depth` trailing zeroes");
+
+                    for _ in 0..=to_pop {
+                        self.back_stack.pop();
+                    }
+                }
+
+                result
+            }
+            Some(Tree::Node { left, right, .. }) => {
+                let depth = self.full_depth - self.back_stack.len();
+
+                // Go left
+                if (index >> (depth + self.packing_depth)) & 1 == 0 {
+                    self.back_stack.push(left);
+                    self.next_back()
+                }
+                // Go right
+                else {
+                    self.back_stack.push(right);
+                    self.next_back()
+                }
+            }
+        }
+    }
 }
 
 impl<'a, T: Value> ExactSizeIterator for Iter<'a, T> {}
@@ -0,0 +1,145 @@
+use crate::{Error, List, Value};
+use ssz::{read_offset, Decode, DecodeError, BYTES_PER_LENGTH_OFFSET};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+use typenum::Unsigned;
+
+/// SSZ list which decodes its elements on first access.
+///
+/// Decoding only validates the length and offsets of the list, keeping a reference to the
+/// original bytes. Each element is deserialized the first time it is read, and cached thereafter.
+/// This makes reading a few elements of a large list much cheaper than decoding a `List`.
+#[derive(Debug)]
+pub struct LazyList<T: Value, N: Unsigned> {
+    bytes: Arc<[u8]>,
+    /// Byte offsets of the start of each element, followed by the end of the last element.
+    ///
+    /// Empty for fixed-length types, whose offsets are computed on the fly.
+    offsets: Vec<usize>,
+    length: usize,
+    values: Vec<OnceLock<Box<T>>>,
+    _phantom: PhantomData<N>,
+}
+
+impl<T: Value, N: Unsigned> LazyList<T, N> {
+    /// Check the structure of SSZ `bytes` without decoding any of their elements.
+    pub fn from_ssz_bytes(bytes: impl Into<Arc<[u8]>>) -> Result<Self, DecodeError> {
+        let bytes = bytes.into();
+        let max_len = N::to_usize();
+
+        let (length, offsets) = if bytes.is_empty() {
+            (0, vec![])
+        } else if <T as Decode>::is_ssz_fixed_len() {
+            let item_len = <T as Decode>::ssz_fixed_len();
+            if item_len == 0 {
+                return Err(DecodeError::ZeroLengthItem);
+            }
+            if bytes.len() % item_len != 0 {
+                return Err(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: bytes.len() / item_len * item_len,
+                });
+            }
+            (bytes.len() / item_len, vec![])
+        } else {
+            let first_offset = read_offset(&bytes)?;
+            if first_offset == 0 || first_offset % BYTES_PER_LENGTH_OFFSET != 0 {
+                return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
+            }
+            let length = first_offset / BYTES_PER_LENGTH_OFFSET;
+            if first_offset > bytes.len() {
+                return Err(DecodeError::OffsetOutOfBounds(first_offset));
+            }
+
+            let mut offsets = Vec::with_capacity(length + 1);
+            for i in 0..length {
+                let offset = read_offset(&bytes[i * BYTES_PER_LENGTH_OFFSET..])?;
+                if offset > bytes.len() {
+                    return Err(DecodeError::OffsetOutOfBounds(offset));
+                }
+                if offsets.last().is_some_and(|prev| offset < *prev) {
+                    return Err(DecodeError::OffsetsAreDecreasing(offset));
+                }
+                offsets.push(offset);
+            }
+            offsets.push(bytes.len());
+            (length, offsets)
+        };
+
+        if length > max_len {
+            return Err(DecodeError::BytesInvalid(format!(
+                "List of {} items exceeds maximum of {}",
+                length, max_len
+            )));
+        }
+
+        Ok(Self {
+            bytes,
+            offsets,
+            length,
+            values: (0..length).map(|_| OnceLock::new()).collect(),
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn byte_range(&self, index: usize) -> Range<usize> {
+        if self.offsets.is_empty() {
+            let item_len = <T as Decode>::ssz_fixed_len();
+            index * item_len..(index + 1) * item_len
+        } else {
+            self.offsets[index]..self.offsets[index + 1]
+        }
+    }
+
+    /// Return the `index`th element, decoding it if it has not been accessed before.
+    pub fn get(&self, index: usize) -> Result<Option<&T>, DecodeError> {
+        let Some(cell) = self.values.get(index) else {
+            return Ok(None);
+        };
+        if let Some(value) = cell.get() {
+            return Ok(Some(value));
+        }
+
+        let value = T::from_ssz_bytes(&self.bytes[self.byte_range(index)])?;
+        Ok(Some(cell.get_or_init(|| Box::new(value))))
+    }
+
+    /// Return `true` if the `index`th element has already been decoded.
+    pub fn is_decoded(&self, index: usize) -> bool {
+        self.values
+            .get(index)
+            .is_some_and(|cell| cell.get().is_some())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<&T, DecodeError>> {
+        (0..self.len()).filter_map(|index| self.get(index).transpose())
+    }
+
+    /// Decode all remaining elements and build a `List`, reusing any already-decoded values.
+    pub fn into_list(mut self) -> Result<List<T, N>, DecodeError> {
+        let mut builder = List::<T, N>::builder();
+        let values = std::mem::take(&mut self.values);
+        for (index, cell) in values.into_iter().enumerate() {
+            let value = match cell.into_inner() {
+                Some(value) => *value,
+                None => T::from_ssz_bytes(&self.bytes[self.byte_range(index)])?,
+            };
+            builder.push(value).map_err(builder_error)?;
+        }
+        let (tree, depth, length) = builder.finish().map_err(builder_error)?;
+        Ok(List::from_parts(tree, depth, length))
+    }
+}
+
+fn builder_error(e: Error) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e))
+}
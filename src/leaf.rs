@@ -7,6 +7,13 @@ use derivative::Derivative;
 use parking_lot::RwLock;
 use tree_hash::Hash256;
 
+/// Leaf holding a single value which is too large to be packed.
+///
+/// The value lives in its own allocation rather than inline, even though that costs a second
+/// allocation per leaf. Every node of a tree has the size of the largest `Tree` variant, so
+/// storing a large `T` inline would grow every internal node to fit it, and internal nodes are
+/// as numerous as leaves. Keeping `Leaf` no larger than `Node` keeps nodes at 64 bytes for all
+/// `T`, see the `size_of` tests.
 #[derive(Debug, Derivative, Arbitrary)]
 #[derivative(PartialEq, Hash)]
 pub struct Leaf<T> {
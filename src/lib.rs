@@ -1,34 +1,131 @@
+#![forbid(unsafe_code)]
 #![allow(clippy::comparison_chain)]
+#![allow(
+    mismatched_lifetime_syntaxes,
+    clippy::manual_div_ceil,
+    clippy::manual_is_multiple_of,
+    clippy::manual_repeat_n,
+    clippy::unnecessary_map_or
+)]
 #![deny(clippy::unwrap_used)]
+#![cfg_attr(test, allow(clippy::unwrap_used))]
 
+pub mod applied;
+pub mod bitfield;
 pub mod builder;
+pub mod bytes;
+pub mod cached_reader;
+pub mod cached_tree_hash;
+pub mod changelog;
+pub mod chunk_iter;
 pub mod cow;
+pub mod diff;
+#[cfg(feature = "debug")]
+pub mod dot;
+pub mod dyn_list;
 pub mod error;
+pub mod frozen;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+pub mod gindex;
+pub mod guard;
+pub mod hasher;
 pub mod interface;
 pub mod interface_iter;
+#[cfg(feature = "intern")]
+pub mod intern;
+pub mod into_iter;
 pub mod iter;
+pub mod lazy;
 pub mod leaf;
 pub mod level_iter;
 pub mod list;
+pub mod mem;
+pub mod merkle_map;
+pub mod metrics;
+#[cfg(feature = "node_count")]
+pub mod node_count;
+pub mod overlay;
 pub mod packed_leaf;
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+pub mod par_iter;
+pub mod partial;
+pub mod progressive;
+pub mod proof;
 mod repeat;
+#[cfg(feature = "serde")]
 pub mod serde;
+pub mod sharded_updates;
+pub mod small_list;
+pub mod snapshot;
+pub mod stable_container;
+pub mod store;
+mod stream;
+pub mod summary;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod tests;
+mod trace;
+pub mod transaction;
 pub mod tree;
 pub mod update_map;
 pub mod utils;
+pub mod validate;
 pub mod vector;
+pub mod versioned;
+pub mod view;
+pub mod windows;
+pub mod zip;
 
+pub use applied::{Applied, PendingUpdates};
+pub use bitfield::{Bitlist, Bitvector};
+pub use bytes::{ByteList, ByteVector};
+pub use cached_reader::CachedReader;
+pub use cached_tree_hash::{CachedRoot, CachedTreeHash};
+pub use changelog::Changelog;
 pub use cow::Cow;
+pub use diff::ListDiff;
+pub use dyn_list::DynList;
 pub use error::Error;
-pub use interface::ImmList;
+pub use frozen::FrozenList;
+pub use guard::MutGuard;
+pub use hasher::{MerkleHasher, Sha256Hasher};
+pub use interface::{ApplyPolicy, Checkpoint, ImmList};
+#[cfg(feature = "intern")]
+pub use intern::{InternPool, NodeInterner, NodeKey, NodeKind};
+pub use lazy::LazyList;
 pub use leaf::Leaf;
 pub use list::List;
+pub use mem::{MemoryUsage, NodeSharing};
+pub use merkle_map::{MapEntry, MerkleMap};
+pub use metrics::MetricsSink;
+pub use overlay::OverlayList;
 pub use packed_leaf::PackedLeaf;
+pub use partial::{PartialList, PartialVector};
+pub use progressive::ProgressiveList;
+pub use proof::{verify_multiproof, verify_proof, MultiProof, Proof};
+pub use sharded_updates::ShardedUpdates;
+pub use small_list::SmallList;
+pub use snapshot::Snapshot;
+pub use stable_container::{StableContainer, StableField};
+#[cfg(milhouse_rc)]
+pub use std::rc::Rc as Arc;
+pub use store::{MemoryNodeStore, NodeSink, NodeStore, StoredList, StoredNode};
+pub use summary::Summary;
+pub use transaction::Transaction;
 pub use tree::Tree;
+/// Reference-counted pointer used for tree nodes.
+///
+/// This is `triomphe::Arc` in `milhouse`. The `milhouse-rc` crate is built from the same source
+/// with `std::rc::Rc` instead, avoiding atomic reference counting for single-threaded users at
+/// the cost of making trees `!Send`. It has no `rayon` feature.
+#[cfg(not(milhouse_rc))]
 pub use triomphe::Arc;
-pub use update_map::UpdateMap;
+pub use update_map::{AutoApply, BitmapUpdateMap, UpdateMap, VecUpdateMap};
 pub use vector::Vector;
+pub use versioned::{Pruned, Versioned};
+pub use view::SubtreeView;
+pub use windows::Windows;
 
 use ssz::{Decode, Encode};
 use tree_hash::TreeHash;
@@ -44,3 +141,19 @@ pub trait Value: Encode + Decode + TreeHash + PartialEq + Clone {}
 
 #[cfg(not(feature = "debug"))]
 impl<T> Value for T where T: Encode + Decode + TreeHash + PartialEq + Clone {}
+
+/// Bound on values that can be hashed in parallel.
+///
+/// This is `Send + Sync`, except in `milhouse-rc`, where tree nodes are never shared between
+/// threads and any type satisfies it.
+#[cfg(not(milhouse_rc))]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(not(milhouse_rc))]
+impl<T> MaybeSync for T where T: Send + Sync {}
+
+#[cfg(milhouse_rc)]
+pub trait MaybeSync {}
+
+#[cfg(milhouse_rc)]
+impl<T> MaybeSync for T {}
@@ -1,26 +1,55 @@
 use crate::builder::Builder;
-use crate::interface::{ImmList, Interface, MutList};
+use crate::changelog::Changelog;
+use crate::chunk_iter::{ChunkIter, SszChunks};
+use crate::gindex::{LIST_DATA_GINDEX, LIST_LENGTH_GINDEX};
+use crate::guard::MutGuard;
+use crate::interface::{ApplyPolicy, Checkpoint, ImmList, Interface, MutList};
 use crate::interface_iter::{InterfaceIter, InterfaceIterCow};
+#[cfg(feature = "intern")]
+use crate::intern::NodeInterner;
+use crate::into_iter::IntoIter;
 use crate::iter::Iter;
 use crate::level_iter::{LevelIter, LevelNode};
-use crate::serde::ListVisitor;
+use crate::proof::{length_chunk, prove_tree_indices, MultiProof, Proof};
+#[cfg(feature = "serde")]
+use crate::serde::{ListVisitor, SszBytesVisitor};
+use crate::sharded_updates::ShardedUpdates;
+use crate::store::{load_list_root, store_list_root, NodeSink, NodeStore};
+use crate::stream::{check_offset, decode_list_from_reader, encode_list_to_writer, too_long};
+use crate::trace::op_span;
+use crate::transaction::Transaction;
 use crate::tree::RebaseAction;
 use crate::update_map::MaxMap;
-use crate::utils::{arb_arc, compute_level, int_log, opt_packing_depth, updated_length, Length};
-use crate::{Arc, Cow, Error, Tree, UpdateMap, Value};
+use crate::utils::{
+    arb_arc, compute_level, int_log, opt_packing_depth, ssz_append_variable, updated_length, Length,
+};
+use crate::view::SubtreeView;
+use crate::windows::Windows;
+use crate::zip::{ChangedIter, ZipIter};
+use crate::{
+    Arc, Cow, Error, MaybeSync, MemoryUsage, MerkleHasher, NodeSharing, Tree, UpdateMap, Value,
+};
 use arbitrary::Arbitrary;
 use derivative::Derivative;
 use itertools::process_results;
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
-use ssz::{Decode, Encode, SszEncoder, TryFromIter, BYTES_PER_LENGTH_OFFSET};
+use ssz::{Decode, Encode, TryFromIter, BYTES_PER_LENGTH_OFFSET};
+#[cfg(feature = "ssz_types")]
+use ssz_types::VariableList;
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::iter::StepBy;
 use std::marker::PhantomData;
+use std::ops::{ControlFlow, Index, Range};
 use tree_hash::{Hash256, PackedEncoding, TreeHash};
 use typenum::Unsigned;
 use vec_map::VecMap;
 
-#[derive(Debug, Clone, Derivative, Arbitrary)]
-#[derivative(PartialEq(bound = "T: Value, N: Unsigned, U: UpdateMap<T> + PartialEq"))]
+#[derive(Debug, Clone, Arbitrary)]
 #[arbitrary(bound = "T: Arbitrary<'arbitrary> + Value")]
 #[arbitrary(bound = "N: Unsigned, U: Arbitrary<'arbitrary> + UpdateMap<T> + PartialEq")]
 pub struct List<T: Value, N: Unsigned, U: UpdateMap<T> = MaxMap<VecMap<T>>> {
@@ -65,6 +94,12 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         Self::from_parts(tree, depth, Length(0))
     }
 
+    /// Create a list of `n` copies of `elem`.
+    ///
+    /// Identical subtrees are shared, so the list takes O(log n) memory however large `n` is.
+    /// Writes only copy the path from the root to the modified leaf, leaving the rest shared, so
+    /// this can be used to represent a large list of mostly default values sparsely. Use an
+    /// update map which doesn't allocate per index, like `BTreeMap`, for sparse writes.
     pub fn repeat(elem: T, n: usize) -> Result<Self, Error> {
         crate::repeat::repeat_list(elem, n)
     }
@@ -78,9 +113,21 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
     }
 
     pub fn try_from_iter(iter: impl IntoIterator<Item = T>) -> Result<Self, Error> {
-        let mut builder = Self::builder();
-
-        for item in iter.into_iter() {
+        let iter = iter.into_iter();
+        let mut builder = match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => {
+                Builder::with_capacity(Self::depth(), 0, lower)
+            }
+            _ => Self::builder(),
+        };
+
+        for (index, item) in iter.enumerate() {
+            if index == N::to_usize() {
+                return Err(Error::ListFull {
+                    len: index,
+                    max_len: N::to_usize(),
+                });
+            }
             builder.push(item)?;
         }
 
@@ -89,6 +136,44 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         Ok(Self::from_parts(tree, depth, length))
     }
 
+    /// Create a list from an iterator which reports its exact length.
+    ///
+    /// Errors before consuming the iterator if the length exceeds `N`, and errors if the number of
+    /// values produced differs from the reported length.
+    pub fn try_from_exact_iter<I>(iter: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let expected = iter.len();
+        if expected > N::to_usize() {
+            return Err(Error::ListFull {
+                len: 0,
+                max_len: N::to_usize(),
+            });
+        }
+
+        let mut builder = Builder::with_capacity(Self::depth(), 0, expected);
+        let mut len = 0;
+        for item in iter {
+            if len == expected {
+                return Err(Error::WrongIteratorLength {
+                    len: len + 1,
+                    expected,
+                });
+            }
+            builder.push(item)?;
+            len += 1;
+        }
+        if len != expected {
+            return Err(Error::WrongIteratorLength { len, expected });
+        }
+
+        let (tree, depth, length) = builder.finish()?;
+        Ok(Self::from_parts(tree, depth, length))
+    }
+
     /// This method exists for testing purposes.
     #[doc(hidden)]
     pub fn try_from_iter_slow(iter: impl IntoIterator<Item = T>) -> Result<Self, Error> {
@@ -107,10 +192,36 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         self.iter().cloned().collect()
     }
 
+    /// Create a new list by applying `f` to each element, including any pending updates.
+    ///
+    /// The new tree is built bottom-up in a single pass, as for `List::new`.
+    pub fn map<V: Value>(&self, mut f: impl FnMut(&T) -> V) -> Result<List<V, N>, Error> {
+        self.try_map(|value| Ok(f(value)))
+    }
+
+    /// Create a new list by applying the fallible `f` to each element.
+    ///
+    /// Stops at the first error from `f`.
+    pub fn try_map<V: Value, E: From<Error>>(
+        &self,
+        mut f: impl FnMut(&T) -> Result<V, E>,
+    ) -> Result<List<V, N>, E> {
+        let mut builder = Builder::with_capacity(List::<V, N>::depth(), 0, self.len());
+        for value in self.iter() {
+            builder.push(f(value)?)?;
+        }
+        let (tree, depth, length) = builder.finish()?;
+        Ok(List::from_parts(tree, depth, length))
+    }
+
     pub fn iter(&self) -> InterfaceIter<T, U> {
         self.interface.iter()
     }
 
+    /// Iterate over the elements starting from `index`, including any pending updates.
+    ///
+    /// The tree is descended once to find the starting leaf, after which each element costs
+    /// amortised O(1), so scanning `k` elements is O(log n + k).
     pub fn iter_from(&self, index: usize) -> Result<InterfaceIter<T, U>, Error> {
         // Return an empty iterator at index == length, just like slicing.
         if index > self.len() {
@@ -122,6 +233,94 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         Ok(self.interface.iter_from(index))
     }
 
+    /// Iterate over every `step`th element starting from `start`, including pending updates.
+    ///
+    /// Each step only re-descends from the nearest common ancestor of consecutive elements, so
+    /// striding by `step` costs O(log step) per element rather than a `get` from the root.
+    ///
+    /// Panics if `step` is 0, as for `Iterator::step_by`.
+    pub fn iter_step_by_from(
+        &self,
+        start: usize,
+        step: usize,
+    ) -> Result<StepBy<InterfaceIter<'_, T, U>>, Error> {
+        Ok(self.iter_from(start)?.step_by(step))
+    }
+
+    /// Iterate over all windows of `k` consecutive elements, including pending updates.
+    ///
+    /// Panics if `k` is 0, as for `slice::windows`.
+    pub fn iter_windows(&self, k: usize) -> Windows<'_, T, U> {
+        Windows::new(self.iter(), k)
+    }
+
+    /// Iterate over the elements in contiguous slices, one per leaf of the tree.
+    ///
+    /// For packed types each slice contains all of the elements in a 32-byte chunk, which allows
+    /// reductions to process values without traversing the tree for each element.
+    pub fn iter_chunks(&self) -> Result<ChunkIter<'_, T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ChunkIterPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Ok(ChunkIter::new(&backing.tree, backing.depth, self.len()))
+    }
+
+    /// Iterate over the SSZ encoding of the list in chunks of at least `min_chunk_len` bytes
+    /// (except for the last), each made up of whole leaves.
+    ///
+    /// Errors if there are pending updates. See `SszChunks`.
+    pub fn ssz_chunks(&self, min_chunk_len: usize) -> Result<SszChunks<'_, T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ChunkIterPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Ok(SszChunks::new(
+            &backing.tree,
+            backing.depth,
+            self.len(),
+            min_chunk_len,
+        ))
+    }
+
+    /// Clear the cached hashes of the elements in `range`, so that they are recomputed by the
+    /// next call to `tree_hash_root`.
+    ///
+    /// This is only needed after modifying elements through interior mutability, which the
+    /// list can't detect. Only the hashes on the paths from this list's root are cleared: other
+    /// trees sharing the modified elements keep stale hashes in their own nodes above them, so
+    /// each of them must be invalidated as well. Errors if `range` is out of bounds.
+    pub fn invalidate_range(&self, range: Range<usize>) -> Result<(), Error> {
+        let len = self.len();
+        if range.start > range.end || range.end > len {
+            return Err(Error::OutOfBoundsSubtree {
+                start: range.start,
+                end: range.end,
+                len,
+            });
+        }
+        let backing = &self.interface.backing;
+        backing.tree.invalidate_range(
+            range.start,
+            range.end,
+            0,
+            backing.depth,
+            backing.packing_depth,
+        );
+        Ok(())
+    }
+
+    /// Return a view of the elements in `range`, sharing the nodes of this tree.
+    ///
+    /// Errors if there are pending updates, or if `range` is out of bounds.
+    pub fn subtree(&self, range: Range<usize>) -> Result<SubtreeView<T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::SubtreePendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        SubtreeView::new(&backing.tree, backing.depth, self.len(), range)
+    }
+
     /// Iterate all internal nodes on the same level as `index`.
     pub fn level_iter_from(&self, index: usize) -> Result<LevelIter<T>, Error> {
         // Return an empty iterator at index == length, just like slicing.
@@ -143,18 +342,89 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         self.interface.get(index)
     }
 
+    /// Return the first element, descending only the left edge of the tree.
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Return the last element, descending only the path to it.
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Return the elements at each of `indices`, in the same order, including pending updates.
+    ///
+    /// Out of bounds indices give `None`. The indices are looked up in sorted order, so that
+    /// nearby indices share the path from the root rather than each descending the whole tree.
+    pub fn get_many(&self, indices: &[usize]) -> Vec<Option<&T>> {
+        let backing = &self.interface.backing;
+        let len = self.len();
+        let mut values = backing
+            .tree
+            .get_many(indices, backing.depth, backing.packing_depth);
+        for (value, &index) in values.iter_mut().zip(indices) {
+            if index >= len {
+                *value = None;
+            } else if let Some(updated) = self.interface.updates.get(index) {
+                *value = Some(updated);
+            }
+        }
+        values
+    }
+
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
         self.interface.get_mut(index)
     }
 
+    /// Return a guard for the `index`th element which records a pending update when dropped.
+    ///
+    /// See `MutGuard`.
+    pub fn get_mut_guard(&mut self, index: usize) -> Option<MutGuard<'_, T, ListInner<T, N>, U>> {
+        self.interface.get_mut_guard(index)
+    }
+
     pub fn get_cow(&mut self, index: usize) -> Option<Cow<T>> {
         self.interface.get_cow(index)
     }
 
+    /// Stage each `(index, value)` pair in `updates` as a pending update.
+    ///
+    /// Errors without changing anything if any index is out of bounds. Unlike calling `get_mut`
+    /// for each index, the replaced values are never read. See `Interface::update_many`.
+    pub fn update_many(
+        &mut self,
+        updates: impl IntoIterator<Item = (usize, T)>,
+    ) -> Result<(), Error> {
+        self.interface.update_many(updates)
+    }
+
+    /// Swap the elements at indices `i` and `j`.
+    ///
+    /// Both writes are recorded as pending updates. Errors if either index is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), Error> {
+        self.interface.swap(i, j)
+    }
+
     pub fn push(&mut self, value: T) -> Result<(), Error> {
         self.interface.push(value)
     }
 
+    /// Push `value` if the list has space for it, otherwise return it.
+    pub fn push_within_capacity(&mut self, value: T) -> Result<(), T> {
+        self.interface.push_within_capacity(value)
+    }
+
+    /// Call `f` with mutable access to each element in turn, stopping early if it breaks.
+    ///
+    /// Only elements whose values change are recorded as pending updates. Changes made before
+    /// breaking are kept.
+    pub fn try_for_each_mut<R, F>(&mut self, f: F) -> ControlFlow<R>
+    where
+        F: FnMut(usize, &mut T) -> ControlFlow<R>,
+    {
+        self.interface.try_for_each_mut(f)
+    }
+
     pub fn len(&self) -> usize {
         self.interface.len()
     }
@@ -167,10 +437,56 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         self.interface.has_pending_updates()
     }
 
+    /// The policy for applying pending updates, which is determined by `U`.
+    pub fn apply_policy(&self) -> ApplyPolicy {
+        self.interface.apply_policy()
+    }
+
     pub fn apply_updates(&mut self) -> Result<(), Error> {
         self.interface.apply_updates()
     }
 
+    /// Apply the pending updates, returning a summary of the indices and nodes rewritten.
+    pub fn apply_updates_with_changelog(&mut self) -> Result<Changelog, Error> {
+        let indices = self.interface.pending_indices();
+        let prev_len = self.interface.backing.length.as_usize();
+        self.apply_updates()?;
+        let backing = &self.interface.backing;
+        Ok(Changelog::new(
+            indices,
+            prev_len,
+            self.len(),
+            backing.depth,
+            backing.packing_depth,
+        ))
+    }
+
+    /// Start a batch of modifications which can be committed or rolled back as a whole.
+    ///
+    /// See `Transaction`.
+    pub fn begin_transaction(&mut self) -> Transaction<'_, T, ListInner<T, N>, U> {
+        self.interface.begin_transaction()
+    }
+
+    /// Record the current state so that later modifications can be undone by `revert_to`.
+    ///
+    /// See `Interface::checkpoint`.
+    pub fn checkpoint(&self) -> Checkpoint<ListInner<T, N>, U> {
+        self.interface.checkpoint()
+    }
+
+    /// Restore the state recorded by `checkpoint`.
+    pub fn revert_to(&mut self, checkpoint: Checkpoint<ListInner<T, N>, U>) {
+        self.interface.revert_to(checkpoint)
+    }
+
+    /// Add updates staged concurrently in `updates` to the pending updates.
+    ///
+    /// See `ShardedUpdates`.
+    pub fn merge_updates(&mut self, updates: ShardedUpdates<T>) -> Result<(), Error> {
+        self.interface.merge_updates(updates)
+    }
+
     pub fn bulk_update(&mut self, updates: U) -> Result<(), Error> {
         self.interface.bulk_update(updates)
     }
@@ -183,6 +499,157 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         }
     }
 
+    /// Return the index of the first element for which `pred` is false.
+    ///
+    /// The list must be partitioned by `pred`, as for `slice::partition_point`. If there are no
+    /// pending updates the tree is searched in a single descent, otherwise each probe goes
+    /// through `get`.
+    pub fn partition_point<P>(&self, mut pred: P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        if !self.has_pending_updates() {
+            let backing = &self.interface.backing;
+            return backing.tree.partition_point(
+                self.len(),
+                backing.depth,
+                backing.packing_depth,
+                pred,
+            );
+        }
+
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get(mid).is_some_and(&mut pred) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// Binary search a sorted list with a comparator function, as for `slice::binary_search_by`.
+    ///
+    /// If there are several matches then the index of the first is returned.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let index = self.partition_point(|value| f(value) == Ordering::Less);
+        match self.get(index) {
+            Some(value) if f(value) == Ordering::Equal => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    /// Binary search a sorted list for `value`, as for `slice::binary_search`.
+    pub fn binary_search(&self, value: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|probe| probe.cmp(value))
+    }
+
+    /// Reorder the elements so that the element at index `i` is the one previously at
+    /// `permutation[i]`.
+    ///
+    /// Pending updates are applied first. The new tree is built directly, reusing every aligned
+    /// subtree over which the permutation is the identity. Errors if `permutation` is not a
+    /// permutation of `0..self.len()`, reporting the first invalid position.
+    pub fn permute(&mut self, permutation: &[usize]) -> Result<(), Error> {
+        self.apply_updates()?;
+        let len = self.len();
+        if permutation.len() != len {
+            return Err(Error::InvalidPermutation {
+                index: std::cmp::min(permutation.len(), len),
+            });
+        }
+
+        // Count the fixed points before each index, so that ranges can be checked in O(1).
+        let mut seen = vec![false; len];
+        let mut fixed_before = Vec::with_capacity(len + 1);
+        fixed_before.push(0);
+        for (index, &source) in permutation.iter().enumerate() {
+            if source >= len || std::mem::replace(&mut seen[source], true) {
+                return Err(Error::InvalidPermutation { index });
+            }
+            fixed_before.push(fixed_before[index] + usize::from(source == index));
+        }
+
+        let backing = &self.interface.backing;
+        let (depth, packing_depth) = (backing.depth, backing.packing_depth);
+        let tree = Tree::permute(
+            &backing.tree,
+            0,
+            len,
+            depth,
+            packing_depth,
+            &|index| {
+                backing
+                    .tree
+                    .get_recursive(permutation[index], depth, packing_depth)
+                    .cloned()
+                    .ok_or(Error::InvalidPermuteNode)
+            },
+            &|start, end| fixed_before[end] - fixed_before[start] == end - start,
+        )?;
+        self.interface.backing.tree = tree;
+        Ok(())
+    }
+
+    /// Return a copy of the list sorted with a comparator function, as for
+    /// `slice::sort_unstable_by`.
+    ///
+    /// The values are sorted in a flat buffer and the new tree is built bottom-up. If the list is
+    /// already sorted then it is cloned instead, so that its tree remains shared.
+    pub fn sorted_by<F>(&self, mut compare: F) -> Result<Self, Error>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if self
+            .iter()
+            .is_sorted_by(|a, b| compare(a, b) != Ordering::Greater)
+        {
+            return Ok(self.clone());
+        }
+        let mut values = self.to_vec();
+        values.sort_unstable_by(compare);
+        Self::try_from_iter(values)
+    }
+
+    /// Return a copy of the list sorted in ascending order, see `sorted_by`.
+    pub fn sorted(&self) -> Result<Self, Error>
+    where
+        T: Ord,
+    {
+        self.sorted_by(T::cmp)
+    }
+
+    /// Insert `value` into a sorted list after any equal elements, returning its index.
+    ///
+    /// The elements after the insertion point are shifted along by one as pending updates, so
+    /// this is cheapest when inserting near the end of the list.
+    pub fn insert_sorted(&mut self, value: T) -> Result<usize, Error>
+    where
+        T: Ord,
+    {
+        let index = self.partition_point(|probe| *probe <= value);
+        self.interface.backing.validate_push(self.len())?;
+
+        let len = self.len();
+        let mut value = value;
+        for i in index..len {
+            let slot = self
+                .get_mut(i)
+                .ok_or(Error::OutOfBoundsUpdate { index: i, len })?;
+            std::mem::swap(slot, &mut value);
+        }
+        self.push(value)?;
+        Ok(index)
+    }
+
     /// Remove `n` elements from the front of `self`.
     ///
     /// Errors if `n > self.len()`.
@@ -230,6 +697,89 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
 
         Ok(())
     }
+
+    /// Shorten the list to `len` elements, pruning subtrees which become empty.
+    ///
+    /// Pending updates are applied before truncating. Has no effect if `len` is greater than or
+    /// equal to the current length.
+    pub fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        if len >= self.len() {
+            return Ok(());
+        }
+        self.apply_updates()?;
+
+        let backing = &mut self.interface.backing;
+        backing.tree = Tree::truncate(&backing.tree, len, backing.depth, backing.packing_depth)?;
+        backing.length = Length(len);
+
+        Ok(())
+    }
+
+    /// Append all elements of `iter` to the list.
+    ///
+    /// The new elements are staged alongside any pending updates, and then all of them are
+    /// applied to the tree in a single pass, which is faster than pushing and applying them one
+    /// at a time. If the list would exceed its maximum length then an error is returned and the
+    /// list is left unchanged, including its pending updates.
+    pub fn try_extend(&mut self, iter: impl IntoIterator<Item = T>) -> Result<(), Error> {
+        let prev_updates = self.interface.updates.clone();
+
+        for (index, value) in (self.len()..).zip(iter) {
+            if let Err(e) = self.interface.backing.validate_push(index) {
+                self.interface.updates = prev_updates;
+                return Err(e);
+            }
+            self.interface.updates.insert(index, value);
+        }
+
+        self.apply_updates()
+    }
+
+    /// Remove the last element from the list and return it, or `None` if it is empty.
+    ///
+    /// Pending updates are applied before removing the element.
+    pub fn pop(&mut self) -> Result<Option<T>, Error> {
+        let Some(index) = self.len().checked_sub(1) else {
+            return Ok(None);
+        };
+        let value = self.get(index).cloned();
+        self.truncate(index)?;
+        Ok(value)
+    }
+
+    /// Remove the element at `index` and return it, replacing it with the last element.
+    ///
+    /// This doesn't preserve the order of the elements, but unlike removing from the middle of
+    /// the list it only modifies one element besides the last. Pending updates are applied when
+    /// the list is shortened, and the moved element is then recorded as a new pending update.
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, Error> {
+        let len = self.len();
+        let value = self
+            .get(index)
+            .cloned()
+            .ok_or(Error::OutOfBoundsUpdate { index, len })?;
+        let last = self.pop()?.ok_or(Error::OutOfBoundsUpdate { index, len })?;
+        if let Some(moved) = self.get_mut(index) {
+            *moved = last;
+        }
+        Ok(value)
+    }
+
+    /// Keep only the elements for which `f` returns `true`, preserving their order.
+    ///
+    /// The list is rebuilt bottom-up from the kept elements, including any pending updates, so
+    /// this is O(n) regardless of how many elements are removed.
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) -> Result<(), Error> {
+        *self = self.filtered(f)?;
+        Ok(())
+    }
+
+    /// Return a new list containing only the elements for which `f` returns `true`.
+    ///
+    /// See `retain`.
+    pub fn filtered(&self, mut f: impl FnMut(&T) -> bool) -> Result<Self, Error> {
+        Self::try_from_iter(self.iter().filter(|value| f(value)).cloned())
+    }
 }
 
 impl<T: Value, N: Unsigned> ImmList<T> for ListInner<T, N> {
@@ -260,9 +810,12 @@ where
     T: Value,
     N: Unsigned,
 {
-    fn validate_push(current_len: usize) -> Result<(), Error> {
+    fn validate_push(&self, current_len: usize) -> Result<(), Error> {
         if current_len == N::to_usize() {
-            Err(Error::ListFull { len: current_len })
+            Err(Error::ListFull {
+                len: current_len,
+                max_len: N::to_usize(),
+            })
         } else {
             Ok(())
         }
@@ -296,22 +849,37 @@ where
             // Nothing to do.
             return Ok(());
         }
-        self.length = updated_length(self.length, &updates);
-        self.tree =
-            self.tree
-                .with_updated_leaves(&updates, 0, self.depth, hash_updates.as_ref())?;
+        let length = updated_length(self.length, &updates);
+        Tree::update_leaves_in_place(
+            &mut self.tree,
+            &updates,
+            0,
+            self.depth,
+            hash_updates.as_ref(),
+        )?;
+        self.length = length;
         Ok(())
     }
 }
 
 impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Return a copy of `self` which shares as much of its structure as possible with `base`.
+    ///
+    /// See `rebase_on`.
     pub fn rebase(&self, base: &Self) -> Result<Self, Error> {
         let mut rebased = self.clone();
         rebased.rebase_on(base)?;
         Ok(rebased)
     }
 
+    /// Replace subtrees of `self` by equal subtrees from `base`, so that they share memory.
+    ///
+    /// This is useful after deserializing, when `self` shares nothing with an existing copy in
+    /// memory. Subtrees are compared by their cached hashes where available, and by value
+    /// otherwise.
     pub fn rebase_on(&mut self, base: &Self) -> Result<(), Error> {
+        let span = op_span!("rebase");
+        span.record_elements(self.len());
         match Tree::rebase_on(
             &self.interface.backing.tree,
             &base.interface.backing.tree,
@@ -328,15 +896,335 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
         }
         Ok(())
     }
+
+    /// Compute the memory used by the tree backing this list, excluding pending updates.
+    ///
+    /// See `Tree::mem_usage`.
+    pub fn mem_usage(&self) -> MemoryUsage {
+        Tree::mem_usage(&self.interface.backing.tree)
+    }
+
+    /// Count the nodes which are exclusive to each of `lists`, and the nodes which they share.
+    ///
+    /// Pending updates are not included. See `Tree::count_unique_nodes`.
+    pub fn count_unique_nodes(lists: &[&Self]) -> NodeSharing {
+        let trees = lists
+            .iter()
+            .map(|list| &list.interface.backing.tree)
+            .collect::<Vec<_>>();
+        Tree::count_unique_nodes(&trees)
+    }
+
+    /// Iterate over the elements of `self` and `other` in pairs, with their indices, skipping
+    /// subtrees which the two lists share.
+    ///
+    /// Subtrees are skipped when they are pointer-equal or have equal cached hashes, so this is
+    /// fastest when `other` was derived from `self` (or rebased onto it). Both lists must have the
+    /// same length and no pending updates. See `ZipIter`.
+    pub fn iter_zip<'a>(&'a self, other: &'a Self) -> Result<ZipIter<'a, T>, Error> {
+        if self.has_pending_updates() || other.has_pending_updates() {
+            return Err(Error::ZipPendingUpdates);
+        }
+        if self.len() != other.len() {
+            return Err(Error::ZipLengthMismatch {
+                len: self.len(),
+                other_len: other.len(),
+            });
+        }
+        let backing = &self.interface.backing;
+        Ok(ZipIter::new(
+            &backing.tree,
+            &other.interface.backing.tree,
+            backing.depth,
+            self.len(),
+        ))
+    }
+
+    /// Iterate over the elements of `self` which differ from the element at the same index in
+    /// `base`, with their indices.
+    ///
+    /// Elements beyond the length of `base` are included, so this yields the same updates as
+    /// `ListDiff::compute(base, self)`, without collecting them. Subtrees which the two lists
+    /// share are skipped, as for `iter_zip`. Neither list may have pending updates.
+    pub fn iter_changed<'a>(&'a self, base: &'a Self) -> Result<ChangedIter<'a, T>, Error> {
+        if self.has_pending_updates() || base.has_pending_updates() {
+            return Err(Error::ZipPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Ok(ChangedIter::new(
+            &base.interface.backing.tree,
+            base.len(),
+            &backing.tree,
+            self.len(),
+            backing.depth,
+        ))
+    }
+
+    /// Call `f` with mutable access to each element of `self` alongside the element of `other`
+    /// at the same index, skipping subtrees which the two lists share.
+    ///
+    /// Modified values are recorded as pending updates. See `iter_zip`.
+    pub fn for_each_zip_mut<F>(&mut self, other: &Self, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(usize, &mut T, &T),
+    {
+        let changes = self
+            .iter_zip(other)?
+            .filter_map(|(index, value, other_value)| {
+                let mut new_value = value.clone();
+                f(index, &mut new_value, other_value);
+                (new_value != *value).then_some((index, new_value))
+            })
+            .collect::<Vec<_>>();
+        for (index, value) in changes {
+            self.interface.updates.insert(index, value);
+        }
+        Ok(())
+    }
+
+    /// Check that the tree backing this list is well-formed, excluding pending updates.
+    ///
+    /// See `Tree::validate`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let backing = &self.interface.backing;
+        let len = backing.length.as_usize();
+        if len > N::to_usize() {
+            return Err(Error::InvalidTreeLength {
+                len,
+                max_len: N::to_usize(),
+            });
+        }
+        backing
+            .tree
+            .validate(len, backing.depth, backing.packing_depth)?;
+        Ok(())
+    }
 }
 
-impl<T: Value, N: Unsigned> Default for List<T, N> {
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Compare two lists by their tree hash roots.
+    ///
+    /// This is much faster than comparing elements when the hashes of both lists are
+    /// already cached, and computes them otherwise. Falls back to comparing elements if either
+    /// list has pending updates.
+    pub fn eq_by_root(&self, other: &Self) -> bool {
+        if self.has_pending_updates() || other.has_pending_updates() {
+            return self == other;
+        }
+        self.len() == other.len()
+            && self.interface.backing.tree.tree_hash() == other.interface.backing.tree.tree_hash()
+    }
+
+    /// Compute the tree hash root using `H` to hash internal nodes, including the length mix-in.
+    ///
+    /// Pending updates are included, as for `tree_hash_root`. See `Tree::tree_hash_with`.
+    pub fn tree_hash_root_with<H: MerkleHasher>(&self) -> Result<Hash256, Error> {
+        let root = self.interface.applied_backing()?.tree.tree_hash_with::<H>();
+        Ok(H::hash_pair(&root, &length_chunk(self.len())))
+    }
+
+    /// Compute the tree hash root, hashing subtrees at or below depth `threshold` sequentially.
+    ///
+    /// Pending updates are included, as for `tree_hash_root`. See
+    /// `Tree::tree_hash_with_threshold`.
+    pub fn tree_hash_root_with_threshold(&self, threshold: usize) -> Result<Hash256, Error> {
+        let root = self
+            .interface
+            .applied_backing()?
+            .tree
+            .tree_hash_with_threshold(threshold);
+        Ok(tree_hash::mix_in_length(&root, self.len()))
+    }
+
+    /// Compute a Merkle proof for the chunk containing the `index`th element.
+    ///
+    /// The proof is relative to the list's `tree_hash_root`, so its branch ends with the
+    /// length mix-in.
+    pub fn prove(&self, index: usize) -> Result<Proof, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ProofPendingUpdates);
+        }
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsProof {
+                index,
+                len: self.len(),
+            });
+        }
+
+        let backing = &self.interface.backing;
+        let chunk_index = index >> backing.packing_depth;
+        let (leaf, mut branch) = backing.tree.prove_chunk(chunk_index, backing.depth)?;
+        branch.push(length_chunk(self.len()));
+
+        Ok(Proof {
+            gindex: (2 << backing.depth) + chunk_index,
+            leaf,
+            branch,
+        })
+    }
+
+    /// Compute a Merkle multiproof for the chunks containing each of the elements in `indices`.
+    pub fn prove_indices(&self, indices: &[usize]) -> Result<MultiProof, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ProofPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        prove_tree_indices(
+            &backing.tree,
+            backing.depth,
+            LIST_DATA_GINDEX,
+            backing.packing_depth,
+            self.len(),
+            indices,
+            &[(LIST_LENGTH_GINDEX, length_chunk(self.len()))],
+        )
+    }
+
+    /// Write every node of the list to `store`, returning its tree hash root.
+    ///
+    /// Errors if there are pending updates. Nodes already in the store are not written again, see
+    /// `Tree::store`.
+    pub fn store<S: NodeStore<T>>(&self, store: &S) -> Result<Hash256, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::StorePendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        let data_root = Tree::store(&backing.tree, backing.depth, store)?;
+        store_list_root(data_root, self.len(), backing.depth, store)
+    }
+
+    /// Apply the pending updates, and write the nodes they create to `sink`.
+    ///
+    /// Returns the new tree hash root. If the list was written to a store with `List::store` and
+    /// has only been modified via this method since, then the store contains the entire list
+    /// without having to walk the whole tree again. See `Tree::write_unhashed`.
+    pub fn apply_updates_with_sink<S: NodeSink<T>>(&mut self, sink: &S) -> Result<Hash256, Error> {
+        self.apply_updates()?;
+        let backing = &self.interface.backing;
+        let data_root = Tree::write_unhashed(&backing.tree, backing.depth, sink)?;
+        store_list_root(data_root, self.len(), backing.depth, sink)
+    }
+
+    /// Load the list with tree hash root `root` from `store`, as written by `List::store`.
+    ///
+    /// The contents of the store are trusted, so the loaded tree is not validated.
+    pub fn load<S: NodeStore<T>>(store: &S, root: Hash256) -> Result<Self, Error> {
+        let depth = Self::depth();
+        let (data_root, len) = load_list_root(root, depth, N::to_usize(), store)?;
+        let tree = Tree::load(depth, data_root, store)?;
+        Ok(Self::from_parts(tree, depth, Length(len)))
+    }
+
+    /// Share identical subtrees of this list with other trees known to `interner`.
+    ///
+    /// Any pending updates are applied first.
+    #[cfg(feature = "intern")]
+    pub fn intern<I: NodeInterner<T>>(&mut self, interner: &I) -> Result<(), Error> {
+        self.apply_updates()?;
+        let backing = &mut self.interface.backing;
+        backing.tree = Tree::intern(&backing.tree, backing.depth, interner);
+        Ok(())
+    }
+}
+
+/// Depth of the subtrees built by each task in `List::from_slice_parallel`.
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+const PARALLEL_BUILD_SUBTREE_DEPTH: usize = 10;
+
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+impl<T: Value + Send + Sync, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Create a list from a slice, building its subtrees in parallel.
+    ///
+    /// The slice is split into subtrees of `2^10` leaves, which are built on the `rayon` thread
+    /// pool and then joined. The resulting tree is identical to the one built by `new`.
+    pub fn from_slice_parallel(values: &[T]) -> Result<Self, Error> {
+        if values.len() > N::to_usize() {
+            return Err(Error::ListFull {
+                len: 0,
+                max_len: N::to_usize(),
+            });
+        }
+
+        let depth = Self::depth();
+        let subtree_depth = PARALLEL_BUILD_SUBTREE_DEPTH;
+        if depth <= subtree_depth {
+            return Self::try_from_exact_iter(values.iter().cloned());
+        }
+
+        let level = subtree_depth + opt_packing_depth::<T>().unwrap_or(0);
+        let subtrees = values
+            .par_chunks(1 << level)
+            .map(|chunk| {
+                let mut builder = Builder::with_capacity(subtree_depth, 0, chunk.len());
+                for value in chunk {
+                    builder.push(value.clone())?;
+                }
+                let (subtree, _, length) = builder.finish()?;
+                Ok((subtree, length.as_usize()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut builder = Builder::new(depth, level);
+        for (subtree, len) in subtrees {
+            builder.push_node(subtree, len)?;
+        }
+        let (tree, depth, length) = builder.finish()?;
+        Ok(Self::from_parts(tree, depth, length))
+    }
+
+    /// Return a copy of the list sorted with a comparator function, sorting and building the
+    /// new tree in parallel.
+    ///
+    /// See `sorted_by` and `from_slice_parallel`.
+    pub fn par_sorted_by<F>(&self, compare: F) -> Result<Self, Error>
+    where
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        if self
+            .iter()
+            .is_sorted_by(|a, b| compare(a, b) != Ordering::Greater)
+        {
+            return Ok(self.clone());
+        }
+        let mut values = self.to_vec();
+        values.par_sort_unstable_by(compare);
+        Self::from_slice_parallel(&values)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Extend<T> for List<T, N, U> {
+    /// Append all elements of `iter` to the list.
+    ///
+    /// Panics if the list would exceed its maximum length, see `try_extend` for a fallible
+    /// alternative.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.try_extend(iter)
+            .unwrap_or_else(|e| panic!("List::extend failed: {:?}", e))
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> PartialEq for List<T, N, U> {
+    /// Compare two lists by value.
+    ///
+    /// If neither has pending updates then their trees are compared directly, skipping shared
+    /// subtrees and subtrees with cached hashes. Otherwise the elements are compared one by one.
+    fn eq(&self, other: &Self) -> bool {
+        if self.has_pending_updates() || other.has_pending_updates() {
+            self.len() == other.len() && self.iter().eq(other.iter())
+        } else {
+            Arc::ptr_eq(&self.interface.backing.tree, &other.interface.backing.tree)
+                || self.interface.backing == other.interface.backing
+        }
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Default for List<T, N, U> {
     fn default() -> Self {
         Self::empty()
     }
 }
 
-impl<T: Value + Send + Sync, N: Unsigned> TreeHash for List<T, N> {
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> TreeHash for List<T, N, U> {
     fn tree_hash_type() -> tree_hash::TreeHashType {
         tree_hash::TreeHashType::List
     }
@@ -349,12 +1237,45 @@ impl<T: Value + Send + Sync, N: Unsigned> TreeHash for List<T, N> {
         unreachable!("List should never be packed.")
     }
 
+    /// Compute the tree hash root, including any pending updates.
+    ///
+    /// Pending updates are applied to a temporary copy of the tree. The hashes of unmodified
+    /// subtrees are cached as usual, but those of the modified nodes are recomputed on every call
+    /// until `apply_updates` is called, so hashing a list repeatedly should be done after applying.
     fn tree_hash_root(&self) -> Hash256 {
-        // FIXME(sproul): remove assert
-        assert!(!self.interface.has_pending_updates());
+        let backing = self
+            .interface
+            .applied_backing()
+            .expect("pending updates should be valid");
+        tree_hash::mix_in_length(&backing.tree.tree_hash(), self.len())
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Index<usize> for List<T, N, U> {
+    type Output = T;
+
+    /// Return the element at `index`, panicking if it is out of bounds.
+    ///
+    /// Pending updates are included, as for `get`.
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            )
+        })
+    }
+}
 
-        let root = self.interface.backing.tree.tree_hash();
-        tree_hash::mix_in_length(&root, self.len())
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> IntoIterator for List<T, N, U> {
+    type Item = T;
+    type IntoIter = IntoIter<T, U>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        let interface = self.interface;
+        IntoIter::new(interface.backing.tree, interface.updates, len)
     }
 }
 
@@ -367,14 +1288,35 @@ impl<'a, T: Value, N: Unsigned, U: UpdateMap<T>> IntoIterator for &'a List<T, N,
     }
 }
 
+#[cfg(feature = "ssz_types")]
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<VariableList<T, N>> for List<T, N, U> {
+    /// Build the tree from the list's elements in a single pass.
+    fn from(list: VariableList<T, N>) -> Self {
+        Self::new(list.into()).expect("variable list has at most N elements")
+    }
+}
+
+#[cfg(feature = "ssz_types")]
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<List<T, N, U>> for VariableList<T, N> {
+    fn from(list: List<T, N, U>) -> Self {
+        list.to_vec().into()
+    }
+}
+
+#[cfg(feature = "serde")]
 impl<T: Value, N: Unsigned, U: UpdateMap<T>> Serialize for List<T, N, U>
 where
     T: Serialize,
 {
+    /// Human-readable formats (e.g. JSON) get a sequence of elements, while binary formats get
+    /// the SSZ encoding as a byte string.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&self.as_ssz_bytes());
+        }
         let mut seq = serializer.serialize_seq(Some(self.len()))?;
         for e in self {
             seq.serialize_element(e)?;
@@ -383,6 +1325,7 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de, T, N, U> Deserialize<'de> for List<T, N, U>
 where
     T: Deserialize<'de> + Value,
@@ -393,12 +1336,21 @@ where
     where
         D: Deserializer<'de>,
     {
+        if !deserializer.is_human_readable() {
+            let list: List<T, N> = deserializer.deserialize_bytes(SszBytesVisitor::default())?;
+            let backing = list.interface.backing;
+            return Ok(Self::from_parts(
+                backing.tree,
+                backing.depth,
+                backing.length,
+            ));
+        }
         deserializer.deserialize_seq(ListVisitor::default())
     }
 }
 
 // FIXME: duplicated from `ssz::encode::impl_for_vec`
-impl<T: Value, N: Unsigned> Encode for List<T, N> {
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Encode for List<T, N, U> {
     fn is_ssz_fixed_len() -> bool {
         false
     }
@@ -421,21 +1373,16 @@ impl<T: Value, N: Unsigned> Encode for List<T, N> {
                 item.ssz_append(buf);
             }
         } else {
-            let mut encoder = SszEncoder::container(buf, self.len() * BYTES_PER_LENGTH_OFFSET);
-
-            for item in self {
-                encoder.append(item);
-            }
-
-            encoder.finalize();
+            ssz_append_variable(|| self.iter(), self.len(), buf);
         }
     }
 }
 
-impl<T, N> TryFromIter<T> for List<T, N>
+impl<T, N, U> TryFromIter<T> for List<T, N, U>
 where
     T: Value,
     N: Unsigned,
+    U: UpdateMap<T>,
 {
     type Error = Error;
 
@@ -447,45 +1394,149 @@ where
     }
 }
 
-impl<T, N> Decode for List<T, N>
-where
-    T: Value,
-    N: Unsigned,
-{
-    fn is_ssz_fixed_len() -> bool {
-        false
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Write the SSZ encoding of the list to `writer`, including any pending updates.
+    ///
+    /// The encoding is written in small pieces as the tree is traversed, rather than being
+    /// collected into a single `Vec` first. Wrapping `writer` in a `BufWriter` is unnecessary.
+    pub fn ssz_append_to<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        encode_list_to_writer(|| self.iter(), self.len(), writer)
     }
+}
 
-    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Decode a list from SSZ bytes read incrementally from `reader`.
+    ///
+    /// Unlike `from_ssz_bytes`, the full encoding is never held in memory: each item is added to
+    /// the tree as soon as its bytes have been read.
+    pub fn from_ssz_reader<R: Read>(reader: R) -> Result<Self, ssz::DecodeError> {
+        Ok(Self::try_from_ssz_reader(reader)?)
+    }
+
+    /// Decode a list from SSZ bytes read incrementally from `reader`, returning a typed error.
+    ///
+    /// See `try_from_ssz_bytes`.
+    pub fn try_from_ssz_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let span = op_span!("decode_list");
+        let (tree, depth, length) =
+            decode_list_from_reader(reader, N::to_usize(), Self::builder())?;
+        span.record_elements(length.as_usize());
+        Ok(Self::from_parts(tree, depth, length))
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Decode a list from SSZ bytes, checking each element with `validate` as it is decoded.
+    ///
+    /// `validate` is called with the index and value of every element, before it is added to the
+    /// tree, and an error from it aborts decoding. This rejects invalid elements without a
+    /// second pass over the decoded list.
+    pub fn from_ssz_bytes_with<F>(bytes: &[u8], validate: F) -> Result<Self, ssz::DecodeError>
+    where
+        F: FnMut(usize, &T) -> Result<(), ssz::DecodeError>,
+    {
+        Ok(Self::try_from_ssz_bytes_with(bytes, validate)?)
+    }
+
+    /// Decode a list from SSZ bytes, returning a typed error.
+    ///
+    /// An oversized list is reported as `Error::DecodeListTooLong`, and any other malformed
+    /// input as `Error::SszDecode`. The `Decode` impl converts these back to a `DecodeError`.
+    pub fn try_from_ssz_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::try_from_ssz_bytes_with(bytes, |_, _| Ok(()))
+    }
+
+    fn try_from_ssz_bytes_with<F>(bytes: &[u8], mut validate: F) -> Result<Self, Error>
+    where
+        F: FnMut(usize, &T) -> Result<(), ssz::DecodeError>,
+    {
+        let span = op_span!("decode_list");
         let max_len = N::to_usize();
+        let mut decode_item = |index: usize, item_bytes: &[u8]| {
+            let item = T::from_ssz_bytes(item_bytes)?;
+            validate(index, &item)?;
+            Ok::<_, ssz::DecodeError>(item)
+        };
 
         if bytes.is_empty() {
             Ok(List::empty())
         } else if <T as Decode>::is_ssz_fixed_len() {
+            let item_len = <T as Decode>::ssz_fixed_len();
             let num_items = bytes
                 .len()
-                .checked_div(<T as Decode>::ssz_fixed_len())
+                .checked_div(item_len)
                 .ok_or(ssz::DecodeError::ZeroLengthItem)?;
 
             if num_items > max_len {
-                return Err(ssz::DecodeError::BytesInvalid(format!(
-                    "List of {} items exceeds maximum of {}",
-                    num_items, max_len
-                )));
+                return Err(too_long(num_items, max_len));
+            }
+            span.record_elements(num_items);
+            if !bytes.len().is_multiple_of(item_len) {
+                return Err(ssz::DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: (num_items + 1) * item_len,
+                }
+                .into());
             }
 
-            process_results(
-                bytes
-                    .chunks(<T as Decode>::ssz_fixed_len())
-                    .map(T::from_ssz_bytes),
-                |iter| {
-                    List::try_from_iter(iter).map_err(|e| {
-                        ssz::DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e))
-                    })
-                },
-            )?
+            let items = bytes
+                .chunks(item_len)
+                .enumerate()
+                .map(|(index, item_bytes)| decode_item(index, item_bytes));
+            Ok(process_results(items, |iter| {
+                List::try_from_iter(iter).map_err(|e| {
+                    ssz::DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e))
+                })
+            })??)
         } else {
-            ssz::decode_list_of_variable_length_items(bytes, Some(max_len))
+            // Check the number of items implied by the first offset up front, so that the error
+            // for an oversized list is the same as for fixed-length items.
+            let first_offset = ssz::read_offset(bytes)?;
+            let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
+            if num_items > max_len {
+                return Err(too_long(num_items, max_len));
+            }
+            span.record_elements(num_items);
+            check_offset(first_offset, first_offset, first_offset, bytes.len())?;
+            if !first_offset.is_multiple_of(BYTES_PER_LENGTH_OFFSET) || num_items == 0 {
+                return Err(ssz::DecodeError::InvalidListFixedBytesLen(first_offset).into());
+            }
+
+            // Each item runs from its offset to the next, and the last to the end of the input.
+            let mut offset = first_offset;
+            let items = (1..=num_items).map(|i| {
+                let start = offset;
+                if i < num_items {
+                    let next_offset = ssz::read_offset(&bytes[i * BYTES_PER_LENGTH_OFFSET..])?;
+                    offset = check_offset(next_offset, offset, first_offset, bytes.len())?;
+                } else {
+                    offset = bytes.len();
+                }
+                let item_bytes = bytes
+                    .get(start..offset)
+                    .ok_or(ssz::DecodeError::OutOfBoundsByte { i: offset })?;
+                decode_item(i - 1, item_bytes)
+            });
+            Ok(process_results(items, |iter| {
+                List::try_from_iter(iter).map_err(|e| {
+                    ssz::DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e))
+                })
+            })??)
         }
     }
 }
+
+impl<T, N, U> Decode for List<T, N, U>
+where
+    T: Value,
+    N: Unsigned,
+    U: UpdateMap<T>,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        Ok(Self::try_from_ssz_bytes(bytes)?)
+    }
+}
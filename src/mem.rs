@@ -0,0 +1,219 @@
+use crate::utils::ref_count;
+use crate::{Arc, Leaf, PackedLeaf, Tree, Value};
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+/// Summary of the memory used by a tree.
+///
+/// Only the memory of the tree nodes and the values stored inline in them is counted. Memory
+/// allocated by the values themselves (e.g. the contents of a nested `List`) is not included.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Number of distinct nodes in the tree.
+    pub nodes: usize,
+    /// Number of nodes which are not referenced by any other tree.
+    pub unique_nodes: usize,
+    /// Bytes used by all distinct nodes in the tree.
+    pub bytes: usize,
+    /// Bytes used by nodes which are not referenced by any other tree.
+    ///
+    /// This is the amount of memory that would be freed if the tree were dropped.
+    pub unique_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Bytes used by nodes which are also referenced by other trees.
+    pub fn shared_bytes(&self) -> usize {
+        self.bytes - self.unique_bytes
+    }
+}
+
+/// Ownership of the nodes of a set of trees, from `Tree::count_unique_nodes`.
+///
+/// Bytes are counted as for `MemoryUsage`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeSharing {
+    /// Number of nodes reachable from each tree and from no other tree in the set.
+    pub exclusive_nodes: Vec<usize>,
+    /// Bytes used by the nodes counted in `exclusive_nodes`.
+    ///
+    /// This is the memory that would be freed by dropping just that tree, assuming its nodes are
+    /// not referenced from outside the set.
+    pub exclusive_bytes: Vec<usize>,
+    /// Number of nodes reachable from more than one tree in the set.
+    pub shared_nodes: usize,
+    /// Bytes used by the nodes counted in `shared_nodes`.
+    pub shared_bytes: usize,
+}
+
+impl NodeSharing {
+    /// Number of distinct nodes reachable from any tree in the set.
+    pub fn total_nodes(&self) -> usize {
+        self.exclusive_nodes.iter().sum::<usize>() + self.shared_nodes
+    }
+
+    /// Bytes used by all distinct nodes reachable from any tree in the set.
+    pub fn total_bytes(&self) -> usize {
+        self.exclusive_bytes.iter().sum::<usize>() + self.shared_bytes
+    }
+}
+
+fn node_ptr<T: Value>(node: &Arc<Tree<T>>) -> *const Tree<T> {
+    Arc::as_ptr(node)
+}
+
+impl<T: Value> Tree<T> {
+    /// Compute the memory used by `tree`, and how much of it is shared with other trees.
+    ///
+    /// A node is considered shared if it is referenced from outside `tree`, or if it is a
+    /// descendant of such a node. Subtrees which are referenced multiple times from within `tree`
+    /// (e.g. by `List::repeat`) are only counted once.
+    pub fn mem_usage(tree: &Arc<Self>) -> MemoryUsage {
+        // Find every distinct node, and count the references to it from within the tree.
+        let mut internal_refs = HashMap::new();
+        let mut nodes = vec![];
+        let mut stack = vec![tree];
+        internal_refs.insert(node_ptr(tree), 1);
+
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            if let Self::Node { left, right, .. } = &**node {
+                for child in [left, right] {
+                    let refs = internal_refs.entry(node_ptr(child)).or_insert(0);
+                    *refs += 1;
+                    if *refs == 1 {
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+
+        // Nodes with more references than can be accounted for are shared, along with all of
+        // their descendants.
+        let mut shared = HashSet::new();
+        let mut stack = nodes
+            .iter()
+            .copied()
+            .filter(|node| ref_count(node) > internal_refs[&node_ptr(node)])
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if shared.insert(node_ptr(node)) {
+                if let Self::Node { left, right, .. } = &**node {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        let mut usage = MemoryUsage::default();
+        for node in nodes {
+            let bytes = node.node_bytes();
+            usage.nodes += 1;
+            usage.bytes += bytes;
+            if !shared.contains(&node_ptr(node)) {
+                usage.unique_nodes += 1;
+                usage.unique_bytes += bytes;
+            }
+        }
+        usage
+    }
+
+    /// Count the nodes which are exclusive to each of `trees`, and the nodes which they share.
+    ///
+    /// Unlike `mem_usage`, references from outside the set are not considered, so the result
+    /// describes how the trees share structure with each other regardless of what else is alive.
+    /// This is useful for deciding which trees to evict from a cache.
+    pub fn count_unique_nodes(trees: &[&Arc<Self>]) -> NodeSharing {
+        // The index of the only tree to reach each node, or `None` once a second tree has.
+        let mut owners = HashMap::new();
+        let mut sharing = NodeSharing {
+            exclusive_nodes: vec![0; trees.len()],
+            exclusive_bytes: vec![0; trees.len()],
+            ..NodeSharing::default()
+        };
+
+        for (index, tree) in trees.iter().enumerate() {
+            // Nodes are pushed with a flag indicating that they are below a shared node, and
+            // therefore shared even if this tree has already visited them by another path.
+            let mut stack = vec![(*tree, false)];
+            while let Some((node, below_shared)) = stack.pop() {
+                let owner = owners.get(&node_ptr(node)).copied();
+                let shared = match owner {
+                    // Shared nodes are always visited along with all of their descendants.
+                    Some(None) => continue,
+                    Some(Some(owner)) if owner == index && !below_shared => continue,
+                    None if !below_shared => {
+                        sharing.exclusive_nodes[index] += 1;
+                        sharing.exclusive_bytes[index] += node.node_bytes();
+                        owners.insert(node_ptr(node), Some(index));
+                        false
+                    }
+                    _ => {
+                        let bytes = node.node_bytes();
+                        if let Some(Some(owner)) = owner {
+                            sharing.exclusive_nodes[owner] -= 1;
+                            sharing.exclusive_bytes[owner] -= bytes;
+                        }
+                        sharing.shared_nodes += 1;
+                        sharing.shared_bytes += bytes;
+                        owners.insert(node_ptr(node), None);
+                        true
+                    }
+                };
+                if let Self::Node { left, right, .. } = &**node {
+                    stack.push((left, shared));
+                    stack.push((right, shared));
+                }
+            }
+        }
+        sharing
+    }
+
+    /// Count the distinct nodes reachable from `dropped` but not from `retained`, returning the
+    /// number of nodes and the bytes they use.
+    ///
+    /// This is the memory freed by dropping `dropped` while keeping `retained` alive, assuming
+    /// no other references, as for `count_unique_nodes`.
+    pub(crate) fn count_dropped_nodes(
+        dropped: &[&Arc<Self>],
+        retained: &[&Arc<Self>],
+    ) -> (usize, usize) {
+        let mut visited = HashSet::new();
+        let mut stack = retained.to_vec();
+        while let Some(node) = stack.pop() {
+            if visited.insert(node_ptr(node)) {
+                if let Self::Node { left, right, .. } = &**node {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        // Every descendant of a retained node is also retained, so these are never descended.
+        let (mut nodes, mut bytes) = (0, 0);
+        let mut stack = dropped.to_vec();
+        while let Some(node) = stack.pop() {
+            if visited.insert(node_ptr(node)) {
+                nodes += 1;
+                bytes += node.node_bytes();
+                if let Self::Node { left, right, .. } = &**node {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        (nodes, bytes)
+    }
+
+    /// Bytes used by this node's allocation and the values stored in it.
+    fn node_bytes(&self) -> usize {
+        // Every `Arc` allocation includes a reference count.
+        let arc_overhead = size_of::<usize>();
+        let value_bytes = match self {
+            Self::Leaf(Leaf { .. }) => arc_overhead + size_of::<T>(),
+            Self::PackedLeaf(PackedLeaf { values, .. }) => values.capacity() * size_of::<T>(),
+            Self::Node { .. } | Self::Zero(_) => 0,
+        };
+        arc_overhead + size_of::<Self>() + value_bytes
+    }
+}
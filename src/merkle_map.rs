@@ -0,0 +1,284 @@
+use crate::proof::hash_pair;
+use crate::{Arc, Error, MaybeSync, MemoryUsage, Tree, Value};
+use ssz_derive::{Decode, Encode};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+
+/// Number of bits in a key, and therefore the maximum depth of a `MerkleMap`.
+const KEY_BITS: usize = 256;
+
+/// Node of the trie backing a `MerkleMap`.
+type MapNode<V> = Arc<Tree<MapEntry<V>>>;
+
+/// Key-value pair stored at a leaf of a `MerkleMap`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct MapEntry<V: Value> {
+    pub key: Hash256,
+    pub value: V,
+}
+
+impl<V: Value> TreeHash for MapEntry<V> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Container
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("MapEntry should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("MapEntry should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        hash_pair(&self.key, &self.value.tree_hash_root())
+    }
+}
+
+/// Persistent map from `Hash256` keys to values, stored in a binary trie of `Tree` nodes.
+///
+/// The `i`th bit of a key (most significant first) selects the branch taken at depth `i`, and
+/// each entry is stored at the shallowest depth at which its prefix is unique. The shape of the
+/// trie is therefore determined by its keys alone, so maps with equal contents have equal roots
+/// regardless of the order of insertion.
+///
+/// Like `List`, clones are cheap and share all of their nodes, and an update copies only the
+/// path from the root to the modified entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleMap<V: Value> {
+    tree: MapNode<V>,
+    len: usize,
+}
+
+impl<V: Value> Default for MerkleMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Return the bit of `key` which selects the branch taken at `depth`.
+fn key_bit(key: &Hash256, depth: usize) -> bool {
+    key.as_bytes()[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+impl<V: Value> MerkleMap<V> {
+    pub fn new() -> Self {
+        Self {
+            tree: Tree::zero(0),
+            len: 0,
+        }
+    }
+
+    /// Build a map from `(key, value)` pairs, with later values replacing earlier ones.
+    pub fn try_from_iter(iter: impl IntoIterator<Item = (Hash256, V)>) -> Result<Self, Error> {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value)?;
+        }
+        Ok(map)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: &Hash256) -> Option<&V> {
+        let mut node = &self.tree;
+        for depth in 0..=KEY_BITS {
+            match &**node {
+                Tree::Leaf(leaf) if leaf.value.key == *key => return Some(&leaf.value.value),
+                Tree::Node { left, right, .. } if depth < KEY_BITS => {
+                    node = if key_bit(key, depth) { right } else { left };
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    pub fn contains_key(&self, key: &Hash256) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Insert `value` at `key`, returning the previous value if there was one.
+    pub fn insert(&mut self, key: Hash256, value: V) -> Result<Option<V>, Error> {
+        let (tree, previous) = Self::insert_recursive(&self.tree, MapEntry { key, value }, 0)?;
+        self.tree = tree;
+        if previous.is_none() {
+            self.len += 1;
+        }
+        Ok(previous)
+    }
+
+    fn insert_recursive(
+        node: &MapNode<V>,
+        entry: MapEntry<V>,
+        depth: usize,
+    ) -> Result<(MapNode<V>, Option<V>), Error> {
+        match &**node {
+            Tree::Zero(_) => Ok((Tree::leaf(entry), None)),
+            Tree::Leaf(leaf) if leaf.value.key == entry.key => {
+                Ok((Tree::leaf(entry), Some(leaf.value.value.clone())))
+            }
+            Tree::Leaf(leaf) => {
+                let existing_key = leaf.value.key;
+                Ok((
+                    Self::split(node.clone(), &existing_key, entry, depth)?,
+                    None,
+                ))
+            }
+            Tree::Node { left, right, .. } if depth < KEY_BITS => {
+                if key_bit(&entry.key, depth) {
+                    let (right, previous) = Self::insert_recursive(right, entry, depth + 1)?;
+                    Ok((Tree::node(left.clone(), right, Hash256::zero()), previous))
+                } else {
+                    let (left, previous) = Self::insert_recursive(left, entry, depth + 1)?;
+                    Ok((Tree::node(left, right.clone(), Hash256::zero()), previous))
+                }
+            }
+            _ => Err(Error::InvalidMapNode { depth }),
+        }
+    }
+
+    /// Build the subtree at `depth` containing the existing leaf and the new `entry`.
+    fn split(
+        existing: MapNode<V>,
+        existing_key: &Hash256,
+        entry: MapEntry<V>,
+        depth: usize,
+    ) -> Result<MapNode<V>, Error> {
+        if depth >= KEY_BITS {
+            return Err(Error::InvalidMapNode { depth });
+        }
+        let existing_bit = key_bit(existing_key, depth);
+        let (left, right) = if existing_bit != key_bit(&entry.key, depth) {
+            let new = Tree::leaf(entry);
+            if existing_bit {
+                (new, existing)
+            } else {
+                (existing, new)
+            }
+        } else {
+            let child = Self::split(existing, existing_key, entry, depth + 1)?;
+            if existing_bit {
+                (Tree::zero(0), child)
+            } else {
+                (child, Tree::zero(0))
+            }
+        };
+        Ok(Tree::node(left, right, Hash256::zero()))
+    }
+
+    /// Remove the entry at `key`, returning its value if it was present.
+    pub fn remove(&mut self, key: &Hash256) -> Result<Option<V>, Error> {
+        let Some((tree, value)) = Self::remove_recursive(&self.tree, key, 0)? else {
+            return Ok(None);
+        };
+        self.tree = tree;
+        self.len -= 1;
+        Ok(Some(value))
+    }
+
+    fn remove_recursive(
+        node: &MapNode<V>,
+        key: &Hash256,
+        depth: usize,
+    ) -> Result<Option<(MapNode<V>, V)>, Error> {
+        match &**node {
+            Tree::Zero(_) => Ok(None),
+            Tree::Leaf(leaf) if leaf.value.key == *key => {
+                Ok(Some((Tree::zero(0), leaf.value.value.clone())))
+            }
+            Tree::Leaf(_) => Ok(None),
+            Tree::Node { left, right, .. } if depth < KEY_BITS => {
+                let bit = key_bit(key, depth);
+                let (child, sibling) = if bit { (right, left) } else { (left, right) };
+                let Some((child, value)) = Self::remove_recursive(child, key, depth + 1)? else {
+                    return Ok(None);
+                };
+
+                // Hoist a lone leaf so that every entry stays at the shallowest unique depth.
+                let is_empty = |node: &Tree<_>| matches!(node, Tree::Zero(_));
+                let is_leaf = |node: &Tree<_>| matches!(node, Tree::Leaf(_));
+                let new_node = if is_empty(&child) && (is_leaf(sibling) || is_empty(sibling)) {
+                    sibling.clone()
+                } else if is_empty(sibling) && is_leaf(&child) {
+                    child
+                } else if bit {
+                    Tree::node(sibling.clone(), child, Hash256::zero())
+                } else {
+                    Tree::node(child, sibling.clone(), Hash256::zero())
+                };
+                Ok(Some((new_node, value)))
+            }
+            _ => Err(Error::InvalidMapNode { depth }),
+        }
+    }
+
+    /// Iterate over the entries of the map in ascending order of their keys.
+    pub fn iter(&self) -> MapIter<'_, V> {
+        MapIter {
+            stack: vec![&self.tree],
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Hash256> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Compute the memory used by the tree backing this map.
+    ///
+    /// See `Tree::mem_usage`.
+    pub fn mem_usage(&self) -> MemoryUsage {
+        Tree::mem_usage(&self.tree)
+    }
+}
+
+impl<V: Value + MaybeSync> MerkleMap<V> {
+    /// Compute the root of the trie, which commits to all of its keys and values.
+    ///
+    /// Each leaf hashes to `hash(key, hash_tree_root(value))`, and empty subtrees hash to zero.
+    pub fn tree_hash_root(&self) -> Hash256 {
+        self.tree.tree_hash()
+    }
+}
+
+/// Iterator over the entries of a `MerkleMap`, in ascending order of their keys.
+#[derive(Debug)]
+pub struct MapIter<'a, V: Value> {
+    stack: Vec<&'a Tree<MapEntry<V>>>,
+}
+
+impl<'a, V: Value> Iterator for MapIter<'a, V> {
+    type Item = (&'a Hash256, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            match node {
+                Tree::Leaf(leaf) => return Some((&leaf.value.key, &leaf.value.value)),
+                Tree::Node { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+                Tree::PackedLeaf(_) | Tree::Zero(_) => (),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, V: Value> IntoIterator for &'a MerkleMap<V> {
+    type Item = (&'a Hash256, &'a V);
+    type IntoIter = MapIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
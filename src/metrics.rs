@@ -0,0 +1,51 @@
+//! Pluggable metrics for the expensive parts of tree operations.
+//!
+//! Nothing is recorded until a sink is installed with `set_metrics_sink`, and the only cost until
+//! then is a relaxed atomic load at each recording site. Sinks are global and called from
+//! whichever thread does the work (including `rayon` worker threads when hashing), so they should
+//! be cheap and thread-safe, e.g. incrementing Prometheus counters.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Receiver for metrics recorded by this crate.
+///
+/// All methods do nothing by default, so sinks only need to implement the metrics they collect.
+pub trait MetricsSink: Send + Sync {
+    /// Called with the number of internal node hashes computed by tree hashing.
+    fn hashes_computed(&self, _count: usize) {}
+
+    /// Called with the number of tree nodes created.
+    fn nodes_allocated(&self, _count: usize) {}
+
+    /// Called with the number of pending updates each time a non-empty update map is applied.
+    fn update_map_size(&self, _len: usize) {}
+
+    /// Called each time a packed leaf is copied in order to modify some of its values.
+    fn packed_leaf_rewritten(&self) {}
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SINK: RwLock<Option<Arc<dyn MetricsSink>>> = RwLock::new(None);
+
+/// Install `sink` to receive all metrics, replacing any previous sink.
+pub fn set_metrics_sink(sink: Arc<dyn MetricsSink>) {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = Some(sink);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Remove the current sink, if any, and stop recording metrics.
+pub fn clear_metrics_sink() {
+    ENABLED.store(false, Ordering::Relaxed);
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Pass the current sink to `f`, if one is installed.
+#[inline]
+pub(crate) fn record(f: impl FnOnce(&dyn MetricsSink)) {
+    if ENABLED.load(Ordering::Relaxed) {
+        if let Some(sink) = &*SINK.read().unwrap_or_else(PoisonError::into_inner) {
+            f(&**sink);
+        }
+    }
+}
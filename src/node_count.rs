@@ -0,0 +1,22 @@
+//! Global count of live tree nodes, for detecting leaks in tests.
+//!
+//! Every `Tree` node created by this crate increments the count, and every node dropped
+//! decrements it. A test can record `live_nodes` before creating and dropping some lists, and
+//! check that it has returned to the same value afterwards. The count is shared by all threads,
+//! so such tests should not run concurrently with others that create trees.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIVE_NODES: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of `Tree` nodes currently alive, across all threads.
+pub fn live_nodes() -> usize {
+    LIVE_NODES.load(Ordering::SeqCst)
+}
+
+pub(crate) fn node_created() {
+    LIVE_NODES.fetch_add(1, Ordering::SeqCst);
+}
+
+pub(crate) fn node_dropped() {
+    LIVE_NODES.fetch_sub(1, Ordering::SeqCst);
+}
@@ -0,0 +1,105 @@
+use crate::update_map::MaxMap;
+use crate::utils::{updated_length, Length};
+use crate::{Error, FrozenList, List, UpdateMap, Value};
+use derivative::Derivative;
+use typenum::Unsigned;
+use vec_map::VecMap;
+
+/// Speculative updates on top of a shared, immutable base list.
+///
+/// Reads check the overlay's own updates before falling back to the base, which is never
+/// modified. Unlike the pending updates of a `List`, the overlay is a separate value, so many
+/// overlays can share one base, and abandoning one is just a matter of dropping it. Use
+/// `into_list` to keep the updates.
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = "T: Value, N: Unsigned, U: UpdateMap<T>"))]
+pub struct OverlayList<T: Value, N: Unsigned, U: UpdateMap<T> = MaxMap<VecMap<T>>> {
+    base: FrozenList<T, N>,
+    updates: U,
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> OverlayList<T, N, U> {
+    /// Create an empty overlay on top of `base`.
+    pub fn new(base: FrozenList<T, N>) -> Self {
+        Self {
+            base,
+            updates: U::default(),
+        }
+    }
+
+    /// The list beneath the overlay, without any of its updates.
+    pub fn base(&self) -> &FrozenList<T, N> {
+        &self.base
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.updates.get(index).or_else(|| self.base.get(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let base = &self.base;
+        self.updates
+            .get_mut_with(index, |index| base.get(index).cloned())
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        let index = self.len();
+        if index >= N::to_usize() {
+            return Err(Error::ListFull {
+                len: index,
+                max_len: N::to_usize(),
+            });
+        }
+        self.updates.insert(index, value);
+        Ok(())
+    }
+
+    /// Iterate over the elements, including the overlay's updates.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    pub fn len(&self) -> usize {
+        updated_length(Length(self.base.len()), &self.updates).as_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return `true` if the overlay contains any updates.
+    pub fn has_updates(&self) -> bool {
+        !self.updates.is_empty()
+    }
+
+    /// Discard the overlay's updates, returning the base.
+    pub fn discard(self) -> FrozenList<T, N> {
+        self.base
+    }
+
+    /// Convert into a `List` sharing the base's tree, with the overlay's updates pending.
+    pub fn into_list(self) -> List<T, N, U> {
+        let mut list = self.base.thaw::<U>();
+        list.interface.updates = self.updates;
+        list
+    }
+}
+
+impl<T: Value, N: Unsigned> FrozenList<T, N> {
+    /// Create an empty overlay sharing this list as its base.
+    pub fn overlay<U: UpdateMap<T>>(&self) -> OverlayList<T, N, U> {
+        OverlayList::new(self.clone())
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Create an empty overlay on top of this list, which must not have any pending updates.
+    ///
+    /// The overlay shares the list's tree, so this is O(1).
+    pub fn overlay(&self) -> Result<OverlayList<T, N, U>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::OverlayPendingUpdates);
+        }
+        self.clone().freeze().map(OverlayList::new)
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{utils::arb_rwlock, Error, UpdateMap};
+use crate::{trace, utils::arb_rwlock, Error, UpdateMap};
 use arbitrary::Arbitrary;
 use derivative::Derivative;
 use parking_lot::RwLock;
+use ssz::Encode;
 use std::ops::ControlFlow;
 use tree_hash::{Hash256, TreeHash, BYTES_PER_CHUNK};
 
@@ -11,6 +12,8 @@ pub struct PackedLeaf<T: TreeHash + Clone> {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     #[arbitrary(with = arb_rwlock)]
     pub hash: RwLock<Hash256>,
+    /// Values are stored decoded rather than as bytes of the hash, so accessing them is safe for
+    /// types of any layout.
     pub(crate) values: Vec<T>,
 }
 
@@ -29,25 +32,50 @@ where
 impl<T: TreeHash + Clone> PackedLeaf<T> {
     pub fn tree_hash(&self) -> Hash256 {
         let read_lock = self.hash.read();
-        let mut hash = *read_lock;
+        let hash = *read_lock;
         drop(read_lock);
 
         if !hash.is_zero() {
             return hash;
         }
 
+        let hash = self.compute_hash();
+        *self.hash.write() = hash;
+        trace::node_rewritten();
+        hash
+    }
+
+    /// Compute the hash of the leaf, ignoring any cached hash.
+    pub(crate) fn compute_hash(&self) -> Hash256 {
+        let mut hash = Hash256::zero();
         let hash_bytes = hash.as_bytes_mut();
 
-        let value_len = BYTES_PER_CHUNK / T::tree_hash_packing_factor();
-        for (i, value) in self.values.iter().enumerate() {
-            hash_bytes[i * value_len..(i + 1) * value_len]
-                .copy_from_slice(&value.tree_hash_packed_encoding());
+        let packing_factor = T::tree_hash_packing_factor();
+        if packing_factor == 1 {
+            // Values which fill an entire chunk (e.g. `U256`) are their own hash.
+            if let Some(value) = self.values.first() {
+                copy_packed_encoding(hash_bytes, value);
+            }
+            return hash;
+        }
+
+        // Each value occupies an equal slot of the chunk. Values shorter than their slot are
+        // padded with zeroes.
+        for (slot, value) in hash_bytes
+            .chunks_exact_mut(Self::value_len())
+            .zip(&self.values)
+        {
+            copy_packed_encoding(slot, value);
         }
 
-        *self.hash.write() = hash;
         hash
     }
 
+    /// Number of bytes of the chunk occupied by each value.
+    pub fn value_len() -> usize {
+        BYTES_PER_CHUNK / T::tree_hash_packing_factor()
+    }
+
     pub fn empty() -> Self {
         PackedLeaf {
             hash: RwLock::new(Hash256::zero()),
@@ -56,7 +84,12 @@ impl<T: TreeHash + Clone> PackedLeaf<T> {
     }
 
     pub fn single(value: T) -> Self {
-        let mut values = Vec::with_capacity(T::tree_hash_packing_factor());
+        Self::single_with_capacity(value, T::tree_hash_packing_factor())
+    }
+
+    /// Create a leaf containing `value`, with space for `capacity` values in total.
+    pub fn single_with_capacity(value: T, capacity: usize) -> Self {
+        let mut values = Vec::with_capacity(capacity);
         values.push(value);
 
         PackedLeaf {
@@ -73,6 +106,14 @@ impl<T: TreeHash + Clone> PackedLeaf<T> {
         }
     }
 
+    /// Create a new packed leaf containing only the first `len` values of this one.
+    pub fn truncated(&self, len: usize) -> Self {
+        PackedLeaf {
+            hash: RwLock::new(Hash256::zero()),
+            values: self.values[..std::cmp::min(len, self.values.len())].to_vec(),
+        }
+    }
+
     pub fn insert_at_index(&self, index: usize, value: T) -> Result<Self, Error> {
         let mut updated = PackedLeaf {
             hash: RwLock::new(Hash256::zero()),
@@ -130,3 +171,39 @@ impl<T: TreeHash + Clone> PackedLeaf<T> {
         Ok(())
     }
 }
+
+impl<T: Encode + TreeHash + Clone> PackedLeaf<T> {
+    /// Check that values of type `T` can be packed into leaves without overlapping.
+    ///
+    /// The packing factor must be a power of two no greater than 32, so that the leaf containing
+    /// an index can be found from its bits. Each value's fixed SSZ length must fit within its
+    /// share of the chunk.
+    pub fn check_layout() -> Result<(), Error> {
+        let packing_factor = T::tree_hash_packing_factor();
+        let ssz_len = if T::is_ssz_fixed_len() {
+            T::ssz_fixed_len()
+        } else {
+            usize::MAX
+        };
+        if !packing_factor.is_power_of_two()
+            || packing_factor > BYTES_PER_CHUNK
+            || ssz_len > Self::value_len()
+        {
+            return Err(Error::InvalidPackedLayout {
+                packing_factor,
+                ssz_len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Copy the packed encoding of `value` into the start of `slot`.
+///
+/// Encodings longer than the slot are truncated rather than overwriting the neighbouring values,
+/// although `check_layout` rejects such types before any leaves are created.
+fn copy_packed_encoding<T: TreeHash>(slot: &mut [u8], value: &T) {
+    let encoding = value.tree_hash_packed_encoding();
+    let len = std::cmp::min(encoding.len(), slot.len());
+    slot[..len].copy_from_slice(&encoding[..len]);
+}
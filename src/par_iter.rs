@@ -0,0 +1,198 @@
+use crate::interface_iter::InterfaceIter;
+use crate::iter::Iter;
+use crate::utils::{opt_packing_depth, Length};
+use crate::{Error, List, Tree, UpdateMap, Value, Vector};
+use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use typenum::Unsigned;
+
+/// Parallel iterator over the elements of a `List` or `Vector`.
+///
+/// Work is split along subtree boundaries, so each thread walks a contiguous region of the tree.
+#[derive(Debug)]
+pub struct ParIter<'a, T: Value, U: UpdateMap<T>> {
+    producer: SubtreeProducer<'a, T, U>,
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> ParIter<'a, T, U> {
+    pub(crate) fn new(tree: &'a Tree<T>, depth: usize, updates: &'a U, length: usize) -> Self {
+        Self {
+            producer: SubtreeProducer {
+                tree,
+                depth,
+                packing_depth: opt_packing_depth::<T>().unwrap_or(0),
+                updates,
+                offset: 0,
+                start: 0,
+                end: length,
+            },
+        }
+    }
+}
+
+impl<'a, T, U> ParallelIterator for ParIter<'a, T, U>
+where
+    T: Value + Send + Sync,
+    U: UpdateMap<T> + Sync,
+{
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self.producer, consumer)
+    }
+}
+
+/// Producer for the elements in `start..end` of a subtree whose first element is at `offset`.
+#[derive(Debug)]
+struct SubtreeProducer<'a, T: Value, U: UpdateMap<T>> {
+    tree: &'a Tree<T>,
+    depth: usize,
+    packing_depth: usize,
+    updates: &'a U,
+    offset: usize,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T, U> UnindexedProducer for SubtreeProducer<'a, T, U>
+where
+    T: Value + Send + Sync,
+    U: UpdateMap<T> + Sync,
+{
+    type Item = &'a T;
+
+    fn split(mut self) -> (Self, Option<Self>) {
+        // Descend until the range straddles the two children of the current node.
+        while self.depth > 0 && self.end - self.start > 1 {
+            let Tree::Node { left, right, .. } = self.tree else {
+                break;
+            };
+            let depth = self.depth - 1;
+            let mid = self.offset + (1 << (depth + self.packing_depth));
+
+            if self.end <= mid {
+                self.tree = left;
+                self.depth = depth;
+            } else if self.start >= mid {
+                self.tree = right;
+                self.depth = depth;
+                self.offset = mid;
+            } else {
+                let right = Self {
+                    tree: right,
+                    depth,
+                    offset: mid,
+                    start: mid,
+                    ..self
+                };
+                let left = Self {
+                    tree: left,
+                    depth,
+                    end: mid,
+                    ..self
+                };
+                return (left, Some(right));
+            }
+        }
+        (self, None)
+    }
+
+    fn fold_with<F>(self, folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        let tree_iter = Iter::from_index(
+            self.start - self.offset,
+            self.tree,
+            self.depth,
+            Length(self.end - self.offset),
+        );
+        let iter = InterfaceIter {
+            tree_iter,
+            updates: self.updates,
+            index: self.start,
+            length: self.end,
+        };
+        folder.consume_iter(iter.take(self.end - self.start))
+    }
+}
+
+impl<T, N, U> List<T, N, U>
+where
+    T: Value + Send + Sync,
+    N: Unsigned,
+    U: UpdateMap<T> + Sync,
+{
+    /// Iterate over the elements of this list in parallel, including any pending updates.
+    pub fn par_iter(&self) -> ParIter<'_, T, U> {
+        let interface = &self.interface;
+        ParIter::new(
+            &interface.backing.tree,
+            interface.backing.depth,
+            &interface.updates,
+            self.len(),
+        )
+    }
+
+    /// Create a new list by applying `f` to each element in parallel.
+    ///
+    /// The mapped values are collected and then built into a tree with
+    /// `List::from_slice_parallel`, so this uses more memory than `List::map`.
+    pub fn par_map<V, F>(&self, f: F) -> Result<List<V, N>, Error>
+    where
+        V: Value + Send + Sync,
+        F: Fn(&T) -> V + Sync + Send,
+    {
+        let values = self.par_iter().map(f).collect::<Vec<_>>();
+        List::from_slice_parallel(&values)
+    }
+}
+
+impl<T, N, U> Vector<T, N, U>
+where
+    T: Value + Send + Sync,
+    N: Unsigned,
+    U: UpdateMap<T> + Sync,
+{
+    /// Iterate over the elements of this vector in parallel, including any pending updates.
+    pub fn par_iter(&self) -> ParIter<'_, T, U> {
+        let interface = &self.interface;
+        ParIter::new(
+            &interface.backing.tree,
+            interface.backing.depth,
+            &interface.updates,
+            self.len(),
+        )
+    }
+}
+
+impl<'a, T, N, U> IntoParallelIterator for &'a List<T, N, U>
+where
+    T: Value + Send + Sync,
+    N: Unsigned,
+    U: UpdateMap<T> + Sync,
+{
+    type Iter = ParIter<'a, T, U>;
+    type Item = &'a T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter()
+    }
+}
+
+impl<'a, T, N, U> IntoParallelIterator for &'a Vector<T, N, U>
+where
+    T: Value + Send + Sync,
+    N: Unsigned,
+    U: UpdateMap<T> + Sync,
+{
+    type Iter = ParIter<'a, T, U>;
+    type Item = &'a T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter()
+    }
+}
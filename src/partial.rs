@@ -0,0 +1,187 @@
+use crate::gindex::{concat_gindices, gindex_at_depth, LIST_DATA_GINDEX, LIST_LENGTH_GINDEX};
+use crate::proof::multiproof_nodes;
+use crate::utils::{opt_packing_depth, opt_packing_factor};
+use crate::{Error, List, MultiProof, PackedLeaf, Value};
+use ssz::Decode;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use tree_hash::{Hash256, BYTES_PER_CHUNK};
+use typenum::Unsigned;
+
+/// Nodes of a tree with a known root, proven by one or more multiproofs.
+#[derive(Debug, Clone, PartialEq)]
+struct Witness {
+    root: Hash256,
+    nodes: BTreeMap<usize, Hash256>,
+}
+
+impl Witness {
+    fn new(root: Hash256) -> Self {
+        Self {
+            root,
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    /// Verify `proof` against the root, and record all of the nodes it proves.
+    fn absorb(&mut self, proof: &MultiProof) -> Result<(), Error> {
+        let nodes = multiproof_nodes(proof)
+            .filter(|nodes| nodes.get(&1) == Some(&self.root))
+            .ok_or(Error::InvalidMultiProof)?;
+        self.nodes.extend(nodes);
+        Ok(())
+    }
+
+    /// Decode the `index`th element from the chunk at `gindex`.
+    fn get<T: Value>(&self, index: usize, gindex: usize) -> Result<T, Error> {
+        let chunk = self
+            .nodes
+            .get(&gindex)
+            .ok_or(Error::UnprovenIndex { index })?;
+        let value = if let Some(packing_factor) = opt_packing_factor::<T>() {
+            // The layout guarantees that every value lies within the chunk.
+            PackedLeaf::<T>::check_layout()?;
+            let value_len = BYTES_PER_CHUNK / packing_factor;
+            let start = (index % packing_factor) * value_len;
+            let ssz_len = <T as Decode>::ssz_fixed_len();
+            T::from_ssz_bytes(&chunk.as_bytes()[start..start + ssz_len]).ok()
+        } else {
+            // Values whose hash is their own encoding (e.g. `Hash256`) can be recovered from the
+            // chunk, but in general the chunk is only the root of the value.
+            T::from_ssz_bytes(chunk.as_bytes())
+                .ok()
+                .filter(|value| value.tree_hash_root() == *chunk)
+        };
+        value.ok_or(Error::PartialValueUnavailable { index })
+    }
+}
+
+/// Check that packed values of type `T` fit in their chunks, before locating them.
+fn check_layout<T: Value>() -> Result<(), Error> {
+    if opt_packing_factor::<T>().is_some() {
+        PackedLeaf::<T>::check_layout()?;
+    }
+    Ok(())
+}
+
+/// List whose contents are only partially known, from Merkle proofs against its root.
+///
+/// This allows values to be read from a list which is committed to by a root (e.g. in a block
+/// header) without having the whole list, as in stateless verification. Only the elements whose
+/// chunks have been proven are available, and element values can only be recovered from their
+/// chunks for packed types and types which are their own tree hash (like `Hash256`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialList<T: Value, N: Unsigned> {
+    witness: Witness,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<T: Value, N: Unsigned> PartialList<T, N> {
+    /// Create a partial list with the given root, which must be proven by `proof`.
+    pub fn from_multiproof(root: Hash256, proof: &MultiProof) -> Result<Self, Error> {
+        let mut list = Self {
+            witness: Witness::new(root),
+            _phantom: PhantomData,
+        };
+        list.add_multiproof(proof)?;
+        Ok(list)
+    }
+
+    /// Add the chunks proven by another multiproof against the same root.
+    ///
+    /// Errors without modifying `self` if the proof is invalid.
+    pub fn add_multiproof(&mut self, proof: &MultiProof) -> Result<(), Error> {
+        self.witness.absorb(proof)
+    }
+
+    pub fn root(&self) -> Hash256 {
+        self.witness.root
+    }
+
+    /// The length of the list, if its length chunk has been proven.
+    ///
+    /// Proofs from `List::prove` always include the length chunk.
+    pub fn len(&self) -> Option<usize> {
+        let chunk = self.witness.nodes.get(&LIST_LENGTH_GINDEX)?;
+        let mut bytes = [0; std::mem::size_of::<usize>()];
+        let len = bytes.len();
+        bytes.copy_from_slice(&chunk.as_bytes()[..len]);
+        Some(usize::from_le_bytes(bytes))
+    }
+
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Return the `index`th element, if its chunk has been proven.
+    ///
+    /// Errors with `UnprovenIndex` if the chunk hasn't been proven, or `OutOfBoundsProof` if
+    /// `index` is known to be out of bounds.
+    pub fn get(&self, index: usize) -> Result<T, Error> {
+        let len = self.len().unwrap_or(N::to_usize());
+        if index >= len {
+            return Err(Error::OutOfBoundsProof { index, len });
+        }
+        check_layout::<T>()?;
+        let chunk_index = index >> opt_packing_depth::<T>().unwrap_or(0);
+        let gindex = gindex_at_depth(List::<T, N>::depth(), chunk_index)
+            .and_then(|gindex| concat_gindices(LIST_DATA_GINDEX, gindex))
+            .ok_or(Error::UnprovenIndex { index })?;
+        self.witness.get(index, gindex)
+    }
+}
+
+/// Vector whose contents are only partially known, from Merkle proofs against its root.
+///
+/// See `PartialList`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialVector<T: Value, N: Unsigned> {
+    witness: Witness,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<T: Value, N: Unsigned> PartialVector<T, N> {
+    /// Create a partial vector with the given root, which must be proven by `proof`.
+    pub fn from_multiproof(root: Hash256, proof: &MultiProof) -> Result<Self, Error> {
+        let mut vector = Self {
+            witness: Witness::new(root),
+            _phantom: PhantomData,
+        };
+        vector.add_multiproof(proof)?;
+        Ok(vector)
+    }
+
+    /// Add the chunks proven by another multiproof against the same root.
+    ///
+    /// Errors without modifying `self` if the proof is invalid.
+    pub fn add_multiproof(&mut self, proof: &MultiProof) -> Result<(), Error> {
+        self.witness.absorb(proof)
+    }
+
+    pub fn root(&self) -> Hash256 {
+        self.witness.root
+    }
+
+    pub fn len(&self) -> usize {
+        N::to_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the `index`th element, if its chunk has been proven.
+    pub fn get(&self, index: usize) -> Result<T, Error> {
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsProof {
+                index,
+                len: self.len(),
+            });
+        }
+        check_layout::<T>()?;
+        let chunk_index = index >> opt_packing_depth::<T>().unwrap_or(0);
+        let gindex = gindex_at_depth(List::<T, N>::depth(), chunk_index)
+            .ok_or(Error::UnprovenIndex { index })?;
+        self.witness.get(index, gindex)
+    }
+}
@@ -0,0 +1,229 @@
+use crate::builder::Builder;
+use crate::iter::Iter;
+use crate::proof::{hash_pair, length_chunk};
+use crate::utils::{opt_packing_depth, opt_packing_factor, ssz_append_variable, Length};
+use crate::{Arc, Error, MaybeSync, Tree, Value};
+use ssz::{Decode, Encode};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+
+/// Persistent list without a maximum length, using EIP-7916 progressive merkleization.
+///
+/// The chunks of the list are stored in a sequence of subtrees holding 1, 4, 16, ... chunks. The
+/// root of the list is computed by hashing each subtree's root with the root of all subsequent
+/// subtrees, and mixing in the length.
+///
+/// Unlike `List`, updates are applied immediately rather than being buffered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressiveList<T: Value> {
+    /// Subtrees in order, the `k`th having depth `2 * k`.
+    subtrees: Vec<Arc<Tree<T>>>,
+    length: Length,
+}
+
+impl<T: Value> Default for ProgressiveList<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// Depth of the `k`th subtree, measured in chunks.
+fn subtree_depth(k: usize) -> usize {
+    2 * k
+}
+
+/// Index of the first chunk stored in the `k`th subtree.
+fn subtree_start(k: usize) -> usize {
+    ((1 << subtree_depth(k)) - 1) / 3
+}
+
+fn packing_factor<T: Value>() -> usize {
+    opt_packing_factor::<T>().unwrap_or(1)
+}
+
+/// Find the subtree containing the `index`th element, and the element's index within it.
+fn locate<T: Value>(index: usize) -> (usize, usize) {
+    let chunk = index / packing_factor::<T>();
+    let mut k = 0;
+    while subtree_start(k + 1) <= chunk {
+        k += 1;
+    }
+    (k, index - subtree_start(k) * packing_factor::<T>())
+}
+
+impl<T: Value> ProgressiveList<T> {
+    pub fn empty() -> Self {
+        Self {
+            subtrees: vec![],
+            length: Length(0),
+        }
+    }
+
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        Self::try_from_iter(vec)
+    }
+
+    pub fn try_from_iter(iter: impl IntoIterator<Item = T>) -> Result<Self, Error> {
+        let mut iter = iter.into_iter().peekable();
+        let mut subtrees = vec![];
+        let mut length = 0;
+
+        while iter.peek().is_some() {
+            let depth = subtree_depth(subtrees.len());
+            let capacity = (1 << depth) * packing_factor::<T>();
+            let mut builder = Builder::new(depth, 0);
+            for item in iter.by_ref().take(capacity) {
+                builder.push(item)?;
+            }
+            let (tree, _, subtree_length) = builder.finish()?;
+            subtrees.push(tree);
+            length += subtree_length.as_usize();
+        }
+
+        Ok(Self {
+            subtrees,
+            length: Length(length),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.length.as_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        let (k, sub_index) = locate::<T>(index);
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        self.subtrees
+            .get(k)?
+            .get_recursive(sub_index, subtree_depth(k), packing_depth)
+    }
+
+    /// Replace the `index`th element with `value`.
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsUpdate {
+                index,
+                len: self.len(),
+            });
+        }
+        self.update(index, value)
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        let index = self.len();
+        let (k, _) = locate::<T>(index);
+        if k == self.subtrees.len() {
+            self.subtrees.push(Tree::zero(subtree_depth(k)));
+        }
+        self.update(index, value)?;
+        *self.length.as_mut() += 1;
+        Ok(())
+    }
+
+    fn update(&mut self, index: usize, value: T) -> Result<(), Error> {
+        let (k, sub_index) = locate::<T>(index);
+        let subtree = self.subtrees.get_mut(k).ok_or(Error::OutOfBoundsUpdate {
+            index,
+            len: self.length.as_usize(),
+        })?;
+        *subtree = subtree.with_updated_leaf(sub_index, value, subtree_depth(k))?;
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let len = self.len();
+        self.subtrees.iter().enumerate().flat_map(move |(k, tree)| {
+            let start = subtree_start(k) * packing_factor::<T>();
+            let sub_len = len.saturating_sub(start);
+            Iter::from_index(0, tree, subtree_depth(k), Length(sub_len))
+        })
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Value + MaybeSync> TreeHash for ProgressiveList<T> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("ProgressiveList should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("ProgressiveList should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let root = self
+            .subtrees
+            .iter()
+            .rev()
+            .fold(Hash256::zero(), |rest, subtree| {
+                hash_pair(&rest, &subtree.tree_hash())
+            });
+        hash_pair(&root, &length_chunk(self.len()))
+    }
+}
+
+impl<T: Value> Encode for ProgressiveList<T> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if <T as Encode>::is_ssz_fixed_len() {
+            <T as Encode>::ssz_fixed_len() * self.len()
+        } else {
+            self.iter()
+                .map(|item| ssz::BYTES_PER_LENGTH_OFFSET + item.ssz_bytes_len())
+                .sum()
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if <T as Encode>::is_ssz_fixed_len() {
+            buf.reserve(<T as Encode>::ssz_fixed_len() * self.len());
+            for item in self.iter() {
+                item.ssz_append(buf);
+            }
+        } else {
+            ssz_append_variable(|| self.iter(), self.len(), buf);
+        }
+    }
+}
+
+impl<T: Value> Decode for ProgressiveList<T> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        let items = if bytes.is_empty() {
+            vec![]
+        } else if <T as Decode>::is_ssz_fixed_len() {
+            let item_len = <T as Decode>::ssz_fixed_len();
+            if item_len == 0 {
+                return Err(ssz::DecodeError::ZeroLengthItem);
+            }
+            bytes
+                .chunks(item_len)
+                .map(T::from_ssz_bytes)
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            ssz::decode_list_of_variable_length_items(bytes, None)?
+        };
+        Self::new(items).map_err(|e| {
+            ssz::DecodeError::BytesInvalid(format!("Error building ProgressiveList: {:?}", e))
+        })
+    }
+}
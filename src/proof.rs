@@ -0,0 +1,244 @@
+use crate::{Error, MaybeSync, MerkleHasher, Sha256Hasher, Tree, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use tree_hash::{Hash256, BYTES_PER_CHUNK};
+
+/// Merkle proof for a single chunk of a `List` or `Vector`.
+///
+/// For packed element types the `leaf` is the whole 32-byte chunk containing the element, so
+/// callers wishing to check a particular value should also check its bytes within the chunk.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Proof {
+    /// Generalized index of `leaf` within the tree.
+    pub gindex: usize,
+    /// Hash of the chunk being proven.
+    pub leaf: Hash256,
+    /// Sibling hashes ordered from the leaf to the root.
+    pub branch: Vec<Hash256>,
+}
+
+/// Verify that `proof` is a valid Merkle proof of its leaf against `root`.
+pub fn verify_proof(root: Hash256, proof: &Proof) -> bool {
+    // The depth of the leaf is the position of the most significant bit of its gindex.
+    if proof.gindex == 0 || proof.gindex.ilog2() as usize != proof.branch.len() {
+        return false;
+    }
+
+    let mut gindex = proof.gindex;
+    let mut node = proof.leaf;
+    for sibling in &proof.branch {
+        node = if gindex & 1 == 0 {
+            hash_pair(&node, sibling)
+        } else {
+            hash_pair(sibling, &node)
+        };
+        gindex >>= 1;
+    }
+    node == root
+}
+
+/// Hash two sibling nodes to produce their parent.
+pub fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+    Sha256Hasher::hash_pair(left, right)
+}
+
+/// Compute the chunk that is mixed into a list's root to commit to its length.
+pub fn length_chunk(length: usize) -> Hash256 {
+    let usize_len = std::mem::size_of::<usize>();
+
+    let mut length_bytes = [0; BYTES_PER_CHUNK];
+    length_bytes[0..usize_len].copy_from_slice(&length.to_le_bytes());
+    Hash256::from(length_bytes)
+}
+
+/// Merkle multiproof for a set of chunks of a `List` or `Vector`.
+///
+/// The leaves are sorted by generalized index, and the helper nodes are ordered by descending
+/// generalized index, as computed by `helper_indices`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MultiProof {
+    /// Generalized indices of the leaves, sorted and deduplicated.
+    pub gindices: Vec<usize>,
+    /// Hashes of the chunks being proven, in the same order as `gindices`.
+    pub leaves: Vec<Hash256>,
+    /// Hashes of the additional nodes required to compute the root.
+    pub helpers: Vec<Hash256>,
+}
+
+impl MultiProof {
+    /// Combine several single-chunk proofs into a multiproof, omitting any redundant nodes.
+    pub fn from_proofs(proofs: &[Proof]) -> Result<Self, Error> {
+        let mut leaves = BTreeMap::new();
+        let mut nodes = BTreeMap::new();
+
+        for proof in proofs {
+            leaves.insert(proof.gindex, proof.leaf);
+
+            let mut gindex = proof.gindex;
+            for sibling in &proof.branch {
+                nodes.insert(gindex ^ 1, *sibling);
+                gindex >>= 1;
+            }
+        }
+
+        let gindices = leaves.keys().copied().collect::<Vec<_>>();
+        let helpers = helper_indices(&gindices)
+            .into_iter()
+            .map(|gindex| {
+                nodes
+                    .get(&gindex)
+                    .copied()
+                    .ok_or(Error::MultiProofMissingNode { gindex })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            gindices,
+            leaves: leaves.into_values().collect(),
+            helpers,
+        })
+    }
+}
+
+/// Compute the generalized indices of the helper nodes required to prove `gindices`.
+///
+/// The result is sorted in descending order, and excludes any nodes that can be computed from
+/// the leaves themselves.
+pub fn helper_indices(gindices: &[usize]) -> Vec<usize> {
+    let mut branches = BTreeSet::new();
+    let mut paths = BTreeSet::new();
+
+    for &gindex in gindices {
+        let mut gindex = gindex;
+        while gindex > 1 {
+            branches.insert(gindex ^ 1);
+            paths.insert(gindex);
+            gindex >>= 1;
+        }
+        paths.insert(gindex);
+    }
+
+    let mut helpers = branches.difference(&paths).copied().collect::<Vec<_>>();
+    helpers.reverse();
+    helpers
+}
+
+/// Verify that `proof` is a valid Merkle multiproof of its leaves against `root`.
+pub fn verify_multiproof(root: Hash256, proof: &MultiProof) -> bool {
+    multiproof_nodes(proof).is_some_and(|nodes| nodes.get(&1) == Some(&root))
+}
+
+/// Compute a multiproof for the chunks containing each of the elements in `indices`, walking
+/// `tree` only once.
+///
+/// The tree is taken to be at `gindex`, and `extra_helpers` are included as additional helper
+/// nodes (e.g. the length chunk of a list).
+pub(crate) fn prove_tree_indices<T: Value + MaybeSync>(
+    tree: &Tree<T>,
+    depth: usize,
+    gindex: usize,
+    packing_depth: usize,
+    len: usize,
+    indices: &[usize],
+    extra_helpers: &[(usize, Hash256)],
+) -> Result<MultiProof, Error> {
+    if let Some(&index) = indices.iter().find(|&&index| index >= len) {
+        return Err(Error::OutOfBoundsProof { index, len });
+    }
+    if indices.is_empty() {
+        return Ok(MultiProof {
+            gindices: vec![],
+            leaves: vec![],
+            helpers: vec![],
+        });
+    }
+
+    let mut chunk_indices = indices
+        .iter()
+        .map(|&index| index >> packing_depth)
+        .collect::<Vec<_>>();
+    chunk_indices.sort_unstable();
+    chunk_indices.dedup();
+
+    let mut leaves = Vec::with_capacity(chunk_indices.len());
+    let mut helpers = extra_helpers.iter().copied().collect::<BTreeMap<_, _>>();
+    tree.prove_chunks(&chunk_indices, depth, gindex, &mut leaves, &mut helpers)?;
+
+    Ok(MultiProof {
+        gindices: chunk_indices
+            .into_iter()
+            .map(|chunk_index| (gindex << depth) + chunk_index)
+            .collect(),
+        leaves,
+        helpers: helpers.into_values().rev().collect(),
+    })
+}
+
+/// Compute the hashes of every node which can be derived from `proof`, indexed by generalized
+/// index.
+///
+/// Returns `None` if the proof is malformed, including if any of its leaves is an ancestor of
+/// another, or if any node it provides doesn't match the hash computed from its children. The
+/// root is at index 1 if the proof is complete.
+pub(crate) fn multiproof_nodes(proof: &MultiProof) -> Option<BTreeMap<usize, Hash256>> {
+    if proof.gindices.len() != proof.leaves.len()
+        || proof.gindices.contains(&0)
+        || proof.gindices.windows(2).any(|pair| pair[0] >= pair[1])
+    {
+        return None;
+    }
+
+    // A leaf which is an ancestor of another leaf would never be recomputed, so the proof of the
+    // leaves below it would go unchecked.
+    let gindices = proof.gindices.iter().copied().collect::<BTreeSet<_>>();
+    for &gindex in &proof.gindices {
+        let mut ancestor = gindex >> 1;
+        while ancestor >= 1 {
+            if gindices.contains(&ancestor) {
+                return None;
+            }
+            ancestor >>= 1;
+        }
+    }
+
+    let helper_gindices = helper_indices(&proof.gindices);
+    if helper_gindices.len() != proof.helpers.len() {
+        return None;
+    }
+
+    let mut nodes = proof
+        .gindices
+        .iter()
+        .copied()
+        .zip(proof.leaves.iter().copied())
+        .chain(
+            helper_gindices
+                .into_iter()
+                .zip(proof.helpers.iter().copied()),
+        )
+        .collect::<BTreeMap<_, _>>();
+
+    // Hash pairs of siblings from the bottom of the tree upwards. Parents always have a lower
+    // generalized index than their children, so processing the highest pending index first
+    // means both children of a node are known by the time the right child is processed.
+    let mut pending = nodes.keys().copied().collect::<BTreeSet<_>>();
+    while let Some(gindex) = pending.pop_last() {
+        if gindex <= 1 || gindex & 1 == 0 {
+            continue;
+        }
+        let (Some(left), Some(right)) = (nodes.get(&(gindex - 1)), nodes.get(&gindex)) else {
+            continue;
+        };
+        let parent = hash_pair(left, right);
+        match nodes.get(&(gindex >> 1)) {
+            // A provided node which can also be computed must match.
+            Some(provided) if *provided != parent => return None,
+            Some(_) => {}
+            None => {
+                nodes.insert(gindex >> 1, parent);
+                pending.insert(gindex >> 1);
+            }
+        }
+    }
+
+    Some(nodes)
+}
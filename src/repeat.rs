@@ -1,16 +1,25 @@
 use crate::utils::{opt_packing_factor, Length};
-use crate::{Arc, Error, Leaf, List, PackedLeaf, Tree, UpdateMap, Value};
+use crate::{Error, List, PackedLeaf, Tree, UpdateMap, Value};
 use smallvec::{smallvec, SmallVec};
 use tree_hash::Hash256;
 use typenum::Unsigned;
 
 /// Efficiently construct a list from `n` copies of `elem`.
+///
+/// Identical subtrees are shared, so the tree has at most two distinct nodes per level and uses
+/// O(log n) memory.
 pub fn repeat_list<T, N, U>(elem: T, n: usize) -> Result<List<T, N, U>, Error>
 where
     T: Value,
     N: Unsigned,
     U: UpdateMap<T>,
 {
+    if n > N::to_usize() {
+        return Err(Error::ListFull {
+            len: 0,
+            max_len: N::to_usize(),
+        });
+    }
     if n == 0 {
         return Ok(List::empty());
     }
@@ -22,13 +31,11 @@ where
     let tree_depth = List::<T, N, U>::depth();
 
     let mut layer: SmallVec<[_; 2]> = if let Some(packing_factor) = packing_factor {
+        PackedLeaf::<T>::check_layout()?;
         let repeat_count = n / packing_factor;
         let lonely_count = n % packing_factor;
-        let repeat_leaf = Arc::new(Tree::PackedLeaf(PackedLeaf::repeat(
-            elem.clone(),
-            packing_factor,
-        )));
-        let lonely_leaf = Arc::new(Tree::PackedLeaf(PackedLeaf::repeat(elem, lonely_count)));
+        let repeat_leaf = Tree::packed_leaf(PackedLeaf::repeat(elem.clone(), packing_factor));
+        let lonely_leaf = Tree::packed_leaf(PackedLeaf::repeat(elem, lonely_count));
         match (repeat_count, lonely_count) {
             (0, 0) => unreachable!("n != 0"),
             (_, 0) => smallvec![(repeat_leaf, repeat_count)],
@@ -38,7 +45,7 @@ where
             }
         }
     } else {
-        smallvec![(Arc::new(Tree::Leaf(Leaf::new(elem))), n)]
+        smallvec![(Tree::leaf(elem), n)]
     };
 
     for depth in 0..tree_depth {
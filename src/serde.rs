@@ -1,6 +1,7 @@
 use crate::{List, UpdateMap, Value};
 use itertools::process_results;
 use serde::Deserialize;
+use ssz::Decode;
 use std::marker::PhantomData;
 use typenum::Unsigned;
 
@@ -42,3 +43,130 @@ where
         )?
     }
 }
+
+/// Visitor for the compact representation: a byte string containing the SSZ encoding.
+///
+/// Sequences of bytes are also accepted, for formats without native byte strings.
+pub struct SszBytesVisitor<T> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Default for SszBytesVisitor<T> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Decode> SszBytesVisitor<T> {
+    fn decode<E: serde::de::Error>(bytes: &[u8]) -> Result<T, E> {
+        T::from_ssz_bytes(bytes)
+            .map_err(|e| E::custom(format!("Error decoding SSZ bytes: {:?}", e)))
+    }
+}
+
+impl<'a, T: Decode> serde::de::Visitor<'a> for SszBytesVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "SSZ bytes")
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<T, E> {
+        Self::decode(bytes)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+    where
+        A: serde::de::SeqAccess<'a>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Self::decode(&bytes)
+    }
+}
+
+/// Visitor for the human-readable representation of bitfields and byte lists: a 0x-prefixed
+/// hex string containing the SSZ encoding.
+pub struct HexVisitor<T> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Default for HexVisitor<T> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Decode> serde::de::Visitor<'_> for HexVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a 0x-prefixed hex string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<T, E> {
+        let bytes = decode_hex(s).map_err(E::custom)?;
+        SszBytesVisitor::<T>::decode(&bytes)
+    }
+}
+
+/// Encode `bytes` as a 0x-prefixed lowercase hex string.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    for byte in bytes {
+        s.push_str(&format!("{byte:02x}"));
+    }
+    s
+}
+
+/// Decode a 0x-prefixed hex string.
+pub fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+    let hex = s
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("hex string must start with 0x: {s}"))?;
+    if hex.len() % 2 != 0 {
+        return Err(format!("hex string has odd length: {s}"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| format!("invalid hex string: {s}"))
+        })
+        .collect()
+}
+
+/// Serialize a wrapper type such as `ByteList` using the representation of the type it
+/// dereferences to, for use with `#[serde(with = "milhouse::serde::as_seq")]`.
+///
+/// For byte lists and vectors this gives an array of numbers rather than a hex string.
+pub mod as_seq {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::ops::Deref;
+
+    pub fn serialize<B, S>(value: &B, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        B: Deref,
+        B::Target: Serialize,
+        S: Serializer,
+    {
+        value.deref().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, B, D>(deserializer: D) -> Result<B, D::Error>
+    where
+        B: Deref,
+        B::Target: Deserialize<'de> + Sized + Into<B>,
+        D: Deserializer<'de>,
+    {
+        B::Target::deserialize(deserializer).map(Into::into)
+    }
+}
@@ -0,0 +1,94 @@
+use parking_lot::Mutex;
+use std::collections::{btree_map::Entry, BTreeMap};
+
+/// Default number of shards used by `ShardedUpdates::new`.
+pub const DEFAULT_SHARDS: usize = 64;
+
+/// Buffer of updates which can be written to by many threads at once.
+///
+/// Updates are spread across several independently locked maps according to their index, so
+/// threads writing to different indices rarely contend. Once all writers are done, the buffer is
+/// merged into a list's pending updates with `List::merge_updates` (or `Vector::merge_updates`)
+/// and applied to the tree with a single `apply_updates`.
+///
+/// `UpdateMap` can't be implemented for this type directly because it hands out references to
+/// values, which can't outlive the lock on their shard.
+#[derive(Debug)]
+pub struct ShardedUpdates<T> {
+    shards: Vec<Mutex<BTreeMap<usize, T>>>,
+}
+
+impl<T> Default for ShardedUpdates<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ShardedUpdates<T> {
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Create a buffer with `num_shards` shards (at least 1).
+    pub fn with_shards(num_shards: usize) -> Self {
+        Self {
+            shards: (0..std::cmp::max(num_shards, 1))
+                .map(|_| Mutex::new(BTreeMap::new()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, index: usize) -> &Mutex<BTreeMap<usize, T>> {
+        &self.shards[index % self.shards.len()]
+    }
+
+    /// Stage `value` to be written at `index`, returning any value previously staged there.
+    pub fn insert(&self, index: usize, value: T) -> Option<T> {
+        self.shard(index).lock().insert(index, value)
+    }
+
+    /// Modify the value staged at `index`, staging `f(index)` first if there isn't one.
+    ///
+    /// Typically `f` reads the current value from the list. Nothing is staged if `f` returns
+    /// `None`.
+    pub fn update_with<R>(
+        &self,
+        index: usize,
+        f: impl FnOnce(usize) -> Option<T>,
+        update: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let mut shard = self.shard(index).lock();
+        let value = match shard.entry(index) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f(index)?),
+        };
+        Some(update(value))
+    }
+
+    /// Return a copy of the value staged at `index`, if any.
+    pub fn get_cloned(&self, index: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.shard(index).lock().get(&index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.lock().is_empty())
+    }
+
+    /// Consume the buffer, returning the staged updates in ascending order of index.
+    pub fn into_sorted_vec(self) -> Vec<(usize, T)> {
+        let mut updates = self
+            .shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner())
+            .collect::<Vec<_>>();
+        updates.sort_unstable_by_key(|(index, _)| *index);
+        updates
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{Error, List, MaybeSync, Value};
+use itertools::Either;
+use ssz::{Decode, Encode};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// List which stores up to `K` elements inline, and only builds a tree once it grows beyond
+/// that.
+///
+/// Short lists are kept in a `Vec`, which avoids allocating a node per leaf and the descent from
+/// the root for every access. Pushing the `K + 1`th element promotes the list to a full `List`,
+/// which it then remains. Hashing and encoding are identical to a `List<T, N>` either way.
+#[derive(Debug, Clone)]
+pub struct SmallList<T: Value, N: Unsigned, const K: usize = 8> {
+    repr: Repr<T, N>,
+}
+
+#[derive(Debug, Clone)]
+enum Repr<T: Value, N: Unsigned> {
+    Inline(Vec<T>),
+    Tree(List<T, N>),
+}
+
+impl<T: Value, N: Unsigned, const K: usize> SmallList<T, N, K> {
+    /// Create a list from `vec`, which is stored inline if it has at most `K` elements.
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        if vec.len() > N::to_usize() {
+            return Err(Error::ListFull {
+                len: vec.len(),
+                max_len: N::to_usize(),
+            });
+        }
+        let repr = if vec.len() <= K {
+            Repr::Inline(vec)
+        } else {
+            Repr::Tree(List::new(vec)?)
+        };
+        Ok(Self { repr })
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            repr: Repr::Inline(vec![]),
+        }
+    }
+
+    /// Return `true` if the elements are stored inline rather than in a tree.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline(_))
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline(values) => values.len(),
+            Repr::Tree(list) => list.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match &self.repr {
+            Repr::Inline(values) => values.get(index),
+            Repr::Tree(list) => list.get(index),
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match &mut self.repr {
+            Repr::Inline(values) => values.get_mut(index),
+            Repr::Tree(list) => list.get_mut(index),
+        }
+    }
+
+    /// Push `value`, promoting the list to a tree if it would exceed `K` elements.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        match &mut self.repr {
+            Repr::Inline(values) if values.len() < K => {
+                if values.len() == N::to_usize() {
+                    return Err(Error::ListFull {
+                        len: values.len(),
+                        max_len: N::to_usize(),
+                    });
+                }
+                values.push(value);
+                Ok(())
+            }
+            Repr::Inline(values) => {
+                let mut list = List::new(std::mem::take(values))?;
+                let result = list.push(value);
+                self.repr = Repr::Tree(list);
+                result
+            }
+            Repr::Tree(list) => list.push(value),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        match &self.repr {
+            Repr::Inline(values) => Either::Left(values.iter()),
+            Repr::Tree(list) => Either::Right(list.iter()),
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    /// Convert into a `List`, building its tree if the elements are inline.
+    pub fn into_list(self) -> Result<List<T, N>, Error> {
+        match self.repr {
+            Repr::Inline(values) => List::new(values),
+            Repr::Tree(list) => Ok(list),
+        }
+    }
+}
+
+impl<T: Value, N: Unsigned, const K: usize> Default for SmallList<T, N, K> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: Value, N: Unsigned, const K: usize> PartialEq for SmallList<T, N, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Value, N: Unsigned, const K: usize> From<List<T, N>> for SmallList<T, N, K> {
+    /// Wrap `list`, keeping its tree even if it is short enough to store inline.
+    fn from(list: List<T, N>) -> Self {
+        Self {
+            repr: Repr::Tree(list),
+        }
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned, const K: usize> TreeHash for SmallList<T, N, K> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    /// Compute the tree hash root, hashing inline elements directly without building a tree.
+    fn tree_hash_root(&self) -> Hash256 {
+        let values = match &self.repr {
+            Repr::Inline(values) => values,
+            Repr::Tree(list) => return list.tree_hash_root(),
+        };
+        let root = if T::tree_hash_type() == TreeHashType::Basic {
+            let leaves = N::to_usize().div_ceil(T::tree_hash_packing_factor());
+            let mut hasher = tree_hash::MerkleHasher::with_leaves(leaves);
+            for value in values {
+                hasher
+                    .write(&value.tree_hash_packed_encoding())
+                    .expect("list is within its maximum length");
+            }
+            hasher.finish()
+        } else {
+            let mut hasher = tree_hash::MerkleHasher::with_leaves(N::to_usize());
+            for value in values {
+                hasher
+                    .write(value.tree_hash_root().as_bytes())
+                    .expect("list is within its maximum length");
+            }
+            hasher.finish()
+        };
+        tree_hash::mix_in_length(
+            &root.expect("list is within its maximum length"),
+            values.len(),
+        )
+    }
+}
+
+impl<T: Value, N: Unsigned, const K: usize> Encode for SmallList<T, N, K> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline(values) => values.ssz_bytes_len(),
+            Repr::Tree(list) => list.ssz_bytes_len(),
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        match &self.repr {
+            Repr::Inline(values) => values.ssz_append(buf),
+            Repr::Tree(list) => list.ssz_append(buf),
+        }
+    }
+}
+
+impl<T, N, const K: usize> Decode for SmallList<T, N, K>
+where
+    T: Value,
+    N: Unsigned,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    /// Decode a list, which is stored inline if it has at most `K` elements.
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        let list = List::<T, N>::from_ssz_bytes(bytes)?;
+        if list.len() <= K {
+            Ok(Self {
+                repr: Repr::Inline(list.to_vec()),
+            })
+        } else {
+            Ok(Self::from(list))
+        }
+    }
+}
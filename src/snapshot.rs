@@ -0,0 +1,107 @@
+use crate::{Error, List, MaybeSync, UpdateMap, Value};
+use ssz::Encode;
+use std::ops::Deref;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Read-only list which is guaranteed to have no pending updates.
+///
+/// A `List` is `Send + Sync` whenever its values are, but shared references to a list with
+/// pending updates see a mix of tree values and buffered writes, and every read has to check the
+/// update map first. A snapshot is checked for pending updates when it is created and can't be
+/// mutated, so every reader sees the same values straight from the tree. Cloning a snapshot only
+/// clones the pointer to its tree, which makes it cheap to hand a copy to each thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<T: Value, N: Unsigned> {
+    list: List<T, N>,
+}
+
+// Snapshots exist to be shared between threads, so check that they can be.
+#[cfg(not(milhouse_rc))]
+#[allow(dead_code)]
+fn assert_send_sync<T: Value + Send + Sync, N: Unsigned + Send + Sync>() {
+    fn is_send_sync<S: Send + Sync>() {}
+    is_send_sync::<Snapshot<T, N>>();
+    is_send_sync::<List<T, N>>();
+}
+
+impl<T: Value, N: Unsigned> Snapshot<T, N> {
+    /// Create a snapshot of `list`, which must not have any pending updates.
+    pub fn new<U: UpdateMap<T>>(list: List<T, N, U>) -> Result<Self, Error> {
+        if list.has_pending_updates() {
+            return Err(Error::SnapshotPendingUpdates);
+        }
+        let backing = list.interface.backing;
+        Ok(Self {
+            list: List::from_parts(backing.tree, backing.depth, backing.length),
+        })
+    }
+
+    /// Convert back into a mutable `List`, sharing the same tree.
+    pub fn into_inner(self) -> List<T, N> {
+        self.list
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> List<T, N, U> {
+    /// Take a snapshot of the list, which must not have any pending updates.
+    ///
+    /// To include pending updates, call `apply_updates` first.
+    pub fn snapshot(&self) -> Result<Snapshot<T, N>, Error> {
+        Snapshot::new(self.clone())
+    }
+}
+
+impl<T: Value, N: Unsigned> Deref for Snapshot<T, N> {
+    type Target = List<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<T: Value, N: Unsigned> TryFrom<List<T, N>> for Snapshot<T, N> {
+    type Error = Error;
+
+    fn try_from(list: List<T, N>) -> Result<Self, Error> {
+        Self::new(list)
+    }
+}
+
+impl<T: Value, N: Unsigned> From<Snapshot<T, N>> for List<T, N> {
+    fn from(snapshot: Snapshot<T, N>) -> Self {
+        snapshot.list
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> TreeHash for Snapshot<T, N> {
+    fn tree_hash_type() -> TreeHashType {
+        List::<T, N>::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.list.tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        List::<T, N>::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.list.tree_hash_root()
+    }
+}
+
+impl<T: Value, N: Unsigned> Encode for Snapshot<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        <List<T, N> as Encode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.list.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.list.ssz_append(buf)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{Arc, Bitvector, Error, Vector};
+use ssz::{Decode, DecodeError, Encode, BYTES_PER_LENGTH_OFFSET};
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Field of a `StableContainer`.
+///
+/// This is typically implemented by an enum with one variant per field, which knows how to
+/// encode, decode and hash the value of each field given its index.
+pub trait StableField: Clone + PartialEq {
+    /// Number of fields defined, which must not exceed the capacity of the container.
+    const NUM_FIELDS: usize;
+
+    /// Index of this field within the container.
+    fn index(&self) -> usize;
+
+    /// Whether the field at `index` has a fixed-length SSZ encoding.
+    fn is_ssz_fixed_len(index: usize) -> bool;
+
+    /// Length of the SSZ encoding of the field at `index`, if it is fixed-length.
+    fn ssz_fixed_len(index: usize) -> usize;
+
+    fn ssz_bytes_len(&self) -> usize;
+
+    fn ssz_append(&self, buf: &mut Vec<u8>);
+
+    /// Decode the value of the field at `index`.
+    fn from_ssz_bytes(index: usize, bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    fn tree_hash_root(&self) -> Hash256;
+}
+
+/// Persistent EIP-7495 `StableContainer` with capacity for `N` optional fields.
+///
+/// The roots of the fields are stored in a persistent tree, so clones share structure and only
+/// the path to a modified field is re-hashed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StableContainer<F: StableField, N: Unsigned> {
+    fields: Arc<Vec<Option<F>>>,
+    active_fields: Bitvector<N>,
+    roots: Vector<Hash256, N>,
+}
+
+impl<F: StableField, N: Unsigned> Default for StableContainer<F, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: StableField, N: Unsigned> StableContainer<F, N> {
+    /// Create a container with no active fields.
+    pub fn new() -> Self {
+        assert!(
+            F::NUM_FIELDS <= N::to_usize(),
+            "StableContainer has {} fields but capacity {}",
+            F::NUM_FIELDS,
+            N::to_usize()
+        );
+        Self {
+            fields: Arc::new(vec![None; F::NUM_FIELDS]),
+            active_fields: Bitvector::new(),
+            roots: Vector::from_elem(Hash256::zero())
+                .unwrap_or_else(|e| panic!("StableContainer roots failed to build: {:?}", e)),
+        }
+    }
+
+    pub fn capacity() -> usize {
+        N::to_usize()
+    }
+
+    /// Return the value of the field at `index`, if it is active.
+    pub fn get(&self, index: usize) -> Option<&F> {
+        self.fields.get(index)?.as_ref()
+    }
+
+    pub fn is_active(&self, index: usize) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// The bitvector of active fields.
+    pub fn active_fields(&self) -> &Bitvector<N> {
+        &self.active_fields
+    }
+
+    /// Iterate over the active fields in order of their indices.
+    pub fn iter(&self) -> impl Iterator<Item = &F> {
+        self.fields.iter().flatten()
+    }
+
+    /// Set the value of a field, activating it if necessary.
+    pub fn set(&mut self, field: F) -> Result<(), Error> {
+        let index = field.index();
+        let root = field.tree_hash_root();
+        self.update(index, Some(field), root)
+    }
+
+    /// Deactivate the field at `index`, returning its previous value.
+    pub fn remove(&mut self, index: usize) -> Result<Option<F>, Error> {
+        let previous = self.get(index).cloned();
+        self.update(index, None, Hash256::zero())?;
+        Ok(previous)
+    }
+
+    fn update(&mut self, index: usize, field: Option<F>, root: Hash256) -> Result<(), Error> {
+        if index >= F::NUM_FIELDS {
+            return Err(Error::StableContainerInvalidField { index });
+        }
+        self.active_fields.set(index, field.is_some())?;
+        *self
+            .roots
+            .get_mut(index)
+            .ok_or(Error::StableContainerInvalidField { index })? = root;
+        self.roots.apply_updates()?;
+        Arc::make_mut(&mut self.fields)[index] = field;
+        Ok(())
+    }
+}
+
+impl<F: StableField, N: Unsigned> Encode for StableContainer<F, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        let fields_len = self
+            .iter()
+            .map(|field| {
+                if F::is_ssz_fixed_len(field.index()) {
+                    field.ssz_bytes_len()
+                } else {
+                    BYTES_PER_LENGTH_OFFSET + field.ssz_bytes_len()
+                }
+            })
+            .sum::<usize>();
+        self.active_fields.ssz_bytes_len() + fields_len
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.active_fields.ssz_append(buf);
+
+        // The active fields are encoded as a container, with offsets relative to its start.
+        let fixed_len = self
+            .iter()
+            .map(|field| {
+                if F::is_ssz_fixed_len(field.index()) {
+                    field.ssz_bytes_len()
+                } else {
+                    BYTES_PER_LENGTH_OFFSET
+                }
+            })
+            .sum::<usize>();
+
+        let mut variable_bytes = vec![];
+        for field in self.iter() {
+            if F::is_ssz_fixed_len(field.index()) {
+                field.ssz_append(buf);
+            } else {
+                buf.extend_from_slice(&ssz::encode_length(fixed_len + variable_bytes.len()));
+                field.ssz_append(&mut variable_bytes);
+            }
+        }
+        buf.extend_from_slice(&variable_bytes);
+    }
+}
+
+impl<F: StableField, N: Unsigned> Decode for StableContainer<F, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let bitvector_len = <Bitvector<N> as Decode>::ssz_fixed_len();
+        let (active_bytes, bytes) =
+            bytes
+                .split_at_checked(bitvector_len)
+                .ok_or(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: bitvector_len,
+                })?;
+        let active_fields = Bitvector::<N>::from_ssz_bytes(active_bytes)?;
+
+        let indices = active_fields
+            .iter()
+            .enumerate()
+            .filter_map(|(index, active)| active.then_some(index))
+            .collect::<Vec<_>>();
+        if let Some(index) = indices.iter().find(|index| **index >= F::NUM_FIELDS) {
+            return Err(DecodeError::BytesInvalid(format!(
+                "StableContainer has unknown active field {}",
+                index
+            )));
+        }
+
+        // Work out where each field starts, using either its fixed length or its offset.
+        let fixed_len = indices
+            .iter()
+            .map(|index| {
+                if F::is_ssz_fixed_len(*index) {
+                    F::ssz_fixed_len(*index)
+                } else {
+                    BYTES_PER_LENGTH_OFFSET
+                }
+            })
+            .sum::<usize>();
+        if bytes.len() < fixed_len {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: fixed_len,
+            });
+        }
+
+        let mut position = 0;
+        let mut fixed_ranges = vec![];
+        let mut offsets = vec![];
+        for index in &indices {
+            if F::is_ssz_fixed_len(*index) {
+                let len = F::ssz_fixed_len(*index);
+                fixed_ranges.push((*index, position..position + len));
+                position += len;
+            } else {
+                let offset = ssz::read_offset(&bytes[position..])?;
+                let expected = offsets.last().map_or(fixed_len, |(_, prev)| *prev);
+                if offsets.is_empty() && offset != fixed_len {
+                    return Err(DecodeError::OffsetSkipsVariableBytes(offset));
+                }
+                if offset < expected {
+                    return Err(DecodeError::OffsetsAreDecreasing(offset));
+                }
+                if offset > bytes.len() {
+                    return Err(DecodeError::OffsetOutOfBounds(offset));
+                }
+                offsets.push((*index, offset));
+                position += BYTES_PER_LENGTH_OFFSET;
+            }
+        }
+        if offsets.is_empty() && bytes.len() != fixed_len {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: fixed_len,
+            });
+        }
+
+        let mut container = Self::new();
+        for (index, range) in fixed_ranges {
+            let field = F::from_ssz_bytes(index, &bytes[range])?;
+            container.set(field).map_err(field_error)?;
+        }
+        for (i, (index, start)) in offsets.iter().enumerate() {
+            let end = offsets.get(i + 1).map_or(bytes.len(), |(_, end)| *end);
+            let field = F::from_ssz_bytes(*index, &bytes[*start..end])?;
+            container.set(field).map_err(field_error)?;
+        }
+        Ok(container)
+    }
+}
+
+fn field_error(e: Error) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Invalid StableContainer field: {:?}", e))
+}
+
+impl<F: StableField, N: Unsigned> TreeHash for StableContainer<F, N> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Container
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("StableContainer should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("StableContainer should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let fields_root = self.roots.tree_hash_root();
+        let active_root = self.active_fields.tree_hash_root();
+        crate::proof::hash_pair(&fields_root, &active_root)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::hasher::{MerkleHasher, Sha256Hasher};
+use crate::proof::length_chunk;
+use crate::utils::opt_packing_depth;
+use crate::{Arc, Error, List, MaybeSync, PackedLeaf, Tree, Value};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use tree_hash::Hash256;
+use typenum::Unsigned;
+
+/// Tree node as written to a `NodeStore`, with its children referenced by hash.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoredNode<T> {
+    Leaf(T),
+    PackedLeaf(Vec<T>),
+    Node { left: Hash256, right: Hash256 },
+}
+
+/// Destination for tree nodes, see `List::apply_updates_with_sink`.
+///
+/// Every `NodeStore` is a sink, but a sink only needs to support writes (e.g. a write batch or
+/// a log of nodes to be written later).
+pub trait NodeSink<T: Value> {
+    /// Write `node` as the node with the given `depth` and `hash`.
+    fn put(&self, depth: usize, hash: Hash256, node: StoredNode<T>) -> Result<(), Error>;
+}
+
+/// Storage for tree nodes, addressed by their depth and tree hash.
+///
+/// This allows trees to be written to a key-value database and loaded back later, either in full
+/// or one branch at a time via `StoredList`. As for `NodeInterner`, the depth is part of the key
+/// so that a leaf can never be confused with an internal node with the same hash. Zero subtrees
+/// are never stored.
+///
+/// Trees in memory are always made of `Arc`ed nodes; a store is only used when persisting or
+/// loading explicitly. Implementations backed by a database should report its errors as
+/// `Error::NodeStore`.
+pub trait NodeStore<T: Value>: NodeSink<T> {
+    /// Return the node with the given `depth` and `hash`, if it is stored.
+    fn get(&self, depth: usize, hash: Hash256) -> Result<Option<StoredNode<T>>, Error>;
+
+    /// Check whether the node with the given `depth` and `hash` is stored.
+    ///
+    /// Stores should override this if it can be done without reading the node.
+    fn contains(&self, depth: usize, hash: Hash256) -> Result<bool, Error> {
+        self.get(depth, hash).map(|node| node.is_some())
+    }
+}
+
+/// `NodeStore` backed by a hash map, mostly useful for testing.
+#[derive(Debug)]
+pub struct MemoryNodeStore<T> {
+    nodes: RwLock<HashMap<(usize, Hash256), StoredNode<T>>>,
+}
+
+impl<T> Default for MemoryNodeStore<T> {
+    fn default() -> Self {
+        Self {
+            nodes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T> MemoryNodeStore<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nodes in the store.
+    pub fn len(&self) -> usize {
+        self.nodes.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Value> NodeSink<T> for MemoryNodeStore<T> {
+    fn put(&self, depth: usize, hash: Hash256, node: StoredNode<T>) -> Result<(), Error> {
+        self.nodes.write().insert((depth, hash), node);
+        Ok(())
+    }
+}
+
+impl<T: Value> NodeStore<T> for MemoryNodeStore<T> {
+    fn get(&self, depth: usize, hash: Hash256) -> Result<Option<StoredNode<T>>, Error> {
+        Ok(self.nodes.read().get(&(depth, hash)).cloned())
+    }
+
+    fn contains(&self, depth: usize, hash: Hash256) -> Result<bool, Error> {
+        Ok(self.nodes.read().contains_key(&(depth, hash)))
+    }
+}
+
+impl<T: Value + MaybeSync> Tree<T> {
+    /// Write every node of `tree` to `store`, returning the tree's hash.
+    ///
+    /// Subtrees already in the store are assumed to be stored in full and are skipped, so storing
+    /// a modified copy of a stored tree only writes the modified nodes. Children are written
+    /// before their parents, so this holds even if storing fails part way through.
+    pub fn store<S: NodeStore<T>>(
+        tree: &Arc<Self>,
+        depth: usize,
+        store: &S,
+    ) -> Result<Hash256, Error> {
+        let hash = tree.tree_hash();
+        if matches!(**tree, Self::Zero(_)) || store.contains(depth, hash)? {
+            return Ok(hash);
+        }
+
+        let node = match &**tree {
+            Self::Leaf(leaf) => StoredNode::Leaf((*leaf.value).clone()),
+            Self::PackedLeaf(leaf) => StoredNode::PackedLeaf(leaf.values.clone()),
+            Self::Node { left, right, .. } if depth > 0 => StoredNode::Node {
+                left: Self::store(left, depth - 1, store)?,
+                right: Self::store(right, depth - 1, store)?,
+            },
+            Self::Node { .. } | Self::Zero(_) => {
+                return Err(Error::InvalidStoredNode { depth, hash })
+            }
+        };
+        store.put(depth, hash, node)?;
+        Ok(hash)
+    }
+
+    /// Write the nodes of `tree` without cached hashes to `sink`, returning the tree's hash.
+    ///
+    /// These are the nodes created since the tree was last hashed, so a tree can be persisted
+    /// incrementally by writing it once in full and then calling this after every batch of
+    /// updates. Only the new nodes and their immediate children are visited, and the hashes of
+    /// the new nodes are cached as they are computed.
+    pub fn write_unhashed<S: NodeSink<T>>(
+        tree: &Arc<Self>,
+        depth: usize,
+        sink: &S,
+    ) -> Result<Hash256, Error> {
+        let node = match &**tree {
+            Self::Zero(_) => return Ok(tree.tree_hash()),
+            Self::Leaf(leaf) if leaf.hash.read().is_zero() => {
+                StoredNode::Leaf((*leaf.value).clone())
+            }
+            Self::PackedLeaf(leaf) if leaf.hash.read().is_zero() => {
+                StoredNode::PackedLeaf(leaf.values.clone())
+            }
+            Self::Node { hash, left, right } if hash.read().is_zero() => {
+                if depth == 0 {
+                    return Err(Error::InvalidStoredNode {
+                        depth,
+                        hash: Hash256::zero(),
+                    });
+                }
+                let left = Self::write_unhashed(left, depth - 1, sink)?;
+                let right = Self::write_unhashed(right, depth - 1, sink)?;
+                *hash.write() = Sha256Hasher::hash_pair(&left, &right);
+                StoredNode::Node { left, right }
+            }
+            // Hashed nodes are assumed to have been written already.
+            _ => return Ok(tree.tree_hash()),
+        };
+        let hash = tree.tree_hash();
+        sink.put(depth, hash, node)?;
+        Ok(hash)
+    }
+
+    /// Load the tree with the given `depth` and `hash` from `store`.
+    ///
+    /// Missing nodes with the hash of a zero subtree are loaded as zero subtrees.
+    pub fn load<S: NodeStore<T>>(
+        depth: usize,
+        hash: Hash256,
+        store: &S,
+    ) -> Result<Arc<Self>, Error> {
+        match load_node(depth, hash, store)? {
+            None => Ok(Self::zero(depth)),
+            Some(StoredNode::Leaf(value)) => Ok(Self::leaf_with_hash(value, hash)),
+            Some(StoredNode::PackedLeaf(values)) => Ok(Self::packed_leaf(PackedLeaf {
+                hash: RwLock::new(hash),
+                values,
+            })),
+            Some(StoredNode::Node { left, right }) => Ok(Self::node(
+                Self::load(depth - 1, left, store)?,
+                Self::load(depth - 1, right, store)?,
+                hash,
+            )),
+        }
+    }
+}
+
+/// Read the node with the given `depth` and `hash`, returning `None` for zero subtrees.
+fn load_node<T: Value, S: NodeStore<T>>(
+    depth: usize,
+    hash: Hash256,
+    store: &S,
+) -> Result<Option<StoredNode<T>>, Error> {
+    match store.get(depth, hash)? {
+        Some(StoredNode::Node { .. }) if depth == 0 => {
+            Err(Error::InvalidStoredNode { depth, hash })
+        }
+        Some(StoredNode::Leaf(_) | StoredNode::PackedLeaf(_)) if depth > 0 => {
+            Err(Error::InvalidStoredNode { depth, hash })
+        }
+        Some(node) => Ok(Some(node)),
+        None if hash == Sha256Hasher::zero_hash(depth) => Ok(None),
+        None => Err(Error::MissingStoredNode { depth, hash }),
+    }
+}
+
+/// Write the root node of a list, which mixes the length into the root of its tree.
+pub(crate) fn store_list_root<T: Value, S: NodeSink<T>>(
+    data_root: Hash256,
+    len: usize,
+    depth: usize,
+    store: &S,
+) -> Result<Hash256, Error> {
+    let length = length_chunk(len);
+    let root = Sha256Hasher::hash_pair(&data_root, &length);
+    store.put(
+        depth + 1,
+        root,
+        StoredNode::Node {
+            left: data_root,
+            right: length,
+        },
+    )?;
+    Ok(root)
+}
+
+/// Read the root of a list's tree and its length from the root node of a list.
+pub(crate) fn load_list_root<T: Value, S: NodeStore<T>>(
+    root: Hash256,
+    depth: usize,
+    max_len: usize,
+    store: &S,
+) -> Result<(Hash256, usize), Error> {
+    let Some(StoredNode::Node { left, right }) = store.get(depth + 1, root)? else {
+        return Err(Error::MissingStoredNode {
+            depth: depth + 1,
+            hash: root,
+        });
+    };
+    let mut bytes = [0; std::mem::size_of::<usize>()];
+    let usize_len = bytes.len();
+    bytes.copy_from_slice(&right.as_bytes()[..usize_len]);
+    let len = usize::from_le_bytes(bytes);
+    if len > max_len || length_chunk(len) != right {
+        return Err(Error::InvalidStoredNode {
+            depth: depth + 1,
+            hash: root,
+        });
+    }
+    Ok((left, len))
+}
+
+/// Read-only view of a list in a `NodeStore`, which loads nodes only as they are needed.
+///
+/// Each `get` reads a single branch of the tree from the store, so this can be used to access
+/// lists which don't fit in memory. Nothing is cached, so repeated reads should be cached by the
+/// store.
+#[derive(Debug)]
+pub struct StoredList<'a, T: Value, N: Unsigned, S: NodeStore<T>> {
+    store: &'a S,
+    root: Hash256,
+    data_root: Hash256,
+    len: usize,
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<'a, T: Value + MaybeSync, N: Unsigned, S: NodeStore<T>> StoredList<'a, T, N, S> {
+    /// Open the list with tree hash root `root`, as returned by `List::store`.
+    pub fn open(store: &'a S, root: Hash256) -> Result<Self, Error> {
+        let (data_root, len) = load_list_root(root, List::<T, N>::depth(), N::to_usize(), store)?;
+        Ok(Self {
+            store,
+            root,
+            data_root,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Read the `index`th element from the store.
+    pub fn get(&self, index: usize) -> Result<Option<T>, Error> {
+        if index >= self.len {
+            return Ok(None);
+        }
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        let mut depth = List::<T, N>::depth();
+        let mut hash = self.data_root;
+        loop {
+            match load_node(depth, hash, self.store)? {
+                // Elements within the length are never in zero subtrees.
+                None => return Err(Error::MissingStoredNode { depth, hash }),
+                Some(StoredNode::Leaf(value)) => return Ok(Some(value)),
+                Some(StoredNode::PackedLeaf(values)) => {
+                    let sub_index = index % (1 << packing_depth);
+                    return values
+                        .get(sub_index)
+                        .cloned()
+                        .map(Some)
+                        .ok_or(Error::InvalidStoredNode { depth, hash });
+                }
+                Some(StoredNode::Node { left, right }) => {
+                    let bit = (index >> packing_depth >> (depth - 1)) & 1;
+                    hash = if bit == 0 { left } else { right };
+                    depth -= 1;
+                }
+            }
+        }
+    }
+
+    /// Load the entire list into memory.
+    pub fn load(&self) -> Result<List<T, N>, Error> {
+        List::load(self.store, self.root)
+    }
+}
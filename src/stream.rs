@@ -0,0 +1,202 @@
+use crate::builder::Builder;
+use crate::utils::Length;
+use crate::{Arc, Error, Tree, Value};
+use ssz::{Decode, DecodeError, Encode, BYTES_PER_LENGTH_OFFSET};
+use std::io::{ErrorKind, Read, Write};
+
+/// Number of encoded bytes buffered before each write by `encode_list_to_writer`.
+const WRITE_BUFFER_SIZE: usize = 8192;
+
+/// Maximum number of offsets reserved up front by `decode_list_from_reader`.
+///
+/// The number of offsets comes from the untrusted first offset, so reserving all of them could
+/// allocate gigabytes before a single one has been read.
+const MAX_RESERVED_OFFSETS: usize = 1024;
+
+fn io_error(e: std::io::Error) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Error reading SSZ bytes: {}", e))
+}
+
+fn builder_error<E: std::fmt::Debug>(e: E) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Error building ssz List: {:?}", e))
+}
+
+pub(crate) fn too_long(num_items: usize, max_len: usize) -> Error {
+    Error::DecodeListTooLong {
+        len: num_items,
+        max_len,
+    }
+}
+
+/// Check that `offset` lies between the previous offset (or the end of the offsets, given by
+/// `first_offset`) and the end of the input, as for `ssz::decode_list_of_variable_length_items`.
+pub(crate) fn check_offset(
+    offset: usize,
+    previous_offset: usize,
+    first_offset: usize,
+    num_bytes: usize,
+) -> Result<usize, DecodeError> {
+    if offset < first_offset {
+        Err(DecodeError::OffsetIntoFixedPortion(offset))
+    } else if offset > num_bytes {
+        Err(DecodeError::OffsetOutOfBounds(offset))
+    } else if offset < previous_offset {
+        Err(DecodeError::OffsetsAreDecreasing(offset))
+    } else {
+        Ok(offset)
+    }
+}
+
+/// Fill `buf` from `reader`, returning `false` if the reader was already at EOF.
+///
+/// Reaching EOF after reading only part of `buf` is an error.
+fn read_item<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, DecodeError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => {
+                return Err(DecodeError::InvalidByteLength {
+                    len: filled,
+                    expected: buf.len(),
+                })
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+    Ok(true)
+}
+
+fn read_offset<R: Read>(reader: &mut R) -> Result<Option<usize>, DecodeError> {
+    let mut buf = [0; BYTES_PER_LENGTH_OFFSET];
+    if read_item(reader, &mut buf)? {
+        Ok(Some(u32::from_le_bytes(buf) as usize))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Decode an SSZ list of at most `max_len` items from `reader`, pushing each item into `builder`
+/// as soon as its bytes have been read.
+///
+/// Only one item is buffered at a time, along with the offset table for variable-length items.
+pub(crate) fn decode_list_from_reader<T: Value, R: Read>(
+    mut reader: R,
+    max_len: usize,
+    mut builder: Builder<T>,
+) -> Result<(Arc<Tree<T>>, usize, Length), Error> {
+    if <T as Decode>::is_ssz_fixed_len() {
+        let item_len = <T as Decode>::ssz_fixed_len();
+        if item_len == 0 {
+            return Err(DecodeError::ZeroLengthItem.into());
+        }
+
+        let mut buf = vec![0; item_len];
+        let mut num_items = 0;
+        while read_item(&mut reader, &mut buf)? {
+            num_items += 1;
+            if num_items > max_len {
+                return Err(too_long(num_items, max_len));
+            }
+            builder
+                .push(T::from_ssz_bytes(&buf)?)
+                .map_err(builder_error)?;
+        }
+    } else if let Some(first_offset) = read_offset(&mut reader)? {
+        if first_offset == 0 || first_offset % BYTES_PER_LENGTH_OFFSET != 0 {
+            return Err(DecodeError::InvalidListFixedBytesLen(first_offset).into());
+        }
+        let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
+        if num_items > max_len {
+            return Err(too_long(num_items, max_len));
+        }
+
+        let mut offsets = Vec::with_capacity(num_items.min(MAX_RESERVED_OFFSETS));
+        offsets.push(first_offset);
+        for _ in 1..num_items {
+            let offset = read_offset(&mut reader)?.ok_or(DecodeError::InvalidByteLength {
+                len: offsets.len() * BYTES_PER_LENGTH_OFFSET,
+                expected: first_offset,
+            })?;
+            if offsets.last().is_some_and(|prev| offset < *prev) {
+                return Err(DecodeError::OffsetsAreDecreasing(offset).into());
+            }
+            offsets.push(offset);
+        }
+
+        let mut buf = vec![];
+        for pair in offsets.windows(2) {
+            // The buffer only grows as bytes actually arrive, rather than to the claimed length.
+            let item_len = pair[1] - pair[0];
+            buf.clear();
+            (&mut reader)
+                .take(item_len as u64)
+                .read_to_end(&mut buf)
+                .map_err(io_error)?;
+            if buf.is_empty() && item_len != 0 {
+                return Err(DecodeError::OffsetOutOfBounds(pair[1]).into());
+            }
+            if buf.len() != item_len {
+                return Err(DecodeError::InvalidByteLength {
+                    len: buf.len(),
+                    expected: item_len,
+                }
+                .into());
+            }
+            builder
+                .push(T::from_ssz_bytes(&buf)?)
+                .map_err(builder_error)?;
+        }
+
+        // The last item runs until the end of the input.
+        buf.clear();
+        reader.read_to_end(&mut buf).map_err(io_error)?;
+        builder
+            .push(T::from_ssz_bytes(&buf)?)
+            .map_err(builder_error)?;
+    }
+
+    Ok(builder.finish().map_err(builder_error)?)
+}
+
+fn write_if_full<W: Write>(buf: &mut Vec<u8>, writer: &mut W) -> std::io::Result<()> {
+    if buf.len() >= WRITE_BUFFER_SIZE {
+        writer.write_all(buf)?;
+        buf.clear();
+    }
+    Ok(())
+}
+
+/// Write the SSZ encoding of the `len` items returned by `items` to `writer`.
+///
+/// Items are encoded into a small buffer which is written out whenever it fills up, so the full
+/// encoding is never held in memory. As for `ssz_append_variable`, the offsets of
+/// variable-length items are computed in a first pass over `items`.
+pub(crate) fn encode_list_to_writer<'a, T, I, W>(
+    items: impl Fn() -> I,
+    len: usize,
+    mut writer: W,
+) -> std::io::Result<()>
+where
+    T: Encode + 'a,
+    I: Iterator<Item = &'a T>,
+    W: Write,
+{
+    let mut buf = Vec::with_capacity(WRITE_BUFFER_SIZE);
+
+    if !<T as Encode>::is_ssz_fixed_len() {
+        let mut offset = len * BYTES_PER_LENGTH_OFFSET;
+        for item in items() {
+            buf.extend_from_slice(&ssz::encode_length(offset));
+            offset += item.ssz_bytes_len();
+            write_if_full(&mut buf, &mut writer)?;
+        }
+    }
+    for item in items() {
+        item.ssz_append(&mut buf);
+        write_if_full(&mut buf, &mut writer)?;
+    }
+    writer.write_all(&buf)
+}
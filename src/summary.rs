@@ -0,0 +1,103 @@
+use crate::{Error, List, MaybeSync, Value};
+use derivative::Derivative;
+use std::marker::PhantomData;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::Unsigned;
+
+/// Tree hash root and length of a `List`, without its tree.
+///
+/// A summary hashes like the list it was created from, so it can stand in for the list in
+/// structures which only need its root, and can be compared against full lists without keeping
+/// their trees alive.
+#[derive(Debug, Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct Summary<T, N> {
+    root: Hash256,
+    len: usize,
+    #[derivative(Debug = "ignore")]
+    _phantom: PhantomData<(T, N)>,
+}
+
+impl<T: Value, N: Unsigned> Summary<T, N> {
+    /// Create a summary of a list with tree hash root `root` and length `len`.
+    ///
+    /// The root is taken as given, so it must already have the length mixed in.
+    pub fn new(root: Hash256, len: usize) -> Result<Self, Error> {
+        if len > N::to_usize() {
+            return Err(Error::ListFull {
+                len,
+                max_len: N::to_usize(),
+            });
+        }
+        Ok(Self {
+            root,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn root(&self) -> Hash256 {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> List<T, N> {
+    /// Summarise the list by its tree hash root and length, including any pending updates.
+    pub fn summary(&self) -> Summary<T, N> {
+        Summary {
+            root: self.tree_hash_root(),
+            len: self.len(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> From<&List<T, N>> for Summary<T, N> {
+    fn from(list: &List<T, N>) -> Self {
+        list.summary()
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> PartialEq<List<T, N>> for Summary<T, N> {
+    fn eq(&self, list: &List<T, N>) -> bool {
+        self.len == list.len() && self.root == list.tree_hash_root()
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned> PartialEq<Summary<T, N>> for List<T, N> {
+    fn eq(&self, summary: &Summary<T, N>) -> bool {
+        summary == self
+    }
+}
+
+impl<T: Value, N: Unsigned> TreeHash for Summary<T, N> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.root
+    }
+}
@@ -0,0 +1,212 @@
+//! Proptest strategies for generating lists and vectors.
+//!
+//! Unlike the `Arbitrary` impls, which build trees node-by-node and can produce structures that
+//! are never seen in practice, these strategies build values through the public API. Parameters
+//! control the distribution of lengths, whether updates are left pending, and whether pairs of
+//! lists share structure.
+//!
+//! The `differential` module checks lists and vectors against their `ssz_types` equivalents.
+use crate::{List, Value, Vector};
+use proptest::prelude::*;
+use std::fmt::Debug;
+use typenum::Unsigned;
+
+pub mod differential;
+
+/// Distribution of the lengths of generated lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthDistribution {
+    /// Any length from 0 to the maximum, uniformly.
+    Uniform,
+    /// Lengths from 0 to the given bound (clamped to the maximum).
+    UpTo(usize),
+    /// Lengths around powers of two and the maximum, where the shape of the tree changes.
+    Boundaries,
+    /// Always the maximum length.
+    Full,
+}
+
+/// Whether generated lists are left with pending updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateState {
+    /// All updates are applied.
+    Applied,
+    /// Elements may be pushed and modified without applying the updates.
+    Pending,
+    /// Either of the above, chosen at random.
+    Mixed,
+}
+
+/// Relationship between the two lists generated by `arb_list_pair`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sharing {
+    /// The second list is a modified clone of the first, so they share unmodified subtrees.
+    Shared,
+    /// The second list is built from scratch with the same values as in the `Shared` case.
+    Unshared,
+}
+
+/// Parameters for generating lists and vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub lengths: LengthDistribution,
+    pub updates: UpdateState,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            lengths: LengthDistribution::Uniform,
+            updates: UpdateState::Mixed,
+        }
+    }
+}
+
+/// Strategy for a list length up to `max_len`, according to `dist`.
+pub fn arb_len(max_len: usize, dist: LengthDistribution) -> BoxedStrategy<usize> {
+    match dist {
+        LengthDistribution::Uniform => (0..=max_len).boxed(),
+        LengthDistribution::UpTo(bound) => (0..=std::cmp::min(bound, max_len)).boxed(),
+        LengthDistribution::Boundaries => {
+            let mut lengths = vec![0, max_len, max_len.saturating_sub(1)];
+            let mut power = 1;
+            while power <= max_len {
+                lengths.extend([power - 1, power, power + 1]);
+                power = power.saturating_mul(2);
+            }
+            lengths.retain(|len| *len <= max_len);
+            lengths.sort_unstable();
+            lengths.dedup();
+            proptest::sample::select(lengths).boxed()
+        }
+        LengthDistribution::Full => Just(max_len).boxed(),
+    }
+}
+
+fn arb_pending(state: UpdateState) -> BoxedStrategy<bool> {
+    match state {
+        UpdateState::Applied => Just(false).boxed(),
+        UpdateState::Pending => Just(true).boxed(),
+        UpdateState::Mixed => any::<bool>().boxed(),
+    }
+}
+
+/// Strategy for modifications to a list of `len` elements, as `(index, value)` pairs.
+fn arb_modifications<T, S>(element: S, len: usize) -> BoxedStrategy<Vec<(usize, T)>>
+where
+    T: Clone + Debug + 'static,
+    S: Strategy<Value = T> + 'static,
+{
+    if len == 0 {
+        return Just(vec![]).boxed();
+    }
+    proptest::collection::vec((0..len, element), 0..=std::cmp::min(len, 8)).boxed()
+}
+
+/// Strategy for lists of elements from `element`.
+///
+/// When updates are left pending, a suffix of the elements are pushed after construction, and
+/// some elements are modified via `get_mut`.
+pub fn arb_list<T, N, S>(element: S, params: Params) -> impl Strategy<Value = List<T, N>>
+where
+    T: Value + Debug + 'static,
+    N: Unsigned + Debug + 'static,
+    S: Strategy<Value = T> + Clone + 'static,
+{
+    arb_len(N::to_usize(), params.lengths)
+        .prop_flat_map(move |len| {
+            (
+                proptest::collection::vec(element.clone(), len),
+                0..=len,
+                arb_modifications(element.clone(), len),
+                arb_pending(params.updates),
+            )
+        })
+        .prop_map(|(mut values, split, modifications, pending)| {
+            let rest = values.split_off(split);
+            let mut list = List::new(values).expect("length is within bounds");
+            for value in rest {
+                list.push(value).expect("length is within bounds");
+            }
+            modify(&mut list, modifications);
+            if !pending {
+                list.apply_updates().expect("updates are valid");
+            }
+            list
+        })
+}
+
+/// Strategy for a pair of lists with the same length, where the second is a modification of the
+/// first.
+///
+/// The first list always has its updates applied, while the second follows `params.updates`.
+pub fn arb_list_pair<T, N, S>(
+    element: S,
+    params: Params,
+    sharing: Sharing,
+) -> impl Strategy<Value = (List<T, N>, List<T, N>)>
+where
+    T: Value + Debug + 'static,
+    N: Unsigned + Debug + 'static,
+    S: Strategy<Value = T> + Clone + 'static,
+{
+    let base_params = Params {
+        updates: UpdateState::Applied,
+        ..params
+    };
+    arb_list::<T, N, S>(element.clone(), base_params)
+        .prop_flat_map(move |base| {
+            let len = base.len();
+            (
+                Just(base),
+                arb_modifications(element.clone(), len),
+                arb_pending(params.updates),
+            )
+        })
+        .prop_map(move |(base, modifications, pending)| {
+            let mut other = base.clone();
+            modify(&mut other, modifications);
+            if sharing == Sharing::Unshared {
+                let mut values = other.to_vec();
+                // Push the last element so that there is a pending update, as in the shared case.
+                let last = if pending { values.pop() } else { None };
+                other = List::new(values).expect("length is within bounds");
+                if let Some(value) = last {
+                    other.push(value).expect("length is within bounds");
+                }
+            } else if !pending {
+                other.apply_updates().expect("updates are valid");
+            }
+            (base, other)
+        })
+}
+
+/// Strategy for vectors of elements from `element`.
+pub fn arb_vector<T, N, S>(element: S, updates: UpdateState) -> impl Strategy<Value = Vector<T, N>>
+where
+    T: Value + Debug + 'static,
+    N: Unsigned + Debug + 'static,
+    S: Strategy<Value = T> + Clone + 'static,
+{
+    (
+        proptest::collection::vec(element.clone(), N::to_usize()),
+        arb_modifications(element, N::to_usize()),
+        arb_pending(updates),
+    )
+        .prop_map(|(values, modifications, pending)| {
+            let mut vector = Vector::new(values).expect("length is correct");
+            for (index, value) in modifications {
+                *vector.get_mut(index).expect("index is in bounds") = value;
+            }
+            if !pending {
+                vector.apply_updates().expect("updates are valid");
+            }
+            vector
+        })
+}
+
+fn modify<T: Value, N: Unsigned>(list: &mut List<T, N>, modifications: Vec<(usize, T)>) {
+    for (index, value) in modifications {
+        *list.get_mut(index).expect("index is in bounds") = value;
+    }
+}
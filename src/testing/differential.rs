@@ -0,0 +1,246 @@
+//! Differential testing against `ssz_types`.
+//!
+//! `DifferentialList` and `DifferentialVector` apply each operation to both a milhouse type and
+//! the equivalent `ssz_types` type, and `check` asserts that their lengths, elements, tree hash
+//! roots and SSZ encodings are equal. Crates migrating from `ssz_types` can drive them with the
+//! operation sequences from their own test suites, or with `arb_list_ops` and `arb_vector_ops`.
+use crate::{Error, List, MaybeSync, Value, Vector};
+use proptest::prelude::*;
+use ssz::Encode;
+use ssz_types::{FixedVector, VariableList};
+use std::fmt::Debug;
+use tree_hash::TreeHash;
+use typenum::Unsigned;
+
+/// Operation on a list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListOp<T> {
+    /// Set the element at an index, if it exists.
+    Set(usize, T),
+    /// Push an element, which fails for both lists if they are full.
+    Push(T),
+    /// Remove the last element.
+    Pop,
+    /// Shorten the list to the given length, if it is longer.
+    Truncate(usize),
+    /// Apply pending updates to the milhouse list.
+    ApplyUpdates,
+}
+
+/// Operation on a vector.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorOp<T> {
+    /// Set the element at an index, if it exists.
+    Set(usize, T),
+    /// Apply pending updates to the milhouse vector.
+    ApplyUpdates,
+}
+
+/// `List` paired with the `VariableList` it should behave like.
+#[derive(Debug, Clone)]
+pub struct DifferentialList<T: Value, N: Unsigned> {
+    list: List<T, N>,
+    reference: VariableList<T, N>,
+}
+
+impl<T, N> DifferentialList<T, N>
+where
+    T: Value + MaybeSync + Debug,
+    N: Unsigned,
+{
+    pub fn new(values: Vec<T>) -> Result<Self, Error> {
+        Ok(Self {
+            list: List::new(values.clone())?,
+            reference: VariableList::from(values),
+        })
+    }
+
+    pub fn list(&self) -> &List<T, N> {
+        &self.list
+    }
+
+    pub fn reference(&self) -> &VariableList<T, N> {
+        &self.reference
+    }
+
+    /// Apply `op` to both lists, asserting that it has the same outcome for each.
+    pub fn apply(&mut self, op: &ListOp<T>) {
+        match op {
+            ListOp::Set(index, value) => {
+                match (self.list.get_mut(*index), self.reference.get_mut(*index)) {
+                    (Some(elem), Some(reference)) => {
+                        *elem = value.clone();
+                        *reference = value.clone();
+                    }
+                    (None, None) => {}
+                    (elem, reference) => {
+                        panic!("index {index} exists in only one list: {elem:?} vs {reference:?}")
+                    }
+                }
+            }
+            ListOp::Push(value) => {
+                let result = self.list.push(value.clone());
+                let reference = self.reference.push(value.clone());
+                assert_eq!(result.is_ok(), reference.is_ok(), "push: {result:?}");
+            }
+            ListOp::Pop => {
+                let mut values = Vec::from(std::mem::take(&mut self.reference));
+                let expected = values.pop();
+                self.reference = VariableList::from(values);
+                assert_eq!(self.list.pop().expect("pop succeeds"), expected);
+            }
+            ListOp::Truncate(len) => {
+                let mut values = Vec::from(std::mem::take(&mut self.reference));
+                values.truncate(*len);
+                self.reference = VariableList::from(values);
+                self.list.truncate(*len).expect("truncate succeeds");
+            }
+            ListOp::ApplyUpdates => self.list.apply_updates().expect("updates apply"),
+        }
+    }
+
+    /// Assert that the lists have equal lengths, elements, tree hash roots and SSZ encodings.
+    pub fn check(&self) {
+        assert_eq!(self.list.len(), self.reference.len(), "lengths differ");
+        assert!(
+            self.list.iter().eq(self.reference.iter()),
+            "elements differ: {:?} vs {:?}",
+            self.list.to_vec(),
+            &self.reference[..]
+        );
+        assert_eq!(
+            self.list.tree_hash_root(),
+            self.reference.tree_hash_root(),
+            "tree hash roots differ"
+        );
+        assert_eq!(
+            self.list.as_ssz_bytes(),
+            self.reference.as_ssz_bytes(),
+            "SSZ encodings differ"
+        );
+    }
+
+    /// Apply each of `ops` in turn, checking the lists after each one.
+    pub fn run(&mut self, ops: &[ListOp<T>]) {
+        self.check();
+        for op in ops {
+            self.apply(op);
+            self.check();
+        }
+    }
+}
+
+/// `Vector` paired with the `FixedVector` it should behave like.
+#[derive(Debug, Clone)]
+pub struct DifferentialVector<T: Value, N: Unsigned> {
+    vector: Vector<T, N>,
+    reference: FixedVector<T, N>,
+}
+
+impl<T, N> DifferentialVector<T, N>
+where
+    T: Value + MaybeSync + Debug,
+    N: Unsigned,
+{
+    pub fn new(values: Vec<T>) -> Result<Self, Error> {
+        let vector = Vector::new(values.clone())?;
+        let reference = FixedVector::new(values).expect("length was checked by `Vector::new`");
+        Ok(Self { vector, reference })
+    }
+
+    pub fn vector(&self) -> &Vector<T, N> {
+        &self.vector
+    }
+
+    pub fn reference(&self) -> &FixedVector<T, N> {
+        &self.reference
+    }
+
+    /// Apply `op` to both vectors, asserting that it has the same outcome for each.
+    pub fn apply(&mut self, op: &VectorOp<T>) {
+        match op {
+            VectorOp::Set(index, value) => {
+                match (self.vector.get_mut(*index), self.reference.get_mut(*index)) {
+                    (Some(elem), Some(reference)) => {
+                        *elem = value.clone();
+                        *reference = value.clone();
+                    }
+                    (None, None) => {}
+                    (elem, reference) => {
+                        panic!("index {index} exists in only one vector: {elem:?} vs {reference:?}")
+                    }
+                }
+            }
+            VectorOp::ApplyUpdates => self.vector.apply_updates().expect("updates apply"),
+        }
+    }
+
+    /// Assert that the vectors have equal lengths, elements, tree hash roots and SSZ encodings.
+    pub fn check(&self) {
+        assert_eq!(self.vector.len(), self.reference.len(), "lengths differ");
+        assert!(
+            self.vector.iter().eq(self.reference.iter()),
+            "elements differ: {:?} vs {:?}",
+            self.vector.to_vec(),
+            &self.reference[..]
+        );
+        assert_eq!(
+            self.vector.tree_hash_root(),
+            self.reference.tree_hash_root(),
+            "tree hash roots differ"
+        );
+        assert_eq!(
+            self.vector.as_ssz_bytes(),
+            self.reference.as_ssz_bytes(),
+            "SSZ encodings differ"
+        );
+    }
+
+    /// Apply each of `ops` in turn, checking the vectors after each one.
+    pub fn run(&mut self, ops: &[VectorOp<T>]) {
+        self.check();
+        for op in ops {
+            self.apply(op);
+            self.check();
+        }
+    }
+}
+
+/// Strategy for up to `max_ops` operations on a list with maximum length `max_len`.
+///
+/// Indices range slightly beyond `max_len`, so that out of bounds operations are covered too.
+pub fn arb_list_ops<T, S>(
+    element: S,
+    max_len: usize,
+    max_ops: usize,
+) -> impl Strategy<Value = Vec<ListOp<T>>>
+where
+    T: Clone + Debug + 'static,
+    S: Strategy<Value = T> + Clone + 'static,
+{
+    let op = prop_oneof![
+        (0..=max_len + 1, element.clone()).prop_map(|(index, value)| ListOp::Set(index, value)),
+        element.prop_map(ListOp::Push),
+        Just(ListOp::Pop),
+        (0..=max_len + 1).prop_map(ListOp::Truncate),
+        Just(ListOp::ApplyUpdates),
+    ];
+    proptest::collection::vec(op, 0..=max_ops)
+}
+
+/// Strategy for up to `max_ops` operations on a vector of length `len`.
+pub fn arb_vector_ops<T, S>(
+    element: S,
+    len: usize,
+    max_ops: usize,
+) -> impl Strategy<Value = Vec<VectorOp<T>>>
+where
+    T: Clone + Debug + 'static,
+    S: Strategy<Value = T> + 'static,
+{
+    let op = prop_oneof![
+        (0..=len, element).prop_map(|(index, value)| VectorOp::Set(index, value)),
+        Just(VectorOp::ApplyUpdates),
+    ];
+    proptest::collection::vec(op, 0..=max_ops)
+}
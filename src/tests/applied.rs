@@ -0,0 +1,30 @@
+use crate::{Applied, List, Vector};
+use ssz::Encode;
+use tree_hash::TreeHash;
+use typenum::{U16, U4};
+
+fn root_of(list: &Applied<List<u64, U16>>) -> tree_hash::Hash256 {
+    list.tree_hash_root()
+}
+
+#[test]
+fn wrapping_applies_updates() {
+    let mut list = List::<u64, U16>::new(vec![1, 2]).unwrap();
+    list.push(3).unwrap();
+    let applied = list.clone().into_applied().unwrap();
+    assert!(!applied.has_pending_updates());
+    assert_eq!(applied.to_vec(), vec![1, 2, 3]);
+    assert_eq!(root_of(&applied), list.tree_hash_root());
+    assert_eq!(applied.as_ssz_bytes(), list.as_ssz_bytes());
+
+    let mut list = applied.into_inner();
+    *list.get_mut(0).unwrap() = 10;
+    let applied = Applied::new(list).unwrap();
+    assert_eq!(applied.get(0), Some(&10));
+
+    let mut vector = Vector::<u64, U4>::new(vec![1, 2, 3, 4]).unwrap();
+    *vector.get_mut(3).unwrap() = 40;
+    let applied = vector.into_applied().unwrap();
+    assert!(!applied.has_pending_updates());
+    assert_eq!(<Applied<Vector<u64, U4>> as Encode>::ssz_fixed_len(), 32);
+}
@@ -0,0 +1,65 @@
+use crate::update_map::MaxMap;
+use crate::{ApplyPolicy, AutoApply, DynList, List, UpdateMap, Vector};
+use ssz::{Decode, Encode};
+use tree_hash::TreeHash;
+use typenum::{U16, U8};
+use vec_map::VecMap;
+
+type AutoList<T, N> = List<T, N, AutoApply<MaxMap<VecMap<T>>>>;
+type AutoVector<T, N> = Vector<T, N, AutoApply<MaxMap<VecMap<T>>>>;
+
+#[test]
+fn auto_apply_keeps_one_modification_pending() {
+    let mut list = AutoList::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    assert_eq!(list.apply_policy(), ApplyPolicy::Auto);
+
+    list.push(4).unwrap();
+    assert!(list.has_pending_updates());
+    *list.get_mut(0).unwrap() = 10;
+    assert_eq!(list.interface.updates.len(), 1);
+    list.push(5).unwrap();
+    assert_eq!(list.interface.updates.len(), 1);
+
+    let expected = List::<u64, U16>::new(vec![10, 2, 3, 4, 5]).unwrap();
+    assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+    assert_eq!(list.to_vec(), expected.to_vec());
+
+    // Clones keep the policy.
+    let mut clone = list.clone();
+    *clone.get_mut(1).unwrap() = 20;
+    assert_eq!(clone.interface.updates.len(), 1);
+    assert_eq!(clone.get(4), Some(&5));
+}
+
+#[test]
+fn explicit_is_default() {
+    let mut vector = Vector::<u64, U8>::default();
+    assert_eq!(vector.apply_policy(), ApplyPolicy::Explicit);
+    *vector.get_mut(0).unwrap() = 1;
+    *vector.get_mut(1).unwrap() = 2;
+    assert_eq!(vector.interface.updates.len(), 2);
+}
+
+#[test]
+fn alias_selects_auto_for_every_constructor() {
+    let decoded = AutoList::<u64, U16>::from_ssz_bytes(&vec![1u64, 2, 3].as_ssz_bytes()).unwrap();
+    assert_eq!(decoded.apply_policy(), ApplyPolicy::Auto);
+    assert_eq!(
+        AutoList::<u64, U16>::default().apply_policy(),
+        ApplyPolicy::Auto
+    );
+
+    let mut vector = AutoVector::<u64, U8>::default();
+    assert_eq!(vector.apply_policy(), ApplyPolicy::Auto);
+    *vector.get_mut(0).unwrap() = 1;
+    *vector.get_mut(1).unwrap() = 2;
+    assert_eq!(vector.interface.updates.len(), 1);
+
+    // Conversions keep the update map type, and so the policy.
+    let list = List::from(vector);
+    assert_eq!(list.apply_policy(), ApplyPolicy::Auto);
+    assert_eq!(
+        DynList::from(list).interface.apply_policy(),
+        ApplyPolicy::Auto
+    );
+}
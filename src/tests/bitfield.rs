@@ -0,0 +1,105 @@
+use crate::{Bitlist, Bitvector, Error};
+use ssz::{Decode, Encode};
+use ssz_types::{BitList, BitVector};
+use tree_hash::TreeHash;
+use typenum::{Unsigned, U1, U2048, U300, U7};
+
+/// Deterministic pseudo-random bit pattern.
+fn pattern(i: usize, seed: usize) -> bool {
+    (i * 7 + seed).is_multiple_of(3) || i.is_multiple_of(seed + 2)
+}
+
+fn bitlist_test<N: Unsigned + Clone>() {
+    for len in (0..=N::to_usize()).step_by(std::cmp::max(1, N::to_usize() / 50)) {
+        let mut list = Bitlist::<N>::with_capacity(0).unwrap();
+        let mut expected = BitList::<N>::with_capacity(len).unwrap();
+
+        for i in 0..len {
+            list.push(pattern(i, len)).unwrap();
+            expected.set(i, pattern(i, len)).unwrap();
+        }
+
+        assert_eq!(list.len(), len);
+        assert!(list.iter().eq(expected.iter()));
+        assert_eq!(list.as_ssz_bytes(), expected.as_ssz_bytes());
+        assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+        assert_eq!(Bitlist::from_ssz_bytes(&list.as_ssz_bytes()).unwrap(), list);
+    }
+}
+
+fn bitvector_test<N: Unsigned + Clone>() {
+    let mut vector = Bitvector::<N>::new();
+    let mut expected = BitVector::<N>::new();
+    assert_eq!(vector.tree_hash_root(), expected.tree_hash_root());
+
+    for i in 0..N::to_usize() {
+        vector.set(i, pattern(i, 3)).unwrap();
+        expected.set(i, pattern(i, 3)).unwrap();
+        assert_eq!(vector.get(i), Some(pattern(i, 3)));
+    }
+
+    assert!(vector.iter().eq(expected.iter()));
+    assert_eq!(vector.as_ssz_bytes(), expected.as_ssz_bytes());
+    assert_eq!(vector.tree_hash_root(), expected.tree_hash_root());
+    assert_eq!(
+        Bitvector::from_ssz_bytes(&vector.as_ssz_bytes()).unwrap(),
+        vector
+    );
+}
+
+#[test]
+fn bitlist_1() {
+    bitlist_test::<U1>();
+}
+
+#[test]
+fn bitlist_300() {
+    bitlist_test::<U300>();
+}
+
+#[test]
+fn bitlist_2048() {
+    bitlist_test::<U2048>();
+}
+
+#[test]
+fn bitvector_1() {
+    bitvector_test::<U1>();
+}
+
+#[test]
+fn bitvector_7() {
+    bitvector_test::<U7>();
+}
+
+#[test]
+fn bitvector_300() {
+    bitvector_test::<U300>();
+}
+
+#[test]
+fn bitvector_2048() {
+    bitvector_test::<U2048>();
+}
+
+#[test]
+fn bitlist_full() {
+    let mut list = Bitlist::<U7>::with_capacity(7).unwrap();
+    assert_eq!(list.push(true), Err(Error::ListFull { len: 7, max_len: 7 }));
+    assert_eq!(
+        list.set(7, true),
+        Err(Error::OutOfBoundsUpdate { index: 7, len: 7 })
+    );
+}
+
+#[test]
+fn invalid_ssz() {
+    // Missing delimiter.
+    assert!(Bitlist::<U300>::from_ssz_bytes(&[]).is_err());
+    assert!(Bitlist::<U300>::from_ssz_bytes(&[1, 0]).is_err());
+    // Too long.
+    assert!(Bitlist::<U7>::from_ssz_bytes(&[0, 1]).is_err());
+    // Non-zero padding bits.
+    assert!(Bitvector::<U7>::from_ssz_bytes(&[0b1000_0000]).is_err());
+    assert!(Bitvector::<U7>::from_ssz_bytes(&[0, 0]).is_err());
+}
@@ -1,4 +1,4 @@
-use crate::List;
+use crate::{Error, List};
 use tree_hash::Hash256;
 use typenum::{Unsigned, U16, U32};
 
@@ -37,3 +37,77 @@ fn build_packed_u64_list() {
         assert_eq!(fast_list.iter().cloned().collect::<Vec<_>>(), sub_vec);
     }
 }
+
+/// Iterator which reports a length of `len`, but produces `actual` values.
+struct WrongLength {
+    len: usize,
+    actual: u64,
+}
+
+impl Iterator for WrongLength {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.actual = self.actual.checked_sub(1)?;
+        Some(self.actual)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for WrongLength {}
+
+#[test]
+fn build_from_exact_iter() {
+    type N = U32;
+    for k in 0..=N::to_usize() {
+        let vec = (0..k as u64).collect::<Vec<_>>();
+        let list = List::<u64, N>::try_from_exact_iter(vec.iter().copied()).unwrap();
+        assert_eq!(
+            list,
+            List::<u64, N>::try_from_iter_slow(vec.clone()).unwrap()
+        );
+        list.validate().unwrap();
+    }
+
+    // The last leaf only has space for the values it contains.
+    let list = List::<u8, U32>::try_from_exact_iter([1, 2, 3]).unwrap();
+    assert_eq!(
+        list.mem_usage().bytes,
+        List::<u8, U32>::new(vec![1, 2, 3])
+            .unwrap()
+            .mem_usage()
+            .bytes
+    );
+    assert!(
+        list.mem_usage().bytes
+            < List::<u8, U32>::try_from_iter_slow([1, 2, 3])
+                .unwrap()
+                .mem_usage()
+                .bytes
+    );
+
+    assert_eq!(
+        List::<u64, U16>::try_from_exact_iter(vec![0; 17]),
+        Err(Error::ListFull {
+            len: 0,
+            max_len: 16
+        })
+    );
+    assert_eq!(
+        List::<u64, U16>::try_from_exact_iter(WrongLength { len: 4, actual: 3 }),
+        Err(Error::WrongIteratorLength {
+            len: 3,
+            expected: 4
+        })
+    );
+    assert_eq!(
+        List::<u64, U16>::try_from_exact_iter(WrongLength { len: 4, actual: 6 }),
+        Err(Error::WrongIteratorLength {
+            len: 5,
+            expected: 4
+        })
+    );
+}
@@ -0,0 +1,153 @@
+use crate::{ByteList, ByteVector, Error, List, Vector};
+use ssz::{Decode, Encode};
+use tree_hash::TreeHash;
+use typenum::{U1024, U32, U4};
+
+#[test]
+fn same_encoding_as_inner() {
+    let bytes = ByteList::<U32>::new(vec![1, 2, 3]).unwrap();
+    let list = List::<u8, U32>::new(vec![1, 2, 3]).unwrap();
+    assert_eq!(bytes.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(bytes.as_ssz_bytes(), list.as_ssz_bytes());
+    assert_eq!(ByteList::<U32>::from_ssz_bytes(&[1, 2, 3]).unwrap(), bytes);
+    assert_eq!(bytes.len(), 3);
+    assert_eq!(List::from(bytes), list);
+
+    let bytes = ByteVector::<U4>::new(vec![1, 2, 3, 4]).unwrap();
+    let vector = Vector::<u8, U4>::new(vec![1, 2, 3, 4]).unwrap();
+    assert_eq!(bytes.tree_hash_root(), vector.tree_hash_root());
+    assert_eq!(<ByteVector<U4> as Encode>::ssz_fixed_len(), 4);
+    assert_eq!(
+        ByteVector::<U4>::from_ssz_bytes(&[1, 2, 3, 4]).unwrap(),
+        bytes
+    );
+    assert!(ByteVector::<U4>::from_ssz_bytes(&[1, 2, 3]).is_err());
+    assert_eq!(bytes.into_inner(), vector);
+}
+
+#[test]
+fn mutate_through_deref() {
+    let mut bytes = ByteList::<U4>::empty();
+    bytes.push(0xaa).unwrap();
+    bytes.push(0xbb).unwrap();
+    *bytes.get_mut(0).unwrap() = 0xcc;
+    bytes.apply_updates().unwrap();
+    assert_eq!(bytes.to_vec(), vec![0xcc, 0xbb]);
+}
+
+#[test]
+fn contiguous_reads() {
+    let data = (0..100).collect::<Vec<u8>>();
+    let mut bytes = ByteList::<U1024>::new(data.clone()).unwrap();
+
+    let slices = bytes.slices().unwrap().collect::<Vec<_>>();
+    assert_eq!(slices.len(), 4);
+    assert_eq!(slices[0], &data[..32]);
+    assert_eq!(slices[3], &data[96..]);
+    assert_eq!(bytes.to_bytes(), data);
+
+    let mut buf = [0; 10];
+    bytes.copy_to_slice(30, &mut buf).unwrap();
+    assert_eq!(buf, data[30..40]);
+    assert_eq!(
+        bytes.copy_to_slice(95, &mut buf),
+        Err(Error::OutOfBoundsSlice {
+            start: 95,
+            end: 105,
+            len: 100
+        })
+    );
+
+    // Pending updates are included, but prevent iterating by slice.
+    bytes.push(100).unwrap();
+    assert_eq!(bytes.slices().unwrap_err(), Error::ChunkIterPendingUpdates);
+    assert_eq!(bytes.to_bytes(), (0..=100).collect::<Vec<u8>>());
+}
+
+#[test]
+fn bulk_writes() {
+    let mut bytes = ByteList::<U32>::new(vec![0; 8]).unwrap();
+    bytes.copy_from_slice(4, &[1, 2, 3, 4, 5, 6]).unwrap();
+    assert!(!bytes.has_pending_updates());
+    assert_eq!(bytes.to_bytes(), vec![0, 0, 0, 0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        bytes.tree_hash_root(),
+        List::<u8, U32>::new(bytes.to_bytes())
+            .unwrap()
+            .tree_hash_root()
+    );
+
+    assert_eq!(
+        bytes.copy_from_slice(11, &[1]),
+        Err(Error::OutOfBoundsUpdate { index: 11, len: 10 })
+    );
+    assert_eq!(
+        bytes.copy_from_slice(10, &[1; 23]),
+        Err(Error::ListFull {
+            len: 10,
+            max_len: 32
+        })
+    );
+    assert_eq!(bytes.len(), 10);
+
+    let mut vector = ByteVector::<U32>::default();
+    vector.copy_from_slice(30, &[7, 8]).unwrap();
+    assert_eq!(vector.to_bytes()[28..], [0, 0, 7, 8]);
+    assert_eq!(
+        vector.copy_from_slice(31, &[7, 8]),
+        Err(Error::OutOfBoundsSlice {
+            start: 31,
+            end: 33,
+            len: 32
+        })
+    );
+    let mut buf = [0; 3];
+    vector.copy_to_slice(29, &mut buf).unwrap();
+    assert_eq!(buf, [0, 7, 8]);
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::*;
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Block {
+        graffiti: ByteVector<U4>,
+        extra_data: ByteList<U32>,
+        #[serde(with = "crate::serde::as_seq")]
+        raw: ByteList<U4>,
+    }
+
+    #[test]
+    fn hex_json() {
+        let block = Block {
+            graffiti: ByteVector::new(vec![0xde, 0xad, 0xbe, 0xef]).unwrap(),
+            extra_data: ByteList::empty(),
+            raw: ByteList::new(vec![1, 2]).unwrap(),
+        };
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(
+            json,
+            r#"{"graffiti":"0xdeadbeef","extra_data":"0x","raw":[1,2]}"#
+        );
+        assert_eq!(serde_json::from_str::<Block>(&json).unwrap(), block);
+    }
+
+    #[test]
+    fn hex_json_errors() {
+        assert!(serde_json::from_str::<ByteVector<U4>>(r#""0xdead""#).is_err());
+        assert!(serde_json::from_str::<ByteList<U4>>(r#""0x0102030405""#).is_err());
+        assert!(serde_json::from_str::<ByteList<U4>>("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn compact() {
+        let bytes = ByteList::<U32>::new(vec![5; 20]).unwrap();
+        let mut buf = vec![];
+        ciborium::into_writer(&bytes, &mut buf).unwrap();
+        assert!(buf.ends_with(&[5; 20]));
+        let decoded: ByteList<U32> = ciborium::from_reader(&buf[..]).unwrap();
+        assert_eq!(decoded, bytes);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{List, Vector};
+use typenum::{U1024, U16};
+
+#[test]
+fn sequential_reads_hit_cache() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let reader = list.cached_reader();
+    for i in 0..1000 {
+        assert_eq!(reader.get(i), Some(&(i as u64)));
+    }
+    assert_eq!(reader.get(1000), None);
+    // One descent per packed leaf of 4 values.
+    assert_eq!(reader.misses(), 250);
+    assert_eq!(reader.hits(), 750);
+
+    // Reads in reverse also stay within each leaf.
+    let reader = list.cached_reader();
+    for i in (0..1000).rev() {
+        assert_eq!(reader.get(i), list.get(i));
+    }
+    assert_eq!(reader.misses(), 250);
+}
+
+#[test]
+fn unpacked_values() {
+    let list =
+        List::<List<u8, U16>, U16>::new((0..10).map(|i| List::new(vec![i; 3]).unwrap()).collect())
+            .unwrap();
+    let reader = list.cached_reader();
+    for i in 0..10 {
+        assert_eq!(reader.get(i), list.get(i));
+        assert_eq!(reader.get(i), list.get(i));
+    }
+    assert_eq!(reader.misses(), 10);
+    assert_eq!(reader.hits(), 10);
+}
+
+#[test]
+fn pending_updates() {
+    let mut list = List::<u64, U1024>::new((0..8).collect()).unwrap();
+    *list.get_mut(1).unwrap() = 100;
+    list.push(8).unwrap();
+    let reader = list.cached_reader();
+    assert_eq!(reader.len(), 9);
+    assert_eq!(
+        (0..9).map(|i| *reader.get(i).unwrap()).collect::<Vec<_>>(),
+        list.to_vec()
+    );
+
+    let vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    let reader = vector.cached_reader();
+    assert!((0..16).all(|i| reader.get(i) == vector.get(i)));
+    assert_eq!(reader.get(16), None);
+}
@@ -0,0 +1,89 @@
+use crate::{CachedRoot, CachedTreeHash, Error, List, Vector};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use ssz_types::VariableList;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+use typenum::{U1024, U8};
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, TreeHash)]
+struct State {
+    slot: u64,
+    balances: List<u64, U1024>,
+    roots: Vector<u64, U8>,
+    history: CachedRoot<VariableList<u64, U1024>>,
+}
+
+impl CachedTreeHash for State {
+    fn prepare_tree_hash(&mut self) -> Result<(), Error> {
+        self.balances.prepare_tree_hash()?;
+        self.roots.prepare_tree_hash()?;
+        self.history.prepare_tree_hash()
+    }
+}
+
+#[derive(Encode, TreeHash)]
+struct Plain {
+    slot: u64,
+    balances: List<u64, U1024>,
+    roots: Vector<u64, U8>,
+    history: VariableList<u64, U1024>,
+}
+
+fn state() -> State {
+    State {
+        slot: 5,
+        balances: List::new((0..100).collect()).unwrap(),
+        roots: Vector::new((0..8).collect()).unwrap(),
+        history: CachedRoot::new(VariableList::from((0..50).collect::<Vec<_>>())),
+    }
+}
+
+fn plain(state: &State) -> Plain {
+    Plain {
+        slot: state.slot,
+        balances: state.balances.clone(),
+        roots: state.roots.clone(),
+        history: (*state.history).clone(),
+    }
+}
+
+#[test]
+fn container_root_matches_uncached() {
+    let mut state = state();
+    state.balances.push(100).unwrap();
+    *state.roots.get_mut(3).unwrap() = 30;
+    assert!(state.balances.has_pending_updates());
+
+    let expected = plain(&state).tree_hash_root();
+    assert_eq!(state.tree_hash_root(), expected);
+    assert_eq!(state.cached_tree_hash_root(), Ok(expected));
+    assert!(!state.balances.has_pending_updates());
+    assert!(!state.roots.has_pending_updates());
+    assert!(state.history.is_cached());
+    assert_eq!(state.tree_hash_root(), expected);
+}
+
+#[test]
+fn mutation_clears_cached_root() {
+    let mut state = state();
+    let before = state.cached_tree_hash_root().unwrap();
+
+    state.history.push(50).unwrap();
+    assert!(!state.history.is_cached());
+    let after = state.cached_tree_hash_root().unwrap();
+    assert_ne!(after, before);
+    assert_eq!(after, plain(&state).tree_hash_root());
+}
+
+#[test]
+fn cached_root_is_transparent_to_ssz() {
+    let mut state = state();
+    state.cached_tree_hash_root().unwrap();
+    let bytes = state.as_ssz_bytes();
+    let decoded = State::from_ssz_bytes(&bytes).unwrap();
+    assert_eq!(decoded, state);
+    assert!(!decoded.history.is_cached());
+
+    assert_eq!(plain(&state).as_ssz_bytes(), bytes);
+}
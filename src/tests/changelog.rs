@@ -0,0 +1,48 @@
+use crate::{Changelog, List, Vector};
+use tree_hash::Hash256;
+use typenum::{U16, U8};
+
+#[test]
+fn packed_list_changelog() {
+    let mut list = List::<u64, U16>::new((0..8).collect()).unwrap();
+    *list.get_mut(5).unwrap() = 50;
+    *list.get_mut(0).unwrap() = 10;
+    *list.get_mut(1).unwrap() = 11;
+    list.push(8).unwrap();
+
+    let changelog = list.apply_updates_with_changelog().unwrap();
+    assert_eq!(
+        changelog,
+        Changelog {
+            indices: vec![0, 1, 5, 8],
+            prev_len: 8,
+            len: 9,
+            // Chunks 0, 1 and 2, their parents 0 and 1, and the root.
+            nodes_rewritten: 6,
+        }
+    );
+    assert!(changelog.contains(5));
+    assert!(!changelog.contains(4));
+    assert_eq!(list.to_vec(), vec![10, 11, 2, 3, 4, 50, 6, 7, 8]);
+
+    let empty = list.apply_updates_with_changelog().unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.nodes_rewritten, 0);
+}
+
+#[test]
+fn nodes_rewritten_matches_unique_nodes() {
+    let mut vector =
+        Vector::<Hash256, U8>::new((0..8).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let original = vector.clone();
+    *vector.get_mut(1).unwrap() = Hash256::repeat_byte(1);
+    *vector.get_mut(6).unwrap() = Hash256::repeat_byte(6);
+
+    let changelog = vector.apply_updates_with_changelog().unwrap();
+    assert_eq!(changelog.indices, vec![1, 6]);
+    assert_eq!(changelog.prev_len, 8);
+    assert_eq!(changelog.len, 8);
+    assert_eq!(changelog.nodes_rewritten, 7);
+    assert_eq!(vector.mem_usage().unique_nodes, changelog.nodes_rewritten);
+    drop(original);
+}
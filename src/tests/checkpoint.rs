@@ -0,0 +1,39 @@
+use crate::{List, Vector};
+use typenum::{U16, U4};
+
+#[test]
+fn nested_checkpoints() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    *list.get_mut(0).unwrap() = 10;
+
+    let outer = list.checkpoint();
+    list.push(4).unwrap();
+
+    let inner = list.checkpoint();
+    *list.get_mut(1).unwrap() = 20;
+    list.push(5).unwrap();
+    assert_eq!(list.to_vec(), vec![10, 20, 3, 4, 5]);
+
+    // Only the modifications made since the inner checkpoint are undone.
+    list.revert_to(inner);
+    assert_eq!(list.to_vec(), vec![10, 2, 3, 4]);
+
+    list.revert_to(outer);
+    assert_eq!(list.to_vec(), vec![10, 2, 3]);
+    assert!(list.has_pending_updates());
+    list.apply_updates().unwrap();
+    assert_eq!(list, List::new(vec![10, 2, 3]).unwrap());
+}
+
+#[test]
+fn revert_after_apply() {
+    let mut vector = Vector::<u64, U4>::new(vec![1, 2, 3, 4]).unwrap();
+    let checkpoint = vector.checkpoint();
+    *vector.get_mut(2).unwrap() = 30;
+    vector.apply_updates().unwrap();
+    assert_eq!(vector.to_vec(), vec![1, 2, 30, 4]);
+
+    vector.revert_to(checkpoint);
+    assert_eq!(vector.to_vec(), vec![1, 2, 3, 4]);
+    assert!(!vector.has_pending_updates());
+}
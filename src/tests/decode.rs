@@ -0,0 +1,141 @@
+use crate::{Error, List, Vector};
+use proptest::prelude::*;
+use ssz::{Decode, DecodeError, Encode};
+use typenum::{U2, U3, U4};
+
+fn too_long(len: usize, max_len: usize) -> DecodeError {
+    DecodeError::BytesInvalid(format!(
+        "List of {} items exceeds maximum of {}",
+        len, max_len
+    ))
+}
+
+#[test]
+fn oversized_lists() {
+    let bytes = List::<u64, U4>::new(vec![1; 4]).unwrap().as_ssz_bytes();
+    assert_eq!(List::<u64, U3>::from_ssz_bytes(&bytes), Err(too_long(4, 3)));
+
+    let nested = List::<List<u8, U2>, U4>::new(vec![List::empty(); 4])
+        .unwrap()
+        .as_ssz_bytes();
+    assert_eq!(
+        List::<List<u8, U2>, U3>::from_ssz_bytes(&nested),
+        Err(too_long(4, 3))
+    );
+
+    assert_eq!(
+        List::<u64, U4>::from_ssz_bytes(&[0; 12]),
+        Err(DecodeError::InvalidByteLength {
+            len: 12,
+            expected: 16
+        })
+    );
+}
+
+#[test]
+fn wrong_vector_lengths() {
+    assert_eq!(
+        Vector::<u64, U4>::from_ssz_bytes(&[0; 40]),
+        Err(DecodeError::InvalidByteLength {
+            len: 40,
+            expected: 32
+        })
+    );
+    assert_eq!(
+        Vector::<u64, U4>::from_ssz_bytes(&[0; 24]),
+        Err(DecodeError::InvalidByteLength {
+            len: 24,
+            expected: 32
+        })
+    );
+
+    let bytes = List::<List<u8, U2>, U4>::new(vec![List::empty(); 3])
+        .unwrap()
+        .as_ssz_bytes();
+    assert_eq!(
+        Vector::<List<u8, U2>, U4>::from_ssz_bytes(&bytes),
+        Err(DecodeError::BytesInvalid(
+            "Vector of 3 items, expected 4".into()
+        ))
+    );
+    let bytes = List::<List<u8, U2>, U4>::new(vec![List::empty(); 4])
+        .unwrap()
+        .as_ssz_bytes();
+    assert_eq!(
+        Vector::<List<u8, U2>, U3>::from_ssz_bytes(&bytes),
+        Err(DecodeError::BytesInvalid(
+            "Vector of 4 items, expected 3".into()
+        ))
+    );
+}
+
+#[test]
+fn typed_decode_errors() {
+    let bytes = List::<u64, U4>::new(vec![1; 4]).unwrap().as_ssz_bytes();
+    assert_eq!(
+        List::<u64, U3>::try_from_ssz_bytes(&bytes),
+        Err(Error::DecodeListTooLong { len: 4, max_len: 3 })
+    );
+    assert_eq!(
+        List::<u64, U3>::try_from_ssz_reader(&bytes[..]),
+        Err(Error::DecodeListTooLong { len: 4, max_len: 3 })
+    );
+    assert_eq!(
+        List::<u64, U4>::try_from_ssz_bytes(&[0; 12]),
+        Err(Error::SszDecode(DecodeError::InvalidByteLength {
+            len: 12,
+            expected: 16
+        }))
+    );
+
+    let short = List::<List<u8, U2>, U4>::new(vec![List::empty(); 3])
+        .unwrap()
+        .as_ssz_bytes();
+    let long = List::<List<u8, U2>, U4>::new(vec![List::empty(); 4])
+        .unwrap()
+        .as_ssz_bytes();
+    assert_eq!(
+        Vector::<List<u8, U2>, U4>::try_from_ssz_bytes(&short),
+        Err(Error::DecodeWrongVectorLength {
+            len: 3,
+            expected: 4
+        })
+    );
+    assert_eq!(
+        Vector::<List<u8, U2>, U3>::try_from_ssz_bytes(&long),
+        Err(Error::DecodeWrongVectorLength {
+            len: 4,
+            expected: 3
+        })
+    );
+    assert_eq!(
+        Vector::<List<u8, U2>, U3>::try_from_ssz_reader(&long[..]),
+        Err(Error::DecodeWrongVectorLength {
+            len: 4,
+            expected: 3
+        })
+    );
+}
+
+proptest! {
+    /// Decoding arbitrary bytes never panics, and anything decoded is within bounds and
+    /// re-encodes to the same bytes.
+    #[test]
+    fn decode_arbitrary_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+        if let Ok(list) = List::<u64, U4>::from_ssz_bytes(&bytes) {
+            prop_assert!(list.len() <= 4);
+            prop_assert_eq!(list.as_ssz_bytes(), bytes.clone());
+        }
+        if let Ok(list) = List::<List<u8, U2>, U3>::from_ssz_bytes(&bytes) {
+            prop_assert!(list.len() <= 3);
+            prop_assert!(list.iter().all(|inner| inner.len() <= 2));
+            prop_assert_eq!(list.as_ssz_bytes(), bytes.clone());
+        }
+        if let Ok(vector) = Vector::<List<u8, U2>, U2>::from_ssz_bytes(&bytes) {
+            prop_assert_eq!(vector.as_ssz_bytes(), bytes.clone());
+        }
+        if let Ok(vector) = Vector::<u64, U3>::from_ssz_bytes(&bytes) {
+            prop_assert_eq!(vector.as_ssz_bytes(), bytes);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::List;
+use ssz::{DecodeError, Encode};
+use typenum::{U1024, U16};
+
+fn reject_large(limit: u64) -> impl FnMut(usize, &u64) -> Result<(), DecodeError> {
+    move |index, value| {
+        if *value > limit {
+            Err(DecodeError::BytesInvalid(format!(
+                "element {index} is {value}, above {limit}"
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn fixed_length_validation() {
+    let bytes = (0..1000u64).collect::<Vec<_>>().as_ssz_bytes();
+    let list = List::<u64, U1024>::from_ssz_bytes_with(&bytes, reject_large(999)).unwrap();
+    assert_eq!(list.to_vec(), (0..1000).collect::<Vec<_>>());
+
+    assert_eq!(
+        List::<u64, U1024>::from_ssz_bytes_with(&bytes, reject_large(500)),
+        Err(DecodeError::BytesInvalid(
+            "element 501 is 501, above 500".into()
+        ))
+    );
+}
+
+#[test]
+fn validation_stops_at_first_error() {
+    let bytes = vec![5u64, 10, 20, 1].as_ssz_bytes();
+    let mut seen = vec![];
+    let result = List::<u64, U16>::from_ssz_bytes_with(&bytes, |index, value| {
+        seen.push(index);
+        reject_large(8)(index, value)
+    });
+    assert!(result.is_err());
+    assert_eq!(seen, vec![0, 1]);
+}
+
+#[test]
+fn variable_length_validation() {
+    let values = vec![vec![1u8, 2], vec![], vec![3, 4, 5]];
+    let bytes = values.as_ssz_bytes();
+    let mut indices = vec![];
+    let list = List::<List<u8, U16>, U16>::from_ssz_bytes_with(&bytes, |index, value| {
+        indices.push((index, value.len()));
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(indices, vec![(0, 2), (1, 0), (2, 3)]);
+    assert_eq!(list.len(), 3);
+
+    let result = List::<List<u8, U16>, U16>::from_ssz_bytes_with(&bytes, |index, value| {
+        if value.is_empty() {
+            Err(DecodeError::BytesInvalid(format!(
+                "element {index} is empty"
+            )))
+        } else {
+            Ok(())
+        }
+    });
+    assert_eq!(
+        result,
+        Err(DecodeError::BytesInvalid("element 1 is empty".into()))
+    );
+}
+
+#[test]
+fn decode_errors_precede_validation() {
+    let mut bytes = vec![1u64, 2].as_ssz_bytes();
+    bytes.pop();
+    let result = List::<u64, U16>::from_ssz_bytes_with(&bytes, |_, _| panic!("not called"));
+    assert!(matches!(result, Err(DecodeError::InvalidByteLength { .. })));
+}
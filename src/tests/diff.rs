@@ -0,0 +1,125 @@
+use crate::tests::proptest::Large;
+use crate::{List, ListDiff};
+use tree_hash::Hash256;
+use typenum::{U1024, U16};
+
+#[test]
+fn diff_packed_mutations() {
+    let base = List::<u64, U1024>::new((0..700).collect()).unwrap();
+    let mut new = base.clone();
+    for i in [0, 5, 6, 333, 699] {
+        *new.get_mut(i).unwrap() += 1000;
+    }
+    new.push(42).unwrap();
+    new.apply_updates().unwrap();
+
+    let diff = ListDiff::compute(&base, &new).unwrap();
+    assert_eq!(diff.len(), 701);
+    assert_eq!(
+        diff.updates().iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        vec![0, 5, 6, 333, 699, 700]
+    );
+
+    let mut applied = base.clone();
+    diff.apply(&mut applied).unwrap();
+    assert_eq!(applied, new);
+}
+
+#[test]
+fn diff_identical_is_empty() {
+    let base = List::<Hash256, U16>::new(vec![Hash256::repeat_byte(7); 9]).unwrap();
+    let diff = ListDiff::compute(&base, &base.clone()).unwrap();
+    assert!(diff.is_empty());
+    assert_eq!(diff.len(), 9);
+}
+
+#[test]
+fn diff_shrink_and_grow() {
+    let large = |i: u8| Large {
+        a: i as u64,
+        b: i,
+        c: Hash256::repeat_byte(i),
+        d: List::empty(),
+    };
+    let base = List::<Large, U16>::new((0..10).map(large).collect()).unwrap();
+
+    // Shrinking.
+    let mut shorter = base.clone();
+    shorter.truncate(3).unwrap();
+    let diff = ListDiff::compute(&base, &shorter).unwrap();
+    assert!(diff.is_empty());
+    let mut applied = base.clone();
+    diff.apply(&mut applied).unwrap();
+    assert_eq!(applied, shorter);
+
+    // Growing from empty.
+    let empty = List::<Large, U16>::empty();
+    let diff = ListDiff::compute(&empty, &base).unwrap();
+    assert_eq!(diff.updates().len(), 10);
+    let mut applied = empty.clone();
+    diff.apply(&mut applied).unwrap();
+    assert_eq!(applied, base);
+}
+
+#[test]
+fn diff_unshared_lists() {
+    // Lists with no structural sharing still produce a minimal diff.
+    let base = List::<u64, U16>::new((0..16).collect()).unwrap();
+    let mut vec = (0..16).collect::<Vec<_>>();
+    vec[9] = 99;
+    let new = List::<u64, U16>::new(vec).unwrap();
+
+    let diff = ListDiff::compute(&base, &new).unwrap();
+    assert_eq!(diff.updates(), &[(9, 99)]);
+}
+
+#[test]
+fn diff_bytes_roundtrip() {
+    let base = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    let mut new = base.clone();
+    *new.get_mut(17).unwrap() = 1;
+    new.try_extend(200..250).unwrap();
+
+    let diff = ListDiff::compute(&base, &new).unwrap();
+    let decoded = ListDiff::<u64, U1024>::from_bytes(&diff.as_bytes()).unwrap();
+    assert_eq!(decoded, diff);
+
+    let mut applied = base;
+    decoded.apply(&mut applied).unwrap();
+    assert_eq!(applied, new);
+}
+
+#[test]
+fn diff_bytes_variable_length_roundtrip() {
+    let large = |i: u8| Large {
+        a: i as u64,
+        b: i,
+        c: Hash256::repeat_byte(i),
+        d: List::new(vec![i as u64; i as usize % 4]).unwrap(),
+    };
+    let base = List::<Large, U16>::new((0..5).map(large).collect()).unwrap();
+    let new = List::<Large, U16>::new((3..12).map(large).collect()).unwrap();
+
+    let diff = ListDiff::compute(&base, &new).unwrap();
+    let decoded = ListDiff::<Large, U16>::from_bytes(&diff.as_bytes()).unwrap();
+    assert_eq!(decoded, diff);
+}
+
+#[test]
+fn diff_bytes_invalid() {
+    let diff = ListDiff::<u64, U16>::from_parts(4, vec![(1, 10), (3, 30)]);
+    let bytes = diff.as_bytes();
+
+    // Unknown version.
+    let mut wrong_version = bytes.clone();
+    wrong_version[0] = 0;
+    assert!(ListDiff::<u64, U16>::from_bytes(&wrong_version).is_err());
+
+    // Truncated.
+    assert!(ListDiff::<u64, U16>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(ListDiff::<u64, U16>::from_bytes(&[]).is_err());
+
+    // Out of bounds index.
+    let out_of_bounds = ListDiff::<u64, U16>::from_parts(2, vec![(3, 30)]);
+    assert!(ListDiff::<u64, U16>::from_bytes(&out_of_bounds.as_bytes()).is_err());
+}
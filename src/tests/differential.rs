@@ -0,0 +1,65 @@
+use crate::testing::differential::{
+    arb_list_ops, arb_vector_ops, DifferentialList, DifferentialVector, ListOp, VectorOp,
+};
+use proptest::prelude::*;
+use tree_hash::Hash256;
+use typenum::{U13, U33, U8};
+
+#[test]
+fn list_ops() {
+    let mut list = DifferentialList::<u16, U13>::new((0..10).collect()).unwrap();
+    list.run(&[
+        ListOp::Set(3, 100),
+        ListOp::Push(10),
+        ListOp::Push(11),
+        ListOp::Push(12),
+        ListOp::Push(13),
+        ListOp::Set(13, 5),
+        ListOp::ApplyUpdates,
+        ListOp::Pop,
+        ListOp::Truncate(4),
+        ListOp::Truncate(8),
+    ]);
+    assert_eq!(list.list().to_vec(), vec![0, 1, 2, 100]);
+}
+
+#[test]
+fn list_too_long() {
+    assert!(DifferentialList::<u16, U8>::new((0..9).collect()).is_err());
+}
+
+#[test]
+fn vector_ops() {
+    let mut vector = DifferentialVector::<u64, U8>::new((0..8).collect()).unwrap();
+    vector.run(&[
+        VectorOp::Set(0, 5),
+        VectorOp::Set(8, 5),
+        VectorOp::ApplyUpdates,
+        VectorOp::Set(7, 1),
+    ]);
+    assert_eq!(vector.reference()[..], [5, 1, 2, 3, 4, 5, 6, 1]);
+    assert!(DifferentialVector::<u64, U8>::new((0..7).collect()).is_err());
+}
+
+proptest! {
+    #[test]
+    fn random_list_ops(
+        values in proptest::collection::vec(any::<u8>(), 0..=33),
+        ops in arb_list_ops(any::<u8>(), 33, 32),
+    ) {
+        DifferentialList::<u8, U33>::new(values).unwrap().run(&ops);
+    }
+
+    #[test]
+    fn random_hash_list_ops(ops in arb_list_ops(any::<[u8; 32]>().prop_map(Hash256::from), 13, 32)) {
+        DifferentialList::<Hash256, U13>::new(vec![]).unwrap().run(&ops);
+    }
+
+    #[test]
+    fn random_vector_ops(
+        values in proptest::collection::vec(any::<u16>(), 33),
+        ops in arb_vector_ops(any::<u16>(), 33, 32),
+    ) {
+        DifferentialVector::<u16, U33>::new(values).unwrap().run(&ops);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{List, Tree};
+use tree_hash::TreeHash;
+use typenum::U16;
+
+fn node_count(dot: &str) -> usize {
+    dot.lines()
+        .map(str::trim_start)
+        .filter(|line| line.starts_with('n') && !line.starts_with("node") && !line.contains("->"))
+        .count()
+}
+
+#[test]
+fn node_kinds_and_hashes() {
+    let list = List::<u64, U16>::new(vec![1, 2, 3, 4, 5]).unwrap();
+    let dot = Tree::to_dot(&list.interface.backing.tree);
+
+    assert!(dot.starts_with("digraph tree {"));
+    assert!(dot.contains("PackedLeaf (4 values)"));
+    assert!(dot.contains("Zero(1)"));
+    assert!(dot.contains("unhashed"));
+
+    list.tree_hash_root();
+    let dot = Tree::to_dot(&list.interface.backing.tree);
+    assert!(!dot.contains("Node\\nunhashed"));
+}
+
+#[test]
+fn shared_nodes_drawn_once() {
+    let list = List::<u64, U16>::new((0..16).collect()).unwrap();
+    let mut modified = list.clone();
+    *modified.get_mut(0).unwrap() = 100;
+    modified.apply_updates().unwrap();
+
+    let tree = &list.interface.backing.tree;
+    let modified_tree = &modified.interface.backing.tree;
+    assert_eq!(node_count(&Tree::to_dot(tree)), 7);
+
+    // Only the path to the first leaf differs between the two trees.
+    let combined = Tree::to_dot_many(&[tree, modified_tree]);
+    assert!(combined.contains("root1 -> "));
+    assert_eq!(node_count(&combined), 10);
+}
@@ -0,0 +1,89 @@
+use crate::{DynList, Error, List};
+use ssz::Encode;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U17, U8};
+
+#[test]
+fn matches_static_list_packed() {
+    let vec = (0..700u64).collect::<Vec<_>>();
+    let list = List::<u64, U1024>::new(vec.clone()).unwrap();
+    let dyn_list = DynList::<u64>::new(vec, 1024).unwrap();
+
+    assert_eq!(dyn_list.max_len(), 1024);
+    assert_eq!(dyn_list.to_vec(), list.to_vec());
+    assert_eq!(dyn_list.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(dyn_list.as_ssz_bytes(), list.as_ssz_bytes());
+}
+
+#[test]
+fn matches_static_list_unpacked_odd_length() {
+    let vec = (0..10u64).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let list = List::<Hash256, U17>::new(vec.clone()).unwrap();
+    let dyn_list = DynList::<Hash256>::new(vec, 17).unwrap();
+
+    assert_eq!(dyn_list.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(
+        DynList::<Hash256>::empty(17).tree_hash_root(),
+        List::<Hash256, U17>::empty().tree_hash_root()
+    );
+}
+
+#[test]
+fn push_to_max_len() {
+    let mut dyn_list = DynList::<u64>::empty(5);
+    let mut list = List::<u64, typenum::U5>::empty();
+    for i in 0..5 {
+        dyn_list.push(i).unwrap();
+        list.push(i).unwrap();
+    }
+    assert_eq!(
+        dyn_list.push(5),
+        Err(Error::ListFull { len: 5, max_len: 5 })
+    );
+    assert_eq!(dyn_list.tree_hash_root(), list.tree_hash_root());
+    dyn_list.apply_updates().unwrap();
+    list.apply_updates().unwrap();
+    assert_eq!(dyn_list.tree_hash_root(), list.tree_hash_root());
+
+    assert_eq!(
+        DynList::<u64>::new(vec![0; 6], 5),
+        Err(Error::ListFull { len: 5, max_len: 5 })
+    );
+}
+
+#[test]
+fn ssz_round_trip() {
+    let list = DynList::<u64>::new(vec![1, 2, 3], 8).unwrap();
+    let bytes = list.as_ssz_bytes();
+    assert_eq!(DynList::<u64>::from_ssz_bytes(&bytes, 8).unwrap(), list);
+    assert!(DynList::<u64>::from_ssz_bytes(&bytes, 2).is_err());
+
+    let nested = [vec![1u8, 2], vec![], vec![3]]
+        .into_iter()
+        .map(|vec| List::<u8, U8>::new(vec).unwrap())
+        .collect::<Vec<_>>();
+    let bytes = List::<_, U8>::new(nested.clone()).unwrap().as_ssz_bytes();
+    let decoded = DynList::<List<u8, U8>>::from_ssz_bytes(&bytes, 3).unwrap();
+    assert_eq!(decoded.to_vec(), nested);
+    assert!(DynList::<List<u8, U8>>::from_ssz_bytes(&bytes, 2).is_err());
+}
+
+#[test]
+fn convert_to_and_from_list() {
+    let mut list = List::<u64, U8>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+
+    let dyn_list = DynList::from(list.clone());
+    assert_eq!(dyn_list.max_len(), 8);
+    assert_eq!(dyn_list.to_vec(), list.to_vec());
+    assert!(dyn_list.has_pending_updates());
+
+    assert_eq!(
+        dyn_list.clone().try_into_list::<U17>(),
+        Err(Error::WrongListMaxLength {
+            max_len: 8,
+            expected: 17
+        })
+    );
+    assert_eq!(dyn_list.try_into_list::<U8>().unwrap(), list);
+}
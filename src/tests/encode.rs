@@ -0,0 +1,64 @@
+use crate::tests::proptest::Large;
+use crate::{DynList, FrozenList, List, Vector};
+use ssz::Encode;
+use ssz_types::VariableList;
+use tree_hash::Hash256;
+use typenum::{U1024, U4, U8};
+
+fn large(i: u8) -> Large {
+    Large {
+        a: i as u64,
+        b: i,
+        c: Hash256::repeat_byte(i),
+        d: List::new(vec![i as u64; (i % 5) as usize]).unwrap(),
+    }
+}
+
+/// Encode `value` into a buffer preallocated from `ssz_bytes_len`, checking that it fits exactly.
+fn encode_preallocated<E: Encode>(value: &E) -> Vec<u8> {
+    let len = value.ssz_bytes_len();
+    let mut buf = Vec::with_capacity(len);
+    value.ssz_append(&mut buf);
+    assert_eq!(buf.len(), len);
+    assert_eq!(buf.capacity(), len);
+    buf
+}
+
+#[test]
+fn variable_length_items() {
+    let values = (0..20).map(large).collect::<Vec<_>>();
+    let expected = VariableList::<Large, U1024>::new(values.clone())
+        .unwrap()
+        .as_ssz_bytes();
+
+    let mut list = List::<Large, U1024>::new(values[..19].to_vec()).unwrap();
+    list.push(values[19].clone()).unwrap();
+    assert_eq!(encode_preallocated(&list), expected);
+
+    list.apply_updates().unwrap();
+    let frozen: FrozenList<Large, U1024> = list.clone().freeze().unwrap();
+    assert_eq!(encode_preallocated(&frozen), expected);
+    let dyn_list = DynList::from(list);
+    assert_eq!(encode_preallocated(&dyn_list), expected);
+
+    let vector = Vector::<Large, U4>::new(values[..4].to_vec()).unwrap();
+    assert_eq!(
+        encode_preallocated(&vector),
+        VariableList::<Large, U4>::new(values[..4].to_vec())
+            .unwrap()
+            .as_ssz_bytes()
+    );
+}
+
+#[test]
+fn nested_lists() {
+    let list = List::<List<u8, U8>, U4>::new(vec![
+        List::new(vec![1, 2, 3]).unwrap(),
+        List::empty(),
+        List::new(vec![4]).unwrap(),
+    ])
+    .unwrap();
+    let bytes = encode_preallocated(&list);
+    assert_eq!(bytes, [12, 0, 0, 0, 15, 0, 0, 0, 15, 0, 0, 0, 1, 2, 3, 4]);
+    assert!(encode_preallocated(&List::<List<u8, U8>, U4>::empty()).is_empty());
+}
@@ -0,0 +1,65 @@
+use crate::{List, Vector};
+use tree_hash::TreeHash;
+use typenum::{U1024, U16};
+
+#[test]
+fn pending_updates_compare_elements() {
+    let list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    let mut pending = List::<u64, U16>::new(vec![1, 2]).unwrap();
+    pending.push(3).unwrap();
+
+    assert!(pending.has_pending_updates());
+    assert_eq!(list, pending);
+    assert!(list.eq_by_root(&pending));
+
+    *pending.get_mut(0).unwrap() = 10;
+    assert_ne!(list, pending);
+    assert!(!list.eq_by_root(&pending));
+}
+
+#[test]
+fn independent_trees() {
+    let vec = (0..1000u64).collect::<Vec<_>>();
+    let l1 = List::<u64, U1024>::new(vec.clone()).unwrap();
+    let l2 = List::<u64, U1024>::new(vec).unwrap();
+    let mut l3 = l2.clone();
+    *l3.get_mut(999).unwrap() = 0;
+    l3.apply_updates().unwrap();
+
+    // Unhashed, hashed on one side only, and hashed on both sides.
+    assert_eq!(l1, l2);
+    assert_ne!(l1, l3);
+    l1.tree_hash_root();
+    assert_eq!(l1, l2);
+    assert_ne!(l1, l3);
+    l2.tree_hash_root();
+    l3.tree_hash_root();
+    assert_eq!(l1, l2);
+    assert_ne!(l1, l3);
+
+    assert!(l1.eq_by_root(&l2));
+    assert!(!l1.eq_by_root(&l3));
+}
+
+#[test]
+fn different_lengths() {
+    // Lists of zeroes of different lengths have the same tree, but are not equal.
+    let l1 = List::<u64, U16>::new(vec![0; 3]).unwrap();
+    let l2 = List::<u64, U16>::new(vec![0; 4]).unwrap();
+    assert_ne!(l1, l2);
+    assert!(!l1.eq_by_root(&l2));
+}
+
+#[test]
+fn vector_eq() {
+    let v1 = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    let mut v2 = v1.clone();
+    assert!(v1.eq_by_root(&v2));
+    *v2.get_mut(3).unwrap() = 3;
+    assert_eq!(v1, v2);
+    assert!(v1.eq_by_root(&v2));
+    *v2.get_mut(3).unwrap() = 4;
+    assert_ne!(v1, v2);
+    v2.apply_updates().unwrap();
+    assert!(!v1.eq_by_root(&v2));
+}
@@ -0,0 +1,81 @@
+use crate::utils::Length;
+use crate::{Arc, Error, List, PackedLeaf, Tree};
+use std::error::Error as _;
+use typenum::U16;
+
+/// A list of `u64`s which claims to have 3 elements, but whose first leaf only contains 1.
+fn truncated_leaf_list() -> List<u64, U16> {
+    let leaf = Arc::new(Tree::PackedLeaf(PackedLeaf::repeat(1u64, 1)));
+    let tree = Tree::node(
+        Tree::node(leaf, Tree::zero(0), Default::default()),
+        Tree::zero(1),
+        Default::default(),
+    );
+    List::from_parts(tree, 2, Length(3))
+}
+
+#[test]
+fn tree_errors_have_position() {
+    let mut list = truncated_leaf_list();
+    list.push(4).unwrap();
+    let error = list.apply_updates().unwrap_err();
+
+    let cause = Error::PackedLeafOutOfBounds {
+        sub_index: 3,
+        len: 1,
+    };
+    assert_eq!(
+        error,
+        Error::InTree {
+            depth: 0,
+            prefix: 0,
+            gindex: 4,
+            source: Box::new(cause.clone()),
+        }
+    );
+    assert_eq!(error.root_cause(), &cause);
+    assert_eq!(
+        error.source().map(ToString::to_string),
+        Some(cause.to_string())
+    );
+    assert_eq!(
+        error.to_string(),
+        "error in subtree at generalized index 4 (depth 0, prefix 0)"
+    );
+}
+
+#[test]
+fn single_leaf_errors_have_position() {
+    let list = truncated_leaf_list();
+    let tree = &list.interface.backing.tree;
+    let error = tree.with_updated_leaf(2, 9, 2).unwrap_err();
+    assert_eq!(
+        error.root_cause(),
+        &Error::PackedLeafOutOfBounds {
+            sub_index: 2,
+            len: 1,
+        }
+    );
+    assert!(matches!(error, Error::InTree { gindex: 4, .. }));
+
+    // Passing the wrong depth leads to a mismatch between the tree and the index's path.
+    assert_eq!(
+        tree.with_updated_leaf(9, 9, 3),
+        Err(Error::InTree {
+            depth: 1,
+            prefix: 8,
+            gindex: 5,
+            source: Box::new(Error::UpdateLeafError),
+        })
+    );
+}
+
+#[test]
+fn errors_without_position() {
+    let error = Error::ListFull {
+        len: 16,
+        max_len: 16,
+    };
+    assert_eq!(error.root_cause(), &error);
+    assert!(error.source().is_none());
+}
@@ -0,0 +1,96 @@
+use crate::{Error, List};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{Unsigned, U16, U64};
+
+#[test]
+fn extend_packed_matches_fresh_list() {
+    type N = U64;
+    let vec = (0..N::to_u64()).collect::<Vec<_>>();
+
+    for split in [0, 1, 3, 4, 17, 32, 63] {
+        let mut list = List::<u64, N>::new(vec[..split].to_vec()).unwrap();
+        list.try_extend(vec[split..].iter().copied()).unwrap();
+        assert!(!list.has_pending_updates());
+
+        let expected = List::<u64, N>::new(vec.clone()).unwrap();
+        assert_eq!(list, expected);
+        assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+    }
+}
+
+#[test]
+fn extend_with_pending_updates() {
+    let mut list = List::<Hash256, U16>::new(vec![Hash256::zero(); 3]).unwrap();
+    *list.get_mut(1).unwrap() = Hash256::repeat_byte(1);
+    list.push(Hash256::repeat_byte(3)).unwrap();
+
+    list.extend((4..6).map(Hash256::repeat_byte));
+
+    let expected = [0, 1, 0, 3, 4, 5].map(Hash256::repeat_byte).to_vec();
+    assert_eq!(list.to_vec(), expected);
+    assert_eq!(list, List::new(expected).unwrap());
+}
+
+#[test]
+fn extend_full_list_unmodified() {
+    let mut list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    *list.get_mut(0).unwrap() = 100;
+
+    assert_eq!(
+        list.try_extend(0..7),
+        Err(Error::ListFull {
+            len: 16,
+            max_len: 16
+        })
+    );
+
+    // The pending update is kept, and none of the new elements were appended.
+    let mut expected = (0..10).collect::<Vec<_>>();
+    expected[0] = 100;
+    assert!(list.has_pending_updates());
+    assert_eq!(list.to_vec(), expected);
+}
+
+#[test]
+fn extend_full_list_keeps_pending_pushes() {
+    let mut list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    *list.get_mut(3).unwrap() = 30;
+    list.push(10).unwrap();
+    list.push(11).unwrap();
+    let before = list.clone();
+
+    assert_eq!(
+        list.try_extend(12..17),
+        Err(Error::ListFull {
+            len: 16,
+            max_len: 16
+        })
+    );
+    assert!(list.has_pending_updates());
+    assert_eq!(list.len(), 12);
+    assert_eq!(list.to_vec(), before.to_vec());
+
+    list.apply_updates().unwrap();
+    let mut expected = (0..12).collect::<Vec<_>>();
+    expected[3] = 30;
+    assert_eq!(list, List::new(expected).unwrap());
+}
+
+#[test]
+fn push_within_capacity_returns_value() {
+    let mut list = List::<Hash256, U16>::new(vec![Hash256::zero(); 15]).unwrap();
+    assert_eq!(list.push_within_capacity(Hash256::repeat_byte(1)), Ok(()));
+    assert_eq!(
+        list.push_within_capacity(Hash256::repeat_byte(2)),
+        Err(Hash256::repeat_byte(2))
+    );
+    assert_eq!(
+        list.push(Hash256::repeat_byte(2)),
+        Err(Error::ListFull {
+            len: 16,
+            max_len: 16
+        })
+    );
+    assert_eq!(list.len(), 16);
+    assert_eq!(list.get(15), Some(&Hash256::repeat_byte(1)));
+}
@@ -0,0 +1,62 @@
+use crate::{FrozenList, List};
+use ssz::Encode;
+use std::mem::size_of;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16};
+
+#[test]
+fn freeze_applies_pending_updates() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    *list.get_mut(0).unwrap() = 10;
+
+    let frozen = list.clone().freeze().unwrap();
+    assert_eq!(frozen.len(), 4);
+    assert_eq!(frozen.to_vec(), vec![10, 2, 3, 4]);
+    assert_eq!(frozen.get(3), Some(&4));
+    assert_eq!(frozen.get(4), None);
+
+    list.apply_updates().unwrap();
+    assert_eq!(frozen.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(frozen.as_ssz_bytes(), list.as_ssz_bytes());
+}
+
+#[test]
+fn thaw_round_trip() {
+    let vec = (0..100).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let list = List::<Hash256, U1024>::new(vec.clone()).unwrap();
+    let frozen = list.clone().freeze().unwrap();
+
+    let mut thawed: List<Hash256, U1024> = frozen.thaw();
+    assert_eq!(thawed, list);
+
+    thawed.push(Hash256::repeat_byte(0xff)).unwrap();
+    thawed.apply_updates().unwrap();
+    assert_eq!(thawed.len(), 101);
+
+    // The frozen list is unaffected by changes to the thawed copy.
+    assert_eq!(frozen.to_vec(), vec);
+    assert_eq!(frozen.tree_hash_root(), list.tree_hash_root());
+}
+
+#[test]
+fn empty_and_packed() {
+    let frozen = List::<u8, U1024>::empty().freeze().unwrap();
+    assert!(frozen.is_empty());
+    assert_eq!(frozen.iter().count(), 0);
+    assert_eq!(
+        frozen.tree_hash_root(),
+        List::<u8, U1024>::empty().tree_hash_root()
+    );
+
+    let list = List::<u8, U1024>::new((0..=255).collect()).unwrap();
+    let frozen = list.clone().freeze().unwrap();
+    assert_eq!(frozen.to_vec(), list.to_vec());
+    assert_eq!(frozen.get(200), Some(&200));
+    assert_eq!(frozen, frozen.clone());
+}
+
+#[test]
+fn smaller_than_list() {
+    assert!(size_of::<FrozenList<u64, U16>>() < size_of::<List<u64, U16>>());
+}
@@ -0,0 +1,53 @@
+use crate::fuzz::{ops_model, packed_leaf_boundaries, ssz_round_trip, update_map_equivalence};
+use ssz::Encode;
+
+/// Deterministic pseudo-random inputs, so a short run of each harness happens on every test.
+fn inputs(count: usize, len: usize) -> impl Iterator<Item = Vec<u8>> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    (0..count).map(move |_| {
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn ssz_round_trip_random() {
+    for input in inputs(200, 64) {
+        ssz_round_trip(&input);
+    }
+}
+
+#[test]
+fn ssz_round_trip_valid_encodings() {
+    ssz_round_trip(&[]);
+    ssz_round_trip(&(0..255u8).collect::<Vec<_>>());
+    ssz_round_trip(&(0..130u16).collect::<Vec<_>>().as_ssz_bytes());
+    ssz_round_trip(&vec![vec![1u16, 2], vec![], vec![3]].as_ssz_bytes());
+}
+
+#[test]
+fn ops_model_random() {
+    for input in inputs(100, 512) {
+        ops_model(&input);
+    }
+}
+
+#[test]
+fn update_map_equivalence_random() {
+    for input in inputs(100, 512) {
+        update_map_equivalence(&input);
+    }
+}
+
+#[test]
+fn packed_leaf_boundaries_random() {
+    for input in inputs(200, 256) {
+        packed_leaf_boundaries(&input);
+    }
+}
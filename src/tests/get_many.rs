@@ -0,0 +1,53 @@
+use crate::{List, Vector};
+use tree_hash::Hash256;
+use typenum::{U1024, U16, U8};
+
+#[test]
+fn first_and_last() {
+    let mut list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    assert_eq!(list.first(), Some(&0));
+    assert_eq!(list.last(), Some(&9));
+
+    list.push(10).unwrap();
+    *list.get_mut(0).unwrap() = 100;
+    assert_eq!(list.first(), Some(&100));
+    assert_eq!(list.last(), Some(&10));
+
+    let empty = List::<u64, U16>::empty();
+    assert_eq!(empty.first(), None);
+    assert_eq!(empty.last(), None);
+
+    let vector =
+        Vector::<Hash256, U8>::new((0..8).map(Hash256::from_low_u64_be).collect()).unwrap();
+    assert_eq!(vector.first(), Some(&Hash256::from_low_u64_be(0)));
+    assert_eq!(vector.last(), Some(&Hash256::from_low_u64_be(7)));
+}
+
+#[test]
+fn get_many() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let indices = [999, 0, 500, 3, 3, 1000, 4, usize::MAX, 1023, 2048];
+    let expected = indices.iter().map(|&i| list.get(i)).collect::<Vec<_>>();
+    assert_eq!(list.get_many(&indices), expected);
+    assert_eq!(list.get_many(&[]), Vec::<Option<&u64>>::new());
+
+    let packed = List::<u8, U1024>::new((0..700).map(|i| i as u8).collect()).unwrap();
+    let indices = (0..1030).rev().step_by(7).collect::<Vec<_>>();
+    let expected = indices.iter().map(|&i| packed.get(i)).collect::<Vec<_>>();
+    assert_eq!(packed.get_many(&indices), expected);
+}
+
+#[test]
+fn get_many_pending_updates() {
+    let mut list = List::<u64, U16>::new((0..8).collect()).unwrap();
+    list.push(8).unwrap();
+    *list.get_mut(2).unwrap() = 20;
+    assert_eq!(
+        list.get_many(&[8, 2, 1, 9]),
+        vec![Some(&8), Some(&20), Some(&1), None]
+    );
+
+    let mut vector = Vector::<u64, U8>::new((0..8).collect()).unwrap();
+    *vector.get_mut(7).unwrap() = 70;
+    assert_eq!(vector.get_many(&[7, 6, 8]), vec![Some(&70), Some(&6), None]);
+}
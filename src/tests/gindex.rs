@@ -0,0 +1,99 @@
+use crate::gindex::{
+    concat_gindices, gindex_at_depth, gindex_child, gindex_depth, gindex_parent, gindex_sibling,
+    LIST_DATA_GINDEX, LIST_LENGTH_GINDEX,
+};
+use crate::{Error, List, Tree, Vector};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16, U8};
+
+#[test]
+fn helpers() {
+    assert_eq!(gindex_depth(0), None);
+    assert_eq!(gindex_depth(1), Some(0));
+    assert_eq!(gindex_depth(7), Some(2));
+    assert_eq!(gindex_parent(7), 3);
+    assert_eq!(gindex_sibling(6), 7);
+    assert_eq!(gindex_child(3, false), 6);
+    assert_eq!(gindex_child(3, true), 7);
+    assert_eq!(gindex_at_depth(3, 5), Some(13));
+    assert_eq!(gindex_at_depth(3, 8), None);
+    assert_eq!(concat_gindices(2, 1), Some(2));
+    assert_eq!(concat_gindices(2, 5), Some(9));
+    assert_eq!(concat_gindices(3, 6), Some(14));
+    assert_eq!(concat_gindices(3, 0), None);
+    assert_eq!(concat_gindices(usize::MAX, 2), None);
+}
+
+#[test]
+fn list_gindices_match_proofs() {
+    let list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    for index in [0, 3, 4, 50, 99] {
+        let proof = list.prove(index).unwrap();
+        let gindex = List::<u64, U1024>::generalized_index(index).unwrap();
+        assert_eq!(gindex, proof.gindex);
+        assert_eq!(list.hash_at(gindex).unwrap(), proof.leaf);
+        assert_eq!(
+            list.hash_at(gindex_sibling(gindex)).unwrap(),
+            proof.branch[0]
+        );
+    }
+    assert_eq!(List::<u64, U1024>::generalized_index(1024), None);
+
+    assert_eq!(list.hash_at(1).unwrap(), list.tree_hash_root());
+    assert_eq!(
+        list.hash_at(LIST_DATA_GINDEX).unwrap(),
+        list.interface.backing.tree.tree_hash()
+    );
+    assert_eq!(
+        list.hash_at(LIST_LENGTH_GINDEX).unwrap(),
+        crate::proof::length_chunk(100)
+    );
+}
+
+#[test]
+fn list_navigation() {
+    let list = List::<Hash256, U16>::new((1..=3).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let gindex = List::<Hash256, U16>::generalized_index(2).unwrap();
+    assert_eq!(gindex, 34);
+    assert_eq!(list.hash_at(gindex).unwrap(), Hash256::from_low_u64_be(3));
+
+    // Nodes in the padding are zero subtrees.
+    let padding = list.node_at(gindex_parent(
+        List::<Hash256, U16>::generalized_index(8).unwrap(),
+    ));
+    assert_eq!(*padding.unwrap(), Tree::Zero(1));
+    assert_eq!(
+        list.hash_at(List::<Hash256, U16>::generalized_index(15).unwrap())
+            .unwrap(),
+        Hash256::zero()
+    );
+
+    for gindex in [0, 3, 64, 1 << 20] {
+        assert_eq!(
+            list.node_at(gindex),
+            Err(Error::InvalidGeneralizedIndex { gindex })
+        );
+    }
+
+    let mut list = list;
+    list.push(Hash256::zero()).unwrap();
+    assert_eq!(list.hash_at(1), Err(Error::GeneralizedIndexPendingUpdates));
+    assert_eq!(list.node_at(2), Err(Error::GeneralizedIndexPendingUpdates));
+}
+
+#[test]
+fn vector_gindices_match_proofs() {
+    let vector = Vector::<u64, U8>::new((10..18).collect()).unwrap();
+    for index in 0..8 {
+        let proof = vector.prove(index).unwrap();
+        let gindex = Vector::<u64, U8>::generalized_index(index).unwrap();
+        assert_eq!(gindex, proof.gindex);
+        assert_eq!(vector.hash_at(gindex).unwrap(), proof.leaf);
+    }
+    assert_eq!(Vector::<u64, U8>::generalized_index(8), None);
+    assert_eq!(vector.hash_at(1).unwrap(), vector.tree_hash_root());
+    assert_eq!(
+        vector.node_at(4),
+        Err(Error::InvalidGeneralizedIndex { gindex: 4 })
+    );
+}
@@ -0,0 +1,71 @@
+use crate::{List, Vector};
+use ssz_derive::{Decode, Encode};
+use tree_hash_derive::TreeHash;
+use typenum::{U4, U8};
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, TreeHash)]
+struct Validator {
+    effective_balance: u64,
+    slashed: bool,
+}
+
+fn validators() -> List<Validator, U8> {
+    List::new(
+        (0..4)
+            .map(|i| Validator {
+                effective_balance: i * 32,
+                slashed: false,
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn write_back_on_drop() {
+    let mut list = validators();
+    {
+        let mut validator = list.get_mut_guard(2).unwrap();
+        validator.effective_balance += 1;
+        validator.slashed = true;
+        assert_eq!(validator.index(), 2);
+        // The change is not visible until the guard is dropped.
+    }
+    assert!(list.has_pending_updates());
+    assert_eq!(
+        list.get(2),
+        Some(&Validator {
+            effective_balance: 65,
+            slashed: true
+        })
+    );
+
+    list.get_mut_guard(2).unwrap().effective_balance += 1;
+    list.apply_updates().unwrap();
+    assert_eq!(list.get(2).unwrap().effective_balance, 66);
+    assert!(list.get_mut_guard(4).is_none());
+}
+
+#[test]
+fn unchanged_values_are_not_recorded() {
+    let mut list = validators();
+    {
+        let mut validator = list.get_mut_guard(1).unwrap();
+        validator.effective_balance += 1;
+        validator.effective_balance -= 1;
+    }
+    assert!(!list.has_pending_updates());
+
+    let mut validator = list.get_mut_guard(0).unwrap();
+    validator.slashed = true;
+    validator.discard();
+    assert!(!list.has_pending_updates());
+    assert_eq!(list, validators());
+}
+
+#[test]
+fn vector_guard() {
+    let mut vector = Vector::<u64, U4>::new(vec![1, 2, 3, 4]).unwrap();
+    *vector.get_mut_guard(3).unwrap() *= 10;
+    assert_eq!(vector.to_vec(), vec![1, 2, 3, 40]);
+}
@@ -0,0 +1,118 @@
+use crate::{List, MerkleHasher, Sha256Hasher, Vector};
+use std::cell::Cell;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16};
+
+thread_local! {
+    /// Custom hashers hash on the calling thread, so each test has its own count.
+    static HASHES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// SHA256 hasher which counts how many pairs it hashes.
+struct CountingHasher;
+
+impl MerkleHasher for CountingHasher {
+    fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+        HASHES.with(|hashes| hashes.set(hashes.get() + 1));
+        Sha256Hasher::hash_pair(left, right)
+    }
+
+    fn zero_hash(depth: usize) -> Hash256 {
+        Sha256Hasher::zero_hash(depth)
+    }
+}
+
+/// Trivial (insecure) hasher which XORs its inputs.
+struct XorHasher;
+
+impl MerkleHasher for XorHasher {
+    fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+        let mut result = *left ^ *right;
+        result.0[0] = result.0[0].wrapping_add(1);
+        result
+    }
+}
+
+fn hashes() -> usize {
+    HASHES.with(Cell::get)
+}
+
+#[test]
+fn sha256_hasher_matches_tree_hash() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let expected = list.tree_hash_root();
+    let fresh = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    assert_eq!(
+        fresh.tree_hash_root_with::<Sha256Hasher>().unwrap(),
+        expected
+    );
+
+    let vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    assert_eq!(
+        vector.tree_hash_root_with::<Sha256Hasher>().unwrap(),
+        vector.tree_hash_root()
+    );
+}
+
+#[test]
+fn custom_hasher_ignores_cached_hashes() {
+    let mut list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let before = hashes();
+    let root = list.tree_hash_root_with::<CountingHasher>().unwrap();
+    // One hash per internal node, plus the length mix-in.
+    assert_eq!(hashes() - before, 255 + 1);
+    assert_eq!(root, list.tree_hash_root());
+
+    // The SHA256 hashes cached by `tree_hash_root` aren't used by other hashers.
+    *list.get_mut(0).unwrap() = 1;
+    list.apply_updates().unwrap();
+    list.tree_hash_root();
+    let before = hashes();
+    list.tree_hash_root_with::<CountingHasher>().unwrap();
+    assert_eq!(hashes() - before, 255 + 1);
+}
+
+#[test]
+fn shared_subtrees_hashed_once() {
+    let list = List::<u64, U1024>::repeat(7, 1024).unwrap();
+    let before = hashes();
+    let root = list.tree_hash_root_with::<CountingHasher>().unwrap();
+    // Every subtree at each level is identical, so only one node per level is hashed.
+    assert_eq!(hashes() - before, List::<u64, U1024>::depth() + 1);
+    assert_eq!(root, list.tree_hash_root());
+}
+
+#[test]
+fn custom_hasher() {
+    let list = List::<u64, U16>::new((0..5).collect()).unwrap();
+    let root = list.tree_hash_root_with::<XorHasher>().unwrap();
+    assert_ne!(root, list.tree_hash_root_with::<Sha256Hasher>().unwrap());
+
+    // Hashing with a custom hasher doesn't affect the SHA256 root, or equality with identical
+    // lists.
+    let fresh = List::<u64, U16>::new((0..5).collect()).unwrap();
+    assert_eq!(list.tree_hash_root(), fresh.tree_hash_root());
+    assert_eq!(list, fresh);
+
+    let list = List::<u64, U16>::new((0..5).collect()).unwrap();
+    let clone = list.clone();
+    list.tree_hash_root_with::<XorHasher>().unwrap();
+    assert_eq!(clone.tree_hash_root(), fresh.tree_hash_root());
+    assert_eq!(clone, List::<u64, U16>::new((0..5).collect()).unwrap());
+
+    // Zero subtrees are hashed with the custom hasher too.
+    assert_eq!(
+        XorHasher::zero_hash(1),
+        XorHasher::hash_pair(&Hash256::zero(), &Hash256::zero())
+    );
+}
+
+#[test]
+fn pending_updates_included() {
+    let mut list = List::<u64, U16>::new(vec![1]).unwrap();
+    list.push(2).unwrap();
+    assert_eq!(
+        list.tree_hash_root_with::<Sha256Hasher>(),
+        Ok(List::<u64, U16>::new(vec![1, 2]).unwrap().tree_hash_root())
+    );
+}
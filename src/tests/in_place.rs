@@ -0,0 +1,93 @@
+use crate::interface::MutList;
+use crate::{Arc, Error, List, Vector};
+use std::collections::BTreeMap;
+use tree_hash::TreeHash;
+use typenum::{U1024, U16, U8};
+
+#[test]
+fn unique_tree_updated_in_place() {
+    let mut list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    list.tree_hash_root();
+    let root_ptr = Arc::as_ptr(&list.interface.backing.tree);
+
+    *list.get_mut(3).unwrap() = 300;
+    *list.get_mut(500).unwrap() = 5000;
+    list.push(1000).unwrap();
+    list.apply_updates().unwrap();
+    assert_eq!(Arc::as_ptr(&list.interface.backing.tree), root_ptr);
+
+    // Cached hashes along the modified paths are invalidated.
+    let mut expected = (0..=1000).collect::<Vec<u64>>();
+    expected[3] = 300;
+    expected[500] = 5000;
+    let expected = List::<u64, U1024>::new(expected).unwrap();
+    assert_eq!(list, expected);
+    assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+}
+
+#[test]
+fn failed_update_leaves_tree_unchanged() {
+    let mut list = List::<u64, U16>::new((0..5).collect()).unwrap();
+    let root = list.tree_hash_root();
+
+    // The first update is valid, but the second leaves a gap in the packed leaf after index 4.
+    let updates = BTreeMap::from([(1, 100), (6, 6)]);
+    let result = list.interface.backing.update(updates, None);
+    assert_eq!(
+        result.map_err(|e| e.root_cause().clone()),
+        Err(Error::PackedLeafOutOfBounds {
+            sub_index: 2,
+            len: 1
+        })
+    );
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.to_vec(), (0..5).collect::<Vec<_>>());
+    assert_eq!(list.tree_hash_root(), root);
+}
+
+#[test]
+fn shared_tree_copied_on_write() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    let clone = list.clone();
+    list.tree_hash_root();
+
+    *list.get_mut(10).unwrap() = 0;
+    list.apply_updates().unwrap();
+    assert_eq!(clone.get(10), Some(&10));
+    assert_eq!(list.get(10), Some(&0));
+    assert_ne!(list.tree_hash_root(), clone.tree_hash_root());
+
+    // The newly copied path is unique to `list`, while the rest stays shared.
+    *list.get_mut(11).unwrap() = 0;
+    *list.get_mut(99).unwrap() = 0;
+    list.apply_updates().unwrap();
+    assert_eq!(clone.to_vec(), (0..100).collect::<Vec<_>>());
+    let mut expected = (0..100).collect::<Vec<u64>>();
+    expected[10] = 0;
+    expected[11] = 0;
+    expected[99] = 0;
+    assert_eq!(list.to_vec(), expected);
+    assert_eq!(
+        list.tree_hash_root(),
+        List::<u64, U1024>::new(expected).unwrap().tree_hash_root()
+    );
+}
+
+#[test]
+fn unpacked_values_updated_in_place() {
+    let mut list =
+        List::<List<u8, U8>, U16>::new((0..10).map(|i| List::new(vec![i; 3]).unwrap()).collect())
+            .unwrap();
+    let clone = list.clone();
+    for _ in 0..2 {
+        *list.get_mut(4).unwrap() = List::new(vec![100]).unwrap();
+        list.apply_updates().unwrap();
+    }
+    assert_eq!(list.get(4).unwrap().to_vec(), vec![100]);
+    assert_eq!(clone.get(4).unwrap().to_vec(), vec![4; 3]);
+
+    let mut vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    *vector.get_mut(15).unwrap() = 0;
+    vector.apply_updates().unwrap();
+    assert_eq!(vector.get(15), Some(&0));
+}
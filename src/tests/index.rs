@@ -0,0 +1,40 @@
+use crate::{List, Vector};
+use typenum::{U1024, U8};
+
+#[test]
+fn index_list() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    assert_eq!(list[0], 0);
+    assert_eq!(list[99], 99);
+
+    // Pending updates are included.
+    *list.get_mut(5).unwrap() = 500;
+    list.push(100).unwrap();
+    assert_eq!(list[5], 500);
+    assert_eq!(list[100], 100);
+
+    list.apply_updates().unwrap();
+    let view = list.subtree(10..20).unwrap();
+    assert_eq!(view[0], 10);
+    assert_eq!(view[9], 19);
+}
+
+#[test]
+fn index_vector() {
+    let vector = Vector::<u64, U8>::new((10..18).collect()).unwrap();
+    assert!((0..8).all(|i| vector[i] == 10 + i as u64));
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 3 but the index is 3")]
+fn index_out_of_bounds() {
+    let list = List::<u64, U8>::new(vec![1, 2, 3]).unwrap();
+    let _ = list[3];
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 2 but the index is 2")]
+fn index_view_out_of_bounds() {
+    let list = List::<u64, U8>::new(vec![1, 2, 3, 4]).unwrap();
+    let _ = list.subtree(1..3).unwrap()[2];
+}
@@ -0,0 +1,107 @@
+use crate::{Arc, InternPool, List, Vector};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16, U4, U64};
+
+#[test]
+fn identical_lists_share_tree() {
+    let pool = InternPool::new();
+    let mut l1 = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let mut l2 = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    assert!(!Arc::ptr_eq(
+        &l1.interface.backing.tree,
+        &l2.interface.backing.tree
+    ));
+
+    l1.intern(&pool).unwrap();
+    l2.intern(&pool).unwrap();
+    assert!(Arc::ptr_eq(
+        &l1.interface.backing.tree,
+        &l2.interface.backing.tree
+    ));
+    assert_eq!(l1, l2);
+}
+
+#[test]
+fn common_subtrees_shared() {
+    let pool = InternPool::new();
+    let mut l1 = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let mut l2 = List::<u64, U1024>::new((0..1024).map(|i| i.min(1000)).collect()).unwrap();
+    let expected_root = l2.tree_hash_root();
+
+    l1.intern(&pool).unwrap();
+    l2.intern(&pool).unwrap();
+
+    // Only the right-most path of `l2` differs from `l1`, so the rest of its nodes are shared.
+    let usage = l2.mem_usage();
+    assert!(usage.unique_nodes < usage.nodes);
+    assert_eq!(l2.tree_hash_root(), expected_root);
+    assert_eq!(l2.iter().copied().collect::<Vec<_>>()[1023], 1000);
+}
+
+#[test]
+fn pending_updates_applied() {
+    let pool = InternPool::new();
+    let mut vector = Vector::<u64, U16>::new(vec![0; 16]).unwrap();
+    *vector.get_mut(3).unwrap() = 3;
+    vector.intern(&pool).unwrap();
+    assert!(!vector.has_pending_updates());
+    assert_eq!(vector.get(3), Some(&3));
+
+    let mut values = vec![0; 16];
+    values[3] = 3;
+    let mut other = Vector::<u64, U16>::new(values).unwrap();
+    other.intern(&pool).unwrap();
+    assert!(Arc::ptr_eq(
+        &vector.interface.backing.tree,
+        &other.interface.backing.tree
+    ));
+}
+
+#[test]
+fn prune_drops_unreferenced_nodes() {
+    let pool = InternPool::new();
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    list.intern(&pool).unwrap();
+    assert!(!pool.is_empty());
+
+    pool.prune();
+    assert!(!pool.is_empty());
+
+    drop(list);
+    pool.prune();
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn zero_padded_leaves_not_confused() {
+    let pool = InternPool::new();
+    let mut short = List::<u64, U64>::new(vec![0, 0, 0]).unwrap();
+    short.intern(&pool).unwrap();
+
+    // The first packed leaf of `long` hashes the same as the only leaf of `short`.
+    let mut long = List::<u64, U64>::new(vec![0, 0, 0, 0, 7]).unwrap();
+    let expected_root = long.tree_hash_root();
+    long.intern(&pool).unwrap();
+    assert_eq!(long.get(3), Some(&0));
+    assert_eq!(long.to_vec(), vec![0, 0, 0, 0, 7]);
+    assert_eq!(long.tree_hash_root(), expected_root);
+    assert_eq!(short.to_vec(), vec![0, 0, 0]);
+}
+
+#[test]
+fn zero_leaves_not_confused_with_zero_subtrees() {
+    let pool = InternPool::new();
+    let mut one = List::<Hash256, U4>::new(vec![Hash256::zero()]).unwrap();
+    one.intern(&pool).unwrap();
+
+    let mut two = List::<Hash256, U4>::new(vec![Hash256::zero(); 2]).unwrap();
+    two.intern(&pool).unwrap();
+    assert_eq!(two.get(1), Some(&Hash256::zero()));
+    assert_eq!(two.iter().count(), 2);
+    two.validate().unwrap();
+
+    let mut zero = List::<Hash256, U4>::empty();
+    zero.intern(&pool).unwrap();
+    assert_eq!(zero.iter().count(), 0);
+    assert_eq!(one.to_vec(), vec![Hash256::zero()]);
+}
@@ -0,0 +1,77 @@
+use crate::{List, Vector};
+use ssz_derive::{Decode, Encode};
+use std::cell::Cell;
+use tree_hash_derive::TreeHash;
+use typenum::{U1024, U16, U64};
+
+thread_local! {
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Value which counts how many times it has been cloned on the current thread.
+#[derive(Debug, PartialEq, Encode, Decode, TreeHash)]
+struct Counted {
+    value: u64,
+    other: u64,
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        CLONES.with(|clones| clones.set(clones.get() + 1));
+        Self {
+            value: self.value,
+            other: self.other,
+        }
+    }
+}
+
+fn counted_list(n: u64) -> List<Counted, U64> {
+    List::new((0..n).map(|value| Counted { value, other: 0 }).collect()).unwrap()
+}
+
+fn clones() -> usize {
+    CLONES.with(Cell::get)
+}
+
+#[test]
+fn into_iter_matches_iter() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let expected = list.to_vec();
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), expected);
+
+    let vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    assert_eq!(
+        vector.into_iter().collect::<Vec<_>>(),
+        (0..16).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn into_iter_pending_updates() {
+    let mut list = List::<u64, U1024>::new((0..10).collect()).unwrap();
+    *list.get_mut(3).unwrap() = 30;
+    list.push(10).unwrap();
+    let expected = list.to_vec();
+
+    let iter = list.into_iter();
+    assert_eq!(iter.len(), 11);
+    assert_eq!(iter.collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn into_iter_unique_moves_values() {
+    let list = counted_list(50);
+    let before = clones();
+    assert_eq!(list.into_iter().count(), 50);
+    assert_eq!(clones(), before);
+}
+
+#[test]
+fn into_iter_shared_clones_values() {
+    let list = counted_list(50);
+    let other = list.clone();
+    let before = clones();
+    let values = list.into_iter().collect::<Vec<_>>();
+    assert_eq!(clones(), before + 50);
+    assert_eq!(values, other.to_vec());
+}
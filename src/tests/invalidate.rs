@@ -0,0 +1,183 @@
+use crate::{Error, List, Vector};
+use ssz::{Decode, DecodeError, Encode};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
+use typenum::{U16, U8};
+
+/// `u64` which can be modified through a shared reference, and hashes like a `u64`.
+#[derive(Debug, Clone, Default)]
+struct Shared(Arc<AtomicU64>);
+
+impl Shared {
+    fn new(value: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(value)))
+    }
+
+    fn load(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn store(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for Shared {
+    fn eq(&self, other: &Self) -> bool {
+        self.load() == other.load()
+    }
+}
+
+impl Encode for Shared {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        8
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        8
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.load().ssz_append(buf)
+    }
+}
+
+impl Decode for Shared {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        8
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        u64::from_ssz_bytes(bytes).map(Self::new)
+    }
+}
+
+impl TreeHash for Shared {
+    fn tree_hash_type() -> TreeHashType {
+        u64::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        self.load().tree_hash_packed_encoding()
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        u64::tree_hash_packing_factor()
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.load().tree_hash_root()
+    }
+}
+
+fn expected_root(values: &[Shared]) -> Hash256 {
+    List::<u64, U16>::new(values.iter().map(Shared::load).collect())
+        .unwrap()
+        .tree_hash_root()
+}
+
+#[test]
+fn invalidate_list() {
+    let values = (0..10).map(Shared::new).collect::<Vec<_>>();
+    let list = List::<Shared, U16>::new(values.clone()).unwrap();
+    let root = list.tree_hash_root();
+    assert_eq!(root, expected_root(&values));
+
+    // The cached hash goes stale when an element is modified behind the list's back.
+    values[5].store(100);
+    assert_eq!(list.tree_hash_root(), root);
+
+    list.invalidate_range(5..6).unwrap();
+    assert_eq!(list.tree_hash_root(), expected_root(&values));
+
+    // Invalidating more than necessary is harmless.
+    values[0].store(7);
+    values[9].store(8);
+    list.invalidate_range(0..10).unwrap();
+    assert_eq!(list.tree_hash_root(), expected_root(&values));
+    list.invalidate_range(3..3).unwrap();
+    assert_eq!(list.tree_hash_root(), expected_root(&values));
+}
+
+#[test]
+fn invalidate_shared_subtrees() {
+    let values = (0..10).map(Shared::new).collect::<Vec<_>>();
+    let list = List::<Shared, U16>::new(values.clone()).unwrap();
+    let mut clone = list.clone();
+    clone.push(Shared::new(10)).unwrap();
+    clone.apply_updates().unwrap();
+    list.tree_hash_root();
+    clone.tree_hash_root();
+
+    // The clone shares the leaf holding element 2, but not its ancestors, so both lists must be
+    // invalidated.
+    values[2].store(50);
+    list.invalidate_range(2..3).unwrap();
+    clone.invalidate_range(2..3).unwrap();
+    assert_eq!(list.tree_hash_root(), expected_root(&values));
+
+    let mut clone_values = values.clone();
+    clone_values.push(Shared::new(10));
+    assert_eq!(clone.tree_hash_root(), expected_root(&clone_values));
+}
+
+#[test]
+fn invalidate_one_of_shared_lists() {
+    let values = (0..10).map(Shared::new).collect::<Vec<_>>();
+    let list = List::<Shared, U16>::new(values.clone()).unwrap();
+    let mut clone = list.clone();
+    clone.push(Shared::new(10)).unwrap();
+    clone.apply_updates().unwrap();
+    let clone_root = clone.tree_hash_root();
+    list.tree_hash_root();
+
+    // Invalidating only `list` leaves the clone's root stale, even though the modified leaf is
+    // shared between them.
+    values[2].store(50);
+    list.invalidate_range(2..3).unwrap();
+    assert_eq!(list.tree_hash_root(), expected_root(&values));
+    assert_eq!(clone.tree_hash_root(), clone_root);
+
+    let mut clone_values = values.clone();
+    clone_values.push(Shared::new(10));
+    assert_ne!(clone_root, expected_root(&clone_values));
+    clone.invalidate_range(2..3).unwrap();
+    assert_eq!(clone.tree_hash_root(), expected_root(&clone_values));
+}
+
+#[test]
+fn invalidate_vector() {
+    let values = (0..8).map(Shared::new).collect::<Vec<_>>();
+    let vector = Vector::<Shared, U8>::new(values.clone()).unwrap();
+    vector.tree_hash_root();
+
+    values[7].store(70);
+    vector.invalidate_range(6..8).unwrap();
+    let expected = Vector::<u64, U8>::new(values.iter().map(Shared::load).collect()).unwrap();
+    assert_eq!(vector.tree_hash_root(), expected.tree_hash_root());
+}
+
+#[test]
+fn invalidate_out_of_bounds() {
+    let list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    assert_eq!(
+        list.invalidate_range(8..11),
+        Err(Error::OutOfBoundsSubtree {
+            start: 8,
+            end: 11,
+            len: 10
+        })
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let result = list.invalidate_range(4..2);
+    assert!(result.is_err());
+}
@@ -1,6 +1,6 @@
 use crate::{Error, List, Vector};
 use tree_hash::Hash256;
-use typenum::{Unsigned, U64};
+use typenum::{Unsigned, U1024, U64};
 
 #[test]
 fn hash256_vec_iter() {
@@ -71,3 +71,103 @@ fn hash256_vector_iter_from() {
         }
     );
 }
+
+#[test]
+fn u64_list_iter_chunks() {
+    let vec = (0..70).collect::<Vec<u64>>();
+    let list = List::<u64, U1024>::new(vec.clone()).unwrap();
+
+    let chunks = list.iter_chunks().unwrap().collect::<Vec<_>>();
+    assert_eq!(chunks.len(), 18);
+    assert!(chunks[..17].iter().all(|chunk| chunk.len() == 4));
+    assert_eq!(chunks[17], &[68, 69]);
+    assert_eq!(chunks.concat(), vec);
+}
+
+#[test]
+fn hash256_vector_iter_chunks() {
+    let vec = (0..64).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let vector = Vector::<Hash256, U64>::new(vec.clone()).unwrap();
+
+    let chunks = vector.iter_chunks().unwrap().collect::<Vec<_>>();
+    assert!(chunks.iter().all(|chunk| chunk.len() == 1));
+    assert_eq!(chunks.concat(), vec);
+}
+
+#[test]
+fn iter_chunks_empty() {
+    let list = List::<u64, U1024>::empty();
+    assert_eq!(list.iter_chunks().unwrap().count(), 0);
+}
+
+#[test]
+fn iter_chunks_pending_updates() {
+    let mut list = List::<u64, U1024>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    assert_eq!(
+        list.iter_chunks().unwrap_err(),
+        Error::ChunkIterPendingUpdates
+    );
+}
+
+fn check_rev<T: crate::Value + std::fmt::Debug, N: Unsigned>(list: &List<T, N>) {
+    let expected = list.to_vec();
+    assert!(list.iter().rev().eq(expected.iter().rev()));
+    assert_eq!(list.iter().rev().len(), expected.len());
+
+    // Alternate between the two ends, so that they meet in the middle.
+    let mut iter = list.iter();
+    let mut front = vec![];
+    let mut back = vec![];
+    while let Some(value) = iter.next() {
+        front.push(value.clone());
+        if let Some(value) = iter.next_back() {
+            back.push(value.clone());
+        }
+        assert_eq!(iter.len(), expected.len() - front.len() - back.len());
+    }
+    back.reverse();
+    front.extend(back);
+    assert_eq!(front, expected);
+}
+
+#[test]
+fn iter_rev() {
+    for len in [0, 1, 2, 3, 4, 5, 31, 32, 33, 63, 64, 100, 1024] {
+        check_rev(&List::<u8, U1024>::new((0..len).map(|i| i as u8).collect()).unwrap());
+        check_rev(&List::<u64, U1024>::new((0..len as u64).collect()).unwrap());
+        check_rev(
+            &List::<Hash256, U1024>::new((0..len as u64).map(Hash256::from_low_u64_be).collect())
+                .unwrap(),
+        );
+    }
+}
+
+#[test]
+fn iter_rev_pending_updates() {
+    let mut list = List::<u64, U1024>::new((0..10).collect()).unwrap();
+    for i in 10..20 {
+        list.push(i).unwrap();
+    }
+    *list.get_mut(3).unwrap() = 30;
+    *list.get_mut(15).unwrap() = 150;
+    check_rev(&list);
+
+    // Taking the tail of a list doesn't touch its head.
+    let tail = list.iter().rev().take(3).copied().collect::<Vec<_>>();
+    assert_eq!(tail, vec![19, 18, 17]);
+
+    let mut iter = list.iter_from(8).unwrap();
+    assert_eq!(iter.next_back(), Some(&19));
+    assert_eq!(iter.next(), Some(&8));
+    assert_eq!(iter.len(), 10);
+    assert!(iter
+        .rev()
+        .eq([18, 17, 16, 150, 14, 13, 12, 11, 10, 9].iter()));
+}
+
+#[test]
+fn vector_iter_rev() {
+    let vector = Vector::<u64, U64>::new((0..64).collect()).unwrap();
+    assert!(vector.iter().rev().copied().eq((0..64).rev()));
+}
@@ -0,0 +1,71 @@
+use crate::{LazyList, List};
+use ssz::{DecodeError, Encode};
+use typenum::{U1024, U16, U8};
+
+#[test]
+fn lazy_fixed_len() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let lazy = LazyList::<u64, U1024>::from_ssz_bytes(list.as_ssz_bytes()).unwrap();
+    assert_eq!(lazy.len(), 1000);
+    assert!(!lazy.is_decoded(500));
+
+    assert_eq!(lazy.get(500).unwrap(), Some(&500));
+    assert!(lazy.is_decoded(500));
+    assert!(!lazy.is_decoded(499));
+    assert_eq!(lazy.get(1000).unwrap(), None);
+
+    assert_eq!(lazy.into_list().unwrap(), list);
+}
+
+#[test]
+fn lazy_variable_len() {
+    type Inner = List<u8, U16>;
+    let list = List::<Inner, U8>::new(
+        (0..8)
+            .map(|i| Inner::new((0..i).collect()).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    let lazy = LazyList::<Inner, U8>::from_ssz_bytes(list.as_ssz_bytes()).unwrap();
+
+    assert_eq!(lazy.get(3).unwrap(), list.get(3));
+    assert_eq!(
+        lazy.iter().collect::<Result<Vec<_>, _>>().unwrap(),
+        list.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(lazy.into_list().unwrap(), list);
+}
+
+#[test]
+fn lazy_errors_deferred() {
+    // The structure is valid, but the last element is not.
+    type Inner = List<u8, U8>;
+    let mut bytes = List::<Inner, U8>::new(vec![Inner::empty(), Inner::empty()])
+        .unwrap()
+        .as_ssz_bytes();
+    bytes.extend_from_slice(&[0; 9]);
+
+    let lazy = LazyList::<Inner, U8>::from_ssz_bytes(bytes).unwrap();
+    assert!(lazy.get(0).unwrap().unwrap().is_empty());
+    assert!(lazy.get(1).is_err());
+    assert!(lazy.into_list().is_err());
+}
+
+#[test]
+fn lazy_structure_errors() {
+    let bytes = List::<u64, U1024>::new((0..9).collect())
+        .unwrap()
+        .as_ssz_bytes();
+    assert!(LazyList::<u64, U8>::from_ssz_bytes(bytes.clone()).is_err());
+    assert_eq!(
+        LazyList::<u64, U1024>::from_ssz_bytes(&bytes[..12]).unwrap_err(),
+        DecodeError::InvalidByteLength {
+            len: 12,
+            expected: 8
+        }
+    );
+    assert_eq!(
+        LazyList::<List<u8, U8>, U8>::from_ssz_bytes(&[16, 0, 0, 0][..]).unwrap_err(),
+        DecodeError::OffsetOutOfBounds(16)
+    );
+}
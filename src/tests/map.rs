@@ -0,0 +1,55 @@
+use crate::{Error, List};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U8};
+
+#[test]
+fn map_changes_packing() {
+    let mut list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    list.push(1000).unwrap();
+    *list.get_mut(3).unwrap() = 0;
+
+    let mapped = list.map(|&x| Hash256::from_low_u64_be(x)).unwrap();
+    let expected =
+        List::<Hash256, U1024>::new(list.iter().map(|&x| Hash256::from_low_u64_be(x)).collect())
+            .unwrap();
+    assert_eq!(mapped, expected);
+    assert_eq!(mapped.tree_hash_root(), expected.tree_hash_root());
+    mapped.validate().unwrap();
+
+    let bytes = list.map(|&x| x as u8).unwrap();
+    assert_eq!(bytes.len(), 1001);
+    assert_eq!(bytes.get(1000), Some(&(1000u64 as u8)));
+    bytes.validate().unwrap();
+
+    assert!(List::<u64, U8>::empty().map(|&x| x + 1).unwrap().is_empty());
+}
+
+#[test]
+fn try_map_stops_at_error() {
+    let list = List::<u64, U8>::new(vec![1, 2, 3, 4]).unwrap();
+    let mut calls = 0;
+    let result = list.try_map(|&x| {
+        calls += 1;
+        if x == 2 {
+            Err(Error::InvalidMultiProof)
+        } else {
+            Ok(x * 2)
+        }
+    });
+    assert_eq!(result, Err(Error::InvalidMultiProof));
+    assert_eq!(calls, 2);
+
+    let doubled = list.try_map(|&x| Ok::<_, Error>(x * 2)).unwrap();
+    assert_eq!(doubled.to_vec(), vec![2, 4, 6, 8]);
+}
+
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+#[test]
+fn par_map() {
+    use typenum::U1048576;
+
+    let list = List::<u64, U1048576>::new((0..5000).collect()).unwrap();
+    let mapped = list.par_map(|&x| x * 3).unwrap();
+    assert_eq!(mapped, list.map(|&x| x * 3).unwrap());
+    mapped.validate().unwrap();
+}
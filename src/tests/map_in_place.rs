@@ -0,0 +1,82 @@
+use crate::{Arc, List, Tree, UpdateMap, Vector};
+use std::ops::ControlFlow;
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+#[test]
+fn map_all_elements() {
+    let mut vector = Vector::<u64, U1024>::new((0..1024).collect()).unwrap();
+    vector.map_in_place(|_, value| Some(value * 2));
+    assert!(vector.has_pending_updates());
+    vector.apply_updates().unwrap();
+
+    let expected = Vector::<u64, U1024>::new((0..1024).map(|i| i * 2).collect()).unwrap();
+    assert_eq!(vector, expected);
+    assert_eq!(vector.tree_hash_root(), expected.tree_hash_root());
+}
+
+#[test]
+fn map_sees_pending_updates() {
+    let mut vector = Vector::<u64, U1024>::new(vec![1; 1024]).unwrap();
+    *vector.get_mut(10).unwrap() = 5;
+    vector.map_in_place(|index, value| (index >= 10).then_some(value + 1));
+    vector.apply_updates().unwrap();
+
+    assert_eq!(vector.get(9), Some(&1));
+    assert_eq!(vector.get(10), Some(&6));
+    assert_eq!(vector.get(11), Some(&2));
+}
+
+#[test]
+fn untouched_subtrees_shared() {
+    let original = Vector::<u64, U1024>::new(vec![0; 1024]).unwrap();
+    let mut vector = original.clone();
+    vector.map_in_place(|index, _| (index == 1023).then_some(1));
+    vector.apply_updates().unwrap();
+
+    let (Tree::Node { left: l1, .. }, Tree::Node { left: l2, .. }) = (
+        &*original.interface.backing.tree,
+        &*vector.interface.backing.tree,
+    ) else {
+        panic!("expected nodes");
+    };
+    assert!(Arc::ptr_eq(l1, l2));
+}
+
+#[test]
+fn try_for_each_mut_breaks_early() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    list.push(100).unwrap();
+
+    let result = list.try_for_each_mut(|index, value| {
+        if *value == 50 {
+            return ControlFlow::Break(index);
+        }
+        if index % 2 == 0 {
+            *value += 1000;
+        }
+        ControlFlow::Continue(())
+    });
+    assert_eq!(result, ControlFlow::Break(50));
+
+    // Only the even elements before the break were modified, plus the earlier push.
+    assert_eq!(list.interface.updates.len(), 26);
+    assert_eq!(list.get(48), Some(&1048));
+    assert_eq!(list.get(49), Some(&49));
+    assert_eq!(list.get(52), Some(&52));
+}
+
+#[test]
+fn try_for_each_mut_sees_pending_updates() {
+    let mut list = List::<u64, U1024>::new(vec![1, 2, 3]).unwrap();
+    *list.get_mut(1).unwrap() = 20;
+    list.push(4).unwrap();
+
+    let result = list.try_for_each_mut::<(), _>(|_, value| {
+        *value *= 2;
+        ControlFlow::Continue(())
+    });
+    assert_eq!(result, ControlFlow::Continue(()));
+    list.apply_updates().unwrap();
+    assert_eq!(list.to_vec(), vec![2, 40, 6, 8]);
+}
@@ -0,0 +1,103 @@
+use crate::{List, Vector};
+use typenum::{U1024, U16};
+
+#[test]
+fn fresh_list_is_unique() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let usage = list.mem_usage();
+    assert!(usage.nodes > 0);
+    assert_eq!(usage.unique_nodes, usage.nodes);
+    assert_eq!(usage.unique_bytes, usage.bytes);
+    assert_eq!(usage.shared_bytes(), 0);
+}
+
+#[test]
+fn clone_shares_everything() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let clone = list.clone();
+    let usage = clone.mem_usage();
+    assert_eq!(usage.unique_nodes, 0);
+    assert_eq!(usage.unique_bytes, 0);
+    assert_eq!(usage.shared_bytes(), usage.bytes);
+
+    // Dropping the clone makes the original unique again.
+    drop(clone);
+    assert_eq!(list.mem_usage().unique_bytes, list.mem_usage().bytes);
+}
+
+#[test]
+fn mutation_unshares_path_only() {
+    let list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let mut modified = list.clone();
+    *modified.get_mut(0).unwrap() = 42;
+    modified.apply_updates().unwrap();
+
+    // Only the nodes on the path from the root to the modified leaf are unique.
+    let usage = modified.mem_usage();
+    assert_eq!(usage.unique_nodes, List::<u64, U1024>::depth() + 1);
+    assert_eq!(usage.nodes, list.mem_usage().nodes);
+
+    let original = list.mem_usage();
+    assert_eq!(original.unique_nodes, List::<u64, U1024>::depth() + 1);
+}
+
+#[test]
+fn repeated_subtrees_counted_once() {
+    let list = List::<u64, U1024>::repeat(7, 1024).unwrap();
+    let usage = list.mem_usage();
+
+    // One node per level, all owned exclusively by this list.
+    assert_eq!(usage.nodes, List::<u64, U1024>::depth() + 1);
+    assert_eq!(usage.unique_nodes, usage.nodes);
+}
+
+#[test]
+fn vector_mem_usage() {
+    let vector = Vector::<u64, U16>::new(vec![1; 16]).unwrap();
+    let usage = vector.mem_usage();
+    assert_eq!(usage.unique_bytes, usage.bytes);
+
+    let clone = vector.clone();
+    assert_eq!(clone.mem_usage().unique_bytes, 0);
+}
+
+#[test]
+fn count_unique_nodes() {
+    let depth = List::<u64, U1024>::depth();
+    let base = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let mut first = base.clone();
+    *first.get_mut(0).unwrap() = 42;
+    first.apply_updates().unwrap();
+    let mut second = base.clone();
+    *second.get_mut(1023).unwrap() = 42;
+    second.apply_updates().unwrap();
+
+    let sharing = List::count_unique_nodes(&[&base, &first, &second]);
+    // Each modified list owns the path to its modified leaf. The rest of each path in the base
+    // list is shared with the other modified list, leaving only the root exclusive to it.
+    let path = depth + 1;
+    assert_eq!(sharing.exclusive_nodes, vec![1, path, path]);
+    assert_eq!(sharing.shared_nodes, base.mem_usage().nodes - 1);
+    assert_eq!(sharing.total_nodes(), base.mem_usage().nodes + 2 * path);
+
+    // References from outside the set are ignored.
+    let sharing = List::count_unique_nodes(&[&first]);
+    assert_eq!(sharing.exclusive_nodes, vec![first.mem_usage().nodes]);
+    assert_eq!(sharing.shared_nodes, 0);
+    assert_eq!(sharing.total_bytes(), first.mem_usage().bytes);
+}
+
+#[test]
+fn count_unique_nodes_repeated_subtrees() {
+    // The repeated subtrees of one list are reached by many paths, but are still exclusive to
+    // it unless they are also reachable from another list.
+    let repeated = List::<u64, U1024>::repeat(7, 1024).unwrap();
+    let mut modified = repeated.clone();
+    *modified.get_mut(0).unwrap() = 0;
+    modified.apply_updates().unwrap();
+
+    let sharing = List::count_unique_nodes(&[&modified, &repeated]);
+    let depth = List::<u64, U1024>::depth();
+    assert_eq!(sharing.exclusive_nodes, vec![depth + 1, 1]);
+    assert_eq!(sharing.shared_nodes, depth);
+}
@@ -0,0 +1,94 @@
+use crate::MerkleMap;
+use std::collections::BTreeMap;
+use tree_hash::Hash256;
+
+fn key(i: u64) -> Hash256 {
+    Hash256::from_slice(&ethereum_hashing::hash(&i.to_le_bytes()))
+}
+
+#[test]
+fn insert_get_remove_matches_btree_map() {
+    let mut map = MerkleMap::<u64>::new();
+    let mut expected = BTreeMap::new();
+
+    for i in 0..500 {
+        assert_eq!(map.insert(key(i), i).unwrap(), expected.insert(key(i), i));
+    }
+    for i in (0..500).step_by(3) {
+        assert_eq!(
+            map.insert(key(i), i * 10).unwrap(),
+            expected.insert(key(i), i * 10)
+        );
+    }
+    for i in (0..600).step_by(7) {
+        assert_eq!(map.remove(&key(i)).unwrap(), expected.remove(&key(i)));
+    }
+
+    assert_eq!(map.len(), expected.len());
+    for i in 0..600 {
+        assert_eq!(map.get(&key(i)), expected.get(&key(i)));
+    }
+    assert_eq!(
+        map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        expected.into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn root_independent_of_insertion_order() {
+    let forwards = MerkleMap::try_from_iter((0..100).map(|i| (key(i), i))).unwrap();
+    let backwards = MerkleMap::try_from_iter((0..100).rev().map(|i| (key(i), i))).unwrap();
+    assert_eq!(forwards, backwards);
+    assert_eq!(forwards.tree_hash_root(), backwards.tree_hash_root());
+
+    // Removing entries restores the canonical shape of the smaller map.
+    let mut shrunk = MerkleMap::try_from_iter((0..150).map(|i| (key(i), i))).unwrap();
+    for i in 100..150 {
+        shrunk.remove(&key(i)).unwrap();
+    }
+    assert_eq!(shrunk, forwards);
+    assert_eq!(shrunk.tree_hash_root(), forwards.tree_hash_root());
+
+    for i in 0..100 {
+        shrunk.remove(&key(i)).unwrap();
+    }
+    assert!(shrunk.is_empty());
+    assert_eq!(shrunk, MerkleMap::new());
+    assert_eq!(shrunk.tree_hash_root(), Hash256::zero());
+}
+
+#[test]
+fn root_commits_to_values() {
+    let mut map = MerkleMap::try_from_iter((0..10).map(|i| (key(i), i))).unwrap();
+    let root = map.tree_hash_root();
+    map.insert(key(3), 33).unwrap();
+    assert_ne!(map.tree_hash_root(), root);
+    map.insert(key(3), 3).unwrap();
+    assert_eq!(map.tree_hash_root(), root);
+}
+
+#[test]
+fn adjacent_keys() {
+    // Keys differing only in their last bit are stored at the bottom of the trie.
+    let a = Hash256::zero();
+    let b = Hash256::from_low_u64_be(1);
+    let mut map = MerkleMap::new();
+    map.insert(a, 1u64).unwrap();
+    map.insert(b, 2).unwrap();
+    assert_eq!(map.get(&a), Some(&1));
+    assert_eq!(map.get(&b), Some(&2));
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec![&a, &b]);
+    assert_eq!(map.remove(&a).unwrap(), Some(1));
+    assert_eq!(map, MerkleMap::try_from_iter([(b, 2)]).unwrap());
+}
+
+#[test]
+fn clone_shares_structure() {
+    let map = MerkleMap::try_from_iter((0..256).map(|i| (key(i), i))).unwrap();
+    let mut modified = map.clone();
+    modified.insert(key(0), 1000).unwrap();
+
+    let usage = modified.mem_usage();
+    assert!(usage.unique_nodes < 20, "{usage:?}");
+    assert!(usage.nodes > 256);
+}
@@ -0,0 +1,39 @@
+use crate::{BitmapUpdateMap, List, Vector};
+use ssz::Encode;
+use ssz_types::{FixedVector, VariableList};
+use tree_hash::TreeHash;
+use typenum::{U1024, U16};
+
+#[test]
+fn variable_list_round_trip() {
+    let variable = VariableList::<u64, U1024>::from((0..1000).collect::<Vec<_>>());
+    let list = List::<u64, U1024>::from(variable.clone());
+    assert_eq!(list.tree_hash_root(), variable.tree_hash_root());
+    assert_eq!(list.as_ssz_bytes(), variable.as_ssz_bytes());
+    assert!(!list.has_pending_updates());
+
+    assert_eq!(VariableList::from(list), variable);
+    assert_eq!(List::<u64, U16>::from(VariableList::empty()), List::empty());
+}
+
+#[test]
+fn list_with_pending_updates_to_variable_list() {
+    let mut list = List::<u64, U16, BitmapUpdateMap<u64>>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    *list.get_mut(0).unwrap() = 0;
+    assert_eq!(VariableList::from(list)[..], [0, 2, 3, 4]);
+}
+
+#[test]
+fn fixed_vector_round_trip() {
+    let fixed = FixedVector::<u16, U16>::new((0..16).collect()).unwrap();
+    let vector = Vector::<u16, U16>::from(fixed.clone());
+    assert_eq!(vector.tree_hash_root(), fixed.tree_hash_root());
+    assert_eq!(vector.as_ssz_bytes(), fixed.as_ssz_bytes());
+
+    let mut vector = vector;
+    *vector.get_mut(15).unwrap() = 100;
+    let fixed = FixedVector::from(vector);
+    assert_eq!(fixed[15], 100);
+    assert_eq!(fixed[..15], (0..15).collect::<Vec<_>>());
+}
@@ -1,9 +1,84 @@
 #![cfg(test)]
 
+mod applied;
+mod apply_policy;
+mod bitfield;
 mod builder;
+mod bytes;
+mod cached_reader;
+mod cached_tree_hash;
+mod changelog;
+mod checkpoint;
+mod decode;
+mod decode_with;
+mod diff;
+#[cfg(feature = "testing")]
+mod differential;
+#[cfg(feature = "debug")]
+mod dot;
+mod dyn_list;
+mod encode;
+mod eq;
+mod error;
+mod extend;
+mod frozen;
+#[cfg(feature = "fuzzing")]
+mod fuzz;
+mod get_many;
+mod gindex;
+mod guard;
+mod hasher;
+mod in_place;
+mod index;
+#[cfg(feature = "intern")]
+mod intern;
+mod into_iter;
+mod invalidate;
 mod iterator;
+mod lazy;
+mod map;
+mod map_in_place;
+mod mem_usage;
+mod merkle_map;
+#[cfg(feature = "ssz_types")]
+mod migration;
+mod overlay;
 mod packed;
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+mod par_iter;
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+mod parallel_build;
+mod partial;
+mod permute;
 mod pop_front;
+mod progressive;
+mod proof;
 mod proptest;
+mod rebase;
 mod repeat;
+mod retain;
+#[cfg(feature = "serde")]
+mod serde;
+mod sharded_updates;
 mod size_of;
+mod small_list;
+mod snapshot;
+mod sorted;
+mod stable_container;
+mod store;
+mod stream;
+mod summary;
+mod swap;
+#[cfg(feature = "testing")]
+mod testing;
+mod transaction;
+mod tree_hash;
+mod truncate;
+mod update_many;
+mod update_map;
+mod validate;
+mod vector;
+mod versioned;
+mod view;
+mod windows;
+mod zip;
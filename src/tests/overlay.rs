@@ -0,0 +1,60 @@
+use crate::{Error, List, OverlayList};
+use typenum::{U4, U64};
+
+#[test]
+fn overlay_reads_through_to_base() {
+    let list = List::<u64, U64>::new((0..10).collect()).unwrap();
+    let mut overlay = list.overlay().unwrap();
+    assert!(!overlay.has_updates());
+
+    *overlay.get_mut(3).unwrap() = 30;
+    overlay.push(10).unwrap();
+    assert_eq!(overlay.len(), 11);
+    assert_eq!(overlay.get(3), Some(&30));
+    assert_eq!(overlay.get(4), Some(&4));
+    assert_eq!(overlay.get(10), Some(&10));
+    assert_eq!(overlay.get(11), None);
+    assert_eq!(
+        overlay.iter().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, 30, 4, 5, 6, 7, 8, 9, 10]
+    );
+
+    // The base is untouched.
+    assert_eq!(overlay.base().get(3), Some(&3));
+    assert_eq!(list.get(3), Some(&3));
+
+    let mut committed = overlay.clone().into_list();
+    assert!(committed.has_pending_updates());
+    committed.apply_updates().unwrap();
+    assert_eq!(
+        committed.to_vec(),
+        overlay.iter().copied().collect::<Vec<_>>()
+    );
+
+    let base = overlay.discard();
+    assert_eq!(base.to_vec(), list.to_vec());
+}
+
+#[test]
+fn overlays_share_a_base() {
+    let base = List::<u64, U4>::new(vec![1, 2, 3])
+        .unwrap()
+        .freeze()
+        .unwrap();
+    let mut first: OverlayList<u64, U4> = base.overlay();
+    let mut second: OverlayList<u64, U4> = base.overlay();
+    *first.get_mut(0).unwrap() = 100;
+    second.push(4).unwrap();
+    assert_eq!(second.push(5), Err(Error::ListFull { len: 4, max_len: 4 }));
+    assert_eq!(first.get(0), Some(&100));
+    assert_eq!(second.get(0), Some(&1));
+    assert_eq!(first.len(), 3);
+    assert_eq!(second.len(), 4);
+}
+
+#[test]
+fn overlay_pending_updates() {
+    let mut list = List::<u64, U4>::new(vec![1]).unwrap();
+    list.push(2).unwrap();
+    assert_eq!(list.overlay().unwrap_err(), Error::OverlayPendingUpdates);
+}
@@ -1,6 +1,8 @@
-use crate::{List, Vector};
+use crate::{Error, List, Vector};
+use ethereum_types::{U128, U256};
+use ssz::{Decode, DecodeError, Encode};
 use ssz_types::{FixedVector, VariableList};
-use tree_hash::TreeHash;
+use tree_hash::{Hash256, PackedEncoding, TreeHash, TreeHashType};
 use typenum::U16;
 
 #[test]
@@ -83,3 +85,168 @@ fn out_of_order_mutations() {
 
     assert_eq!(list.to_vec(), vec);
 }
+
+#[test]
+fn u128_packed_list_tree_hash() {
+    for len in 0..=16u64 {
+        let vec = (0..len)
+            .map(|i| U128::from(i) << 64 | U128::from(i + 1))
+            .collect::<Vec<_>>();
+        let list = List::<U128, U16>::new(vec.clone()).unwrap();
+        let var_list = VariableList::<U128, U16>::new(vec.clone()).unwrap();
+
+        assert_eq!(list.to_vec(), vec);
+        assert_eq!(list.tree_hash_root(), var_list.tree_hash_root());
+    }
+}
+
+#[test]
+fn u256_one_per_chunk_tree_hash() {
+    for len in 0..=16u64 {
+        let vec = (0..len)
+            .map(|i| U256::MAX - U256::from(i))
+            .collect::<Vec<_>>();
+        let mut list = List::<U256, U16>::new(vec.clone()).unwrap();
+        let var_list = VariableList::<U256, U16>::new(vec.clone()).unwrap();
+
+        assert_eq!(list.to_vec(), vec);
+        assert_eq!(list.tree_hash_root(), var_list.tree_hash_root());
+
+        if len > 0 {
+            *list.get_mut(0).unwrap() = U256::zero();
+            list.apply_updates().unwrap();
+            let mut vec = vec;
+            vec[0] = U256::zero();
+            let var_list = VariableList::<U256, U16>::new(vec).unwrap();
+            assert_eq!(list.tree_hash_root(), var_list.tree_hash_root());
+        }
+    }
+}
+
+/// Fixed-length type of `LEN` bytes, packed `FACTOR` to a chunk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Bytes<const LEN: usize, const FACTOR: usize>(pub(super) [u8; LEN]);
+
+impl<const LEN: usize, const FACTOR: usize> Encode for Bytes<LEN, FACTOR> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        LEN
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        LEN
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.0);
+    }
+}
+
+impl<const LEN: usize, const FACTOR: usize> Decode for Bytes<LEN, FACTOR> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        LEN
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: LEN,
+            })
+    }
+}
+
+impl<const LEN: usize, const FACTOR: usize> TreeHash for Bytes<LEN, FACTOR> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Basic
+    }
+
+    fn tree_hash_packed_encoding(&self) -> PackedEncoding {
+        PackedEncoding::from_slice(&self.0)
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        FACTOR
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let mut root = Hash256::zero();
+        root.as_bytes_mut()[..LEN].copy_from_slice(&self.0);
+        root
+    }
+}
+
+#[test]
+fn narrow_values_are_padded() {
+    type U24 = Bytes<3, 8>;
+    let vec = (0..10u8).map(|i| Bytes([i, i, i])).collect::<Vec<U24>>();
+    let list = List::<U24, U16>::new(vec.clone()).unwrap();
+    assert_eq!(list.to_vec(), vec);
+
+    // Each value occupies 4 bytes of the chunk, with a zero byte of padding.
+    let chunk = |values: &[U24]| {
+        let mut chunk = [0; 32];
+        for (slot, value) in chunk.chunks_exact_mut(4).zip(values) {
+            slot[..3].copy_from_slice(&value.0);
+        }
+        Hash256::from(chunk)
+    };
+    let bytes = [chunk(&vec[..8]), chunk(&vec[8..])]
+        .iter()
+        .flat_map(|chunk| chunk.as_bytes().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        list.tree_hash_root(),
+        tree_hash::mix_in_length(&tree_hash::merkle_root(&bytes, 2), 10)
+    );
+}
+
+#[test]
+fn wide_values_are_rejected() {
+    type Wide = Bytes<6, 8>;
+    type Uneven = Bytes<3, 10>;
+    let wide = Error::InvalidPackedLayout {
+        packing_factor: 8,
+        ssz_len: 6,
+    };
+    let uneven = Error::InvalidPackedLayout {
+        packing_factor: 10,
+        ssz_len: 3,
+    };
+
+    assert_eq!(
+        List::<Wide, U16>::new(vec![Bytes([1; 6])]),
+        Err(wide.clone())
+    );
+    assert_eq!(
+        List::<Wide, U16>::repeat(Bytes([1; 6]), 3),
+        Err(wide.clone())
+    );
+    assert_eq!(List::<Uneven, U16>::new(vec![Bytes([1; 3])]), Err(uneven));
+
+    let mut list = List::<Wide, U16>::empty();
+    list.push(Bytes([1; 6])).unwrap();
+    assert_eq!(list.apply_updates(), Err(wide));
+}
+
+#[test]
+fn packing_factor_above_chunk_size_is_rejected() {
+    // Bit-granularity packing would need 256 values per chunk, which `PackedLeaf` can't represent.
+    type Bit = Bytes<0, 256>;
+    assert_eq!(
+        List::<Bit, U16>::new(vec![Bytes([]); 3]),
+        Err(Error::InvalidPackedLayout {
+            packing_factor: 256,
+            ssz_len: 0,
+        })
+    );
+}
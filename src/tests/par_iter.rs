@@ -0,0 +1,46 @@
+use crate::{List, Vector};
+use rayon::prelude::*;
+use tree_hash::Hash256;
+use typenum::{U1024, U16, U64};
+
+#[test]
+fn par_iter_matches_iter() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let values = list.par_iter().copied().collect::<Vec<_>>();
+    assert_eq!(values, list.iter().copied().collect::<Vec<_>>());
+    assert_eq!(list.par_iter().sum::<u64>(), (0..1000).sum::<u64>());
+}
+
+#[test]
+fn par_iter_unpacked() {
+    let list = List::<Hash256, U64>::new((0..50).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let values = list.into_par_iter().copied().collect::<Vec<_>>();
+    assert_eq!(values, list.to_vec());
+}
+
+#[test]
+fn par_iter_pending_updates() {
+    let mut list = List::<u64, U1024>::new((0..500).collect()).unwrap();
+    *list.get_mut(100).unwrap() = 0;
+    for i in 500..700 {
+        list.push(i).unwrap();
+    }
+    let values = list.par_iter().copied().collect::<Vec<_>>();
+    assert_eq!(values, list.iter().copied().collect::<Vec<_>>());
+    assert_eq!(values.len(), 700);
+}
+
+#[test]
+fn par_iter_vector() {
+    let vector = Vector::<u8, U16>::new((0..16).collect()).unwrap();
+    assert_eq!(
+        vector.par_iter().map(|x| *x as u64).sum::<u64>(),
+        (0..16).sum::<u64>()
+    );
+}
+
+#[test]
+fn par_iter_empty() {
+    let list = List::<u64, U1024>::empty();
+    assert_eq!(list.par_iter().count(), 0);
+}
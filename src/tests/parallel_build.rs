@@ -0,0 +1,43 @@
+use crate::{Error, List};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1048576, U16, U8192};
+
+fn check_parallel_build<T, N>(values: Vec<T>)
+where
+    T: crate::Value + Send + Sync + std::fmt::Debug,
+    N: typenum::Unsigned + std::fmt::Debug,
+{
+    let parallel = List::<T, N>::from_slice_parallel(&values).unwrap();
+    let sequential = List::<T, N>::new(values).unwrap();
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.tree_hash_root(), sequential.tree_hash_root());
+    parallel.validate().unwrap();
+}
+
+#[test]
+fn unpacked_lengths() {
+    for len in [0, 1, 1023, 1024, 1025, 3000, 4096] {
+        let values = (0..len).map(Hash256::from_low_u64_be).collect();
+        check_parallel_build::<Hash256, U1048576>(values);
+    }
+}
+
+#[test]
+fn packed_lengths() {
+    for len in [0, 3, 4095, 4096, 4097, 10_000] {
+        check_parallel_build::<u64, U1048576>((0..len).collect());
+    }
+    check_parallel_build::<u8, U8192>((0..8192).map(|i| i as u8).collect());
+}
+
+#[test]
+fn small_and_full_lists() {
+    check_parallel_build::<u64, U16>((0..16).collect());
+    assert_eq!(
+        List::<u64, U16>::from_slice_parallel(&[0; 17]),
+        Err(Error::ListFull {
+            len: 0,
+            max_len: 16
+        })
+    );
+}
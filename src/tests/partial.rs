@@ -0,0 +1,145 @@
+use super::packed::Bytes;
+use crate::{Error, List, PartialList, PartialVector, Vector};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U16, U4, U64, U8};
+
+#[test]
+fn packed_list() {
+    let list = List::<u64, U64>::new((0..20).collect()).unwrap();
+    let root = list.tree_hash_root();
+    let mut partial =
+        PartialList::<u64, U64>::from_multiproof(root, &list.prove_indices(&[1, 9]).unwrap())
+            .unwrap();
+
+    assert_eq!(partial.root(), root);
+    assert_eq!(partial.len(), Some(20));
+    // Elements in the same chunk as a proven element are available, as are elements in sibling
+    // chunks included in the proof.
+    for index in 0..16 {
+        assert_eq!(partial.get(index), Ok(index as u64));
+    }
+    assert_eq!(partial.get(16), Err(Error::UnprovenIndex { index: 16 }));
+    assert_eq!(
+        partial.get(20),
+        Err(Error::OutOfBoundsProof { index: 20, len: 20 })
+    );
+
+    partial
+        .add_multiproof(&list.prove_indices(&[17]).unwrap())
+        .unwrap();
+    assert_eq!(partial.get(19), Ok(19));
+    assert_eq!(partial.get(16), Ok(16));
+}
+
+#[test]
+fn hash_list() {
+    let values = (0..5).map(Hash256::repeat_byte).collect::<Vec<_>>();
+    let list = List::<Hash256, U8>::new(values.clone()).unwrap();
+    let partial = PartialList::<Hash256, U8>::from_multiproof(
+        list.tree_hash_root(),
+        &list.prove_indices(&[0, 3]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(partial.get(0), Ok(values[0]));
+    assert_eq!(partial.get(3), Ok(values[3]));
+    assert_eq!(partial.get(4), Err(Error::UnprovenIndex { index: 4 }));
+}
+
+#[test]
+fn composite_values_unavailable() {
+    let list = List::<List<u8, U8>, U8>::new(vec![List::new(vec![1, 2]).unwrap()]).unwrap();
+    let partial = PartialList::<List<u8, U8>, U8>::from_multiproof(
+        list.tree_hash_root(),
+        &list.prove_indices(&[0]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        partial.get(0),
+        Err(Error::PartialValueUnavailable { index: 0 })
+    );
+}
+
+#[test]
+fn invalid_proofs() {
+    let list = List::<u64, U64>::new((0..20).collect()).unwrap();
+    let other = List::<u64, U64>::new((1..21).collect()).unwrap();
+    let proof = list.prove_indices(&[0]).unwrap();
+
+    assert_eq!(
+        PartialList::<u64, U64>::from_multiproof(other.tree_hash_root(), &proof),
+        Err(Error::InvalidMultiProof)
+    );
+
+    // A rejected proof leaves the existing witnesses intact.
+    let mut partial =
+        PartialList::<u64, U64>::from_multiproof(list.tree_hash_root(), &proof).unwrap();
+    let before = partial.clone();
+    assert_eq!(
+        partial.add_multiproof(&other.prove_indices(&[8]).unwrap()),
+        Err(Error::InvalidMultiProof)
+    );
+    assert_eq!(partial, before);
+}
+
+#[test]
+fn vector() {
+    let vector = Vector::<u16, U16>::new((100..116).collect()).unwrap();
+    let mut partial = PartialVector::<u16, U16>::from_multiproof(
+        vector.tree_hash_root(),
+        &vector.prove_indices(&[15]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(partial.len(), 16);
+    // All 16 elements fit in a single chunk.
+    assert_eq!(partial.get(0), Ok(100));
+    assert_eq!(partial.get(15), Ok(115));
+    assert_eq!(
+        partial.get(16),
+        Err(Error::OutOfBoundsProof { index: 16, len: 16 })
+    );
+
+    partial
+        .add_multiproof(&vector.prove_indices(&[3]).unwrap())
+        .unwrap();
+    assert_eq!(partial.get(3), Ok(103));
+}
+
+#[test]
+fn hash_vector() {
+    let values = (0..4).map(Hash256::repeat_byte).collect::<Vec<_>>();
+    let vector = Vector::<Hash256, U4>::new(values.clone()).unwrap();
+    let partial = PartialVector::<Hash256, U4>::from_multiproof(
+        vector.tree_hash_root(),
+        &vector.prove_indices(&[2]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(partial.get(2), Ok(values[2]));
+    assert_eq!(partial.get(1), Err(Error::UnprovenIndex { index: 1 }));
+}
+
+#[test]
+fn invalid_packed_layout() {
+    // `Wide` values would overrun the chunk at the last of their 8 slots. The proofs come from
+    // `u32` values, which have the same tree shape.
+    type Wide = Bytes<6, 8>;
+    let error = Err(Error::InvalidPackedLayout {
+        packing_factor: 8,
+        ssz_len: 6,
+    });
+
+    let list = List::<u32, U16>::new((0..16).collect()).unwrap();
+    let partial = PartialList::<Wide, U16>::from_multiproof(
+        list.tree_hash_root(),
+        &list.prove_indices(&[7]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(partial.get(7), error);
+
+    let vector = Vector::<u32, U16>::new((0..16).collect()).unwrap();
+    let partial = PartialVector::<Wide, U16>::from_multiproof(
+        vector.tree_hash_root(),
+        &vector.prove_indices(&[7]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(partial.get(7), error);
+}
@@ -0,0 +1,84 @@
+use crate::{Error, List};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16};
+
+fn check_permute<T: crate::Value + std::fmt::Debug + Send + Sync>(
+    values: Vec<T>,
+    permutation: Vec<usize>,
+) {
+    let mut list = List::<T, U1024>::new(values.clone()).unwrap();
+    list.permute(&permutation).unwrap();
+    let expected = List::<T, U1024>::new(
+        permutation
+            .iter()
+            .map(|&source| values[source].clone())
+            .collect(),
+    )
+    .unwrap();
+    assert_eq!(list, expected);
+    assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+}
+
+#[test]
+fn permute_matches_fresh_list() {
+    for len in [0, 1, 3, 4, 5, 100, 1000, 1024] {
+        let identity = (0..len).collect::<Vec<_>>();
+        let reversed = identity.iter().rev().copied().collect::<Vec<_>>();
+        let rotated = (0..len).map(|i| (i + 1) % len).collect::<Vec<_>>();
+        for permutation in [identity, reversed, rotated] {
+            check_permute((0..len as u64).collect(), permutation.clone());
+            check_permute(
+                (0..len as u64).map(Hash256::from_low_u64_be).collect(),
+                permutation,
+            );
+        }
+    }
+}
+
+#[test]
+fn permute_reuses_unchanged_subtrees() {
+    let depth = List::<u64, U1024>::depth();
+    let original = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let mut list = original.clone();
+    let mut permutation = (0..1000).collect::<Vec<_>>();
+    permutation.swap(1, 2);
+    list.permute(&permutation).unwrap();
+
+    // Only the path to the single modified leaf is rebuilt.
+    let sharing = List::count_unique_nodes(&[&original, &list]);
+    assert_eq!(sharing.exclusive_nodes, vec![depth + 1, depth + 1]);
+    assert_eq!(list.get(1), Some(&2));
+
+    // The identity permutation keeps the whole tree.
+    let mut same = original.clone();
+    same.permute(&(0..1000).collect::<Vec<_>>()).unwrap();
+    assert_eq!(
+        List::count_unique_nodes(&[&original, &same]).exclusive_nodes,
+        vec![0, 0]
+    );
+}
+
+#[test]
+fn permute_pending_updates() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    list.permute(&[3, 2, 1, 0]).unwrap();
+    assert_eq!(list.to_vec(), vec![4, 3, 2, 1]);
+}
+
+#[test]
+fn invalid_permutation() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    for (permutation, index) in [
+        (vec![0, 1], 2),
+        (vec![0, 1, 2, 3], 3),
+        (vec![0, 3, 1], 1),
+        (vec![0, 1, 0], 2),
+    ] {
+        assert_eq!(
+            list.permute(&permutation),
+            Err(Error::InvalidPermutation { index })
+        );
+    }
+    assert_eq!(list.to_vec(), vec![1, 2, 3]);
+}
@@ -0,0 +1,99 @@
+use crate::proof::{hash_pair, length_chunk};
+use crate::ProgressiveList;
+use ssz::{Decode, Encode};
+use tree_hash::{Hash256, TreeHash};
+
+/// Reference implementation of `merkleize(chunks, limit)`.
+fn merkleize(chunks: &[Hash256], limit: usize) -> Hash256 {
+    if limit == 1 {
+        return chunks.first().copied().unwrap_or_else(Hash256::zero);
+    }
+    let half = limit / 2;
+    let (left, right) = chunks.split_at(half.min(chunks.len()));
+    hash_pair(&merkleize(left, half), &merkleize(right, half))
+}
+
+/// Reference implementation of `merkleize_progressive` from EIP-7916.
+fn merkleize_progressive(chunks: &[Hash256], num_leaves: usize) -> Hash256 {
+    if chunks.is_empty() {
+        return Hash256::zero();
+    }
+    let (current, rest) = chunks.split_at(num_leaves.min(chunks.len()));
+    hash_pair(
+        &merkleize_progressive(rest, num_leaves * 4),
+        &merkleize(current, num_leaves),
+    )
+}
+
+fn u64_chunks(values: &[u64]) -> Vec<Hash256> {
+    values
+        .chunks(4)
+        .map(|chunk| {
+            let mut bytes = [0; 32];
+            for (i, value) in chunk.iter().enumerate() {
+                bytes[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+            }
+            Hash256::from(bytes)
+        })
+        .collect()
+}
+
+#[test]
+fn tree_hash_matches_reference() {
+    for n in [0, 1, 3, 4, 5, 20, 21, 84, 85, 100, 400] {
+        let values = (0..n).collect::<Vec<u64>>();
+        let list = ProgressiveList::new(values.clone()).unwrap();
+        let expected = hash_pair(
+            &merkleize_progressive(&u64_chunks(&values), 1),
+            &length_chunk(n as usize),
+        );
+        assert_eq!(list.tree_hash_root(), expected, "n = {}", n);
+    }
+}
+
+#[test]
+fn tree_hash_unpacked() {
+    let values = (0..30).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let list = ProgressiveList::new(values.clone()).unwrap();
+    let expected = hash_pair(&merkleize_progressive(&values, 1), &length_chunk(30));
+    assert_eq!(list.tree_hash_root(), expected);
+}
+
+#[test]
+fn push_get_set() {
+    let mut list = ProgressiveList::<u64>::empty();
+    for i in 0..200 {
+        list.push(i).unwrap();
+    }
+    assert_eq!(list, ProgressiveList::new((0..200).collect()).unwrap());
+    assert_eq!(list.len(), 200);
+    assert_eq!(list.get(150), Some(&150));
+    assert_eq!(list.get(200), None);
+
+    let original = list.clone();
+    list.set(150, 0).unwrap();
+    assert_eq!(list.get(150), Some(&0));
+    assert_eq!(original.get(150), Some(&150));
+    assert!(list.set(200, 0).is_err());
+
+    let mut values = (0..200).collect::<Vec<u64>>();
+    values[150] = 0;
+    assert_eq!(list.to_vec(), values);
+    assert_eq!(
+        list.tree_hash_root(),
+        ProgressiveList::new(values).unwrap().tree_hash_root()
+    );
+}
+
+#[test]
+fn ssz_round_trip() {
+    let list = ProgressiveList::<u64>::new((0..100).collect()).unwrap();
+    let bytes = list.as_ssz_bytes();
+    assert_eq!(bytes, (0..100).collect::<Vec<u64>>().as_ssz_bytes());
+    assert_eq!(ProgressiveList::from_ssz_bytes(&bytes).unwrap(), list);
+
+    let nested = ProgressiveList::new(vec![list.clone(), ProgressiveList::empty(), list]).unwrap();
+    let bytes = nested.as_ssz_bytes();
+    assert_eq!(bytes.len(), nested.ssz_bytes_len());
+    assert_eq!(ProgressiveList::from_ssz_bytes(&bytes).unwrap(), nested);
+}
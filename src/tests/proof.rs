@@ -0,0 +1,209 @@
+use crate::proof::helper_indices;
+use crate::{verify_multiproof, verify_proof, Error, List, MultiProof, PartialList, Vector};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{Unsigned, U1, U16, U32};
+
+#[test]
+fn u64_list_proofs() {
+    type N = U32;
+    for len in 1..=N::to_usize() {
+        let list = List::<u64, N>::new((0..len as u64).collect()).unwrap();
+        let root = list.tree_hash_root();
+
+        for i in 0..len {
+            let proof = list.prove(i).unwrap();
+            assert!(verify_proof(root, &proof), "len = {len}, i = {i}");
+        }
+        assert_eq!(
+            list.prove(len),
+            Err(Error::OutOfBoundsProof { index: len, len })
+        );
+    }
+}
+
+#[test]
+fn hash256_list_proofs() {
+    type N = U16;
+    for len in 1..=N::to_usize() {
+        let list = List::<Hash256, N>::new((0..len as u64).map(Hash256::from_low_u64_be).collect())
+            .unwrap();
+        let root = list.tree_hash_root();
+
+        for i in 0..len {
+            let proof = list.prove(i).unwrap();
+            assert_eq!(proof.leaf, Hash256::from_low_u64_be(i as u64));
+            assert!(verify_proof(root, &proof));
+        }
+    }
+}
+
+#[test]
+fn single_element_vector_proof() {
+    let vector = Vector::<u64, U1>::new(vec![42]).unwrap();
+    let proof = vector.prove(0).unwrap();
+    assert_eq!(proof.gindex, 1);
+    assert!(proof.branch.is_empty());
+    assert!(verify_proof(vector.tree_hash_root(), &proof));
+}
+
+#[test]
+fn hash256_vector_proofs() {
+    type N = U16;
+    let vector =
+        Vector::<Hash256, N>::new((0..16).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let root = vector.tree_hash_root();
+
+    for i in 0..N::to_usize() {
+        let proof = vector.prove(i).unwrap();
+        assert_eq!(proof.gindex, 16 + i);
+        assert!(verify_proof(root, &proof));
+    }
+}
+
+#[test]
+fn tampered_proof_fails() {
+    let list = List::<u64, U32>::new((0..20).collect()).unwrap();
+    let root = list.tree_hash_root();
+    let proof = list.prove(7).unwrap();
+
+    let mut wrong_leaf = proof.clone();
+    wrong_leaf.leaf = Hash256::repeat_byte(1);
+    assert!(!verify_proof(root, &wrong_leaf));
+
+    let mut wrong_gindex = proof.clone();
+    wrong_gindex.gindex += 1;
+    assert!(!verify_proof(root, &wrong_gindex));
+
+    let mut short_branch = proof;
+    short_branch.branch.pop();
+    assert!(!verify_proof(root, &short_branch));
+}
+
+#[test]
+fn proof_with_pending_updates() {
+    let mut list = List::<u64, U32>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    assert_eq!(list.prove(0), Err(Error::ProofPendingUpdates));
+
+    list.apply_updates().unwrap();
+    let proof = list.prove(3).unwrap();
+    assert!(verify_proof(list.tree_hash_root(), &proof));
+}
+
+#[test]
+fn u64_list_multiproofs() {
+    let list = List::<u64, U32>::new((0..27).collect()).unwrap();
+    let root = list.tree_hash_root();
+
+    let index_sets: &[&[usize]] = &[&[0], &[0, 1], &[26, 0], &[3, 4, 5, 12, 20], &[8, 8, 9]];
+    for indices in index_sets {
+        let proof = list.prove_indices(indices).unwrap();
+        assert!(verify_multiproof(root, &proof), "indices = {indices:?}");
+    }
+}
+
+#[test]
+fn multiproof_is_compact() {
+    let vector =
+        Vector::<Hash256, U16>::new((0..16).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let root = vector.tree_hash_root();
+
+    // Proving a pair of siblings requires one fewer helper than a single proof.
+    let proof = vector.prove_indices(&[6, 7]).unwrap();
+    assert_eq!(proof.gindices, vec![22, 23]);
+    assert_eq!(proof.helpers.len(), 3);
+    assert!(verify_multiproof(root, &proof));
+
+    // Proving every element requires no helpers at all.
+    let all = (0..16).collect::<Vec<_>>();
+    let proof = vector.prove_indices(&all).unwrap();
+    assert!(proof.helpers.is_empty());
+    assert!(verify_multiproof(root, &proof));
+}
+
+#[test]
+fn tampered_multiproof_fails() {
+    let list = List::<Hash256, U16>::new((0..10).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let root = list.tree_hash_root();
+    let proof = list.prove_indices(&[1, 5, 9]).unwrap();
+    assert!(verify_multiproof(root, &proof));
+
+    let mut wrong_leaf = proof.clone();
+    wrong_leaf.leaves[1] = Hash256::repeat_byte(1);
+    assert!(!verify_multiproof(root, &wrong_leaf));
+
+    let mut missing_helper = proof;
+    missing_helper.helpers.pop();
+    assert!(!verify_multiproof(root, &missing_helper));
+}
+
+#[test]
+fn multiproof_with_ancestor_leaf_fails() {
+    let list = List::<Hash256, U16>::new((0..10).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let root = list.tree_hash_root();
+
+    // Claiming the root as a leaf must not allow an arbitrary hash to be proven below it.
+    let forged = MultiProof {
+        gindices: vec![1, 32],
+        leaves: vec![root, Hash256::repeat_byte(1)],
+        helpers: vec![Hash256::zero(); helper_indices(&[1, 32]).len()],
+    };
+    assert!(!verify_multiproof(root, &forged));
+    assert_eq!(
+        PartialList::<Hash256, U16>::from_multiproof(root, &forged),
+        Err(Error::InvalidMultiProof)
+    );
+
+    // Likewise for an internal node above a leaf.
+    let proof = list.prove_indices(&[0]).unwrap();
+    let mut forged = proof.clone();
+    forged.gindices.insert(0, proof.gindices[0] >> 1);
+    forged.leaves.insert(0, Hash256::repeat_byte(2));
+    forged.helpers = vec![Hash256::zero(); helper_indices(&forged.gindices).len()];
+    assert!(!verify_multiproof(root, &forged));
+}
+
+#[test]
+fn multiproof_with_duplicate_leaf_fails() {
+    let list = List::<Hash256, U16>::new((0..10).map(Hash256::from_low_u64_be).collect()).unwrap();
+    let root = list.tree_hash_root();
+    let proof = list.prove_indices(&[3]).unwrap();
+
+    let mut duplicated = proof.clone();
+    duplicated.gindices.insert(0, proof.gindices[0]);
+    duplicated.leaves.insert(0, Hash256::repeat_byte(1));
+    assert!(!verify_multiproof(root, &duplicated));
+}
+
+#[test]
+fn prove_indices_matches_single_proofs() {
+    let list = List::<u64, U32>::new((0..27).collect()).unwrap();
+    let vector =
+        Vector::<Hash256, U16>::new((0..16).map(Hash256::from_low_u64_be).collect()).unwrap();
+
+    let index_sets: &[&[usize]] = &[&[0], &[15, 2, 2], &[3, 4, 5, 12], &[0, 7, 8, 15]];
+    for indices in index_sets {
+        let proofs = indices
+            .iter()
+            .map(|&i| list.prove(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            list.prove_indices(indices).unwrap(),
+            MultiProof::from_proofs(&proofs).unwrap()
+        );
+
+        let proofs = indices
+            .iter()
+            .map(|&i| vector.prove(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vector.prove_indices(indices).unwrap(),
+            MultiProof::from_proofs(&proofs).unwrap()
+        );
+    }
+
+    assert_eq!(
+        list.prove_indices(&[1, 27]),
+        Err(Error::OutOfBoundsProof { index: 27, len: 27 })
+    );
+}
@@ -1,5 +1,5 @@
 use super::{arb_hash256, arb_index, arb_large, arb_list, arb_vect, Large};
-use crate::{Error, List, Value, Vector};
+use crate::{Error, List, MaybeSync, Value, Vector};
 use proptest::prelude::*;
 use ssz::{Decode, Encode};
 use std::fmt::Debug;
@@ -67,6 +67,14 @@ impl<T: Value, N: Unsigned> Spec<T, N> {
         }
     }
 
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.values.pop()
+    }
+
     pub fn get(&self, index: usize) -> Option<&T> {
         self.values.get(index)
     }
@@ -81,6 +89,7 @@ impl<T: Value, N: Unsigned> Spec<T, N> {
             if self.values.len() == N::to_usize() {
                 return Err(Error::ListFull {
                     len: self.values.len(),
+                    max_len: N::to_usize(),
                 });
             }
             self.values.push(value);
@@ -123,6 +132,10 @@ pub enum Op<T> {
     Debase,
     /// Roundtrip via a list/vect using the TryFrom/From implementations.
     FromIntoRoundtrip,
+    /// Check the `truncate` method.
+    Truncate(usize),
+    /// Check the `pop` method.
+    Pop,
 }
 
 fn arb_op<'a, T, S>(strategy: &'a S, n: usize) -> impl Strategy<Value = Op<T>> + 'a
@@ -150,7 +163,9 @@ where
         Just(Op::Checkpoint),
         Just(Op::Rebase),
         Just(Op::Debase),
-        Just(Op::FromIntoRoundtrip)
+        Just(Op::FromIntoRoundtrip),
+        arb_index(n).prop_map(Op::Truncate),
+        Just(Op::Pop)
     ];
     prop_oneof![
         10 => a_block,
@@ -172,7 +187,7 @@ where
 
 fn apply_ops_list<T, N>(list: &mut List<T, N>, spec: &mut Spec<T, N>, ops: Vec<Op<T>>)
 where
-    T: Value + Debug + Send + Sync,
+    T: Value + Debug + MaybeSync,
     N: Unsigned + Debug,
 {
     let mut checkpoint = list.clone();
@@ -252,13 +267,23 @@ where
                     assert!(list.iter().eq(re_list.iter()));
                 }
             }
+            Op::Truncate(len) => {
+                list.truncate(len).unwrap();
+                spec.truncate(len);
+                assert_eq!(list.len(), spec.len());
+                assert!(list.iter().eq(spec.iter()));
+            }
+            Op::Pop => {
+                assert_eq!(list.pop().unwrap(), spec.pop());
+                assert_eq!(list.len(), spec.len());
+            }
         }
     }
 }
 
 fn apply_ops_vect<T, N>(vect: &mut Vector<T, N>, spec: &mut Spec<T, N>, ops: Vec<Op<T>>)
 where
-    T: Value + Debug + Send + Sync,
+    T: Value + Debug + MaybeSync,
     N: Unsigned + Debug,
 {
     let mut checkpoint = vect.clone();
@@ -330,6 +355,9 @@ where
                     assert!(vect.iter().eq(re_vect.iter()));
                 }
             }
+            Op::Truncate(_) | Op::Pop => {
+                // No-op
+            }
         }
     }
 }
@@ -0,0 +1,47 @@
+use crate::{Arc, List, Tree};
+use ssz::{Decode, Encode};
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+/// Count the nodes of `tree` which are pointer-equal to the corresponding node of `base`.
+fn count_shared<T: crate::Value>(tree: &Arc<Tree<T>>, base: &Arc<Tree<T>>) -> usize {
+    if Arc::ptr_eq(tree, base) {
+        return 1;
+    }
+    match (&**tree, &**base) {
+        (
+            Tree::Node {
+                left: l1,
+                right: r1,
+                ..
+            },
+            Tree::Node {
+                left: l2,
+                right: r2,
+                ..
+            },
+        ) => count_shared(l1, l2) + count_shared(r1, r2),
+        _ => 0,
+    }
+}
+
+#[test]
+fn rebase_after_ssz_decode() {
+    let base = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    base.tree_hash_root();
+
+    let mut modified = base.clone();
+    *modified.get_mut(500).unwrap() = 0;
+    modified.apply_updates().unwrap();
+
+    // Decoding shares nothing with the original.
+    let mut decoded = List::<u64, U1024>::from_ssz_bytes(&modified.as_ssz_bytes()).unwrap();
+    let base_tree = &base.interface.backing.tree;
+    assert_eq!(count_shared(&decoded.interface.backing.tree, base_tree), 0);
+
+    // Rebasing shares every subtree except those on the path to the modified element.
+    decoded.rebase_on(&base).unwrap();
+    assert_eq!(decoded, modified);
+    assert_eq!(count_shared(&decoded.interface.backing.tree, base_tree), 8);
+    assert_eq!(decoded.tree_hash_root(), modified.tree_hash_root());
+}
@@ -1,9 +1,10 @@
-use crate::{List, Value};
+use crate::{Error, List, MaybeSync, Sha256Hasher, Value, Vector};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use tree_hash::TreeHash;
 use typenum::{Unsigned, U1024, U64, U8};
 
-fn list_test<T: Value + Send + Sync + Debug, N: Unsigned + Debug>(val: T) {
+fn list_test<T: Value + MaybeSync + Debug, N: Unsigned + Debug>(val: T) {
     for n in 96..=N::to_usize() {
         let fast = List::<T, N>::repeat(val.clone(), n).unwrap();
         let slow = List::<T, N>::repeat_slow(val.clone(), n).unwrap();
@@ -39,3 +40,56 @@ fn list_list_u8_64() {
     list_test::<List<u8, U8>, U64>(List::repeat(1u8, 1).unwrap());
     list_test::<List<u8, U8>, U64>(List::repeat(255u8, 8).unwrap());
 }
+
+#[test]
+fn repeat_shares_subtrees() {
+    type Huge = typenum::U1099511627776;
+    let n = (1 << 30) + 3;
+    let list = List::<u64, Huge>::repeat(0, n).unwrap();
+    assert_eq!(list.len(), n);
+    assert_eq!(list.get(n - 1), Some(&0));
+    let depth = List::<u64, Huge>::depth();
+    // At most one repeated node, one partial node and one zero node per level.
+    assert!(list.mem_usage().nodes <= 3 * (depth + 1));
+
+    let vector = Vector::<u64, U1024>::repeat(7).unwrap();
+    assert_eq!(vector.to_vec(), vec![7; 1024]);
+    assert_eq!(vector, Vector::from_elem(7).unwrap());
+    assert!(vector.mem_usage().nodes <= 3 * (List::<u64, U1024>::depth() + 1));
+}
+
+#[test]
+fn repeat_too_long() {
+    assert_eq!(
+        List::<u64, U8>::repeat(0, 9),
+        Err(Error::ListFull { len: 0, max_len: 8 })
+    );
+}
+
+#[test]
+fn sparse_writes() {
+    let root_of = |list: &List<_, _, _>| list.tree_hash_root_with::<Sha256Hasher>().unwrap();
+    type Huge = typenum::U1099511627776;
+    let depth = List::<u64, Huge>::depth();
+    let n = 1 << 32;
+    // The default `VecMap` allocates space for every index up to the largest one written.
+    let mut list = List::<u64, Huge, BTreeMap<usize, u64>>::repeat(0, n).unwrap();
+    let root = root_of(&list);
+    let initial = list.mem_usage();
+
+    // Writing the default value leaves the tree untouched.
+    *list.get_mut(12345).unwrap() = 0;
+    list.apply_updates().unwrap();
+    assert_eq!(list.mem_usage(), initial);
+    assert_eq!(root_of(&list), root);
+
+    // Each write only materialises the path to the modified leaf.
+    for index in [0, 1 << 20, n - 1] {
+        *list.get_mut(index).unwrap() = index as u64 + 1;
+    }
+    list.apply_updates().unwrap();
+    assert!(list.mem_usage().nodes <= initial.nodes + 3 * (depth + 1));
+    assert_eq!(list.get(1 << 20), Some(&((1 << 20) + 1)));
+    assert_eq!(list.get((1 << 20) + 1), Some(&0));
+    assert_ne!(root_of(&list), root);
+}
@@ -0,0 +1,55 @@
+use crate::update_map::MaxMap;
+use crate::{ApplyPolicy, AutoApply, List};
+use tree_hash::TreeHash;
+use typenum::{Unsigned, U1024, U16};
+use vec_map::VecMap;
+
+#[test]
+fn retain_matches_vec() {
+    type N = U1024;
+    let vec = (0..N::to_u64()).collect::<Vec<_>>();
+    let list = List::<u64, N>::new(vec.clone()).unwrap();
+
+    for modulus in [1, 2, 3, 7, 1000, 2000] {
+        let mut retained = list.clone();
+        retained.retain(|value| value % modulus == 0).unwrap();
+
+        let mut expected = vec.clone();
+        expected.retain(|value| value % modulus == 0);
+        let expected = List::<u64, N>::new(expected).unwrap();
+
+        assert_eq!(retained, expected);
+        assert_eq!(retained.tree_hash_root(), expected.tree_hash_root());
+    }
+}
+
+#[test]
+fn retain_none_or_all() {
+    let mut list = List::<u64, U16>::new((0..16).collect()).unwrap();
+    let all = list.filtered(|_| true).unwrap();
+    assert_eq!(all, list);
+
+    list.retain(|_| false).unwrap();
+    assert!(list.is_empty());
+    assert_eq!(
+        list.tree_hash_root(),
+        List::<u64, U16>::empty().tree_hash_root()
+    );
+}
+
+#[test]
+fn retain_pending_updates() {
+    let mut list = List::<u64, U16, AutoApply<MaxMap<VecMap<u64>>>>::new((0..8).collect()).unwrap();
+    *list.get_mut(1).unwrap() = 101;
+    list.push(9).unwrap();
+
+    let filtered = list.filtered(|value| value % 2 == 1).unwrap();
+    assert_eq!(filtered.to_vec(), vec![101, 3, 5, 7, 9]);
+    // The original list is unchanged.
+    assert_eq!(list.len(), 9);
+
+    list.retain(|value| value % 2 == 1).unwrap();
+    assert_eq!(list, filtered);
+    assert!(!list.has_pending_updates());
+    assert_eq!(list.apply_policy(), ApplyPolicy::Auto);
+}
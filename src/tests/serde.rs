@@ -0,0 +1,124 @@
+use crate::serde::{decode_hex, encode_hex};
+use crate::{Bitlist, Bitvector, List, VecUpdateMap, Vector};
+use serde::{de::DeserializeOwned, Serialize};
+use ssz::Encode;
+use typenum::{U16, U4, U8};
+
+fn to_cbor<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    ciborium::into_writer(value, &mut buf).unwrap();
+    buf
+}
+
+fn from_cbor<T: DeserializeOwned>(bytes: &[u8]) -> T {
+    ciborium::from_reader(bytes).unwrap()
+}
+
+#[test]
+fn list_json_sequence() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, "[1,2,3,4]");
+
+    let decoded: List<u64, U16> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, list);
+
+    let too_long = serde_json::to_string(&(0..17).collect::<Vec<u64>>()).unwrap();
+    assert!(serde_json::from_str::<List<u64, U16>>(&too_long).is_err());
+}
+
+#[test]
+fn list_compact_bytes() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+
+    let cbor = to_cbor(&list);
+    let ssz = list.as_ssz_bytes();
+    // The CBOR byte string header is followed by the SSZ bytes.
+    assert!(cbor.ends_with(&ssz));
+    assert_eq!(cbor.len(), ssz.len() + 2);
+
+    let decoded: List<u64, U16> = from_cbor(&cbor);
+    assert_eq!(decoded, list);
+
+    let decoded: List<u64, U16, VecUpdateMap<u64>> = from_cbor(&cbor);
+    assert_eq!(decoded.to_vec(), list.to_vec());
+
+    let too_long = List::<u64, U16>::new((0..16).collect()).unwrap();
+    let result: Result<List<u64, U8>, _> = ciborium::from_reader(&to_cbor(&too_long)[..]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn nested_lists() {
+    let list = List::<List<u8, U4>, U4>::new(vec![
+        List::new(vec![1, 2]).unwrap(),
+        List::empty(),
+        List::new(vec![3]).unwrap(),
+    ])
+    .unwrap();
+
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, "[[1,2],[],[3]]");
+    assert_eq!(
+        serde_json::from_str::<List<List<u8, U4>, U4>>(&json).unwrap(),
+        list
+    );
+    assert_eq!(from_cbor::<List<List<u8, U4>, U4>>(&to_cbor(&list)), list);
+}
+
+#[test]
+fn vector_representations() {
+    let vector = Vector::<u64, U4>::new(vec![5, 6, 7, 8]).unwrap();
+
+    let json = serde_json::to_string(&vector).unwrap();
+    assert_eq!(json, "[5,6,7,8]");
+    assert_eq!(
+        serde_json::from_str::<Vector<u64, U4>>(&json).unwrap(),
+        vector
+    );
+    assert!(serde_json::from_str::<Vector<u64, U4>>("[1,2,3]").is_err());
+
+    assert_eq!(from_cbor::<Vector<u64, U4>>(&to_cbor(&vector)), vector);
+}
+
+#[test]
+fn bitfield_representations() {
+    let mut bitlist = Bitlist::<U16>::with_capacity(10).unwrap();
+    bitlist.set(0, true).unwrap();
+    bitlist.set(9, true).unwrap();
+
+    let json = serde_json::to_string(&bitlist).unwrap();
+    assert_eq!(json, format!("\"{}\"", encode_hex(&bitlist.as_ssz_bytes())));
+    assert_eq!(
+        serde_json::from_str::<Bitlist<U16>>(&json).unwrap(),
+        bitlist
+    );
+    assert_eq!(from_cbor::<Bitlist<U16>>(&to_cbor(&bitlist)), bitlist);
+
+    let mut bitvector = Bitvector::<U16>::new();
+    bitvector.set(3, true).unwrap();
+    let json = serde_json::to_string(&bitvector).unwrap();
+    assert_eq!(json, "\"0x0800\"");
+    assert_eq!(
+        serde_json::from_str::<Bitvector<U16>>(&json).unwrap(),
+        bitvector
+    );
+    assert_eq!(from_cbor::<Bitvector<U16>>(&to_cbor(&bitvector)), bitvector);
+
+    assert!(serde_json::from_str::<Bitvector<U16>>("\"0x08\"").is_err());
+    assert!(serde_json::from_str::<Bitvector<U16>>("\"0800\"").is_err());
+}
+
+#[test]
+fn hex_round_trip() {
+    assert_eq!(encode_hex(&[]), "0x");
+    assert_eq!(encode_hex(&[0x00, 0xab, 0xff]), "0x00abff");
+    assert_eq!(decode_hex("0x00abff").unwrap(), vec![0x00, 0xab, 0xff]);
+    assert_eq!(decode_hex("0xABFF").unwrap(), vec![0xab, 0xff]);
+    assert!(decode_hex("0xabc").is_err());
+    assert!(decode_hex("0xzz").is_err());
+    assert!(decode_hex("abcd").is_err());
+}
@@ -0,0 +1,87 @@
+use crate::{Error, List, ShardedUpdates, Vector};
+use tree_hash::TreeHash;
+use typenum::{U1024, U8};
+
+#[test]
+fn merge_then_apply() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    *list.get_mut(5).unwrap() = 1000;
+    *list.get_mut(6).unwrap() = 1000;
+
+    let updates = ShardedUpdates::with_shards(4);
+    assert_eq!(updates.insert(6, 2000), None);
+    assert_eq!(updates.insert(99, 1), None);
+    assert_eq!(updates.insert(99, 2), Some(1));
+    assert_eq!(
+        updates.update_with(3, |i| list.get(i).copied(), |value| *value += 1),
+        Some(())
+    );
+    assert_eq!(updates.update_with(3, |_| None, |value| *value), Some(4));
+    assert_eq!(
+        updates.update_with(200, |i| list.get(i).copied(), |_| ()),
+        None
+    );
+    assert_eq!(updates.len(), 3);
+    assert_eq!(updates.get_cloned(99), Some(2));
+
+    list.merge_updates(updates).unwrap();
+    list.apply_updates().unwrap();
+
+    let mut expected = (0..100).collect::<Vec<u64>>();
+    expected[3] = 4;
+    expected[5] = 1000;
+    expected[6] = 2000;
+    expected[99] = 2;
+    assert_eq!(list.to_vec(), expected);
+    assert_eq!(
+        list.tree_hash_root(),
+        List::<u64, U1024>::new(expected).unwrap().tree_hash_root()
+    );
+}
+
+#[test]
+fn out_of_bounds() {
+    let mut vector = Vector::<u64, U8>::new(vec![0; 8]).unwrap();
+    let updates = ShardedUpdates::new();
+    updates.insert(1, 1);
+    updates.insert(8, 1);
+    assert_eq!(
+        vector.merge_updates(updates),
+        Err(Error::OutOfBoundsUpdate { index: 8, len: 8 })
+    );
+    assert!(!vector.has_pending_updates());
+
+    let updates = ShardedUpdates::with_shards(0);
+    assert!(updates.is_empty());
+    updates.insert(7, 7);
+    vector.merge_updates(updates).unwrap();
+    vector.apply_updates().unwrap();
+    assert_eq!(vector.get(7), Some(&7));
+}
+
+#[cfg(not(milhouse_rc))]
+#[test]
+fn concurrent_writers() {
+    let mut list = List::<u64, U1024>::new(vec![0; 1000]).unwrap();
+    let updates = ShardedUpdates::new();
+
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let (list, updates) = (&list, &updates);
+            scope.spawn(move || {
+                for index in (thread..1000).step_by(4) {
+                    updates.update_with(
+                        index,
+                        |i| list.get(i).copied(),
+                        |value| *value += index as u64,
+                    );
+                }
+            });
+        }
+    });
+    assert_eq!(updates.len(), 1000);
+
+    list.merge_updates(updates).unwrap();
+    list.apply_updates().unwrap();
+    assert_eq!(list.to_vec(), (0..1000).collect::<Vec<u64>>());
+}
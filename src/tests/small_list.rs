@@ -0,0 +1,77 @@
+use crate::{Error, List, SmallList};
+use ssz::{Decode, Encode};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16, U4, U8};
+
+fn check_matches_list<T: crate::Value + crate::MaybeSync, const K: usize>(
+    small: &SmallList<T, U1024, K>,
+) {
+    let list = List::<T, U1024>::new(small.to_vec()).unwrap();
+    assert_eq!(small.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(small.as_ssz_bytes(), list.as_ssz_bytes());
+}
+
+#[test]
+fn promoted_when_full() {
+    let mut small = SmallList::<u64, U1024, 4>::empty();
+    for i in 0..4 {
+        small.push(i).unwrap();
+        assert!(small.is_inline());
+        check_matches_list(&small);
+    }
+    small.push(4).unwrap();
+    assert!(!small.is_inline());
+    assert_eq!(small.to_vec(), vec![0, 1, 2, 3, 4]);
+    check_matches_list(&small);
+
+    *small.get_mut(0).unwrap() = 100;
+    assert_eq!(small.get(0), Some(&100));
+    check_matches_list(&small);
+}
+
+#[test]
+fn unpacked_values() {
+    let values = (0..3).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let mut small = SmallList::<Hash256, U1024>::new(values).unwrap();
+    assert!(small.is_inline());
+    check_matches_list(&small);
+    *small.get_mut(1).unwrap() = Hash256::repeat_byte(1);
+    check_matches_list(&small);
+
+    let nested = SmallList::<List<u8, U16>, U1024>::new(
+        (0..5).map(|i| List::new(vec![i; 3]).unwrap()).collect(),
+    )
+    .unwrap();
+    check_matches_list(&nested);
+}
+
+#[test]
+fn decode() {
+    let list = List::<u64, U1024>::new((0..20).collect()).unwrap();
+    let bytes = list.as_ssz_bytes();
+    let small = SmallList::<u64, U1024, 32>::from_ssz_bytes(&bytes).unwrap();
+    assert!(small.is_inline());
+    let large = SmallList::<u64, U1024, 8>::from_ssz_bytes(&bytes).unwrap();
+    assert!(!large.is_inline());
+    assert_eq!(small.to_vec(), large.to_vec());
+    assert_eq!(large.into_list().unwrap(), list);
+}
+
+#[test]
+fn max_len() {
+    assert_eq!(
+        SmallList::<u64, U4>::new(vec![0; 5]),
+        Err(Error::ListFull { len: 5, max_len: 4 })
+    );
+    let mut small = SmallList::<u64, U4>::new(vec![0; 4]).unwrap();
+    assert_eq!(small.push(0), Err(Error::ListFull { len: 4, max_len: 4 }));
+    assert!(small.is_inline());
+
+    let mut small = SmallList::<u64, U8, 2>::new(vec![1, 2, 3]).unwrap();
+    assert!(!small.is_inline());
+    assert_eq!(small.len(), 3);
+    for i in 3..8 {
+        small.push(i).unwrap();
+    }
+    assert!(small.push(8).is_err());
+}
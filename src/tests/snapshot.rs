@@ -0,0 +1,54 @@
+use crate::{Error, List, Snapshot, VecUpdateMap};
+use ssz::Encode;
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+#[test]
+fn pending_updates_rejected() {
+    let mut list = List::<u64, U1024>::new((0..10).collect()).unwrap();
+    list.push(10).unwrap();
+    assert_eq!(list.snapshot(), Err(Error::SnapshotPendingUpdates));
+    assert_eq!(
+        Snapshot::try_from(list.clone()),
+        Err(Error::SnapshotPendingUpdates)
+    );
+
+    list.apply_updates().unwrap();
+    let snapshot = list.snapshot().unwrap();
+    assert_eq!(snapshot.to_vec(), (0..=10).collect::<Vec<_>>());
+    assert_eq!(snapshot.tree_hash_root(), list.tree_hash_root());
+    assert_eq!(snapshot.as_ssz_bytes(), list.as_ssz_bytes());
+}
+
+#[test]
+fn other_update_maps() {
+    let list = List::<u64, U1024, VecUpdateMap<u64>>::new((0..50).collect()).unwrap();
+    let snapshot = Snapshot::new(list.clone()).unwrap();
+    assert_eq!(snapshot.to_vec(), list.to_vec());
+
+    // Converting back gives a mutable list sharing the same tree.
+    let mut list = snapshot.clone().into_inner();
+    *list.get_mut(0).unwrap() = 100;
+    list.apply_updates().unwrap();
+    assert_eq!(snapshot.get(0), Some(&0));
+    assert_eq!(list.get(0), Some(&100));
+}
+
+#[cfg(not(milhouse_rc))]
+#[test]
+fn concurrent_reads() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let snapshot = list.snapshot().unwrap();
+    let expected_root = list.tree_hash_root();
+
+    std::thread::scope(|scope| {
+        for i in 0..4 {
+            let snapshot = &snapshot;
+            scope.spawn(move || {
+                assert_eq!(snapshot.get(i * 100), Some(&(i as u64 * 100)));
+                assert_eq!(snapshot.iter().sum::<u64>(), 499500);
+                assert_eq!(snapshot.tree_hash_root(), expected_root);
+            });
+        }
+    });
+}
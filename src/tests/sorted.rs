@@ -0,0 +1,122 @@
+use crate::{Error, List};
+use tree_hash::Hash256;
+use typenum::{U1024, U8};
+
+fn check_partition_point<T: crate::Value + Ord>(values: &[T], list: &List<T, U1024>) {
+    for value in values {
+        assert_eq!(
+            list.partition_point(|x| x < value),
+            values.partition_point(|x| x < value)
+        );
+        assert_eq!(
+            list.partition_point(|x| x <= value),
+            values.partition_point(|x| x <= value)
+        );
+    }
+    assert_eq!(list.partition_point(|_| true), values.len());
+    assert_eq!(list.partition_point(|_| false), 0);
+}
+
+#[test]
+fn partition_point_packed() {
+    for len in [0, 1, 3, 4, 5, 100, 513, 1024] {
+        let values = (0..len as u64).map(|i| i * 2 / 3).collect::<Vec<_>>();
+        let list = List::<u64, U1024>::new(values.clone()).unwrap();
+        check_partition_point(&values, &list);
+    }
+}
+
+#[test]
+fn partition_point_unpacked() {
+    for len in [0, 1, 2, 77, 1024] {
+        let values = (0..len as u64)
+            .map(|i| Hash256::from_low_u64_be(i * 3))
+            .collect::<Vec<_>>();
+        let list = List::<Hash256, U1024>::new(values.clone()).unwrap();
+        check_partition_point(&values, &list);
+    }
+}
+
+#[test]
+fn binary_search_with_pending_updates() {
+    let mut list = List::<u64, U1024>::new(vec![1, 3, 5]).unwrap();
+    list.push(7).unwrap();
+    list.push(7).unwrap();
+    list.push(9).unwrap();
+
+    assert_eq!(list.binary_search(&1), Ok(0));
+    assert_eq!(list.binary_search(&7), Ok(3));
+    assert_eq!(list.binary_search(&8), Err(5));
+    assert_eq!(list.binary_search(&10), Err(6));
+    assert_eq!(list.binary_search(&0), Err(0));
+
+    list.apply_updates().unwrap();
+    assert_eq!(list.binary_search(&7), Ok(3));
+    assert_eq!(list.binary_search_by(|x| x.cmp(&4)), Err(2));
+}
+
+#[test]
+fn insert_sorted() {
+    let mut list = List::<u64, U8>::new(vec![2, 4]).unwrap();
+    assert_eq!(list.insert_sorted(3), Ok(1));
+    assert_eq!(list.insert_sorted(5), Ok(3));
+    assert_eq!(list.insert_sorted(0), Ok(0));
+    list.apply_updates().unwrap();
+    assert_eq!(list.insert_sorted(4), Ok(4));
+    assert_eq!(list.to_vec(), vec![0, 2, 3, 4, 4, 5]);
+
+    list.insert_sorted(1).unwrap();
+    list.insert_sorted(1).unwrap();
+    assert_eq!(
+        list.insert_sorted(1),
+        Err(Error::ListFull { len: 8, max_len: 8 })
+    );
+    list.apply_updates().unwrap();
+    assert_eq!(list, List::new(vec![0, 1, 1, 2, 3, 4, 4, 5]).unwrap());
+}
+
+#[test]
+fn sorted_by_matches_vec() {
+    for len in [0, 1, 5, 100, 1024] {
+        let values = (0..len as u64)
+            .map(|i| (i * 7919) % 1031)
+            .collect::<Vec<_>>();
+        let list = List::<u64, U1024>::new(values.clone()).unwrap();
+
+        let mut expected = values.clone();
+        expected.sort_unstable();
+        assert_eq!(list.sorted().unwrap(), List::new(expected.clone()).unwrap());
+
+        expected.reverse();
+        let descending = list.sorted_by(|a, b| b.cmp(a)).unwrap();
+        assert_eq!(descending, List::new(expected).unwrap());
+    }
+}
+
+#[test]
+fn sorted_by_shares_sorted_list() {
+    let list = List::<u64, U1024>::new((0..500).collect()).unwrap();
+    let sorted = list.sorted().unwrap();
+    assert_eq!(
+        List::count_unique_nodes(&[&list, &sorted]).exclusive_nodes,
+        vec![0, 0]
+    );
+
+    // Pending updates are included.
+    let mut list = list;
+    *list.get_mut(0).unwrap() = 1000;
+    let sorted = list.sorted().unwrap();
+    assert_eq!(sorted.get(0), Some(&1));
+    assert_eq!(sorted.get(499), Some(&1000));
+}
+
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+#[test]
+fn par_sorted_by_matches_sorted_by() {
+    let values = (0..1024u64).map(|i| (i * 7919) % 1031).collect::<Vec<_>>();
+    let list = List::<u64, U1024>::new(values).unwrap();
+    assert_eq!(
+        list.par_sorted_by(|a, b| b.cmp(a)).unwrap(),
+        list.sorted_by(|a, b| b.cmp(a)).unwrap()
+    );
+}
@@ -0,0 +1,181 @@
+use crate::proof::hash_pair;
+use crate::{List, StableContainer, StableField};
+use ssz::{Decode, DecodeError, Encode};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U16, U4, U8};
+
+/// The `Shape` example from EIP-7495, extended with a variable-length field.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Side(u16),
+    Color(u8),
+    Radius(u16),
+    Label(List<u8, U16>),
+}
+
+impl StableField for Shape {
+    const NUM_FIELDS: usize = 4;
+
+    fn index(&self) -> usize {
+        match self {
+            Shape::Side(_) => 0,
+            Shape::Color(_) => 1,
+            Shape::Radius(_) => 2,
+            Shape::Label(_) => 3,
+        }
+    }
+
+    fn is_ssz_fixed_len(index: usize) -> bool {
+        index != 3
+    }
+
+    fn ssz_fixed_len(index: usize) -> usize {
+        match index {
+            1 => 1,
+            _ => 2,
+        }
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        match self {
+            Shape::Side(x) | Shape::Radius(x) => x.ssz_bytes_len(),
+            Shape::Color(x) => x.ssz_bytes_len(),
+            Shape::Label(x) => x.ssz_bytes_len(),
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        match self {
+            Shape::Side(x) | Shape::Radius(x) => x.ssz_append(buf),
+            Shape::Color(x) => x.ssz_append(buf),
+            Shape::Label(x) => x.ssz_append(buf),
+        }
+    }
+
+    fn from_ssz_bytes(index: usize, bytes: &[u8]) -> Result<Self, DecodeError> {
+        match index {
+            0 => u16::from_ssz_bytes(bytes).map(Shape::Side),
+            1 => u8::from_ssz_bytes(bytes).map(Shape::Color),
+            2 => u16::from_ssz_bytes(bytes).map(Shape::Radius),
+            _ => List::from_ssz_bytes(bytes).map(Shape::Label),
+        }
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        match self {
+            Shape::Side(x) | Shape::Radius(x) => x.tree_hash_root(),
+            Shape::Color(x) => x.tree_hash_root(),
+            Shape::Label(x) => x.tree_hash_root(),
+        }
+    }
+}
+
+type ShapeContainer = StableContainer<Shape, U4>;
+
+fn shape(fields: Vec<Shape>) -> ShapeContainer {
+    let mut container = ShapeContainer::new();
+    for field in fields {
+        container.set(field).unwrap();
+    }
+    container
+}
+
+fn expected_root(roots: [Hash256; 4], active: u8) -> Hash256 {
+    let fields_root = hash_pair(
+        &hash_pair(&roots[0], &roots[1]),
+        &hash_pair(&roots[2], &roots[3]),
+    );
+    let mut active_chunk = Hash256::zero();
+    active_chunk.0[0] = active;
+    hash_pair(&fields_root, &active_chunk)
+}
+
+#[test]
+fn eip_serialization() {
+    let container = shape(vec![Shape::Side(0x42), Shape::Color(1)]);
+    assert_eq!(container.as_ssz_bytes(), vec![0x03, 0x42, 0x00, 0x01]);
+    assert_eq!(
+        ShapeContainer::from_ssz_bytes(&[0x03, 0x42, 0x00, 0x01]).unwrap(),
+        container
+    );
+
+    let container = shape(vec![
+        Shape::Side(0x42),
+        Shape::Color(1),
+        Shape::Radius(0x42),
+    ]);
+    let bytes = vec![0x07, 0x42, 0x00, 0x01, 0x42, 0x00];
+    assert_eq!(container.as_ssz_bytes(), bytes);
+    assert_eq!(container.ssz_bytes_len(), bytes.len());
+    assert_eq!(ShapeContainer::from_ssz_bytes(&bytes).unwrap(), container);
+}
+
+#[test]
+fn tree_hash_root() {
+    let container = shape(vec![Shape::Side(0x42), Shape::Color(1)]);
+    let roots = [
+        0x42u16.tree_hash_root(),
+        1u8.tree_hash_root(),
+        Hash256::zero(),
+        Hash256::zero(),
+    ];
+    assert_eq!(container.tree_hash_root(), expected_root(roots, 0x03));
+
+    // Test vector from EIP-7495.
+    assert_eq!(
+        format!("{:?}", container.tree_hash_root()),
+        "0xbfdb6fda9d02805e640c0f5767b8d1bb9ff4211498a5e2d7c0f36e1b88ce57ff"
+    );
+}
+
+#[test]
+fn variable_length_field() {
+    let label = List::<u8, U16>::new(vec![1, 2, 3]).unwrap();
+    let container = shape(vec![Shape::Label(label.clone()), Shape::Color(7)]);
+    let bytes = container.as_ssz_bytes();
+    // Active fields, colour, offset of the label, then the label itself.
+    assert_eq!(bytes, vec![0x0a, 7, 5, 0, 0, 0, 1, 2, 3]);
+    assert_eq!(ShapeContainer::from_ssz_bytes(&bytes).unwrap(), container);
+
+    let roots = [
+        Hash256::zero(),
+        7u8.tree_hash_root(),
+        Hash256::zero(),
+        label.tree_hash_root(),
+    ];
+    assert_eq!(container.tree_hash_root(), expected_root(roots, 0x0a));
+}
+
+#[test]
+fn remove_and_sharing() {
+    let mut container = shape(vec![Shape::Side(1), Shape::Radius(2)]);
+    let original = container.clone();
+
+    assert_eq!(container.remove(0).unwrap(), Some(Shape::Side(1)));
+    assert!(!container.is_active(0));
+    assert_eq!(
+        container.iter().collect::<Vec<_>>(),
+        vec![&Shape::Radius(2)]
+    );
+    assert_eq!(container, shape(vec![Shape::Radius(2)]));
+    assert_eq!(
+        container.tree_hash_root(),
+        shape(vec![Shape::Radius(2)]).tree_hash_root()
+    );
+
+    // The original is unaffected.
+    assert_eq!(original.get(0), Some(&Shape::Side(1)));
+}
+
+#[test]
+fn decode_errors() {
+    // Unknown field in a larger container.
+    type Wide = StableContainer<Shape, U8>;
+    assert!(Wide::from_ssz_bytes(&[0x10]).is_err());
+    // Missing and trailing bytes.
+    assert!(ShapeContainer::from_ssz_bytes(&[0x01, 0x42]).is_err());
+    assert!(ShapeContainer::from_ssz_bytes(&[0x01, 0x42, 0x00, 0x00]).is_err());
+    // Bad offset.
+    assert!(ShapeContainer::from_ssz_bytes(&[0x08, 0x05, 0, 0, 0]).is_err());
+    assert_eq!(ShapeContainer::new().set(Shape::Side(1)), Ok(()));
+}
@@ -0,0 +1,171 @@
+use crate::{
+    Error, List, MemoryNodeStore, NodeSink, NodeStore, StoredList, StoredNode, Tree, Value, Vector,
+};
+use std::cell::{Cell, RefCell};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16, U8};
+
+/// Store which counts the nodes read from it.
+#[derive(Default)]
+struct CountingStore<T> {
+    inner: MemoryNodeStore<T>,
+    reads: Cell<usize>,
+}
+
+impl<T: Value> NodeSink<T> for CountingStore<T> {
+    fn put(&self, depth: usize, hash: Hash256, node: StoredNode<T>) -> Result<(), Error> {
+        self.inner.put(depth, hash, node)
+    }
+}
+
+impl<T: Value> NodeStore<T> for CountingStore<T> {
+    fn get(&self, depth: usize, hash: Hash256) -> Result<Option<StoredNode<T>>, Error> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.get(depth, hash)
+    }
+}
+
+/// Sink which records the nodes written to it.
+#[derive(Default)]
+struct RecordingSink<T> {
+    nodes: RefCell<Vec<(usize, Hash256, StoredNode<T>)>>,
+}
+
+impl<T: Value> NodeSink<T> for RecordingSink<T> {
+    fn put(&self, depth: usize, hash: Hash256, node: StoredNode<T>) -> Result<(), Error> {
+        self.nodes.borrow_mut().push((depth, hash, node));
+        Ok(())
+    }
+}
+
+#[test]
+fn list_round_trip() {
+    let store = MemoryNodeStore::new();
+    let list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    let root = list.store(&store).unwrap();
+    assert_eq!(root, list.tree_hash_root());
+
+    let loaded = List::<u64, U1024>::load(&store, root).unwrap();
+    assert_eq!(loaded, list);
+    assert_eq!(loaded.tree_hash_root(), root);
+    loaded.validate().unwrap();
+
+    let empty = List::<u64, U1024>::empty();
+    let root = empty.store(&store).unwrap();
+    assert_eq!(List::<u64, U1024>::load(&store, root).unwrap(), empty);
+}
+
+#[test]
+fn incremental_store() {
+    let store = MemoryNodeStore::new();
+    let mut list =
+        List::<Hash256, U1024>::new((0..=255).map(Hash256::repeat_byte).collect()).unwrap();
+    let old_root = list.store(&store).unwrap();
+    let nodes = store.len();
+
+    *list.get_mut(7).unwrap() = Hash256::from_low_u64_be(1000);
+    list.apply_updates().unwrap();
+    let new_root = list.store(&store).unwrap();
+
+    // Only the modified branch and the new list root are written.
+    assert_eq!(store.len(), nodes + List::<Hash256, U1024>::depth() + 2);
+    assert_eq!(
+        List::<Hash256, U1024>::load(&store, new_root).unwrap(),
+        list
+    );
+    assert_eq!(
+        List::<Hash256, U1024>::load(&store, old_root)
+            .unwrap()
+            .get(7),
+        Some(&Hash256::repeat_byte(7))
+    );
+}
+
+#[test]
+fn vector_round_trip() {
+    let store = MemoryNodeStore::new();
+    let vector = Vector::<u16, U16>::new((0..16).collect()).unwrap();
+    let root = vector.store(&store).unwrap();
+    assert_eq!(root, vector.tree_hash_root());
+    assert_eq!(Vector::<u16, U16>::load(&store, root).unwrap(), vector);
+}
+
+#[test]
+fn lazy_reads() {
+    let store = CountingStore::default();
+    let list = List::<u64, U1024>::new((0..300).collect()).unwrap();
+    let root = list.store(&store).unwrap();
+
+    store.reads.set(0);
+    let stored = StoredList::<u64, U1024, _>::open(&store, root).unwrap();
+    assert_eq!(stored.len(), 300);
+    assert_eq!(stored.get(123).unwrap(), Some(123));
+    assert_eq!(stored.get(300).unwrap(), None);
+    // The list root, plus one node per level of the tree.
+    assert_eq!(store.reads.get(), 2 + List::<u64, U1024>::depth());
+
+    assert_eq!(stored.load().unwrap(), list);
+}
+
+#[test]
+fn store_errors() {
+    let store = MemoryNodeStore::new();
+    let mut list = List::<u64, U8>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    assert_eq!(list.store(&store), Err(Error::StorePendingUpdates));
+    list.apply_updates().unwrap();
+
+    let root = list.tree_hash_root();
+    assert!(matches!(
+        List::<u64, U8>::load(&store, root),
+        Err(Error::MissingStoredNode { .. })
+    ));
+    list.store(&store).unwrap();
+
+    // A leaf stored where an internal node belongs is rejected.
+    let bad = MemoryNodeStore::new();
+    let hash = Hash256::repeat_byte(1);
+    bad.put(1, hash, StoredNode::Leaf(1u64)).unwrap();
+    assert_eq!(
+        Tree::<u64>::load(1, hash, &bad).unwrap_err(),
+        Error::InvalidStoredNode { depth: 1, hash }
+    );
+}
+
+#[test]
+fn sink_receives_new_nodes() {
+    let store = MemoryNodeStore::new();
+    let mut list = List::<u64, U1024>::new((0..300).collect()).unwrap();
+    list.store(&store).unwrap();
+
+    let sink = RecordingSink::default();
+    *list.get_mut(5).unwrap() = 500;
+    list.push(300).unwrap();
+    let root = list.apply_updates_with_sink(&sink).unwrap();
+    assert_eq!(root, list.tree_hash_root());
+
+    let nodes = sink.nodes.into_inner();
+    assert_eq!(nodes.last().unwrap().1, root);
+    // The two modified branches share the nodes above their lowest common ancestor.
+    assert!(nodes.len() < 2 * (List::<u64, U1024>::depth() + 1) + 1);
+    for (depth, hash, node) in nodes {
+        store.put(depth, hash, node).unwrap();
+    }
+    assert_eq!(List::<u64, U1024>::load(&store, root).unwrap(), list);
+
+    // Without any updates, only the list root is written.
+    let sink = RecordingSink::default();
+    list.apply_updates_with_sink(&sink).unwrap();
+    assert_eq!(sink.nodes.borrow().len(), 1);
+}
+
+#[test]
+fn vector_sink() {
+    let store = MemoryNodeStore::new();
+    let mut vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    vector.store(&store).unwrap();
+
+    *vector.get_mut(15).unwrap() = 0;
+    let root = vector.apply_updates_with_sink(&store).unwrap();
+    assert_eq!(Vector::<u64, U16>::load(&store, root).unwrap(), vector);
+}
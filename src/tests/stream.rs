@@ -0,0 +1,232 @@
+use crate::{List, Vector};
+use ssz::{Decode, DecodeError, Encode};
+use std::io::Read;
+use typenum::{U1024, U1099511627776, U16, U4096, U8};
+
+/// Reader which returns at most `step` bytes per read, to exercise partial reads.
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    step: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.step.min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn stream_fixed_len_list() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let bytes = list.as_ssz_bytes();
+    let reader = Trickle {
+        bytes: &bytes,
+        step: 3,
+    };
+    assert_eq!(List::<u64, U1024>::from_ssz_reader(reader).unwrap(), list);
+}
+
+#[test]
+fn stream_variable_len_list() {
+    type Inner = List<u8, U16>;
+    let list = List::<Inner, U8>::new(
+        (0..8)
+            .map(|i| Inner::new((0..i).collect()).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    let bytes = list.as_ssz_bytes();
+    let reader = Trickle {
+        bytes: &bytes,
+        step: 5,
+    };
+    let decoded = List::<Inner, U8>::from_ssz_reader(reader).unwrap();
+    assert_eq!(decoded, list);
+    assert_eq!(decoded, List::from_ssz_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn stream_empty_list() {
+    let list = List::<u64, U1024>::from_ssz_reader(&[][..]).unwrap();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn stream_vector() {
+    let vector = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    let bytes = vector.as_ssz_bytes();
+    assert_eq!(
+        Vector::<u64, U16>::from_ssz_reader(&bytes[..]).unwrap(),
+        vector
+    );
+    assert!(Vector::<u64, U16>::from_ssz_reader(&bytes[..120]).is_err());
+}
+
+#[test]
+fn stream_errors() {
+    // Trailing partial item.
+    let bytes = List::<u64, U1024>::new(vec![1, 2]).unwrap().as_ssz_bytes();
+    assert_eq!(
+        List::<u64, U1024>::from_ssz_reader(&bytes[..12]),
+        Err(DecodeError::InvalidByteLength {
+            len: 4,
+            expected: 8
+        })
+    );
+
+    // Too many items.
+    let bytes = List::<u64, U1024>::new((0..9).collect())
+        .unwrap()
+        .as_ssz_bytes();
+    assert!(List::<u64, U8>::from_ssz_reader(&bytes[..]).is_err());
+
+    // Truncated offset table and decreasing offsets.
+    type Inner = List<u8, U16>;
+    assert!(List::<Inner, U8>::from_ssz_reader(&[8, 0, 0, 0][..]).is_err());
+    assert_eq!(
+        List::<Inner, U8>::from_ssz_reader(&[12, 0, 0, 0, 11, 0, 0, 0, 12, 0, 0, 0][..]),
+        Err(DecodeError::OffsetsAreDecreasing(11))
+    );
+}
+
+#[test]
+fn stream_huge_offsets() {
+    type Inner = List<u8, U16>;
+    type Huge = List<Inner, U1099511627776>;
+
+    // A first offset claiming 2^30 items is followed by nothing, and must fail without first
+    // reserving space for all of them.
+    let first_offset = (u32::MAX - 3).to_le_bytes();
+    assert_eq!(
+        Huge::from_ssz_reader(&first_offset[..]),
+        Err(DecodeError::InvalidByteLength {
+            len: 4,
+            expected: u32::MAX as usize - 3
+        })
+    );
+
+    // Likewise for an item claiming to be 4 GiB long, of which only one byte is present.
+    let mut bytes = vec![8, 0, 0, 0];
+    bytes.extend_from_slice(&(u32::MAX - 3).to_le_bytes());
+    bytes.push(1);
+    assert_eq!(
+        Huge::from_ssz_reader(&bytes[..]),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: u32::MAX as usize - 11
+        })
+    );
+}
+
+/// Writer which records the size of every write.
+#[derive(Default)]
+struct RecordingWriter {
+    bytes: Vec<u8>,
+    writes: Vec<usize>,
+}
+
+impl std::io::Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        self.writes.push(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_fixed_len_list() {
+    let mut list = List::<u64, U4096>::new((0..3000).collect()).unwrap();
+    list.push(3000).unwrap();
+    let mut writer = RecordingWriter::default();
+    list.ssz_append_to(&mut writer).unwrap();
+    assert_eq!(writer.bytes, list.as_ssz_bytes());
+    // The output is written in pieces rather than all at once.
+    assert!(writer.writes.len() > 1);
+    assert!(writer.writes.iter().all(|len| *len <= 8192 + 8));
+
+    let vector = Vector::<u8, U8>::new(vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    let mut bytes = vec![];
+    vector.ssz_append_to(&mut bytes).unwrap();
+    assert_eq!(bytes, vector.as_ssz_bytes());
+}
+
+#[test]
+fn write_variable_len_list() {
+    let list = List::<List<u8, U16>, U1024>::new(
+        (0..700)
+            .map(|i| List::new(vec![i as u8; i % 17]).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    let mut bytes = vec![];
+    list.ssz_append_to(&mut bytes).unwrap();
+    assert_eq!(bytes, list.as_ssz_bytes());
+
+    let mut empty = vec![];
+    List::<List<u8, U16>, U8>::empty()
+        .ssz_append_to(&mut empty)
+        .unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn ssz_chunks_fixed_len() {
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let chunks = list.ssz_chunks(0).unwrap().collect::<Vec<_>>();
+    // One chunk per packed leaf of 4 values.
+    assert_eq!(chunks.len(), 250);
+    assert!(chunks.iter().all(|chunk| chunk.len() == 32));
+    assert_eq!(chunks.concat(), list.as_ssz_bytes());
+
+    let chunks = list.ssz_chunks(1000).unwrap().collect::<Vec<_>>();
+    assert_eq!(chunks.len(), 8);
+    assert!(chunks[..7].iter().all(|chunk| chunk.len() == 1024));
+    assert_eq!(chunks.concat(), list.as_ssz_bytes());
+
+    let vector = Vector::<u8, U8>::new(vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    let chunks = vector.ssz_chunks(0).unwrap().collect::<Vec<_>>();
+    assert_eq!(chunks.concat(), vector.as_ssz_bytes());
+}
+
+#[test]
+fn ssz_chunks_variable_len() {
+    let list = List::<List<u8, U16>, U4096>::new(
+        (0..3000)
+            .map(|i| List::new(vec![i as u8; i % 17]).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    let chunks = list.ssz_chunks(0).unwrap().collect::<Vec<_>>();
+    // Three chunks of offsets followed by one per non-empty item.
+    let non_empty = (0..3000).filter(|i| i % 17 != 0).count();
+    assert_eq!(chunks.len(), 3 + non_empty);
+    assert_eq!(chunks[0].len(), 4096);
+    assert_eq!(chunks.concat(), list.as_ssz_bytes());
+
+    assert_eq!(
+        List::<List<u8, U16>, U8>::empty()
+            .ssz_chunks(0)
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn ssz_chunks_pending_updates() {
+    let mut list = List::<u64, U8>::new(vec![1, 2]).unwrap();
+    list.push(3).unwrap();
+    assert!(list.ssz_chunks(0).is_err());
+    list.apply_updates().unwrap();
+    assert_eq!(
+        list.ssz_chunks(0).unwrap().collect::<Vec<_>>().concat(),
+        list.as_ssz_bytes()
+    );
+}
@@ -0,0 +1,59 @@
+use crate::{Error, List, Summary};
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+use typenum::{U1024, U8};
+
+#[derive(TreeHash)]
+struct Full {
+    slot: u64,
+    roots: List<u64, U1024>,
+}
+
+#[derive(TreeHash)]
+struct Light {
+    slot: u64,
+    roots: Summary<u64, U1024>,
+}
+
+#[test]
+fn summary_matches_list() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    let summary = list.summary();
+    assert_eq!(summary.root(), list.tree_hash_root());
+    assert_eq!(summary.len(), 100);
+    assert_eq!(Summary::from(&list), summary);
+    assert_eq!(summary, list);
+    assert_eq!(list, summary);
+
+    // Pending updates are included.
+    list.push(100).unwrap();
+    assert_ne!(summary, list);
+    assert_eq!(
+        list.summary(),
+        Summary::new(list.tree_hash_root(), 101).unwrap()
+    );
+    list.apply_updates().unwrap();
+    assert_eq!(list.summary(), list);
+}
+
+#[test]
+fn summary_stands_in_for_list() {
+    let roots = List::<u64, U1024>::new((0..10).collect()).unwrap();
+    let light = Light {
+        slot: 5,
+        roots: roots.summary(),
+    };
+    let full = Full { slot: 5, roots };
+    assert_eq!(light.tree_hash_root(), full.tree_hash_root());
+}
+
+#[test]
+fn summary_too_long() {
+    assert_eq!(
+        Summary::<u64, U8>::new(Default::default(), 9),
+        Err(Error::ListFull { len: 9, max_len: 8 })
+    );
+    assert!(Summary::<u64, U8>::new(Default::default(), 0)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,60 @@
+use crate::{Error, List, Vector};
+use tree_hash::TreeHash;
+use typenum::{U16, U8};
+
+#[test]
+fn swap_list() {
+    let mut list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    list.swap(1, 8).unwrap();
+    assert_eq!(list.to_vec(), vec![0, 8, 2, 3, 4, 5, 6, 7, 1, 9]);
+    list.swap(3, 3).unwrap();
+
+    // Pending pushes can be swapped too.
+    list.push(10).unwrap();
+    list.swap(0, 10).unwrap();
+    let expected = vec![10, 8, 2, 3, 4, 5, 6, 7, 1, 9, 0];
+    assert_eq!(list.to_vec(), expected);
+    list.apply_updates().unwrap();
+    assert_eq!(list, List::new(expected).unwrap());
+
+    assert_eq!(
+        list.swap(2, 11),
+        Err(Error::OutOfBoundsUpdate { index: 11, len: 11 })
+    );
+    assert_eq!(list.get(2), Some(&2));
+}
+
+#[test]
+fn swap_vector() {
+    let mut vector = Vector::<u64, U8>::new((0..8).collect()).unwrap();
+    vector.swap(0, 7).unwrap();
+    assert_eq!(vector.to_vec(), vec![7, 1, 2, 3, 4, 5, 6, 0]);
+    assert!(vector.swap(8, 0).is_err());
+}
+
+#[test]
+fn swap_remove() {
+    let mut list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    assert_eq!(list.swap_remove(2), Ok(2));
+    assert_eq!(list.to_vec(), vec![0, 1, 9, 3, 4, 5, 6, 7, 8]);
+
+    // Removing the last element just shortens the list.
+    assert_eq!(list.swap_remove(8), Ok(8));
+    assert_eq!(list.to_vec(), vec![0, 1, 9, 3, 4, 5, 6, 7]);
+
+    // Pending updates are kept, including one to the element being moved.
+    *list.get_mut(7).unwrap() = 70;
+    list.push(100).unwrap();
+    assert_eq!(list.swap_remove(0), Ok(0));
+    assert_eq!(list.to_vec(), vec![100, 1, 9, 3, 4, 5, 6, 70]);
+    list.apply_updates().unwrap();
+    let expected = List::<u64, U16>::new(vec![100, 1, 9, 3, 4, 5, 6, 70]).unwrap();
+    assert_eq!(list.tree_hash_root(), expected.tree_hash_root());
+
+    assert_eq!(
+        list.swap_remove(8),
+        Err(Error::OutOfBoundsUpdate { index: 8, len: 8 })
+    );
+    let mut empty = List::<u64, U16>::empty();
+    assert!(empty.swap_remove(0).is_err());
+}
@@ -0,0 +1,68 @@
+use crate::testing::{
+    arb_len, arb_list, arb_list_pair, arb_vector, LengthDistribution, Params, Sharing, UpdateState,
+};
+use proptest::prelude::*;
+use typenum::{U1024, U16, U7};
+
+proptest! {
+    #[test]
+    fn boundary_lengths(len in arb_len(1024, LengthDistribution::Boundaries)) {
+        prop_assert!(len <= 2 || len == 1023 || [len - 1, len, len + 1].iter().any(|n| n.is_power_of_two()));
+    }
+
+    #[test]
+    fn bounded_lengths(len in arb_len(7, LengthDistribution::UpTo(100))) {
+        prop_assert!(len <= 7);
+    }
+
+    #[test]
+    fn applied_lists(list in arb_list::<u64, U1024, _>(any::<u64>(), Params {
+        lengths: LengthDistribution::UpTo(100),
+        updates: UpdateState::Applied,
+    })) {
+        prop_assert!(list.len() <= 100);
+        prop_assert!(!list.has_pending_updates());
+        list.validate().unwrap();
+    }
+
+    #[test]
+    fn pending_lists(list in arb_list::<u8, U7, _>(any::<u8>(), Params {
+        lengths: LengthDistribution::Full,
+        updates: UpdateState::Pending,
+    })) {
+        prop_assert_eq!(list.len(), 7);
+        let mut applied = list.clone();
+        applied.apply_updates().unwrap();
+        prop_assert_eq!(applied.to_vec(), list.to_vec());
+        applied.validate().unwrap();
+    }
+
+    #[test]
+    fn vectors(vector in arb_vector::<u64, U16, _>(any::<u64>(), UpdateState::Applied)) {
+        prop_assert!(!vector.has_pending_updates());
+        vector.validate().unwrap();
+    }
+
+    #[test]
+    fn unshared_pairs((base, other) in arb_list_pair::<u64, U16, _>(any::<u64>(), Params::default(), Sharing::Unshared)) {
+        prop_assert_eq!(base.len(), other.len());
+        prop_assert_eq!(other.mem_usage().shared_bytes(), 0);
+    }
+
+    #[test]
+    fn shared_pairs((base, other) in arb_list_pair::<u64, U16, _>(any::<u64>(), Params {
+        lengths: LengthDistribution::Full,
+        updates: UpdateState::Pending,
+    }, Sharing::Shared)) {
+        // The base tree is shared entirely, as the modifications are still pending.
+        let base_usage = base.mem_usage();
+        prop_assert_eq!(other.mem_usage().shared_bytes(), base_usage.bytes);
+    }
+}
+
+#[test]
+fn default_params() {
+    let params = Params::default();
+    assert_eq!(params.lengths, LengthDistribution::Uniform);
+    assert_eq!(params.updates, UpdateState::Mixed);
+}
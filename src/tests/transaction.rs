@@ -0,0 +1,56 @@
+use crate::{Error, List, Vector};
+use typenum::{U4, U8};
+
+#[test]
+fn commit_adds_to_pending_updates() {
+    let mut list = List::<u64, U8>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+
+    let mut tx = list.begin_transaction();
+    *tx.get_mut(0).unwrap() = 10;
+    *tx.get_mut(3).unwrap() = 40;
+    tx.push(5).unwrap();
+    assert_eq!(tx.len(), 5);
+    assert_eq!(tx.get(0), Some(&10));
+    assert_eq!(tx.get(1), Some(&2));
+    tx.commit();
+
+    assert_eq!(list.to_vec(), vec![10, 2, 3, 40, 5]);
+    list.apply_updates().unwrap();
+    assert_eq!(list, List::new(vec![10, 2, 3, 40, 5]).unwrap());
+}
+
+#[test]
+fn rollback_restores_pending_updates() {
+    let mut list = List::<u64, U4>::new(vec![1, 2]).unwrap();
+    *list.get_mut(0).unwrap() = 11;
+    let expected = list.clone();
+
+    let mut tx = list.begin_transaction();
+    *tx.get_mut(0).unwrap() = 100;
+    tx.push(3).unwrap();
+    tx.push(4).unwrap();
+    assert_eq!(tx.push(5), Err(Error::ListFull { len: 4, max_len: 4 }));
+    tx.rollback();
+
+    assert_eq!(list.to_vec(), vec![11, 2]);
+    assert_eq!(list, expected);
+
+    // Dropping the transaction also rolls it back.
+    {
+        let mut tx = list.begin_transaction();
+        tx.push(3).unwrap();
+    }
+    assert_eq!(list, expected);
+}
+
+#[test]
+fn vector_transaction() {
+    let mut vector = Vector::<u64, U4>::new(vec![1, 2, 3, 4]).unwrap();
+    let mut tx = vector.begin_transaction();
+    *tx.get_mut(2).unwrap() = 30;
+    assert_eq!(tx.get_mut(4), None);
+    assert_eq!(tx.push(5), Err(Error::PushNotSupported));
+    tx.commit();
+    assert_eq!(vector.to_vec(), vec![1, 2, 30, 4]);
+}
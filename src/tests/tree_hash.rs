@@ -0,0 +1,97 @@
+use crate::{List, Sha256Hasher, Tree, Vector};
+use ssz::Encode;
+use ssz_types::VariableList;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U4096, U8};
+
+#[test]
+fn tree_depth() {
+    let list = List::<Hash256, U1024>::new(vec![Hash256::zero(); 513]).unwrap();
+    assert_eq!(list.interface.backing.tree.depth(), 10);
+
+    let packed = List::<u64, U4096>::new(vec![]).unwrap();
+    assert_eq!(packed.interface.backing.tree.depth(), 10);
+}
+
+/// The threshold only affects scheduling, so the root must be the same for all thresholds.
+#[test]
+fn parallel_hash_threshold_agnostic() {
+    let vec = (1..=3000).collect::<Vec<u64>>();
+    let expected = VariableList::<u64, U4096>::new(vec.clone())
+        .unwrap()
+        .tree_hash_root();
+
+    for threshold in [0, 1, 4, 64] {
+        let list = List::<u64, U4096>::new(vec.clone()).unwrap();
+        assert_eq!(
+            list.tree_hash_root_with_threshold(threshold),
+            Ok(expected),
+            "threshold = {threshold}"
+        );
+    }
+
+    let vector = Vector::<u64, U4096>::new(vec![7; 4096]).unwrap();
+    let expected = Vector::<u64, U4096>::new(vec![7; 4096])
+        .unwrap()
+        .tree_hash_root();
+    assert_eq!(vector.tree_hash_root_with_threshold(0), Ok(expected));
+}
+
+#[test]
+fn unchanged_updates_keep_cached_hashes() {
+    let mut list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    list.tree_hash_root();
+    let original = list.clone();
+
+    // Touch every element, but only change one of them.
+    for i in 0..1000 {
+        let value = list.get_mut(i).unwrap();
+        if i == 600 {
+            *value += 1;
+        }
+    }
+    list.apply_updates().unwrap();
+
+    // Only the path to the modified leaf is new: the root, 7 internal nodes and the leaf.
+    let usage = list.mem_usage();
+    assert_eq!(usage.unique_nodes, 9);
+    assert_ne!(list.tree_hash_root(), original.tree_hash_root());
+
+    // Applying updates which change nothing keeps the cached root hash.
+    for i in 0..1000 {
+        list.get_mut(i).unwrap();
+    }
+    let expected = list.clone();
+    list.apply_updates().unwrap();
+    let Tree::Node { hash, .. } = &*list.interface.backing.tree else {
+        panic!("expected node");
+    };
+    assert!(!hash.read().is_zero());
+    assert_eq!(list, expected);
+}
+
+#[test]
+fn hash_and_encode_with_pending_updates() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    *list.get_mut(50).unwrap() = 0;
+    list.push(100).unwrap();
+    let root = list.tree_hash_root();
+    let bytes = list.as_ssz_bytes();
+    // Hashing doesn't apply the updates.
+    assert!(list.has_pending_updates());
+    assert_eq!(list.tree_hash_root_with::<Sha256Hasher>(), Ok(root));
+
+    let expected = VariableList::<u64, U1024>::new(list.to_vec()).unwrap();
+    assert_eq!(root, expected.tree_hash_root());
+    assert_eq!(bytes, expected.as_ssz_bytes());
+
+    list.apply_updates().unwrap();
+    assert_eq!(list.tree_hash_root(), root);
+    assert_eq!(list.as_ssz_bytes(), bytes);
+
+    let mut vector = Vector::<Hash256, U8>::default();
+    *vector.get_mut(3).unwrap() = Hash256::repeat_byte(3);
+    let root = vector.tree_hash_root();
+    vector.apply_updates().unwrap();
+    assert_eq!(vector.tree_hash_root(), root);
+}
@@ -0,0 +1,73 @@
+use crate::tests::proptest::Large;
+use crate::List;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{Unsigned, U16, U32};
+
+#[test]
+fn truncate_packed_matches_fresh_list() {
+    type N = U32;
+    let vec = (0..N::to_u64()).collect::<Vec<_>>();
+    let list = List::<u64, N>::new(vec.clone()).unwrap();
+
+    for len in 0..=N::to_usize() {
+        let mut truncated = list.clone();
+        truncated.truncate(len).unwrap();
+        let expected = List::<u64, N>::new(vec[..len].to_vec()).unwrap();
+
+        // Structural equality ensures that emptied subtrees were replaced by zero nodes.
+        assert_eq!(truncated, expected);
+        assert_eq!(truncated.tree_hash_root(), expected.tree_hash_root());
+    }
+}
+
+#[test]
+fn truncate_hash256_matches_fresh_list() {
+    type N = U16;
+    let vec = (0..N::to_u64())
+        .map(Hash256::from_low_u64_be)
+        .collect::<Vec<_>>();
+    let list = List::<Hash256, N>::new(vec.clone()).unwrap();
+
+    for len in 0..=N::to_usize() {
+        let mut truncated = list.clone();
+        truncated.truncate(len).unwrap();
+        let expected = List::<Hash256, N>::new(vec[..len].to_vec()).unwrap();
+        assert_eq!(truncated, expected);
+        assert_eq!(truncated.tree_hash_root(), expected.tree_hash_root());
+    }
+}
+
+#[test]
+fn truncate_pending_updates() {
+    let mut list = List::<u64, U32>::new(vec![1, 2, 3]).unwrap();
+    list.push(4).unwrap();
+    list.push(5).unwrap();
+    *list.get_mut(0).unwrap() = 10;
+
+    list.truncate(4).unwrap();
+    assert!(!list.has_pending_updates());
+    assert_eq!(list.to_vec(), vec![10, 2, 3, 4]);
+
+    // Longer lengths are a no-op.
+    list.truncate(10).unwrap();
+    assert_eq!(list.len(), 4);
+}
+
+#[test]
+fn pop_until_empty() {
+    let vec = (0..7u8)
+        .map(|i| Large {
+            a: i as u64,
+            b: i,
+            c: Hash256::repeat_byte(i),
+            d: List::empty(),
+        })
+        .collect::<Vec<_>>();
+    let mut list = List::<Large, U16>::new(vec.clone()).unwrap();
+
+    for expected in vec.into_iter().rev() {
+        assert_eq!(list.pop().unwrap(), Some(expected));
+    }
+    assert_eq!(list.pop().unwrap(), None);
+    assert_eq!(list, List::empty());
+}
@@ -0,0 +1,69 @@
+use crate::update_map::MaxMap;
+use crate::{AutoApply, BitmapUpdateMap, Error, List, VecUpdateMap, Vector};
+use tree_hash::TreeHash;
+use typenum::{U1024, U8};
+use vec_map::VecMap;
+
+#[test]
+fn update_many() {
+    let mut list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    let updates = (0..1000)
+        .step_by(37)
+        .rev()
+        .map(|i| (i, i as u64 * 2))
+        .collect::<Vec<_>>();
+    list.update_many(updates.iter().copied()).unwrap();
+
+    let mut expected = (0..1000).collect::<Vec<u64>>();
+    for &(index, value) in &updates {
+        expected[index] = value;
+    }
+    assert_eq!(list.to_vec(), expected);
+    list.apply_updates().unwrap();
+    assert_eq!(
+        list.tree_hash_root(),
+        List::<u64, U1024>::new(expected).unwrap().tree_hash_root()
+    );
+}
+
+#[test]
+fn update_many_duplicates_keep_last() {
+    let mut list = List::<u64, U8>::new(vec![0; 4]).unwrap();
+    list.update_many([(2, 1), (0, 5), (2, 3)]).unwrap();
+    assert_eq!(list.to_vec(), vec![5, 0, 3, 0]);
+}
+
+#[test]
+fn update_many_out_of_bounds() {
+    let mut list = List::<u64, U8>::new(vec![0; 4]).unwrap();
+    list.push(4).unwrap();
+    assert_eq!(
+        list.update_many([(0, 1), (5, 1)]),
+        Err(Error::OutOfBoundsUpdate { index: 5, len: 5 })
+    );
+    assert_eq!(list.to_vec(), vec![0, 0, 0, 0, 4]);
+
+    // Pending pushes can be updated.
+    list.update_many([(4, 40)]).unwrap();
+    assert_eq!(list.get(4), Some(&40));
+}
+
+#[test]
+fn update_many_update_maps() {
+    let updates = [(7, 70), (1, 10), (3, 30)];
+    let mut vec_map = List::<u64, U8, VecUpdateMap<u64>>::new((0..8).collect()).unwrap();
+    let mut bitmap = List::<u64, U8, BitmapUpdateMap<u64>>::new((0..8).collect()).unwrap();
+    vec_map.update_many(updates).unwrap();
+    bitmap.update_many(updates).unwrap();
+    assert_eq!(vec_map.to_vec(), vec![0, 10, 2, 30, 4, 5, 6, 70]);
+    assert_eq!(vec_map.to_vec(), bitmap.to_vec());
+}
+
+#[test]
+fn update_many_vector() {
+    let mut vector =
+        Vector::<u64, U8, AutoApply<MaxMap<VecMap<u64>>>>::new((0..8).collect()).unwrap();
+    vector.update_many([(0, 9), (7, 9)]).unwrap();
+    assert_eq!(vector.to_vec(), vec![9, 1, 2, 3, 4, 5, 6, 9]);
+    assert!(vector.update_many([(8, 0)]).is_err());
+}
@@ -0,0 +1,117 @@
+use crate::{BitmapUpdateMap, List, Sha256Hasher, UpdateMap, VecUpdateMap};
+use std::ops::ControlFlow;
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+type SortedList = List<u64, U1024, VecUpdateMap<u64>>;
+
+#[test]
+fn out_of_order_inserts() {
+    let mut map = VecUpdateMap::default();
+    for k in [5, 1, 9, 3, 7, 1, 10] {
+        map.insert(k, k * 10);
+    }
+    assert_eq!(map.insert(3, 33), Some(30));
+    assert_eq!(map.len(), 6);
+    assert_eq!(map.max_index(), Some(10));
+    assert_eq!(map.get(3), Some(&33));
+    assert_eq!(map.get(4), None);
+
+    let mut keys = vec![];
+    map.for_each_range::<_, ()>(2, 10, |k, _| {
+        keys.push(k);
+        ControlFlow::Continue(Ok(()))
+    })
+    .unwrap();
+    assert_eq!(keys, vec![3, 5, 7, 9]);
+}
+
+#[test]
+fn matches_default_update_map() {
+    let vec = (0..700).collect::<Vec<u64>>();
+    let mut list = List::<u64, U1024>::new(vec.clone()).unwrap();
+    let mut sorted = SortedList::new(vec).unwrap();
+
+    // A full ascending pass, then scattered updates, cow writes and pushes.
+    for i in 0..700 {
+        *list.get_mut(i).unwrap() += 1;
+        *sorted.get_mut(i).unwrap() += 1;
+    }
+    for i in (0..700).rev().step_by(13) {
+        *list.get_mut(i).unwrap() *= 3;
+        *sorted.get_mut(i).unwrap() *= 3;
+    }
+    for i in [699usize, 3, 400] {
+        let value = sorted.get_cow(i).unwrap();
+        *value.into_mut().unwrap() = 0;
+        *list.get_mut(i).unwrap() = 0;
+    }
+    for i in 0..50 {
+        list.push(i).unwrap();
+        sorted.push(i).unwrap();
+    }
+
+    assert_eq!(list.to_vec(), sorted.to_vec());
+    list.apply_updates().unwrap();
+    sorted.apply_updates().unwrap();
+    assert_eq!(list.to_vec(), sorted.to_vec());
+    assert_eq!(
+        list.tree_hash_root(),
+        sorted.tree_hash_root_with::<Sha256Hasher>().unwrap()
+    );
+}
+
+#[test]
+fn bitmap_range_queries() {
+    let mut map = BitmapUpdateMap::default();
+    for k in [0, 63, 64, 200, 5000, 70000] {
+        map.insert(k, k);
+    }
+    assert_eq!(map.max_index(), Some(70000));
+
+    let keys_in = |start, end| {
+        let mut keys = vec![];
+        map.for_each_range::<_, ()>(start, end, |k, _| {
+            keys.push(k);
+            ControlFlow::Continue(Ok(()))
+        })
+        .unwrap();
+        keys
+    };
+    assert_eq!(keys_in(0, usize::MAX), vec![0, 63, 64, 200, 5000, 70000]);
+    assert_eq!(keys_in(1, 64), vec![63]);
+    assert_eq!(keys_in(65, 5000), vec![200]);
+    assert_eq!(keys_in(5001, 70000), Vec::<usize>::new());
+    assert_eq!(keys_in(70001, 80000), Vec::<usize>::new());
+}
+
+#[test]
+fn bitmap_matches_default_update_map() {
+    let vec = (0..1000).collect::<Vec<u64>>();
+    let mut list = List::<u64, U1024>::new(vec.clone()).unwrap();
+    let mut bitmap = List::<u64, U1024, BitmapUpdateMap<u64>>::new(vec).unwrap();
+
+    for i in (0..1000).step_by(97) {
+        *list.get_mut(i).unwrap() = 0;
+        *bitmap.get_mut(i).unwrap() = 0;
+    }
+    // Reading through a cow without writing leaves a clean bit set, which must be ignored.
+    let _ = bitmap.get_cow(500).unwrap();
+    let value = bitmap.get_cow(501).unwrap();
+    *value.into_mut().unwrap() = 5;
+    *list.get_mut(501).unwrap() = 5;
+    for i in 0..24 {
+        list.push(i).unwrap();
+        bitmap.push(i).unwrap();
+    }
+
+    assert_eq!(list.to_vec(), bitmap.to_vec());
+    list.apply_updates().unwrap();
+    bitmap.apply_updates().unwrap();
+    assert_eq!(bitmap.len(), 1024);
+    assert_eq!(list.to_vec(), bitmap.to_vec());
+    assert_eq!(
+        list.tree_hash_root(),
+        bitmap.tree_hash_root_with::<Sha256Hasher>().unwrap()
+    );
+}
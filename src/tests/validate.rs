@@ -0,0 +1,139 @@
+use crate::utils::Length;
+use crate::{Arc, Error, List, PackedLeaf, Tree, Vector};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16, U8};
+
+fn hashes(n: u64) -> Vec<Hash256> {
+    (1..=n).map(Hash256::from_low_u64_be).collect()
+}
+
+#[test]
+fn valid_after_operations() {
+    let mut list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    list.validate().unwrap();
+
+    list.push(100).unwrap();
+    *list.get_mut(3).unwrap() = 1000;
+    list.apply_updates().unwrap();
+    list.validate().unwrap();
+
+    list.tree_hash_root();
+    list.validate().unwrap();
+
+    list.pop_front(13).unwrap();
+    list.validate().unwrap();
+    list.truncate(50).unwrap();
+    list.validate().unwrap();
+    list.pop().unwrap();
+    list.validate().unwrap();
+    list.try_extend(0..20).unwrap();
+    list.validate().unwrap();
+
+    List::<u64, U1024>::empty().validate().unwrap();
+    List::<u8, U1024>::repeat(7, 999)
+        .unwrap()
+        .validate()
+        .unwrap();
+
+    let list = List::<Hash256, U16>::new(hashes(11)).unwrap();
+    list.tree_hash_root();
+    list.validate().unwrap();
+
+    let vec = Vector::<u64, U16>::new((0..16).collect()).unwrap();
+    vec.tree_hash_root();
+    vec.validate().unwrap();
+    Vector::<Hash256, U8>::new(hashes(8))
+        .unwrap()
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn length_too_long() {
+    let list = List::<Hash256, U8>::new(hashes(4)).unwrap();
+    let bad = List::<Hash256, U8>::from_parts(list.interface.backing.tree.clone(), 3, Length(9));
+    assert_eq!(
+        bad.validate(),
+        Err(Error::InvalidTreeLength { len: 9, max_len: 8 })
+    );
+}
+
+#[test]
+fn zero_subtree_in_range() {
+    let list = List::<Hash256, U8>::new(hashes(4)).unwrap();
+    let bad = List::<Hash256, U8>::from_parts(list.interface.backing.tree.clone(), 3, Length(5));
+    assert_eq!(
+        bad.validate(),
+        Err(Error::InvalidZeroSubtree { index: 4, depth: 2 })
+    );
+}
+
+#[test]
+fn non_zero_padding() {
+    let list = List::<Hash256, U8>::new(hashes(4)).unwrap();
+    let bad = List::<Hash256, U8>::from_parts(list.interface.backing.tree.clone(), 3, Length(3));
+    assert_eq!(
+        bad.validate(),
+        Err(Error::NonZeroPadding { index: 3, depth: 0 })
+    );
+}
+
+#[test]
+fn wrong_depth() {
+    let tree = Tree::node(Tree::leaf(Hash256::zero()), Tree::zero(1), Hash256::zero());
+    assert_eq!(
+        tree.validate(1, 2, 0),
+        Err(Error::InvalidTreeDepth { index: 0, depth: 1 })
+    );
+
+    let tree = Tree::<Hash256>::node(Tree::zero(2), Tree::zero(1), Hash256::zero());
+    assert_eq!(
+        tree.validate(1, 2, 0),
+        Err(Error::InvalidTreeDepth { index: 0, depth: 1 })
+    );
+}
+
+#[test]
+fn wrong_packed_leaf_length() {
+    let leaf = Tree::PackedLeaf(PackedLeaf::repeat(1u64, 3));
+    let tree = Tree::node(Arc::new(leaf), Tree::zero(0), Hash256::zero());
+    assert_eq!(
+        tree.validate(2, 1, 2),
+        Err(Error::InvalidPackedLeafLength {
+            index: 0,
+            len: 3,
+            expected: 2
+        })
+    );
+}
+
+#[test]
+fn wrong_cached_hash() {
+    let values = hashes(2);
+    let tree = Tree::node(
+        Tree::leaf(values[0]),
+        Tree::leaf_with_hash(values[1], Hash256::repeat_byte(1)),
+        Hash256::zero(),
+    );
+    assert_eq!(
+        tree.validate(2, 1, 0),
+        Err(Error::InvalidCachedHash { index: 1, depth: 0 })
+    );
+
+    let tree = Tree::node(
+        Tree::leaf(values[0]),
+        Tree::leaf(values[1]),
+        Hash256::repeat_byte(1),
+    );
+    assert_eq!(
+        tree.validate(2, 1, 0),
+        Err(Error::InvalidCachedHash { index: 0, depth: 1 })
+    );
+}
+
+#[test]
+fn returns_root() {
+    let list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    let root = list.interface.backing.tree.validate(10, 2, 2).unwrap();
+    assert_eq!(root, list.interface.backing.tree.tree_hash());
+}
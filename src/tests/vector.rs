@@ -0,0 +1,88 @@
+use crate::{BitmapUpdateMap, Error, Vector};
+use std::convert::TryFrom;
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U3, U4};
+
+#[test]
+fn from_array() {
+    let vector = Vector::from([1u64, 2, 3, 4]);
+    assert_eq!(vector, Vector::<u64, U4>::new(vec![1, 2, 3, 4]).unwrap());
+    assert_eq!(vector.to_array(), [1, 2, 3, 4]);
+
+    let hashes = [
+        Hash256::repeat_byte(1),
+        Hash256::repeat_byte(2),
+        Hash256::zero(),
+    ];
+    let vector: Vector<Hash256, U3> = hashes.into();
+    assert_eq!(
+        vector.tree_hash_root(),
+        Vector::<Hash256, U3>::new(hashes.to_vec())
+            .unwrap()
+            .tree_hash_root()
+    );
+}
+
+#[test]
+fn to_array_includes_pending_updates() {
+    let mut vector = Vector::<u8, U4>::from([0; 4]);
+    *vector.get_mut(2).unwrap() = 7;
+    assert_eq!(vector.to_array(), [0, 0, 7, 0]);
+}
+
+#[test]
+fn try_from_vec() {
+    let vector = Vector::<u64, U4>::try_from(vec![5; 4]).unwrap();
+    assert_eq!(vector.to_vec(), vec![5; 4]);
+    assert_eq!(
+        Vector::<u64, U4>::try_from(vec![5; 3]),
+        Err(Error::WrongVectorLength {
+            len: 3,
+            expected: 4
+        })
+    );
+}
+
+#[test]
+fn try_from_vec_error() {
+    let result = Vector::<u64, U4>::try_from_vec(vec![1, 2, 3, 4, 5]);
+    assert_eq!(
+        result,
+        Err(Error::WrongVectorLength {
+            len: 5,
+            expected: 4
+        })
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "wrong vector length: expected 4, got 5"
+    );
+    assert_eq!(
+        Vector::<u64, U4>::try_from_vec(vec![1, 2, 3, 4]).unwrap(),
+        Vector::from([1, 2, 3, 4])
+    );
+}
+
+#[test]
+fn default_shares_subtrees() {
+    let vector = Vector::<u64, U1024>::default();
+    assert_eq!(vector.to_vec(), vec![0; 1024]);
+    assert_eq!(
+        vector.tree_hash_root(),
+        Vector::<u64, U1024>::new(vec![0; 1024])
+            .unwrap()
+            .tree_hash_root()
+    );
+    // One node per level, rather than one per leaf.
+    assert!(vector.mem_usage().nodes < 16);
+
+    let bitmap = Vector::<u64, U4, BitmapUpdateMap<u64>>::default();
+    assert_eq!(bitmap.to_vec(), vec![0; 4]);
+}
+
+#[test]
+fn into_vec() {
+    let mut vector = Vector::<u64, U4>::from([1, 2, 3, 4]);
+    *vector.get_mut(1).unwrap() = 20;
+    assert_eq!(Vec::from(vector), vec![1, 20, 3, 4]);
+}
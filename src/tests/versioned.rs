@@ -0,0 +1,68 @@
+use crate::{Error, List, Pruned, Versioned};
+use typenum::U1024;
+
+#[test]
+fn snapshot_and_get() {
+    let mut list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let mut versions = Versioned::new();
+    for i in 0..5 {
+        assert_eq!(versions.snapshot(&list), Ok(i));
+        *list.get_mut(i * 100).unwrap() = u64::MAX;
+        list.apply_updates().unwrap();
+    }
+    assert_eq!(versions.len(), 5);
+    assert_eq!(versions.next_version(), 5);
+    for i in 0..5 {
+        let version = versions.get(i).unwrap();
+        assert_eq!(version.get(i * 100), Some(&(i as u64 * 100)));
+        assert_eq!(version.get((i + 1) * 100), Some(&(i as u64 * 100 + 100)));
+    }
+    assert!(versions.get(5).is_none());
+    assert_eq!(versions.latest(), versions.get(4));
+
+    // Versions can't include pending updates.
+    list.push(1).unwrap_err();
+    *list.get_mut(0).unwrap() = 1;
+    assert_eq!(versions.snapshot(&list), Err(Error::SnapshotPendingUpdates));
+    assert_eq!(versions.len(), 5);
+}
+
+#[test]
+fn prune_reports_freed_nodes() {
+    let depth = List::<u64, U1024>::depth();
+    let mut list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    let mut versions = Versioned::new();
+    let mut lists = vec![];
+    for i in 0..4 {
+        versions.snapshot(&list).unwrap();
+        lists.push(list.clone());
+        *list.get_mut(i * 256).unwrap() = u64::MAX;
+        list.apply_updates().unwrap();
+    }
+
+    // The first version differs from the second by a single leaf, so only its path is freed.
+    let pruned = versions.prune(1);
+    assert_eq!(pruned.versions, 1);
+    assert_eq!(pruned.nodes, depth + 1);
+    assert_eq!(versions.first_version(), 1);
+    assert!(versions.get(0).is_none());
+    assert!(versions.get(1).is_some());
+
+    // Pruning already pruned versions frees nothing.
+    assert_eq!(versions.prune(1), Pruned::default());
+
+    let all = List::count_unique_nodes(&[&lists[1], &lists[2], &lists[3]]);
+    let retained = List::count_unique_nodes(&[&lists[3]]);
+    let pruned = versions.prune(3);
+    assert_eq!(pruned.versions, 2);
+    assert_eq!(pruned.nodes, all.total_nodes() - retained.total_nodes());
+    assert_eq!(pruned.bytes, all.total_bytes() - retained.total_bytes());
+
+    // Pruning beyond the latest version drops everything.
+    let pruned = versions.prune(10);
+    assert_eq!(pruned.versions, 1);
+    assert_eq!(pruned.nodes, lists[3].mem_usage().nodes);
+    assert!(versions.is_empty());
+    assert_eq!(versions.first_version(), 4);
+    assert_eq!(versions.snapshot(&list), Ok(4));
+}
@@ -0,0 +1,104 @@
+use crate::{Error, List, Vector};
+use tree_hash::Hash256;
+use typenum::{U1024, U64};
+
+fn check_view<T>(values: &[T], view: &crate::SubtreeView<T>)
+where
+    T: crate::Value + std::fmt::Debug,
+{
+    assert_eq!(view.len(), values.len());
+    assert_eq!(view.to_vec(), values);
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(view.get(i), Some(value));
+    }
+    assert_eq!(view.get(values.len()), None);
+}
+
+#[test]
+fn packed_subtree_ranges() {
+    let vec = (0..1000u64).collect::<Vec<_>>();
+    let list = List::<u64, U1024>::new(vec.clone()).unwrap();
+
+    for (start, end) in [
+        (0, 0),
+        (0, 1000),
+        (3, 7),
+        (4, 8),
+        (256, 512),
+        (500, 1000),
+        (999, 1000),
+    ] {
+        let view = list.subtree(start..end).unwrap();
+        check_view(&vec[start..end], &view);
+    }
+}
+
+#[test]
+fn unpacked_subtree_ranges() {
+    let vec = (0..50).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let vector = Vector::<Hash256, U64>::new(
+        vec.iter()
+            .copied()
+            .chain(std::iter::repeat(Hash256::zero()))
+            .take(64)
+            .collect(),
+    )
+    .unwrap();
+
+    for (start, end) in [(0, 64), (1, 2), (16, 32), (17, 49), (60, 64)] {
+        let view = vector.subtree(start..end).unwrap();
+        check_view(&vector.to_vec()[start..end], &view);
+    }
+}
+
+#[test]
+fn nested_subtree_and_iter_from() {
+    let vec = (0..300u64).collect::<Vec<_>>();
+    let list = List::<u64, U1024>::new(vec.clone()).unwrap();
+    let view = list.subtree(100..250).unwrap();
+    let nested = view.subtree(10..90).unwrap();
+    check_view(&vec[110..190], &nested);
+
+    assert_eq!(
+        view.iter_from(140).unwrap().copied().collect::<Vec<_>>(),
+        vec[240..250]
+    );
+    assert_eq!(view.iter_from(150).unwrap().count(), 0);
+    assert_eq!(
+        view.iter_from(151).unwrap_err(),
+        Error::OutOfBoundsIterFrom {
+            index: 151,
+            len: 150
+        }
+    );
+    assert_eq!(
+        view.subtree(100..151).unwrap_err(),
+        Error::OutOfBoundsSubtree {
+            start: 100,
+            end: 151,
+            len: 150
+        }
+    );
+}
+
+#[test]
+fn subtree_errors() {
+    let mut list = List::<u64, U1024>::new(vec![1, 2, 3]).unwrap();
+    assert_eq!(
+        list.subtree(2..4).unwrap_err(),
+        Error::OutOfBoundsSubtree {
+            start: 2,
+            end: 4,
+            len: 3
+        }
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 2..1;
+    assert!(list.subtree(reversed).is_err());
+
+    list.push(4).unwrap();
+    assert_eq!(
+        list.subtree(0..1).unwrap_err(),
+        Error::SubtreePendingUpdates
+    );
+}
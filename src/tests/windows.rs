@@ -0,0 +1,101 @@
+use crate::{List, Vector};
+use tree_hash::Hash256;
+use typenum::{U1024, U16, U64};
+
+#[test]
+fn windows() {
+    let list = List::<u64, U16>::new((0..10).collect()).unwrap();
+    let windows = list.iter_windows(3);
+    assert_eq!(windows.len(), 8);
+    let windows = windows
+        .map(|window| window.into_iter().copied().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let expected = (0..10).collect::<Vec<u64>>();
+    assert_eq!(
+        windows,
+        expected.windows(3).map(<[_]>::to_vec).collect::<Vec<_>>()
+    );
+
+    assert_eq!(list.iter_windows(10).count(), 1);
+    assert_eq!(list.iter_windows(11).len(), 0);
+    assert_eq!(list.iter_windows(11).next(), None);
+    assert_eq!(List::<u64, U16>::empty().iter_windows(1).next(), None);
+}
+
+#[test]
+fn windows_pending_updates() {
+    let mut list = List::<u8, U64>::new((0..40).collect()).unwrap();
+    list.push(40).unwrap();
+    *list.get_mut(31).unwrap() = 100;
+    let expected = list.to_vec();
+    assert!(list
+        .iter_windows(4)
+        .map(|window| window.into_iter().copied().collect::<Vec<_>>())
+        .eq(expected.windows(4).map(<[_]>::to_vec)));
+}
+
+#[test]
+#[should_panic(expected = "window size must be non-zero")]
+fn windows_zero() {
+    List::<u64, U16>::empty().iter_windows(0);
+}
+
+#[test]
+fn step_by() {
+    let values = (0..1000u64).collect::<Vec<_>>();
+    let list = List::<u64, U1024>::new(values.clone()).unwrap();
+    for (start, step) in [
+        (0, 1),
+        (0, 3),
+        (5, 4),
+        (7, 64),
+        (999, 2),
+        (1000, 5),
+        (1, 1000),
+    ] {
+        let strided = list.iter_step_by_from(start, step).unwrap();
+        assert!(
+            strided.eq(values[start..].iter().step_by(step)),
+            "start {start}, step {step}"
+        );
+    }
+    assert!(list.iter_step_by_from(1001, 1).is_err());
+
+    let hashes = (0..64).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+    let vector = Vector::<Hash256, U64>::new(hashes.clone()).unwrap();
+    assert!(vector
+        .iter_step_by_from(3, 5)
+        .unwrap()
+        .eq(hashes[3..].iter().step_by(5)));
+}
+
+#[test]
+fn step_by_pending_updates() {
+    let mut list = List::<u16, U1024>::new((0..100).collect()).unwrap();
+    for i in 100..120 {
+        list.push(i).unwrap();
+    }
+    *list.get_mut(50).unwrap() = 5000;
+    let expected = list.to_vec();
+    for step in [1, 2, 7, 16, 50, 119, 200] {
+        assert!(list
+            .iter_step_by_from(2, step)
+            .unwrap()
+            .eq(expected[2..].iter().step_by(step)));
+    }
+}
+
+#[test]
+fn nth_with_both_ends() {
+    let list = List::<u64, U1024>::new((0..100).collect()).unwrap();
+    let mut iter = list.iter();
+    assert_eq!(iter.next_back(), Some(&99));
+    assert_eq!(iter.nth(10), Some(&10));
+    assert_eq!(iter.nth(87), Some(&98));
+    assert_eq!(iter.next(), None);
+
+    let mut iter = list.iter();
+    assert_eq!(iter.nth(1000), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
@@ -0,0 +1,132 @@
+use crate::{Error, List, ListDiff};
+use tree_hash::{Hash256, TreeHash};
+use typenum::{U1024, U16};
+
+#[test]
+fn zip_skips_shared_subtrees() {
+    let base = List::<u64, U1024>::new((0..700).collect()).unwrap();
+    let mut new = base.clone();
+    for i in [3, 5, 600] {
+        *new.get_mut(i).unwrap() += 10;
+    }
+    new.apply_updates().unwrap();
+
+    // Packed leaves are yielded in full, 4 `u64`s at a time.
+    let pairs = base
+        .iter_zip(&new)
+        .unwrap()
+        .map(|(i, a, b)| (i, *a, *b))
+        .collect::<Vec<_>>();
+    let indices = pairs.iter().map(|(i, _, _)| *i).collect::<Vec<_>>();
+    assert_eq!(indices, vec![0, 1, 2, 3, 4, 5, 6, 7, 600, 601, 602, 603]);
+    assert!(pairs.iter().all(|(i, a, _)| *a == *i as u64));
+    assert_eq!(pairs[3], (3, 3, 13));
+
+    assert_eq!(base.iter_zip(&base.clone()).unwrap().count(), 0);
+}
+
+#[test]
+fn zip_uses_cached_hashes() {
+    let values = (0..10).map(Hash256::repeat_byte).collect::<Vec<_>>();
+    let base = List::<Hash256, U16>::new(values.clone()).unwrap();
+    let mut unshared = List::<Hash256, U16>::new(values).unwrap();
+    *unshared.get_mut(7).unwrap() = Hash256::zero();
+    unshared.apply_updates().unwrap();
+
+    // Without hashes, nothing is known to be equal.
+    assert_eq!(base.iter_zip(&unshared).unwrap().count(), 10);
+
+    base.tree_hash_root();
+    unshared.tree_hash_root();
+    let indices = base
+        .iter_zip(&unshared)
+        .unwrap()
+        .map(|(i, _, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, vec![7]);
+}
+
+#[test]
+fn for_each_zip_mut_deltas() {
+    let old = List::<u64, U1024>::new(vec![100; 500]).unwrap();
+    let mut new = old.clone();
+    *new.get_mut(10).unwrap() = 150;
+    *new.get_mut(499).unwrap() = 40;
+    new.apply_updates().unwrap();
+
+    // Replace the elements in the changed leaves by the difference from `old`.
+    let mut deltas = new.clone();
+    let mut visited = vec![];
+    deltas
+        .for_each_zip_mut(&old, |i, value, old_value| {
+            visited.push(i);
+            *value = value.abs_diff(*old_value);
+        })
+        .unwrap();
+    assert_eq!(visited, (8..12).chain(496..500).collect::<Vec<_>>());
+    assert!(deltas.has_pending_updates());
+    deltas.apply_updates().unwrap();
+
+    let expected = (0..500)
+        .map(|i| match i {
+            10 => 50,
+            499 => 60,
+            8..=11 | 496..=498 => 0,
+            _ => 100,
+        })
+        .collect::<Vec<u64>>();
+    assert_eq!(deltas.to_vec(), expected);
+}
+
+#[test]
+fn changed_elements() {
+    let base = List::<u64, U1024>::new((0..300).collect()).unwrap();
+    let mut new = base.clone();
+    // Rewriting an element with its old value is not a change.
+    *new.get_mut(1).unwrap() = 1;
+    *new.get_mut(2).unwrap() = 0;
+    *new.get_mut(257).unwrap() = 0;
+    new.push(7).unwrap();
+    new.push(8).unwrap();
+    new.apply_updates().unwrap();
+
+    let changed = new
+        .iter_changed(&base)
+        .unwrap()
+        .map(|(i, value)| (i, *value))
+        .collect::<Vec<_>>();
+    assert_eq!(changed, vec![(2, 0), (257, 0), (300, 7), (301, 8)]);
+    assert_eq!(
+        changed,
+        ListDiff::compute(&base, &new).unwrap().updates().to_vec()
+    );
+
+    // Elements removed from the base are not reported.
+    assert_eq!(base.iter_changed(&new).unwrap().count(), 2);
+    let mut shorter = base.clone();
+    shorter.truncate(100).unwrap();
+    assert_eq!(shorter.iter_changed(&base).unwrap().count(), 0);
+    assert_eq!(base.iter_changed(&shorter).unwrap().count(), 200);
+}
+
+#[test]
+fn zip_errors() {
+    let mut list = List::<u64, U16>::new(vec![1, 2, 3]).unwrap();
+    let shorter = List::<u64, U16>::new(vec![1, 2]).unwrap();
+    assert_eq!(
+        list.iter_zip(&shorter).unwrap_err(),
+        Error::ZipLengthMismatch {
+            len: 3,
+            other_len: 2
+        }
+    );
+    list.push(4).unwrap();
+    assert_eq!(
+        list.for_each_zip_mut(&shorter, |_, _, _| ()),
+        Err(Error::ZipPendingUpdates)
+    );
+    assert_eq!(
+        shorter.iter_changed(&list).unwrap_err(),
+        Error::ZipPendingUpdates
+    );
+}
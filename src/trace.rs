@@ -0,0 +1,110 @@
+//! Optional `tracing` spans for the expensive operations: applying updates, hashing whole trees,
+//! SSZ decoding and rebasing.
+//!
+//! Each span records the number of `elements` involved where it is known, and the number of tree
+//! nodes created or modified while it was open as `nodes_rewritten`. Without the `tracing` feature
+//! all of this compiles away.
+#[cfg(feature = "tracing")]
+use std::cell::Cell;
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    static NODES_REWRITTEN: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "tracing")]
+fn nodes_rewritten() -> usize {
+    NODES_REWRITTEN.with(Cell::get)
+}
+
+/// Count a node created or modified on this thread, towards `nodes_rewritten` of open spans.
+#[inline]
+pub(crate) fn node_rewritten() {
+    #[cfg(feature = "tracing")]
+    NODES_REWRITTEN.with(|count| count.set(count.get().wrapping_add(1)));
+}
+
+/// Run `a` and `b` with `rayon::join`, counting the nodes rewritten by both on this thread.
+///
+/// The closures may be run on other threads of the pool, whose counts would otherwise be lost.
+#[cfg(all(feature = "rayon", not(milhouse_rc)))]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "tracing")]
+    {
+        // Each closure returns the count of the thread it ran on, which is then added to the
+        // count of this thread as it was before the join.
+        fn counted<R>(f: impl FnOnce() -> R) -> (R, usize) {
+            let start = nodes_rewritten();
+            let result = f();
+            (result, nodes_rewritten().wrapping_sub(start))
+        }
+        let before = nodes_rewritten();
+        let ((ra, count_a), (rb, count_b)) = rayon::join(|| counted(a), || counted(b));
+        NODES_REWRITTEN.with(|count| count.set(before.wrapping_add(count_a).wrapping_add(count_b)));
+        (ra, rb)
+    }
+    #[cfg(not(feature = "tracing"))]
+    rayon::join(a, b)
+}
+
+/// An entered span for one operation, which records `nodes_rewritten` when dropped.
+pub(crate) struct OpSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    start: usize,
+}
+
+impl OpSpan {
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter(span: tracing::Span) -> Self {
+        Self {
+            span: span.entered(),
+            start: nodes_rewritten(),
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn enter() -> Self {
+        Self {}
+    }
+
+    /// Record the number of elements involved in the operation.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_elements(&self, elements: usize) {
+        #[cfg(feature = "tracing")]
+        self.span.record("elements", elements);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for OpSpan {
+    fn drop(&mut self) {
+        let rewritten = nodes_rewritten().wrapping_sub(self.start);
+        self.span.record("nodes_rewritten", rewritten);
+    }
+}
+
+/// Enter a `DEBUG` span named `$name`, with any extra fields given, returning an `OpSpan`.
+macro_rules! op_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = $crate::trace::OpSpan::enter(tracing::debug_span!(
+            $name,
+            $($field = $value,)*
+            elements = tracing::field::Empty,
+            nodes_rewritten = tracing::field::Empty,
+        ));
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::trace::OpSpan::enter();
+        span
+    }};
+}
+
+pub(crate) use op_span;
@@ -0,0 +1,78 @@
+use crate::interface::{Interface, MutList};
+use crate::utils::{updated_length, Length};
+use crate::{Error, UpdateMap, Value};
+use std::convert::Infallible;
+use std::ops::ControlFlow;
+
+/// Batch of modifications to a list or vector which can be committed or rolled back as a whole.
+///
+/// Modifications made through the transaction are buffered separately from the pending updates
+/// of the underlying list, so rolling back is just a matter of discarding them. Dropping a
+/// transaction without committing it also rolls it back.
+#[derive(Debug)]
+pub struct Transaction<'a, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    interface: &'a mut Interface<T, B, U>,
+    updates: U,
+}
+
+impl<'a, T, B, U> Transaction<'a, T, B, U>
+where
+    T: Value,
+    B: MutList<T>,
+    U: UpdateMap<T>,
+{
+    pub(crate) fn new(interface: &'a mut Interface<T, B, U>) -> Self {
+        Self {
+            interface,
+            updates: U::default(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.updates
+            .get(index)
+            .or_else(|| self.interface.get(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let interface = &*self.interface;
+        self.updates
+            .get_mut_with(index, |index| interface.get(index).cloned())
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        let index = self.len();
+        self.interface.backing.validate_push(index)?;
+        self.updates.insert(index, value);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        updated_length(Length(self.interface.len()), &self.updates).as_usize()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add the modifications made by this transaction to the pending updates of the list.
+    pub fn commit(self) {
+        // Every modified index is less than the length, including those of pushed values.
+        let len = self.len();
+        let pending = &mut self.interface.updates;
+        let _ = self
+            .updates
+            .for_each_range::<_, Infallible>(0, len, |index, value| {
+                pending.insert(index, value.clone());
+                ControlFlow::Continue(Ok(()))
+            });
+    }
+
+    /// Discard the modifications made by this transaction.
+    pub fn rollback(self) {}
+}
@@ -1,33 +1,47 @@
+use crate::hasher::{MerkleHasher, Sha256Hasher};
+use crate::metrics;
+use crate::trace::{self, op_span};
 use crate::utils::{arb_arc, arb_rwlock, opt_hash, opt_packing_depth, opt_packing_factor, Length};
-use crate::{Arc, Error, Leaf, PackedLeaf, UpdateMap, Value};
-use arbitrary::Arbitrary;
+use crate::{Arc, Error, Leaf, MaybeSync, PackedLeaf, UpdateMap, Value};
+use arbitrary::{Arbitrary, Unstructured};
 use derivative::Derivative;
-use ethereum_hashing::{hash32_concat, ZERO_HASHES};
+use ethereum_hashing::ZERO_HASHES;
 use parking_lot::RwLock;
-use std::collections::BTreeMap;
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::ControlFlow;
 use tree_hash::Hash256;
 
-#[derive(Debug, Derivative, Arbitrary)]
-#[derivative(PartialEq, Hash)]
+/// Node of a tree.
+///
+/// Nodes should be created with the constructors (`Tree::node`, `Tree::leaf`, etc.) rather than
+/// from the variants directly, so that they are counted when the `node_count` feature is enabled.
+#[derive(Debug, Derivative)]
+#[derivative(Hash)]
 pub enum Tree<T: Value> {
     Leaf(Leaf<T>),
     PackedLeaf(PackedLeaf<T>),
     Node {
-        #[derivative(PartialEq = "ignore", Hash = "ignore")]
-        #[arbitrary(with = arb_rwlock)]
+        /// Cached hash of the node, or zero if it hasn't been computed since the node was created.
+        ///
+        /// Hashes are filled in on demand by `tree_hash`, so trees which are never hashed never
+        /// compute any. A lock rather than a `OnceLock` is used so that the cache can be reset
+        /// through a shared reference.
+        #[derivative(Hash = "ignore")]
         hash: RwLock<Hash256>,
-        #[arbitrary(with = arb_arc)]
         left: Arc<Self>,
-        #[arbitrary(with = arb_arc)]
         right: Arc<Self>,
     },
     Zero(usize),
 }
 
+/// Default depth at or below which subtrees are hashed sequentially, see
+/// `Tree::tree_hash_with_threshold`.
+pub const DEFAULT_PARALLEL_HASH_THRESHOLD: usize = 8;
+
 impl<T: Value> Clone for Tree<T> {
     fn clone(&self) -> Self {
-        match self {
+        let tree = match self {
             Self::Node { hash, left, right } => Self::Node {
                 hash: RwLock::new(*hash.read()),
                 left: left.clone(),
@@ -36,6 +50,63 @@ impl<T: Value> Clone for Tree<T> {
             Self::Leaf(leaf) => Self::Leaf(leaf.clone()),
             Self::PackedLeaf(leaf) => Self::PackedLeaf(leaf.clone()),
             Self::Zero(depth) => Self::Zero(*depth),
+        };
+        tree.counted()
+    }
+}
+
+#[cfg(feature = "node_count")]
+impl<T: Value> Drop for Tree<T> {
+    fn drop(&mut self) {
+        crate::node_count::node_dropped();
+    }
+}
+
+impl<'a, T: Value + Arbitrary<'a>> Arbitrary<'a> for Tree<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let tree = match u.int_in_range(0..=3)? {
+            0 => Self::Leaf(Leaf::arbitrary(u)?),
+            1 => Self::PackedLeaf(PackedLeaf::arbitrary(u)?),
+            2 => Self::Node {
+                hash: arb_rwlock(u)?,
+                left: arb_arc(u)?,
+                right: arb_arc(u)?,
+            },
+            _ => Self::Zero(usize::arbitrary(u)?),
+        };
+        Ok(tree.counted())
+    }
+}
+
+impl<T: Value> PartialEq for Tree<T> {
+    /// Compare two trees by value, ignoring cached hashes.
+    ///
+    /// Subtrees which are shared, or which both have cached hashes, are compared without being
+    /// traversed.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Leaf(l1), Self::Leaf(l2)) => l1 == l2,
+            (Self::PackedLeaf(l1), Self::PackedLeaf(l2)) => l1 == l2,
+            (
+                Self::Node {
+                    hash: h1,
+                    left: l1,
+                    right: r1,
+                },
+                Self::Node {
+                    hash: h2,
+                    left: l2,
+                    right: r2,
+                },
+            ) => {
+                let (h1, h2) = (*h1.read(), *h2.read());
+                if !h1.is_zero() && !h2.is_zero() {
+                    return h1 == h2;
+                }
+                (Arc::ptr_eq(l1, l2) || l1 == l2) && (Arc::ptr_eq(r1, r2) || r1 == r2)
+            }
+            (Self::Zero(d1), Self::Zero(d2)) => d1 == d2,
+            _ => false,
         }
     }
 }
@@ -46,39 +117,58 @@ impl<T: Value> Tree<T> {
     }
 
     pub fn node(left: Arc<Self>, right: Arc<Self>, hash: Hash256) -> Arc<Self> {
-        Arc::new(Self::Node {
-            hash: RwLock::new(hash),
-            left,
-            right,
-        })
+        Arc::new(Self::node_with_hash_unboxed(left, right, hash))
     }
 
     pub fn zero(depth: usize) -> Arc<Self> {
-        Arc::new(Self::Zero(depth))
+        Arc::new(Self::zero_unboxed(depth))
     }
 
     pub fn leaf(value: T) -> Arc<Self> {
-        Arc::new(Self::Leaf(Leaf::new(value)))
+        Arc::new(Self::leaf_unboxed(value))
     }
 
     pub fn leaf_with_hash(value: T, hash: Hash256) -> Arc<Self> {
-        Arc::new(Self::Leaf(Leaf::with_hash(value, hash)))
+        Arc::new(Self::Leaf(Leaf::with_hash(value, hash)).counted())
+    }
+
+    pub fn packed_leaf(leaf: PackedLeaf<T>) -> Arc<Self> {
+        Arc::new(Self::packed_leaf_unboxed(leaf))
     }
 
     pub fn node_unboxed(left: Arc<Self>, right: Arc<Self>) -> Self {
+        Self::node_with_hash_unboxed(left, right, Hash256::zero())
+    }
+
+    fn node_with_hash_unboxed(left: Arc<Self>, right: Arc<Self>, hash: Hash256) -> Self {
         Self::Node {
-            hash: RwLock::new(Hash256::zero()),
+            hash: RwLock::new(hash),
             left,
             right,
         }
+        .counted()
     }
 
     pub fn zero_unboxed(depth: usize) -> Self {
-        Self::Zero(depth)
+        Self::Zero(depth).counted()
     }
 
     pub fn leaf_unboxed(value: T) -> Self {
-        Self::Leaf(Leaf::new(value))
+        Self::Leaf(Leaf::new(value)).counted()
+    }
+
+    pub fn packed_leaf_unboxed(leaf: PackedLeaf<T>) -> Self {
+        Self::PackedLeaf(leaf).counted()
+    }
+
+    /// Record the creation of a node, see `node_count::live_nodes`.
+    #[inline]
+    fn counted(self) -> Self {
+        #[cfg(feature = "node_count")]
+        crate::node_count::node_created();
+        metrics::record(|m| m.nodes_allocated(1));
+        trace::node_rewritten();
+        self
     }
 
     pub fn get_recursive(&self, index: usize, depth: usize, packing_depth: usize) -> Option<&T> {
@@ -102,6 +192,141 @@ impl<T: Value> Tree<T> {
         }
     }
 
+    /// Return the values at each of `indices`, in the same order.
+    ///
+    /// The indices are sorted first, so that each node is visited once no matter how many of
+    /// them it contains, and lookups in the same leaf share the whole path from the root.
+    pub fn get_many(
+        &self,
+        indices: &[usize],
+        depth: usize,
+        packing_depth: usize,
+    ) -> Vec<Option<&T>> {
+        let mut order = (0..indices.len())
+            .filter(|&i| {
+                indices[i]
+                    .checked_shr((depth + packing_depth) as u32)
+                    .unwrap_or(0)
+                    == 0
+            })
+            .collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&i| indices[i]);
+        let mut values = vec![None; indices.len()];
+        self.get_many_recursive(indices, &order, depth, packing_depth, &mut values);
+        values
+    }
+
+    fn get_many_recursive<'a>(
+        &'a self,
+        indices: &[usize],
+        order: &[usize],
+        depth: usize,
+        packing_depth: usize,
+        values: &mut [Option<&'a T>],
+    ) {
+        if order.is_empty() {
+            return;
+        }
+        match self {
+            Self::Leaf(Leaf { value, .. }) if depth == 0 => {
+                for &i in order {
+                    values[i] = Some(value);
+                }
+            }
+            Self::PackedLeaf(PackedLeaf { values: leaf, .. }) if depth == 0 => {
+                for &i in order {
+                    values[i] = leaf.get(indices[i] % T::tree_hash_packing_factor());
+                }
+            }
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_depth = depth - 1;
+                let split = order
+                    .partition_point(|&i| (indices[i] >> (new_depth + packing_depth)) & 1 == 0);
+                left.get_many_recursive(indices, &order[..split], new_depth, packing_depth, values);
+                right.get_many_recursive(
+                    indices,
+                    &order[split..],
+                    new_depth,
+                    packing_depth,
+                    values,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Return the values of the leaf containing `index`, and the index of its first value.
+    pub fn get_leaf_recursive(
+        &self,
+        index: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) -> Option<(usize, &[T])> {
+        match self {
+            Self::Leaf(Leaf { value, .. }) if depth == 0 => {
+                Some((index, std::slice::from_ref(&**value)))
+            }
+            Self::PackedLeaf(PackedLeaf { values, .. }) if depth == 0 => {
+                Some((index >> packing_depth << packing_depth, values))
+            }
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_depth = depth - 1;
+                if (index >> (new_depth + packing_depth)) & 1 == 0 {
+                    left.get_leaf_recursive(index, new_depth, packing_depth)
+                } else {
+                    right.get_leaf_recursive(index, new_depth, packing_depth)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the index of the first of the `len` elements for which `pred` is false.
+    ///
+    /// The elements must be partitioned by `pred`, as for `slice::partition_point`. Rather than
+    /// probing from the root each time, the search descends the tree once, comparing against the
+    /// first element of each right subtree and bisecting within packed leaves.
+    pub fn partition_point<P>(
+        &self,
+        len: usize,
+        depth: usize,
+        packing_depth: usize,
+        mut pred: P,
+    ) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut node = self;
+        let mut depth = depth;
+        let mut offset = 0;
+        loop {
+            match node {
+                Self::Leaf(Leaf { value, .. }) if offset < len => {
+                    return offset + pred(value) as usize;
+                }
+                Self::PackedLeaf(PackedLeaf { values, .. }) if offset < len => {
+                    let values = &values[..values.len().min(len - offset)];
+                    return offset + values.partition_point(pred);
+                }
+                Self::Node { left, right, .. } if depth > 0 => {
+                    depth -= 1;
+                    let right_offset = offset + (1 << (depth + packing_depth));
+                    let go_right = right_offset < len
+                        && right
+                            .get_recursive(0, depth, packing_depth)
+                            .is_some_and(&mut pred);
+                    if go_right {
+                        node = right;
+                        offset = right_offset;
+                    } else {
+                        node = left;
+                    }
+                }
+                _ => return offset,
+            }
+        }
+    }
+
     /// Create a new tree where the `index`th leaf is set to `new_value`.
     ///
     /// NOTE: callers are responsible for bounds-checking `index` before calling this function.
@@ -111,18 +336,33 @@ impl<T: Value> Tree<T> {
         new_value: T,
         depth: usize,
     ) -> Result<Arc<Self>, Error> {
+        self.with_updated_leaf_at(index, new_value, depth, 1)
+    }
+
+    fn with_updated_leaf_at(
+        &self,
+        index: usize,
+        new_value: T,
+        depth: usize,
+        gindex: usize,
+    ) -> Result<Arc<Self>, Error> {
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        let prefix = subtree_prefix(index, depth + packing_depth);
         match self {
             Self::Leaf(_) if depth == 0 => Ok(Self::leaf(new_value)),
-            Self::PackedLeaf(leaf) if depth == 0 => Ok(Arc::new(Self::PackedLeaf(
-                leaf.insert_at_index(index, new_value)?,
-            ))),
+            Self::PackedLeaf(leaf) if depth == 0 => {
+                let updated = leaf
+                    .insert_at_index(index, new_value)
+                    .map_err(|e| e.in_tree(depth, prefix, gindex))?;
+                metrics::record(|m| m.packed_leaf_rewritten());
+                Ok(Self::packed_leaf(updated))
+            }
             Self::Node { left, right, .. } if depth > 0 => {
-                let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
                 let new_depth = depth - 1;
                 if (index >> (new_depth + packing_depth)) & 1 == 0 {
                     // Index lies on the left, recurse left
                     Ok(Self::node(
-                        left.with_updated_leaf(index, new_value, new_depth)?,
+                        left.with_updated_leaf_at(index, new_value, new_depth, 2 * gindex)?,
                         right.clone(),
                         Hash256::zero(),
                     ))
@@ -130,7 +370,7 @@ impl<T: Value> Tree<T> {
                     // Index lies on the right, recurse right
                     Ok(Self::node(
                         left.clone(),
-                        right.with_updated_leaf(index, new_value, new_depth)?,
+                        right.with_updated_leaf_at(index, new_value, new_depth, 2 * gindex + 1)?,
                         Hash256::zero(),
                     ))
                 }
@@ -138,7 +378,8 @@ impl<T: Value> Tree<T> {
             Self::Zero(zero_depth) if *zero_depth == depth => {
                 if depth == 0 {
                     if opt_packing_factor::<T>().is_some() {
-                        Ok(Arc::new(Self::PackedLeaf(PackedLeaf::single(new_value))))
+                        PackedLeaf::<T>::check_layout()?;
+                        Ok(Self::packed_leaf(PackedLeaf::single(new_value)))
                     } else {
                         Ok(Self::leaf(new_value))
                     }
@@ -147,10 +388,10 @@ impl<T: Value> Tree<T> {
                     // the appropriate subtree
                     let new_zero = Self::zero(depth - 1);
                     Self::node(new_zero.clone(), new_zero, Hash256::zero())
-                        .with_updated_leaf(index, new_value, depth)
+                        .with_updated_leaf_at(index, new_value, depth, gindex)
                 }
             }
-            _ => Err(Error::UpdateLeafError),
+            _ => Err(Error::UpdateLeafError.in_tree(depth, prefix, gindex)),
         }
     }
 
@@ -161,20 +402,50 @@ impl<T: Value> Tree<T> {
         depth: usize,
         hashes: Option<&BTreeMap<(usize, usize), Hash256>>,
     ) -> Result<Arc<Self>, Error> {
+        // Cloning an unchanged node is cheap, and retains its cached hash.
+        Ok(self
+            .with_changed_leaves(updates, prefix, depth, hashes, 1)?
+            .unwrap_or_else(|| Arc::new(self.clone())))
+    }
+
+    /// Apply `updates` to the subtree, returning `None` if none of its values change.
+    ///
+    /// Unchanged subtrees are kept as-is rather than copied, so that their cached hashes are
+    /// retained and only the ancestors of modified leaves need to be re-hashed.
+    ///
+    /// Errors arising at this node are wrapped in `Error::InTree` with its position.
+    fn with_changed_leaves<U: UpdateMap<T>>(
+        &self,
+        updates: &U,
+        prefix: usize,
+        depth: usize,
+        hashes: Option<&BTreeMap<(usize, usize), Hash256>>,
+        gindex: usize,
+    ) -> Result<Option<Arc<Self>>, Error> {
         let hash = opt_hash(hashes, depth, prefix).unwrap_or_default();
+        let in_tree = |e: Error| e.in_tree(depth, prefix, gindex);
 
         match self {
-            Self::Leaf(_) if depth == 0 => {
+            Self::Leaf(Leaf {
+                value: existing, ..
+            }) if depth == 0 => {
                 let index = prefix;
                 let value = updates
                     .get(index)
-                    .cloned()
-                    .ok_or(Error::LeafUpdateMissing { index })?;
-                Ok(Self::leaf_with_hash(value, hash))
+                    .ok_or_else(|| in_tree(Error::LeafUpdateMissing { index }))?;
+                if value == &**existing {
+                    return Ok(None);
+                }
+                Ok(Some(Self::leaf_with_hash(value.clone(), hash)))
+            }
+            Self::PackedLeaf(packed_leaf) if depth == 0 => {
+                let updated = packed_leaf.update(prefix, hash, updates).map_err(in_tree)?;
+                if updated.values == packed_leaf.values {
+                    return Ok(None);
+                }
+                metrics::record(|m| m.packed_leaf_rewritten());
+                Ok(Some(Self::packed_leaf(updated)))
             }
-            Self::PackedLeaf(packed_leaf) if depth == 0 => Ok(Arc::new(Self::PackedLeaf(
-                packed_leaf.update(prefix, hash, updates)?,
-            ))),
             Self::Node { left, right, .. } if depth > 0 => {
                 let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
                 let new_depth = depth - 1;
@@ -182,56 +453,398 @@ impl<T: Value> Tree<T> {
                 let right_prefix = prefix | (1 << (new_depth + packing_depth));
                 let right_subtree_end = prefix + (1 << (depth + packing_depth));
 
-                let mut has_left_updates = false;
-                updates.for_each_range(left_prefix, right_prefix, |_, _| {
-                    has_left_updates = true;
-                    ControlFlow::Break(())
-                })?;
-                let mut has_right_updates = false;
-                updates.for_each_range(right_prefix, right_subtree_end, |_, _| {
-                    has_right_updates = true;
-                    ControlFlow::Break(())
-                })?;
+                let has_left_updates =
+                    has_updates_in(updates, left_prefix, right_prefix).map_err(in_tree)?;
+                let has_right_updates =
+                    has_updates_in(updates, right_prefix, right_subtree_end).map_err(in_tree)?;
 
                 // Must have some updates else this recursive branch is a complete waste of time.
                 if !has_left_updates && !has_right_updates {
-                    return Err(Error::NodeUpdatesMissing { prefix });
+                    return Err(in_tree(Error::NodeUpdatesMissing { prefix }));
                 }
 
                 let new_left = if has_left_updates {
-                    left.with_updated_leaves(updates, left_prefix, new_depth, hashes)?
+                    left.with_changed_leaves(updates, left_prefix, new_depth, hashes, 2 * gindex)?
                 } else {
-                    left.clone()
+                    None
                 };
                 let new_right = if has_right_updates {
-                    right.with_updated_leaves(updates, right_prefix, new_depth, hashes)?
+                    right.with_changed_leaves(
+                        updates,
+                        right_prefix,
+                        new_depth,
+                        hashes,
+                        2 * gindex + 1,
+                    )?
                 } else {
-                    right.clone()
+                    None
                 };
 
-                Ok(Self::node(new_left, new_right, hash))
+                if new_left.is_none() && new_right.is_none() {
+                    return Ok(None);
+                }
+                Ok(Some(Self::node(
+                    new_left.unwrap_or_else(|| left.clone()),
+                    new_right.unwrap_or_else(|| right.clone()),
+                    hash,
+                )))
             }
             Self::Zero(zero_depth) if *zero_depth == depth => {
+                // New values are always materialised, even if they hash the same as zero.
                 if depth == 0 {
                     if opt_packing_factor::<T>().is_some() {
-                        let packed_leaf = PackedLeaf::empty().update(prefix, hash, updates)?;
-                        Ok(Arc::new(Self::PackedLeaf(packed_leaf)))
+                        PackedLeaf::<T>::check_layout()?;
+                        let packed_leaf = PackedLeaf::empty()
+                            .update(prefix, hash, updates)
+                            .map_err(in_tree)?;
+                        Ok(Some(Self::packed_leaf(packed_leaf)))
                     } else {
                         let index = prefix;
                         let value = updates
                             .get(index)
                             .cloned()
-                            .ok_or(Error::LeafUpdateMissing { index })?;
-                        Ok(Self::leaf_with_hash(value, hash))
+                            .ok_or_else(|| in_tree(Error::LeafUpdateMissing { index }))?;
+                        Ok(Some(Self::leaf_with_hash(value, hash)))
                     }
                 } else {
                     // Split zero node into a node with left and right and recurse.
                     let new_zero = Self::zero(depth - 1);
-                    Self::node(new_zero.clone(), new_zero, hash)
-                        .with_updated_leaves(updates, prefix, depth, hashes)
+                    let node = Self::node(new_zero.clone(), new_zero, hash);
+                    let updated =
+                        node.with_changed_leaves(updates, prefix, depth, hashes, gindex)?;
+                    Ok(Some(updated.unwrap_or(node)))
                 }
             }
-            _ => Err(Error::UpdateLeavesError),
+            _ => Err(in_tree(Error::UpdateLeavesError)),
+        }
+    }
+
+    /// Apply `updates` to `tree`, mutating uniquely owned nodes in place.
+    ///
+    /// Nodes which are shared with other trees are copied on write, as for `with_updated_leaves`,
+    /// but nodes owned only by `tree` are modified without allocating replacements. A list which
+    /// is not shared with any clones therefore only allocates for new leaves. The updates are
+    /// checked against the whole tree before any node is modified, so if an error is returned
+    /// then `tree` is unchanged.
+    pub fn update_leaves_in_place<U: UpdateMap<T>>(
+        tree: &mut Arc<Self>,
+        updates: &U,
+        prefix: usize,
+        depth: usize,
+        hashes: Option<&BTreeMap<(usize, usize), Hash256>>,
+    ) -> Result<(), Error> {
+        Self::check_updates(Some(tree), updates, prefix, depth, 1)?;
+        Self::update_in_place(tree, updates, prefix, depth, hashes, 1).map(|_| ())
+    }
+
+    /// Check that `updates` can be applied to the subtree without error, as by
+    /// `with_changed_leaves`, without modifying it.
+    ///
+    /// A `tree` of `None` stands for a zero subtree of the given `depth`, which avoids allocating
+    /// the children of zero nodes just to check them.
+    fn check_updates<U: UpdateMap<T>>(
+        tree: Option<&Self>,
+        updates: &U,
+        prefix: usize,
+        depth: usize,
+        gindex: usize,
+    ) -> Result<(), Error> {
+        let in_tree = |e: Error| e.in_tree(depth, prefix, gindex);
+
+        let (left, right) = match tree {
+            Some(Self::Leaf(_)) if depth == 0 => {
+                let index = prefix;
+                updates
+                    .get(index)
+                    .ok_or_else(|| in_tree(Error::LeafUpdateMissing { index }))?;
+                return Ok(());
+            }
+            Some(Self::PackedLeaf(leaf)) if depth == 0 => {
+                return check_packed_updates(updates, prefix, leaf.values.len()).map_err(in_tree);
+            }
+            Some(Self::Node { left, right, .. }) if depth > 0 => (Some(&**left), Some(&**right)),
+            None | Some(Self::Zero(0)) if depth == 0 => {
+                if opt_packing_factor::<T>().is_some() {
+                    PackedLeaf::<T>::check_layout()?;
+                    return check_packed_updates(updates, prefix, 0).map_err(in_tree);
+                }
+                let index = prefix;
+                updates
+                    .get(index)
+                    .ok_or_else(|| in_tree(Error::LeafUpdateMissing { index }))?;
+                return Ok(());
+            }
+            None => (None, None),
+            Some(Self::Zero(zero_depth)) if *zero_depth == depth => (None, None),
+            _ => return Err(in_tree(Error::UpdateLeavesError)),
+        };
+
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        let new_depth = depth - 1;
+        let right_prefix = prefix | (1 << (new_depth + packing_depth));
+        let end = prefix + (1 << (depth + packing_depth));
+
+        let has_left_updates = has_updates_in(updates, prefix, right_prefix).map_err(in_tree)?;
+        let has_right_updates = has_updates_in(updates, right_prefix, end).map_err(in_tree)?;
+        if !has_left_updates && !has_right_updates {
+            return Err(in_tree(Error::NodeUpdatesMissing { prefix }));
+        }
+        if has_left_updates {
+            Self::check_updates(left, updates, prefix, new_depth, 2 * gindex)?;
+        }
+        if has_right_updates {
+            Self::check_updates(right, updates, right_prefix, new_depth, 2 * gindex + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Apply `updates` to the subtree, returning `false` if none of its values change.
+    fn update_in_place<U: UpdateMap<T>>(
+        tree: &mut Arc<Self>,
+        updates: &U,
+        prefix: usize,
+        depth: usize,
+        hashes: Option<&BTreeMap<(usize, usize), Hash256>>,
+        gindex: usize,
+    ) -> Result<bool, Error> {
+        let hash = opt_hash(hashes, depth, prefix).unwrap_or_default();
+        let in_tree = |e: Error| e.in_tree(depth, prefix, gindex);
+
+        match Arc::get_mut(tree) {
+            Some(Self::Leaf(leaf)) if depth == 0 => {
+                let index = prefix;
+                let value = updates
+                    .get(index)
+                    .ok_or_else(|| in_tree(Error::LeafUpdateMissing { index }))?;
+                if value == &*leaf.value {
+                    return Ok(false);
+                }
+                match Arc::get_mut(&mut leaf.value) {
+                    Some(existing) => existing.clone_from(value),
+                    None => leaf.value = Arc::new(value.clone()),
+                }
+                *leaf.hash.get_mut() = hash;
+                trace::node_rewritten();
+                Ok(true)
+            }
+            Some(Self::PackedLeaf(leaf)) if depth == 0 => {
+                let packing_factor = T::tree_hash_packing_factor();
+                let mut changed = false;
+                updates
+                    .for_each_range(prefix, prefix + packing_factor, |index, value| {
+                        let sub_index = index % packing_factor;
+                        if leaf.values.get(sub_index) == Some(value) {
+                            return ControlFlow::Continue(Ok(()));
+                        }
+                        changed = true;
+                        ControlFlow::Continue(leaf.insert_mut(sub_index, value.clone()))
+                    })
+                    .map_err(in_tree)?;
+                if changed {
+                    *leaf.hash.get_mut() = hash;
+                    metrics::record(|m| m.packed_leaf_rewritten());
+                    trace::node_rewritten();
+                }
+                Ok(changed)
+            }
+            Some(Self::Node {
+                hash: node_hash,
+                left,
+                right,
+            }) if depth > 0 => {
+                let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+                let new_depth = depth - 1;
+                let right_prefix = prefix | (1 << (new_depth + packing_depth));
+                let end = prefix + (1 << (depth + packing_depth));
+
+                let has_left_updates =
+                    has_updates_in(updates, prefix, right_prefix).map_err(in_tree)?;
+                let has_right_updates =
+                    has_updates_in(updates, right_prefix, end).map_err(in_tree)?;
+                if !has_left_updates && !has_right_updates {
+                    return Err(in_tree(Error::NodeUpdatesMissing { prefix }));
+                }
+
+                let mut changed = false;
+                if has_left_updates {
+                    changed |= Self::update_in_place(
+                        left,
+                        updates,
+                        prefix,
+                        new_depth,
+                        hashes,
+                        2 * gindex,
+                    )?;
+                }
+                if has_right_updates {
+                    changed |= Self::update_in_place(
+                        right,
+                        updates,
+                        right_prefix,
+                        new_depth,
+                        hashes,
+                        2 * gindex + 1,
+                    )?;
+                }
+                if changed {
+                    *node_hash.get_mut() = hash;
+                    trace::node_rewritten();
+                }
+                Ok(changed)
+            }
+            // Shared nodes are copied on write. Zero subtrees are always replaced, and invalid
+            // nodes are reported by `with_changed_leaves`.
+            _ => match tree.with_changed_leaves(updates, prefix, depth, hashes, gindex)? {
+                Some(updated) => {
+                    *tree = updated;
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+        }
+    }
+
+    /// Create a new tree containing only the first `len` elements of `tree`.
+    ///
+    /// Subtrees which no longer contain any elements are replaced by zero nodes.
+    pub fn truncate(
+        tree: &Arc<Self>,
+        len: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) -> Result<Arc<Self>, Error> {
+        if len == 0 {
+            return Ok(Self::zero(depth));
+        }
+        if len >= 1 << (depth + packing_depth) {
+            return Ok(tree.clone());
+        }
+
+        match &**tree {
+            Self::PackedLeaf(leaf) if depth == 0 => Ok(Self::packed_leaf(leaf.truncated(len))),
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_depth = depth - 1;
+                let left_capacity = 1 << (new_depth + packing_depth);
+                if len <= left_capacity {
+                    Ok(Self::node(
+                        Self::truncate(left, len, new_depth, packing_depth)?,
+                        Self::zero(new_depth),
+                        Hash256::zero(),
+                    ))
+                } else {
+                    Ok(Self::node(
+                        left.clone(),
+                        Self::truncate(right, len - left_capacity, new_depth, packing_depth)?,
+                        Hash256::zero(),
+                    ))
+                }
+            }
+            Self::Zero(zero_depth) if *zero_depth == depth => Ok(tree.clone()),
+            _ => Err(Error::InvalidTruncateNode),
+        }
+    }
+
+    /// Create a new tree holding `len` elements, where the element at each index is `value(index)`.
+    ///
+    /// Subtrees of `tree` are reused wherever `unchanged(start, end)` reports that none of the
+    /// elements in `start..end` would change, so only the modified paths are rebuilt.
+    pub fn permute<F, G>(
+        tree: &Arc<Self>,
+        prefix: usize,
+        len: usize,
+        depth: usize,
+        packing_depth: usize,
+        value: &F,
+        unchanged: &G,
+    ) -> Result<Arc<Self>, Error>
+    where
+        F: Fn(usize) -> Result<T, Error>,
+        G: Fn(usize, usize) -> bool,
+    {
+        if prefix >= len {
+            return Ok(Self::zero(depth));
+        }
+        let end = std::cmp::min(prefix + (1 << (depth + packing_depth)), len);
+        if unchanged(prefix, end) {
+            return Ok(tree.clone());
+        }
+
+        if depth == 0 {
+            return if opt_packing_factor::<T>().is_some() {
+                Ok(Self::packed_leaf(PackedLeaf {
+                    hash: RwLock::new(Hash256::zero()),
+                    values: (prefix..end).map(value).collect::<Result<_, _>>()?,
+                }))
+            } else {
+                Ok(Self::leaf(value(prefix)?))
+            };
+        }
+
+        let new_depth = depth - 1;
+        let (left, right) = match &**tree {
+            Self::Node { left, right, .. } => (left.clone(), right.clone()),
+            Self::Zero(_) => (Self::zero(new_depth), Self::zero(new_depth)),
+            _ => return Err(Error::InvalidPermuteNode),
+        };
+        let right_prefix = prefix + (1 << (new_depth + packing_depth));
+        Ok(Self::node(
+            Self::permute(
+                &left,
+                prefix,
+                len,
+                new_depth,
+                packing_depth,
+                value,
+                unchanged,
+            )?,
+            Self::permute(
+                &right,
+                right_prefix,
+                len,
+                new_depth,
+                packing_depth,
+                value,
+                unchanged,
+            )?,
+            Hash256::zero(),
+        ))
+    }
+
+    /// Clear the cached hashes of the leaves holding elements in `start..end`, and of all of
+    /// their ancestors.
+    ///
+    /// `prefix` is the index of the first element of this subtree.
+    pub fn invalidate_range(
+        &self,
+        start: usize,
+        end: usize,
+        prefix: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) {
+        let subtree_end = prefix + (1 << (depth + packing_depth));
+        if start >= subtree_end || end <= prefix {
+            return;
+        }
+        match self {
+            Self::Leaf(leaf) => *leaf.hash.write() = Hash256::zero(),
+            Self::PackedLeaf(leaf) => *leaf.hash.write() = Hash256::zero(),
+            Self::Node { hash, left, right } if depth > 0 => {
+                *hash.write() = Hash256::zero();
+                let right_prefix = prefix | (1 << (depth - 1 + packing_depth));
+                left.invalidate_range(start, end, prefix, depth - 1, packing_depth);
+                right.invalidate_range(start, end, right_prefix, depth - 1, packing_depth);
+            }
+            Self::Node { .. } | Self::Zero(_) => {}
+        }
+    }
+
+    /// Compute the depth of this subtree by walking its left-most branch.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Leaf(_) | Self::PackedLeaf(_) => 0,
+            Self::Node { left, .. } => left.depth() + 1,
+            Self::Zero(depth) => *depth,
         }
     }
 
@@ -341,55 +954,29 @@ impl<T: Value> Tree<T> {
                         Ok(NotEqualNoop)
                     }
                     (EqualNoop, EqualNoop) => Ok(EqualNoop),
-                    (NotEqualNoop | EqualNoop, NotEqualReplace(new_right)) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: l1.clone(),
-                            right: new_right,
-                        })))
-                    }
-                    (NotEqualNoop | EqualNoop, EqualReplace(new_right)) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: l1.clone(),
-                            right: new_right.clone(),
-                        })))
-                    }
+                    (NotEqualNoop | EqualNoop, NotEqualReplace(new_right)) => Ok(NotEqualReplace(
+                        Self::node(l1.clone(), new_right, orig_hash),
+                    )),
+                    (NotEqualNoop | EqualNoop, EqualReplace(new_right)) => Ok(NotEqualReplace(
+                        Self::node(l1.clone(), new_right.clone(), orig_hash),
+                    )),
                     (NotEqualReplace(new_left), NotEqualNoop | EqualNoop) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: new_left,
-                            right: r1.clone(),
-                        })))
+                        Ok(NotEqualReplace(Self::node(new_left, r1.clone(), orig_hash)))
                     }
                     (NotEqualReplace(new_left), NotEqualReplace(new_right)) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: new_left,
-                            right: new_right,
-                        })))
-                    }
-                    (NotEqualReplace(new_left), EqualReplace(new_right)) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: new_left,
-                            right: new_right.clone(),
-                        })))
-                    }
-                    (EqualReplace(new_left), NotEqualNoop) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: new_left.clone(),
-                            right: r1.clone(),
-                        })))
-                    }
-                    (EqualReplace(new_left), NotEqualReplace(new_right)) => {
-                        Ok(NotEqualReplace(Arc::new(Self::Node {
-                            hash: RwLock::new(orig_hash),
-                            left: new_left.clone(),
-                            right: new_right,
-                        })))
+                        Ok(NotEqualReplace(Self::node(new_left, new_right, orig_hash)))
                     }
+                    (NotEqualReplace(new_left), EqualReplace(new_right)) => Ok(NotEqualReplace(
+                        Self::node(new_left, new_right.clone(), orig_hash),
+                    )),
+                    (EqualReplace(new_left), NotEqualNoop) => Ok(NotEqualReplace(Self::node(
+                        new_left.clone(),
+                        r1.clone(),
+                        orig_hash,
+                    ))),
+                    (EqualReplace(new_left), NotEqualReplace(new_right)) => Ok(NotEqualReplace(
+                        Self::node(new_left.clone(), new_right, orig_hash),
+                    )),
                     (EqualReplace(_), EqualReplace(_)) | (EqualReplace(_), EqualNoop) => {
                         Ok(EqualReplace(base))
                     }
@@ -404,8 +991,70 @@ impl<T: Value> Tree<T> {
     }
 }
 
-impl<T: Value + Send + Sync> Tree<T> {
+impl<T: Value + MaybeSync> Tree<T> {
     pub fn tree_hash(&self) -> Hash256 {
+        self.tree_hash_with::<Sha256Hasher>()
+    }
+
+    /// Compute the tree hash, hashing subtrees at or below depth `threshold` sequentially.
+    ///
+    /// Hashing a small subtree is faster than the overhead of sending it to another thread, so
+    /// only subtrees deeper than `threshold` are split across the `rayon` thread pool. The
+    /// threshold only affects scheduling, not the result. Has no effect if the `rayon` feature is
+    /// disabled. `tree_hash` uses `DEFAULT_PARALLEL_HASH_THRESHOLD`.
+    pub fn tree_hash_with_threshold(&self, threshold: usize) -> Hash256 {
+        let _span = op_span!("tree_hash", depth = self.depth());
+        self.tree_hash_recursive(self.depth(), threshold)
+    }
+
+    /// Compute the tree hash using `H` to hash internal nodes.
+    ///
+    /// The hashes cached in the tree are SHA256 hashes shared with `tree_hash` (and with any
+    /// other trees sharing the same nodes), so they are only read and updated if `H` is
+    /// `Sha256Hasher`. Other hashers compute every node, remembering the hashes of shared
+    /// subtrees only for the duration of the call.
+    pub fn tree_hash_with<H: MerkleHasher>(&self) -> Hash256 {
+        let _span = op_span!("tree_hash", depth = self.depth());
+        if TypeId::of::<H>() == TypeId::of::<Sha256Hasher>() {
+            self.tree_hash_recursive(self.depth(), DEFAULT_PARALLEL_HASH_THRESHOLD)
+        } else {
+            self.tree_hash_uncached::<H>(self.depth(), &mut HashMap::new())
+        }
+    }
+
+    /// Compute the tree hash using `H` without touching the cached hashes.
+    ///
+    /// The hashes of subtrees are recorded in `memo` by address, so that subtrees shared within
+    /// the tree are only hashed once.
+    fn tree_hash_uncached<H: MerkleHasher>(
+        &self,
+        depth: usize,
+        memo: &mut HashMap<*const Self, Hash256>,
+    ) -> Hash256 {
+        match self {
+            Self::Leaf(Leaf { value, .. }) => value.tree_hash_root(),
+            Self::PackedLeaf(leaf) => leaf.compute_hash(),
+            Self::Zero(depth) => H::zero_hash(*depth),
+            Self::Node { left, right, .. } => {
+                if let Some(hash) = memo.get(&(self as *const Self)) {
+                    return *hash;
+                }
+                let new_depth = depth.saturating_sub(1);
+                let left_hash = left.tree_hash_uncached::<H>(new_depth, memo);
+                let right_hash = right.tree_hash_uncached::<H>(new_depth, memo);
+                let tree_hash = H::hash_pair(&left_hash, &right_hash);
+                metrics::record(|m| m.hashes_computed(1));
+                memo.insert(self, tree_hash);
+                tree_hash
+            }
+        }
+    }
+
+    #[cfg_attr(
+        not(all(feature = "rayon", not(milhouse_rc))),
+        allow(clippy::only_used_in_recursion)
+    )]
+    fn tree_hash_recursive(&self, depth: usize, threshold: usize) -> Hash256 {
         match self {
             Self::Leaf(Leaf { hash, value }) => {
                 // FIXME(sproul): upgradeable RwLock?
@@ -425,11 +1074,12 @@ impl<T: Value + Send + Sync> Tree<T> {
                 } else {
                     let tree_hash = value.tree_hash_root();
                     *hash.write() = tree_hash;
+                    trace::node_rewritten();
                     tree_hash
                 }
             }
             Self::PackedLeaf(leaf) => leaf.tree_hash(),
-            Self::Zero(depth) => Hash256::from_slice(&ZERO_HASHES[*depth]),
+            Self::Zero(depth) => Sha256Hasher::zero_hash(*depth),
             Self::Node { hash, left, right } => {
                 let read_lock = hash.read();
                 let existing_hash = *read_lock;
@@ -438,15 +1088,163 @@ impl<T: Value + Send + Sync> Tree<T> {
                 if !existing_hash.is_zero() {
                     existing_hash
                 } else {
-                    // Parallelism goes brrrr.
-                    let (left_hash, right_hash) =
-                        rayon::join(|| left.tree_hash(), || right.tree_hash());
-                    let tree_hash =
-                        Hash256::from(hash32_concat(left_hash.as_bytes(), right_hash.as_bytes()));
+                    let new_depth = depth.saturating_sub(1);
+                    let hash_left = || left.tree_hash_recursive(new_depth, threshold);
+                    let hash_right = || right.tree_hash_recursive(new_depth, threshold);
+
+                    // Parallelism goes brrrr, but only for subtrees large enough to be worth it.
+                    #[cfg(all(feature = "rayon", not(milhouse_rc)))]
+                    let (left_hash, right_hash) = if depth > threshold {
+                        trace::join(hash_left, hash_right)
+                    } else {
+                        (hash_left(), hash_right())
+                    };
+                    #[cfg(not(all(feature = "rayon", not(milhouse_rc))))]
+                    let (left_hash, right_hash) = (hash_left(), hash_right());
+
+                    let tree_hash = Sha256Hasher::hash_pair(&left_hash, &right_hash);
+                    metrics::record(|m| m.hashes_computed(1));
                     *hash.write() = tree_hash;
+                    trace::node_rewritten();
                     tree_hash
                 }
             }
         }
     }
+
+    /// Compute the hash of the chunk at `chunk_index` and its Merkle branch.
+    ///
+    /// The branch is ordered from the leaf to the root, and has length `depth`.
+    pub fn prove_chunk(
+        &self,
+        chunk_index: usize,
+        depth: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        match self {
+            Self::Leaf(_) | Self::PackedLeaf(_) if depth == 0 => Ok((self.tree_hash(), vec![])),
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_depth = depth - 1;
+                // Recurse into the subtree containing the chunk, and add the hash of its sibling.
+                let (subtree, sibling) = if (chunk_index >> new_depth) & 1 == 0 {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                let (leaf, mut branch) = subtree.prove_chunk(chunk_index, new_depth)?;
+                branch.push(sibling.tree_hash());
+                Ok((leaf, branch))
+            }
+            Self::Zero(zero_depth) if *zero_depth == depth => {
+                let branch = ZERO_HASHES[..depth]
+                    .iter()
+                    .map(|hash| Hash256::from_slice(hash))
+                    .collect();
+                Ok((Hash256::from_slice(&ZERO_HASHES[0]), branch))
+            }
+            _ => Err(Error::InvalidProofNode),
+        }
+    }
+
+    /// Compute the hashes of the chunks at `chunk_indices` and of the helper nodes required to
+    /// prove them, in a single walk of the tree.
+    ///
+    /// The `chunk_indices` must be sorted and deduplicated. Leaf hashes are appended to `leaves`
+    /// in the same order, and helpers are added to `helpers` by generalized index, taking this
+    /// tree to be at `gindex`.
+    pub fn prove_chunks(
+        &self,
+        chunk_indices: &[usize],
+        depth: usize,
+        gindex: usize,
+        leaves: &mut Vec<Hash256>,
+        helpers: &mut BTreeMap<usize, Hash256>,
+    ) -> Result<(), Error> {
+        if chunk_indices.is_empty() {
+            helpers.insert(gindex, self.tree_hash());
+            return Ok(());
+        }
+        match self {
+            Self::Leaf(_) | Self::PackedLeaf(_) if depth == 0 => {
+                leaves.push(self.tree_hash());
+                Ok(())
+            }
+            Self::Node { left, right, .. } if depth > 0 => {
+                let new_depth = depth - 1;
+                // Chunks in the left subtree have a zero bit at this level, and are sorted first.
+                let split = chunk_indices.partition_point(|&i| (i >> new_depth) & 1 == 0);
+                let (left_chunks, right_chunks) = chunk_indices.split_at(split);
+                left.prove_chunks(left_chunks, new_depth, 2 * gindex, leaves, helpers)?;
+                right.prove_chunks(right_chunks, new_depth, 2 * gindex + 1, leaves, helpers)
+            }
+            Self::Zero(zero_depth) if *zero_depth == depth => {
+                prove_zero_chunks(chunk_indices, depth, gindex, leaves, helpers);
+                Ok(())
+            }
+            _ => Err(Error::InvalidProofNode),
+        }
+    }
+}
+
+/// Equivalent of `Tree::prove_chunks` for a zero subtree of the given `depth`.
+fn prove_zero_chunks(
+    chunk_indices: &[usize],
+    depth: usize,
+    gindex: usize,
+    leaves: &mut Vec<Hash256>,
+    helpers: &mut BTreeMap<usize, Hash256>,
+) {
+    if chunk_indices.is_empty() {
+        helpers.insert(gindex, Hash256::from_slice(&ZERO_HASHES[depth]));
+    } else if depth == 0 {
+        leaves.push(Hash256::from_slice(&ZERO_HASHES[0]));
+    } else {
+        let new_depth = depth - 1;
+        let split = chunk_indices.partition_point(|&i| (i >> new_depth) & 1 == 0);
+        let (left, right) = chunk_indices.split_at(split);
+        prove_zero_chunks(left, new_depth, 2 * gindex, leaves, helpers);
+        prove_zero_chunks(right, new_depth, 2 * gindex + 1, leaves, helpers);
+    }
+}
+
+/// Clear the lowest `bits` bits of `index`, giving the first index of the subtree containing it.
+fn subtree_prefix(index: usize, bits: usize) -> usize {
+    index
+        .checked_shr(bits as u32)
+        .unwrap_or(0)
+        .checked_shl(bits as u32)
+        .unwrap_or(0)
+}
+
+/// Check whether `updates` contains any index in `start..end`.
+fn has_updates_in<T: Value, U: UpdateMap<T>>(
+    updates: &U,
+    start: usize,
+    end: usize,
+) -> Result<bool, Error> {
+    let mut found = false;
+    updates.for_each_range::<_, Error>(start, end, |_, _| {
+        found = true;
+        ControlFlow::Break(())
+    })?;
+    Ok(found)
+}
+
+/// Check that the updates to the packed leaf starting at `prefix` can be inserted into a leaf
+/// holding `len` values, as for `PackedLeaf::insert_mut`.
+fn check_packed_updates<T: Value, U: UpdateMap<T>>(
+    updates: &U,
+    prefix: usize,
+    mut len: usize,
+) -> Result<(), Error> {
+    let packing_factor = T::tree_hash_packing_factor();
+    updates.for_each_range(prefix, prefix + packing_factor, |index, _| {
+        let sub_index = index % packing_factor;
+        if sub_index > len {
+            return ControlFlow::Continue(Err(Error::PackedLeafOutOfBounds { sub_index, len }));
+        }
+        if sub_index == len {
+            len += 1;
+        }
+        ControlFlow::Continue(Ok(()))
+    })
 }
@@ -1,4 +1,5 @@
-use crate::cow::{BTreeCow, Cow, VecCow};
+use crate::cow::{BTreeCow, Cow, SortedVecCow, SortedVecVacantEntry, VecCow};
+use crate::interface::ApplyPolicy;
 use crate::utils::max_btree_index;
 use arbitrary::Arbitrary;
 use std::collections::{btree_map::Entry, BTreeMap};
@@ -8,6 +9,12 @@ use vec_map::VecMap;
 /// Trait for map types which can be used to store intermediate updates before application
 /// to the tree.
 pub trait UpdateMap<T>: Default + Clone {
+    /// When the updates stored in this map are applied to the tree.
+    ///
+    /// This is `Explicit` for all maps except `AutoApply`, so the policy of a list or vector is
+    /// chosen by its update map type.
+    const APPLY_POLICY: ApplyPolicy = ApplyPolicy::Explicit;
+
     fn get(&self, k: usize) -> Option<&T>;
 
     fn get_mut_with<F>(&mut self, k: usize, f: F) -> Option<&mut T>
@@ -169,6 +176,119 @@ impl<T: Clone> UpdateMap<T> for VecMap<T> {
     }
 }
 
+/// Update map backed by a `Vec` of entries sorted by index.
+///
+/// Lookups use binary search, and inserting an index greater than all others is a plain push.
+/// This suits updates which are mostly made in ascending order and touch most indices, such as
+/// a pass over every element of a list.
+#[derive(Debug, Clone, PartialEq, Arbitrary)]
+pub struct VecUpdateMap<T> {
+    #[arbitrary(default)]
+    entries: Vec<(usize, T)>,
+}
+
+impl<T> Default for VecUpdateMap<T> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<T> VecUpdateMap<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Find the position of `k`, or the position at which it should be inserted.
+    fn search(&self, k: usize) -> Result<usize, usize> {
+        // Fast path for appending.
+        match self.entries.last() {
+            None => Err(0),
+            Some((last, _)) if *last < k => Err(self.entries.len()),
+            _ => self.entries.binary_search_by_key(&k, |(key, _)| *key),
+        }
+    }
+}
+
+impl<T: Clone> UpdateMap<T> for VecUpdateMap<T> {
+    fn get(&self, k: usize) -> Option<&T> {
+        let position = self.search(k).ok()?;
+        Some(&self.entries[position].1)
+    }
+
+    fn get_mut_with<F>(&mut self, k: usize, f: F) -> Option<&mut T>
+    where
+        F: FnOnce(usize) -> Option<T>,
+    {
+        let position = match self.search(k) {
+            Ok(position) => position,
+            Err(position) => {
+                // Copy on write.
+                let value = f(k)?;
+                self.entries.insert(position, (k, value));
+                position
+            }
+        };
+        Some(&mut self.entries[position].1)
+    }
+
+    fn get_cow_with<'a, F>(&'a mut self, k: usize, f: F) -> Option<Cow<'a, T>>
+    where
+        F: FnOnce(usize) -> Option<&'a T>,
+    {
+        let cow = match self.search(k) {
+            Ok(position) => SortedVecCow::Mutable {
+                value: &mut self.entries[position].1,
+            },
+            Err(position) => SortedVecCow::Immutable {
+                value: f(k)?,
+                entry: Some(SortedVecVacantEntry {
+                    entries: &mut self.entries,
+                    position,
+                    key: k,
+                }),
+            },
+        };
+        Some(Cow::SortedVec(cow))
+    }
+
+    fn insert(&mut self, k: usize, value: T) -> Option<T> {
+        match self.search(k) {
+            Ok(position) => Some(std::mem::replace(&mut self.entries[position].1, value)),
+            Err(position) => {
+                self.entries.insert(position, (k, value));
+                None
+            }
+        }
+    }
+
+    fn for_each_range<F, E>(&self, start: usize, end: usize, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(usize, &T) -> ControlFlow<(), Result<(), E>>,
+    {
+        let first = self.entries.partition_point(|(key, _)| *key < start);
+        for (key, value) in &self.entries[first..] {
+            if *key >= end {
+                break;
+            }
+            match f(*key, value) {
+                ControlFlow::Continue(res) => res?,
+                ControlFlow::Break(()) => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        self.entries.last().map(|(key, _)| *key)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Arbitrary)]
 #[arbitrary(bound = "M: Default")]
 pub struct MaxMap<M> {
@@ -181,6 +301,8 @@ impl<T, M> UpdateMap<T> for MaxMap<M>
 where
     M: UpdateMap<T>,
 {
+    const APPLY_POLICY: ApplyPolicy = M::APPLY_POLICY;
+
     fn get(&self, k: usize) -> Option<&T> {
         self.inner.get(k)
     }
@@ -222,3 +344,200 @@ where
         Some(self.max_key).filter(|_| !self.inner.is_empty())
     }
 }
+
+/// Update map which applies its updates automatically, see `ApplyPolicy::Auto`.
+///
+/// The updates are stored in the wrapped map `M`. The policy is part of the type, so it can be
+/// chosen for a whole type alias, e.g. `List<T, N, AutoApply<MaxMap<VecMap<T>>>>`.
+#[derive(Debug, Default, Clone, PartialEq, Arbitrary)]
+#[arbitrary(bound = "M: Default")]
+pub struct AutoApply<M> {
+    #[arbitrary(default)]
+    inner: M,
+}
+
+impl<T, M> UpdateMap<T> for AutoApply<M>
+where
+    M: UpdateMap<T>,
+{
+    const APPLY_POLICY: ApplyPolicy = ApplyPolicy::Auto;
+
+    fn get(&self, k: usize) -> Option<&T> {
+        self.inner.get(k)
+    }
+
+    fn get_mut_with<F>(&mut self, k: usize, f: F) -> Option<&mut T>
+    where
+        F: FnOnce(usize) -> Option<T>,
+    {
+        self.inner.get_mut_with(k, f)
+    }
+
+    fn get_cow_with<'a, F>(&'a mut self, k: usize, f: F) -> Option<Cow<'a, T>>
+    where
+        F: FnOnce(usize) -> Option<&'a T>,
+        T: Clone + 'a,
+    {
+        self.inner.get_cow_with(k, f)
+    }
+
+    fn insert(&mut self, k: usize, value: T) -> Option<T> {
+        self.inner.insert(k, value)
+    }
+
+    fn for_each_range<F, E>(&self, start: usize, end: usize, f: F) -> Result<(), E>
+    where
+        F: FnMut(usize, &T) -> ControlFlow<(), Result<(), E>>,
+    {
+        self.inner.for_each_range(start, end, f)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        self.inner.max_index()
+    }
+}
+
+/// Update map with a bitmap of the indices which have been updated.
+///
+/// Values are stored in a `VecMap`, while a two-level bitmap allows `for_each_range` to skip
+/// runs of clean indices a word at a time. This makes the range queries made while applying
+/// updates cheap even for very wide lists with sparse updates.
+#[derive(Debug, Clone, PartialEq, Arbitrary)]
+pub struct BitmapUpdateMap<T> {
+    #[arbitrary(default)]
+    values: VecMap<T>,
+    /// One bit per index, set if the index may have a value.
+    #[arbitrary(default)]
+    bits: Vec<u64>,
+    /// One bit per word of `bits`, set if that word is non-zero.
+    #[arbitrary(default)]
+    summary: Vec<u64>,
+    #[arbitrary(default)]
+    max_key: usize,
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+impl<T> Default for BitmapUpdateMap<T> {
+    fn default() -> Self {
+        Self {
+            values: VecMap::new(),
+            bits: vec![],
+            summary: vec![],
+            max_key: 0,
+        }
+    }
+}
+
+impl<T> BitmapUpdateMap<T> {
+    fn set_bit(&mut self, k: usize) {
+        let word = k / WORD_BITS;
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+            self.summary.resize(word / WORD_BITS + 1, 0);
+        }
+        self.bits[word] |= 1 << (k % WORD_BITS);
+        self.summary[word / WORD_BITS] |= 1 << (word % WORD_BITS);
+    }
+
+    /// Return the first set bit in `words` at or after `start`.
+    fn next_set_in(words: &[u64], start: usize) -> Option<usize> {
+        let mut word = start / WORD_BITS;
+        let mut bits = words.get(word)? & (u64::MAX << (start % WORD_BITS));
+        while bits == 0 {
+            word += 1;
+            bits = *words.get(word)?;
+        }
+        Some(word * WORD_BITS + bits.trailing_zeros() as usize)
+    }
+
+    /// Return the first index at or after `start` whose bit is set.
+    fn next_set(&self, start: usize) -> Option<usize> {
+        let word = start / WORD_BITS;
+        let bits = self.bits.get(word)? & (u64::MAX << (start % WORD_BITS));
+        if bits != 0 {
+            return Some(word * WORD_BITS + bits.trailing_zeros() as usize);
+        }
+        // Use the summary to find the next non-zero word.
+        let next_word = Self::next_set_in(&self.summary, word + 1)?;
+        Some(next_word * WORD_BITS + self.bits[next_word].trailing_zeros() as usize)
+    }
+}
+
+impl<T: Clone> UpdateMap<T> for BitmapUpdateMap<T> {
+    fn get(&self, k: usize) -> Option<&T> {
+        self.values.get(k)
+    }
+
+    fn get_mut_with<F>(&mut self, k: usize, f: F) -> Option<&mut T>
+    where
+        F: FnOnce(usize) -> Option<T>,
+    {
+        if !self.values.contains_key(k) {
+            // Copy on write.
+            let value = f(k)?;
+            self.insert(k, value);
+        }
+        self.values.get_mut(k)
+    }
+
+    fn get_cow_with<'a, F>(&'a mut self, k: usize, f: F) -> Option<Cow<'a, T>>
+    where
+        F: FnOnce(usize) -> Option<&'a T>,
+    {
+        // The bit is set before it is known whether the value will be written, which is harmless
+        // as `for_each_range` only visits indices with values.
+        let value = if self.values.contains_key(k) {
+            None
+        } else {
+            Some(f(k)?)
+        };
+        self.set_bit(k);
+        let cow = match (self.values.entry(k), value) {
+            (vec_map::Entry::Vacant(entry), Some(value)) => VecCow::Immutable {
+                value,
+                entry: Some(entry),
+            },
+            (vec_map::Entry::Occupied(entry), _) => VecCow::Mutable {
+                value: entry.into_mut(),
+            },
+            (vec_map::Entry::Vacant(_), None) => return None,
+        };
+        Some(Cow::Vec(cow))
+    }
+
+    fn insert(&mut self, k: usize, value: T) -> Option<T> {
+        self.set_bit(k);
+        self.max_key = self.max_key.max(k);
+        self.values.insert(k, value)
+    }
+
+    fn for_each_range<F, E>(&self, start: usize, end: usize, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(usize, &T) -> ControlFlow<(), Result<(), E>>,
+    {
+        let mut next = self.next_set(start);
+        while let Some(key) = next.filter(|key| *key < end) {
+            if let Some(value) = self.values.get(key) {
+                match f(key, value) {
+                    ControlFlow::Continue(res) => res?,
+                    ControlFlow::Break(()) => break,
+                }
+            }
+            next = self.next_set(key + 1);
+        }
+        Ok(())
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        Some(self.max_key).filter(|_| !self.values.is_empty())
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+}
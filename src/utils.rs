@@ -1,6 +1,7 @@
 use crate::{Arc, UpdateMap};
 use arbitrary::Arbitrary;
 use parking_lot::RwLock;
+use ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
 use std::collections::BTreeMap;
 use tree_hash::{Hash256, TreeHash, TreeHashType};
 
@@ -98,6 +99,37 @@ pub fn opt_hash(
     hashes?.get(&(depth, prefix)).copied()
 }
 
+/// Number of strong references to `ptr`, for either choice of pointer type.
+pub fn ref_count<T>(ptr: &Arc<T>) -> usize {
+    #[cfg(not(milhouse_rc))]
+    return Arc::count(ptr);
+    #[cfg(milhouse_rc)]
+    return Arc::strong_count(ptr);
+}
+
+/// Append the SSZ encoding of the `len` variable-length items returned by `items` to `buf`.
+///
+/// The offsets are computed from the items' `ssz_bytes_len` in a first pass over `items`, so the
+/// items can then be encoded straight into `buf`, which only has to grow once. The items are not
+/// buffered separately as they are by `SszEncoder`.
+pub(crate) fn ssz_append_variable<'a, T, I>(items: impl Fn() -> I, len: usize, buf: &mut Vec<u8>)
+where
+    T: Encode + 'a,
+    I: Iterator<Item = &'a T>,
+{
+    let fixed_len = len * BYTES_PER_LENGTH_OFFSET;
+    buf.reserve(fixed_len);
+    let mut offset = fixed_len;
+    for item in items() {
+        buf.extend_from_slice(&ssz::encode_length(offset));
+        offset += item.ssz_bytes_len();
+    }
+    buf.reserve(offset - fixed_len);
+    for item in items() {
+        item.ssz_append(buf);
+    }
+}
+
 pub fn arb_arc<'a, T: Arbitrary<'a>>(
     u: &mut arbitrary::Unstructured<'a>,
 ) -> arbitrary::Result<Arc<T>> {
@@ -0,0 +1,90 @@
+use crate::{Error, Leaf, MerkleHasher, Sha256Hasher, Tree, Value};
+use tree_hash::Hash256;
+
+impl<T: Value> Tree<T> {
+    /// Check that the tree is well-formed for a list or vector of `length` elements.
+    ///
+    /// This checks that:
+    ///
+    /// - Internal nodes and leaves only appear at the depths where they belong.
+    /// - Packed leaves hold exactly as many values as the length implies.
+    /// - Zero subtrees only cover indices at or beyond the length, and every subtree beyond the
+    ///   length is a zero subtree.
+    /// - Every cached hash matches the hash recomputed from scratch with `Sha256Hasher`.
+    ///
+    /// On success the recomputed root hash is returned. The tree's cached hashes are not modified.
+    pub fn validate(
+        &self,
+        length: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) -> Result<Hash256, Error> {
+        self.validate_recursive(0, length, depth, packing_depth)
+    }
+
+    fn validate_recursive(
+        &self,
+        index: usize,
+        length: usize,
+        depth: usize,
+        packing_depth: usize,
+    ) -> Result<Hash256, Error> {
+        let invalid_depth = Error::InvalidTreeDepth { index, depth };
+
+        if let Self::Zero(zero_depth) = self {
+            if *zero_depth != depth {
+                return Err(invalid_depth);
+            }
+            if index < length {
+                return Err(Error::InvalidZeroSubtree { index, depth });
+            }
+            return Ok(Sha256Hasher::zero_hash(depth));
+        }
+        if index >= length {
+            return Err(Error::NonZeroPadding { index, depth });
+        }
+
+        let (cached, hash) = match self {
+            Self::Leaf(Leaf { hash, value }) => {
+                if depth != 0 || packing_depth != 0 {
+                    return Err(invalid_depth);
+                }
+                (*hash.read(), value.tree_hash_root())
+            }
+            Self::PackedLeaf(leaf) => {
+                if depth != 0 || packing_depth == 0 {
+                    return Err(invalid_depth);
+                }
+                let expected = std::cmp::min(1 << packing_depth, length - index);
+                if leaf.values.len() != expected {
+                    return Err(Error::InvalidPackedLeafLength {
+                        index,
+                        len: leaf.values.len(),
+                        expected,
+                    });
+                }
+                (*leaf.hash.read(), leaf.compute_hash())
+            }
+            Self::Node { hash, left, right } => {
+                if depth == 0 {
+                    return Err(invalid_depth);
+                }
+                let right_index = index + (1 << (depth - 1 + packing_depth));
+                let left_hash = left.validate_recursive(index, length, depth - 1, packing_depth)?;
+                let right_hash =
+                    right.validate_recursive(right_index, length, depth - 1, packing_depth)?;
+                (
+                    *hash.read(),
+                    Sha256Hasher::hash_pair(&left_hash, &right_hash),
+                )
+            }
+            Self::Zero(_) => unreachable!("zero subtrees are handled above"),
+        };
+
+        // Zero means that the hash has not been computed yet.
+        if !cached.is_zero() && cached != hash {
+            return Err(Error::InvalidCachedHash { index, depth });
+        }
+        Ok(hash)
+    }
+}
@@ -1,28 +1,57 @@
-use crate::interface::{ImmList, Interface, MutList};
+use crate::changelog::Changelog;
+use crate::chunk_iter::{ChunkIter, SszChunks};
+use crate::guard::MutGuard;
+use crate::interface::{ApplyPolicy, Checkpoint, ImmList, Interface, MutList};
 use crate::interface_iter::InterfaceIter;
+#[cfg(feature = "intern")]
+use crate::intern::NodeInterner;
+use crate::into_iter::IntoIter;
 use crate::iter::Iter;
 use crate::level_iter::LevelIter;
+use crate::proof::{prove_tree_indices, MultiProof, Proof};
+use crate::sharded_updates::ShardedUpdates;
+use crate::store::{NodeSink, NodeStore};
+use crate::stream::encode_list_to_writer;
+use crate::trace::op_span;
+use crate::transaction::Transaction;
 use crate::tree::RebaseAction;
 use crate::update_map::MaxMap;
-use crate::utils::{arb_arc, Length};
-use crate::{Arc, Cow, Error, List, Tree, UpdateMap, Value};
+use crate::utils::{arb_arc, ssz_append_variable, Length};
+use crate::view::SubtreeView;
+use crate::windows::Windows;
+use crate::{
+    Arc, Cow, Error, List, MaybeSync, MemoryUsage, MerkleHasher, NodeSharing, Tree, UpdateMap,
+    Value,
+};
 use arbitrary::Arbitrary;
 use derivative::Derivative;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use ssz::{Decode, Encode, SszEncoder, TryFromIter, BYTES_PER_LENGTH_OFFSET};
+use ssz::{Decode, Encode, TryFromIter, BYTES_PER_LENGTH_OFFSET};
+#[cfg(feature = "ssz_types")]
+use ssz_types::FixedVector;
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::iter::StepBy;
 use std::marker::PhantomData;
+use std::ops::{ControlFlow, Index, Range};
 use tree_hash::{Hash256, PackedEncoding};
-use typenum::Unsigned;
+use typenum::{Const, ToUInt, Unsigned};
 use vec_map::VecMap;
 
-#[derive(Debug, Derivative, Clone, Serialize, Deserialize, Arbitrary)]
-#[derivative(PartialEq(bound = "T: Value, N: Unsigned, U: UpdateMap<T> + PartialEq"))]
-#[serde(try_from = "List<T, N, U>")]
-#[serde(into = "List<T, N, U>")]
-#[serde(bound(serialize = "T: Value + Serialize, N: Unsigned, U: UpdateMap<T>"))]
-#[serde(bound(deserialize = "T: Value + Deserialize<'de>, N: Unsigned, U: UpdateMap<T>"))]
+#[derive(Debug, Clone, Arbitrary)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "List<T, N, U>"))]
+#[cfg_attr(feature = "serde", serde(into = "List<T, N, U>"))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(serialize = "T: Value + Serialize, N: Unsigned, U: UpdateMap<T>"))
+)]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "T: Value + Deserialize<'de>, N: Unsigned, U: UpdateMap<T>"))
+)]
 #[arbitrary(bound = "T: Arbitrary<'arbitrary> + Value")]
 #[arbitrary(bound = "N: Unsigned, U: Arbitrary<'arbitrary> + UpdateMap<T>")]
 pub struct Vector<T: Value, N: Unsigned, U: UpdateMap<T> = MaxMap<VecMap<T>>> {
@@ -36,7 +65,7 @@ pub struct VectorInner<T: Value, N: Unsigned> {
     #[arbitrary(with = arb_arc)]
     pub(crate) tree: Arc<Tree<T>>,
     pub(crate) depth: usize,
-    packing_depth: usize,
+    pub(crate) packing_depth: usize,
     #[arbitrary(default)]
     _phantom: PhantomData<N>,
 }
@@ -53,10 +82,25 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
         }
     }
 
-    pub fn from_elem(elem: T) -> Result<Self, Error> {
+    /// Create a vector from `vec`, which must have exactly `N` elements.
+    ///
+    /// Errors with `Error::WrongVectorLength` giving both lengths otherwise. This is the same as
+    /// `new` and `TryFrom<Vec<T>>`.
+    pub fn try_from_vec(vec: Vec<T>) -> Result<Self, Error> {
+        Self::new(vec)
+    }
+
+    /// Create a vector of `N` copies of `elem`, sharing identical subtrees.
+    ///
+    /// See `List::repeat`.
+    pub fn repeat(elem: T) -> Result<Self, Error> {
         Self::try_from(List::repeat(elem, N::to_usize())?)
     }
 
+    pub fn from_elem(elem: T) -> Result<Self, Error> {
+        Self::repeat(elem)
+    }
+
     pub fn try_from_iter(iter: impl IntoIterator<Item = T>) -> Result<Self, Error> {
         Self::try_from(List::try_from_iter(iter)?)
     }
@@ -65,10 +109,25 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
         self.iter().cloned().collect()
     }
 
+    /// Copy the elements into an array of length `N`, including any pending updates.
+    ///
+    /// Only available for vectors whose length is small enough to be a const generic.
+    pub fn to_array<const L: usize>(&self) -> [T; L]
+    where
+        Const<L>: ToUInt<Output = N>,
+    {
+        let mut iter = self.iter();
+        std::array::from_fn(|_| iter.next().cloned().expect("vector has length L"))
+    }
+
     pub fn iter(&self) -> InterfaceIter<T, U> {
         self.interface.iter()
     }
 
+    /// Iterate over the elements starting from `index`, including any pending updates.
+    ///
+    /// The tree is descended once to find the starting leaf, after which each element costs
+    /// amortised O(1), so scanning `k` elements is O(log n + k).
     pub fn iter_from(&self, index: usize) -> Result<InterfaceIter<T, U>, Error> {
         if index > self.len() {
             return Err(Error::OutOfBoundsIterFrom {
@@ -79,19 +138,184 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
         Ok(self.interface.iter_from(index))
     }
 
+    /// Iterate over every `step`th element starting from `start`, including pending updates.
+    ///
+    /// Each step only re-descends from the nearest common ancestor of consecutive elements, so
+    /// striding by `step` costs O(log step) per element rather than a `get` from the root.
+    ///
+    /// Panics if `step` is 0, as for `Iterator::step_by`.
+    pub fn iter_step_by_from(
+        &self,
+        start: usize,
+        step: usize,
+    ) -> Result<StepBy<InterfaceIter<'_, T, U>>, Error> {
+        Ok(self.iter_from(start)?.step_by(step))
+    }
+
+    /// Iterate over all windows of `k` consecutive elements, including pending updates.
+    ///
+    /// Panics if `k` is 0, as for `slice::windows`.
+    pub fn iter_windows(&self, k: usize) -> Windows<'_, T, U> {
+        Windows::new(self.iter(), k)
+    }
+
+    /// Iterate over the elements in contiguous slices, one per leaf of the tree.
+    ///
+    /// For packed types each slice contains all of the elements in a 32-byte chunk, which allows
+    /// reductions to process values without traversing the tree for each element.
+    pub fn iter_chunks(&self) -> Result<ChunkIter<'_, T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ChunkIterPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Ok(ChunkIter::new(&backing.tree, backing.depth, self.len()))
+    }
+
+    /// Iterate over the SSZ encoding of the vector in chunks of at least `min_chunk_len` bytes
+    /// (except for the last), each made up of whole leaves.
+    ///
+    /// Errors if there are pending updates. See `SszChunks`.
+    pub fn ssz_chunks(&self, min_chunk_len: usize) -> Result<SszChunks<'_, T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ChunkIterPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Ok(SszChunks::new(
+            &backing.tree,
+            backing.depth,
+            self.len(),
+            min_chunk_len,
+        ))
+    }
+
+    /// Clear the cached hashes of the elements in `range`, so that they are recomputed by the
+    /// next call to `tree_hash_root`.
+    ///
+    /// This is only needed after modifying elements through interior mutability, which the
+    /// vector can't detect. Only the hashes on the paths from this vector's root are cleared: other
+    /// trees sharing the modified elements keep stale hashes in their own nodes above them, so
+    /// each of them must be invalidated as well. Errors if `range` is out of bounds.
+    pub fn invalidate_range(&self, range: Range<usize>) -> Result<(), Error> {
+        let len = self.len();
+        if range.start > range.end || range.end > len {
+            return Err(Error::OutOfBoundsSubtree {
+                start: range.start,
+                end: range.end,
+                len,
+            });
+        }
+        let backing = &self.interface.backing;
+        backing.tree.invalidate_range(
+            range.start,
+            range.end,
+            0,
+            backing.depth,
+            backing.packing_depth,
+        );
+        Ok(())
+    }
+
+    /// Return a view of the elements in `range`, sharing the nodes of this tree.
+    ///
+    /// Errors if there are pending updates, or if `range` is out of bounds.
+    pub fn subtree(&self, range: Range<usize>) -> Result<SubtreeView<T>, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::SubtreePendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        SubtreeView::new(&backing.tree, backing.depth, self.len(), range)
+    }
+
     // Wrap trait methods so we present a Vec-like interface without having to import anything.
     pub fn get(&self, index: usize) -> Option<&T> {
         self.interface.get(index)
     }
 
+    /// Return the first element, descending only the left edge of the tree.
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Return the last element, descending only the path to it.
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Return the elements at each of `indices`, in the same order, including pending updates.
+    ///
+    /// Out of bounds indices give `None`. The indices are looked up in sorted order, so that
+    /// nearby indices share the path from the root rather than each descending the whole tree.
+    pub fn get_many(&self, indices: &[usize]) -> Vec<Option<&T>> {
+        let backing = &self.interface.backing;
+        let len = self.len();
+        let mut values = backing
+            .tree
+            .get_many(indices, backing.depth, backing.packing_depth);
+        for (value, &index) in values.iter_mut().zip(indices) {
+            if index >= len {
+                *value = None;
+            } else if let Some(updated) = self.interface.updates.get(index) {
+                *value = Some(updated);
+            }
+        }
+        values
+    }
+
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
         self.interface.get_mut(index)
     }
 
+    /// Return a guard for the `index`th element which records a pending update when dropped.
+    ///
+    /// See `MutGuard`.
+    pub fn get_mut_guard(&mut self, index: usize) -> Option<MutGuard<'_, T, VectorInner<T, N>, U>> {
+        self.interface.get_mut_guard(index)
+    }
+
     pub fn get_cow(&mut self, index: usize) -> Option<Cow<T>> {
         self.interface.get_cow(index)
     }
 
+    /// Stage each `(index, value)` pair in `updates` as a pending update.
+    ///
+    /// Errors without changing anything if any index is out of bounds. Unlike calling `get_mut`
+    /// for each index, the replaced values are never read. See `Interface::update_many`.
+    pub fn update_many(
+        &mut self,
+        updates: impl IntoIterator<Item = (usize, T)>,
+    ) -> Result<(), Error> {
+        self.interface.update_many(updates)
+    }
+
+    /// Swap the elements at indices `i` and `j`.
+    ///
+    /// Both writes are recorded as pending updates. Errors if either index is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), Error> {
+        self.interface.swap(i, j)
+    }
+
+    /// Visit every element, replacing those for which `f` returns a new value.
+    ///
+    /// The new values are stored as pending updates, so only the modified subtrees are rebuilt
+    /// by the next call to `apply_updates`.
+    pub fn map_in_place<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, &T) -> Option<T>,
+    {
+        self.interface.map_in_place(f)
+    }
+
+    /// Call `f` with mutable access to each element in turn, stopping early if it breaks.
+    ///
+    /// Only elements whose values change are recorded as pending updates. Changes made before
+    /// breaking are kept.
+    pub fn try_for_each_mut<R, F>(&mut self, f: F) -> ControlFlow<R>
+    where
+        F: FnMut(usize, &mut T) -> ControlFlow<R>,
+    {
+        self.interface.try_for_each_mut(f)
+    }
+
     pub fn len(&self) -> usize {
         self.interface.len()
     }
@@ -104,9 +328,55 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
         self.interface.has_pending_updates()
     }
 
+    /// The policy for applying pending updates, which is determined by `U`.
+    pub fn apply_policy(&self) -> ApplyPolicy {
+        self.interface.apply_policy()
+    }
+
     pub fn apply_updates(&mut self) -> Result<(), Error> {
         self.interface.apply_updates()
     }
+
+    /// Apply the pending updates, returning a summary of the indices and nodes rewritten.
+    pub fn apply_updates_with_changelog(&mut self) -> Result<Changelog, Error> {
+        let indices = self.interface.pending_indices();
+        let prev_len = N::to_usize();
+        self.apply_updates()?;
+        let backing = &self.interface.backing;
+        Ok(Changelog::new(
+            indices,
+            prev_len,
+            self.len(),
+            backing.depth,
+            backing.packing_depth,
+        ))
+    }
+
+    /// Start a batch of modifications which can be committed or rolled back as a whole.
+    ///
+    /// See `Transaction`.
+    pub fn begin_transaction(&mut self) -> Transaction<'_, T, VectorInner<T, N>, U> {
+        self.interface.begin_transaction()
+    }
+
+    /// Record the current state so that later modifications can be undone by `revert_to`.
+    ///
+    /// See `Interface::checkpoint`.
+    pub fn checkpoint(&self) -> Checkpoint<VectorInner<T, N>, U> {
+        self.interface.checkpoint()
+    }
+
+    /// Restore the state recorded by `checkpoint`.
+    pub fn revert_to(&mut self, checkpoint: Checkpoint<VectorInner<T, N>, U>) {
+        self.interface.revert_to(checkpoint)
+    }
+
+    /// Add updates staged concurrently in `updates` to the pending updates.
+    ///
+    /// See `ShardedUpdates`.
+    pub fn merge_updates(&mut self, updates: ShardedUpdates<T>) -> Result<(), Error> {
+        self.interface.merge_updates(updates)
+    }
 }
 
 impl<T: Value, N: Unsigned, U: UpdateMap<T>> TryFrom<List<T, N, U>> for Vector<T, N, U> {
@@ -138,13 +408,23 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> TryFrom<List<T, N, U>> for Vector<T
 }
 
 impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Return a copy of `self` which shares as much of its structure as possible with `base`.
+    ///
+    /// See `rebase_on`.
     pub fn rebase(&self, base: &Self) -> Result<Self, Error> {
         let mut rebased = self.clone();
         rebased.rebase_on(base)?;
         Ok(rebased)
     }
 
+    /// Replace subtrees of `self` by equal subtrees from `base`, so that they share memory.
+    ///
+    /// This is useful after deserializing, when `self` shares nothing with an existing copy in
+    /// memory. Subtrees are compared by their cached hashes where available, and by value
+    /// otherwise.
     pub fn rebase_on(&mut self, base: &Self) -> Result<(), Error> {
+        let span = op_span!("rebase");
+        span.record_elements(self.len());
         match Tree::rebase_on(
             &self.interface.backing.tree,
             &base.interface.backing.tree,
@@ -161,6 +441,188 @@ impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
         }
         Ok(())
     }
+
+    /// Compute the memory used by the tree backing this vector, excluding pending updates.
+    ///
+    /// See `Tree::mem_usage`.
+    pub fn mem_usage(&self) -> MemoryUsage {
+        Tree::mem_usage(&self.interface.backing.tree)
+    }
+
+    /// Count the nodes which are exclusive to each of `vectors`, and the nodes which they share.
+    ///
+    /// Pending updates are not included. See `Tree::count_unique_nodes`.
+    pub fn count_unique_nodes(vectors: &[&Self]) -> NodeSharing {
+        let trees = vectors
+            .iter()
+            .map(|vector| &vector.interface.backing.tree)
+            .collect::<Vec<_>>();
+        Tree::count_unique_nodes(&trees)
+    }
+
+    /// Check that the tree backing this vector is well-formed, excluding pending updates.
+    ///
+    /// See `Tree::validate`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let backing = &self.interface.backing;
+        backing
+            .tree
+            .validate(N::to_usize(), backing.depth, backing.packing_depth)?;
+        Ok(())
+    }
+}
+
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Compare two vectors by their tree hash roots.
+    ///
+    /// This is much faster than comparing elements when the hashes of both vectors are
+    /// already cached, and computes them otherwise. Falls back to comparing elements if either
+    /// vector has pending updates.
+    pub fn eq_by_root(&self, other: &Self) -> bool {
+        if self.has_pending_updates() || other.has_pending_updates() {
+            return self == other;
+        }
+        self.len() == other.len()
+            && self.interface.backing.tree.tree_hash() == other.interface.backing.tree.tree_hash()
+    }
+
+    /// Compute the tree hash root using `H` to hash internal nodes, including pending updates.
+    ///
+    /// See `Tree::tree_hash_with`.
+    pub fn tree_hash_root_with<H: MerkleHasher>(&self) -> Result<Hash256, Error> {
+        Ok(self.interface.applied_backing()?.tree.tree_hash_with::<H>())
+    }
+
+    /// Compute the tree hash root, hashing subtrees at or below depth `threshold` sequentially.
+    ///
+    /// Pending updates are included. See `Tree::tree_hash_with_threshold`.
+    pub fn tree_hash_root_with_threshold(&self, threshold: usize) -> Result<Hash256, Error> {
+        Ok(self
+            .interface
+            .applied_backing()?
+            .tree
+            .tree_hash_with_threshold(threshold))
+    }
+
+    /// Compute a Merkle proof for the chunk containing the `index`th element.
+    pub fn prove(&self, index: usize) -> Result<Proof, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ProofPendingUpdates);
+        }
+        if index >= self.len() {
+            return Err(Error::OutOfBoundsProof {
+                index,
+                len: self.len(),
+            });
+        }
+
+        let backing = &self.interface.backing;
+        let chunk_index = index >> backing.packing_depth;
+        let (leaf, branch) = backing.tree.prove_chunk(chunk_index, backing.depth)?;
+
+        Ok(Proof {
+            gindex: (1 << backing.depth) + chunk_index,
+            leaf,
+            branch,
+        })
+    }
+
+    /// Compute a Merkle multiproof for the chunks containing each of the elements in `indices`.
+    pub fn prove_indices(&self, indices: &[usize]) -> Result<MultiProof, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::ProofPendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        prove_tree_indices(
+            &backing.tree,
+            backing.depth,
+            1,
+            backing.packing_depth,
+            self.len(),
+            indices,
+            &[],
+        )
+    }
+
+    /// Write every node of the vector to `store`, returning its tree hash root.
+    ///
+    /// Errors if there are pending updates. See `List::store`.
+    pub fn store<S: NodeStore<T>>(&self, store: &S) -> Result<Hash256, Error> {
+        if self.has_pending_updates() {
+            return Err(Error::StorePendingUpdates);
+        }
+        let backing = &self.interface.backing;
+        Tree::store(&backing.tree, backing.depth, store)
+    }
+
+    /// Apply the pending updates, and write the nodes they create to `sink`.
+    ///
+    /// Returns the new tree hash root. See `List::apply_updates_with_sink`.
+    pub fn apply_updates_with_sink<S: NodeSink<T>>(&mut self, sink: &S) -> Result<Hash256, Error> {
+        self.apply_updates()?;
+        let backing = &self.interface.backing;
+        Tree::write_unhashed(&backing.tree, backing.depth, sink)
+    }
+
+    /// Load the vector with tree hash root `root` from `store`, as written by `Vector::store`.
+    ///
+    /// The contents of the store are trusted, so the loaded tree is not validated.
+    pub fn load<S: NodeStore<T>>(store: &S, root: Hash256) -> Result<Self, Error> {
+        let depth = List::<T, N, U>::depth();
+        let tree = Tree::load(depth, root, store)?;
+        Self::try_from(List::from_parts(tree, depth, Length(N::to_usize())))
+    }
+
+    /// Share identical subtrees of this vector with other trees known to `interner`.
+    ///
+    /// Any pending updates are applied first.
+    #[cfg(feature = "intern")]
+    pub fn intern<I: NodeInterner<T>>(&mut self, interner: &I) -> Result<(), Error> {
+        self.apply_updates()?;
+        let backing = &mut self.interface.backing;
+        backing.tree = Tree::intern(&backing.tree, backing.depth, interner);
+        Ok(())
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> TryFrom<Vec<T>> for Vector<T, N, U> {
+    type Error = Error;
+
+    fn try_from(vec: Vec<T>) -> Result<Self, Error> {
+        Self::new(vec)
+    }
+}
+
+impl<T: Value, U: UpdateMap<T>, const L: usize> From<[T; L]> for Vector<T, typenum::U<L>, U>
+where
+    Const<L>: ToUInt,
+    typenum::U<L>: Unsigned,
+{
+    fn from(array: [T; L]) -> Self {
+        Self::new(array.into()).expect("array has length N")
+    }
+}
+
+#[cfg(feature = "ssz_types")]
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<FixedVector<T, N>> for Vector<T, N, U> {
+    /// Build the tree from the vector's elements in a single pass.
+    fn from(vector: FixedVector<T, N>) -> Self {
+        Self::new(vector.into()).expect("fixed vector has length N")
+    }
+}
+
+#[cfg(feature = "ssz_types")]
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<Vector<T, N, U>> for FixedVector<T, N> {
+    fn from(vector: Vector<T, N, U>) -> Self {
+        FixedVector::new(vector.to_vec()).expect("vector has length N")
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<Vector<T, N, U>> for Vec<T> {
+    /// Collect the elements, including any pending updates.
+    fn from(vector: Vector<T, N, U>) -> Self {
+        vector.into_iter().collect()
+    }
 }
 
 impl<T: Value, N: Unsigned, U: UpdateMap<T>> From<Vector<T, N, U>> for List<T, N, U> {
@@ -203,7 +665,7 @@ where
     T: Value,
     N: Unsigned,
 {
-    fn validate_push(_current_len: usize) -> Result<(), Error> {
+    fn validate_push(&self, _current_len: usize) -> Result<(), Error> {
         Err(Error::PushNotSupported)
     }
 
@@ -231,14 +693,34 @@ where
             // Nothing to do.
             return Ok(());
         }
-        self.tree =
-            self.tree
-                .with_updated_leaves(&updates, 0, self.depth, hash_updates.as_ref())?;
+        Tree::update_leaves_in_place(
+            &mut self.tree,
+            &updates,
+            0,
+            self.depth,
+            hash_updates.as_ref(),
+        )?;
         Ok(())
     }
 }
 
-impl<T: Default + Value, N: Unsigned> Default for Vector<T, N> {
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> PartialEq for Vector<T, N, U> {
+    /// Compare two vectors by value.
+    ///
+    /// If neither has pending updates then their trees are compared directly, skipping shared
+    /// subtrees and subtrees with cached hashes. Otherwise the elements are compared one by one.
+    fn eq(&self, other: &Self) -> bool {
+        if self.has_pending_updates() || other.has_pending_updates() {
+            self.len() == other.len() && self.iter().eq(other.iter())
+        } else {
+            Arc::ptr_eq(&self.interface.backing.tree, &other.interface.backing.tree)
+                || self.interface.backing == other.interface.backing
+        }
+    }
+}
+
+impl<T: Default + Value, N: Unsigned, U: UpdateMap<T>> Default for Vector<T, N, U> {
+    /// Create a vector of `N` copies of `T::default()`, sharing identical subtrees.
     fn default() -> Self {
         Self::from_elem(T::default()).unwrap_or_else(|e| {
             panic!(
@@ -250,7 +732,7 @@ impl<T: Default + Value, N: Unsigned> Default for Vector<T, N> {
     }
 }
 
-impl<T: Value + Send + Sync, N: Unsigned> tree_hash::TreeHash for Vector<T, N> {
+impl<T: Value + MaybeSync, N: Unsigned, U: UpdateMap<T>> tree_hash::TreeHash for Vector<T, N, U> {
     fn tree_hash_type() -> tree_hash::TreeHashType {
         tree_hash::TreeHashType::Vector
     }
@@ -263,17 +745,23 @@ impl<T: Value + Send + Sync, N: Unsigned> tree_hash::TreeHash for Vector<T, N> {
         unreachable!("Vector should never be packed.")
     }
 
+    /// Compute the tree hash root, including any pending updates.
+    ///
+    /// As for `List`, the modified nodes are only hashed in a temporary copy of the tree.
     fn tree_hash_root(&self) -> Hash256 {
-        // FIXME(sproul): remove assert
-        assert!(!self.interface.has_pending_updates());
-        self.interface.backing.tree.tree_hash()
+        self.interface
+            .applied_backing()
+            .expect("pending updates should be valid")
+            .tree
+            .tree_hash()
     }
 }
 
-impl<T, N> TryFromIter<T> for Vector<T, N>
+impl<T, N, U> TryFromIter<T> for Vector<T, N, U>
 where
     T: Value,
     N: Unsigned,
+    U: UpdateMap<T>,
 {
     type Error = Error;
 
@@ -285,6 +773,34 @@ where
     }
 }
 
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Index<usize> for Vector<T, N, U> {
+    type Output = T;
+
+    /// Return the element at `index`, panicking if it is out of bounds.
+    ///
+    /// Pending updates are included, as for `get`.
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            )
+        })
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> IntoIterator for Vector<T, N, U> {
+    type Item = T;
+    type IntoIter = IntoIter<T, U>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        let interface = self.interface;
+        IntoIter::new(interface.backing.tree, interface.updates, len)
+    }
+}
+
 impl<'a, T: Value, N: Unsigned, U: UpdateMap<T>> IntoIterator for &'a Vector<T, N, U> {
     type Item = &'a T;
     type IntoIter = InterfaceIter<'a, T, U>;
@@ -295,7 +811,7 @@ impl<'a, T: Value, N: Unsigned, U: UpdateMap<T>> IntoIterator for &'a Vector<T,
 }
 
 // FIXME: duplicated from `ssz::encode::impl_for_vec`
-impl<T: Value, N: Unsigned> Encode for Vector<T, N> {
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Encode for Vector<T, N, U> {
     fn is_ssz_fixed_len() -> bool {
         <T as Encode>::is_ssz_fixed_len()
     }
@@ -326,18 +842,83 @@ impl<T: Value, N: Unsigned> Encode for Vector<T, N> {
                 item.ssz_append(buf);
             }
         } else {
-            let mut encoder = SszEncoder::container(buf, self.len() * ssz::BYTES_PER_LENGTH_OFFSET);
+            ssz_append_variable(|| self.iter(), self.len(), buf);
+        }
+    }
+}
 
-            for item in self.iter() {
-                encoder.append(item);
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Write the SSZ encoding of the vector to `writer`, including any pending updates.
+    ///
+    /// See `List::ssz_append_to`.
+    pub fn ssz_append_to<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        encode_list_to_writer(|| self.iter(), self.len(), writer)
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Vector<T, N, U> {
+    /// Decode a vector from SSZ bytes read incrementally from `reader`.
+    ///
+    /// See `List::from_ssz_reader`.
+    pub fn from_ssz_reader<R: Read>(reader: R) -> Result<Self, ssz::DecodeError> {
+        Ok(Self::try_from_ssz_reader(reader)?)
+    }
+
+    /// Decode a vector from SSZ bytes read incrementally from `reader`, returning a typed error.
+    ///
+    /// See `try_from_ssz_bytes`.
+    pub fn try_from_ssz_reader<R: Read>(reader: R) -> Result<Self, Error> {
+        let span = op_span!("decode_vector");
+        span.record_elements(N::to_usize());
+        let list = List::try_from_ssz_reader(reader).map_err(vector_length_error)?;
+        Self::from_decoded_list(list)
+    }
+
+    /// Decode a vector from SSZ bytes, returning a typed error.
+    ///
+    /// Input with the wrong number of items is reported as `Error::DecodeWrongVectorLength`, and
+    /// any other malformed input as `Error::SszDecode`. The `Decode` impl converts these back to
+    /// a `DecodeError`.
+    pub fn try_from_ssz_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let span = op_span!("decode_vector");
+        span.record_elements(N::to_usize());
+        if <Self as Decode>::is_ssz_fixed_len() {
+            let expected = <Self as Decode>::ssz_fixed_len();
+            if bytes.len() != expected {
+                return Err(ssz::DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected,
+                }
+                .into());
             }
+        }
+        let list = List::try_from_ssz_bytes(bytes).map_err(vector_length_error)?;
+        Self::from_decoded_list(list)
+    }
 
-            encoder.finalize();
+    fn from_decoded_list(list: List<T, N, U>) -> Result<Self, Error> {
+        if list.len() != N::to_usize() {
+            return Err(Error::DecodeWrongVectorLength {
+                len: list.len(),
+                expected: N::to_usize(),
+            });
         }
+        Self::try_from(list)
     }
 }
 
-impl<T: Value, N: Unsigned> Decode for Vector<T, N> {
+/// Report a list with more items than the vector's length as a vector of the wrong length.
+fn vector_length_error(e: Error) -> Error {
+    match e {
+        Error::DecodeListTooLong { len, max_len } => Error::DecodeWrongVectorLength {
+            len,
+            expected: max_len,
+        },
+        e => e,
+    }
+}
+
+impl<T: Value, N: Unsigned, U: UpdateMap<T>> Decode for Vector<T, N, U> {
     fn is_ssz_fixed_len() -> bool {
         <T as Decode>::is_ssz_fixed_len()
     }
@@ -351,11 +932,6 @@ impl<T: Value, N: Unsigned> Decode for Vector<T, N> {
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
-        let list = List::from_ssz_bytes(bytes).map_err(|e| {
-            ssz::DecodeError::BytesInvalid(format!("Error decoding vector: {:?}", e))
-        })?;
-        Self::try_from(list).map_err(|e| {
-            ssz::DecodeError::BytesInvalid(format!("Wrong number of vector elements: {:?}", e))
-        })
+        Ok(Self::try_from_ssz_bytes(bytes)?)
     }
 }
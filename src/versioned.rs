@@ -0,0 +1,105 @@
+use crate::{Error, List, Snapshot, Tree, UpdateMap, Value};
+use std::collections::VecDeque;
+use typenum::Unsigned;
+
+/// Sequence of versions of a list, e.g. one per slot.
+///
+/// Each version is a `Snapshot`, so taking one is O(1) and consecutive versions share all of
+/// their unmodified subtrees. Versions are numbered from 0 in the order they were taken, and old
+/// versions can be dropped with `prune`, which reports how much memory was freed.
+#[derive(Debug, Clone)]
+pub struct Versioned<T: Value, N: Unsigned> {
+    versions: VecDeque<Snapshot<T, N>>,
+    /// Version number of the first version in `versions`.
+    first: usize,
+}
+
+/// Versions and memory freed by `Versioned::prune`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Pruned {
+    /// Number of versions dropped.
+    pub versions: usize,
+    /// Number of nodes which were only reachable from the dropped versions.
+    pub nodes: usize,
+    /// Bytes used by the nodes counted in `nodes`, as for `MemoryUsage`.
+    pub bytes: usize,
+}
+
+impl<T: Value, N: Unsigned> Default for Versioned<T, N> {
+    fn default() -> Self {
+        Self {
+            versions: VecDeque::new(),
+            first: 0,
+        }
+    }
+}
+
+impl<T: Value, N: Unsigned> Versioned<T, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a snapshot of `list` as the next version, returning its version number.
+    ///
+    /// The list must not have any pending updates.
+    pub fn snapshot<U: UpdateMap<T>>(&mut self, list: &List<T, N, U>) -> Result<usize, Error> {
+        self.versions.push_back(list.snapshot()?);
+        Ok(self.next_version() - 1)
+    }
+
+    /// Return version `version`, if it has been taken and not pruned.
+    pub fn get(&self, version: usize) -> Option<&Snapshot<T, N>> {
+        self.versions.get(version.checked_sub(self.first)?)
+    }
+
+    /// Return the most recent version.
+    pub fn latest(&self) -> Option<&Snapshot<T, N>> {
+        self.versions.back()
+    }
+
+    /// Version number of the oldest version which has not been pruned.
+    pub fn first_version(&self) -> usize {
+        self.first
+    }
+
+    /// Version number which will be given to the next snapshot.
+    pub fn next_version(&self) -> usize {
+        self.first + self.versions.len()
+    }
+
+    /// Number of versions stored.
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Drop all versions older than `version`.
+    ///
+    /// Nodes are counted as freed if they aren't reachable from any remaining version. Nodes
+    /// which are still referenced from outside the store (e.g. by the list being snapshotted)
+    /// are counted too, so the report is an upper bound on the memory actually freed.
+    pub fn prune(&mut self, version: usize) -> Pruned {
+        let count = version.saturating_sub(self.first).min(self.versions.len());
+        let dropped = self.versions.drain(..count).collect::<Vec<_>>();
+        self.first += count;
+
+        let dropped_trees = dropped
+            .iter()
+            .map(|snapshot| &snapshot.interface.backing.tree)
+            .collect::<Vec<_>>();
+        let retained_trees = self
+            .versions
+            .iter()
+            .map(|snapshot| &snapshot.interface.backing.tree)
+            .collect::<Vec<_>>();
+        let (nodes, bytes) = Tree::count_dropped_nodes(&dropped_trees, &retained_trees);
+        Pruned {
+            versions: count,
+            nodes,
+            bytes,
+        }
+    }
+}
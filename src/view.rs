@@ -0,0 +1,152 @@
+use crate::iter::Iter;
+use crate::utils::{opt_packing_depth, Length};
+use crate::{Arc, Error, Tree, Value};
+use std::ops::{Index, Range};
+
+/// Read-only view of a contiguous range of elements of a list or vector.
+///
+/// The view holds a reference to the smallest subtree containing the range, so creating one is
+/// O(log n) and does not copy any elements. Indices are relative to the start of the range.
+#[derive(Debug, Clone)]
+pub struct SubtreeView<T: Value> {
+    tree: Arc<Tree<T>>,
+    depth: usize,
+    packing_depth: usize,
+    /// Start of the range, relative to the first element of `tree`.
+    start: usize,
+    len: usize,
+}
+
+impl<T: Value> SubtreeView<T> {
+    /// Create a view of `range` within `tree`, which holds `len` elements.
+    pub(crate) fn new(
+        tree: &Arc<Tree<T>>,
+        depth: usize,
+        len: usize,
+        range: Range<usize>,
+    ) -> Result<Self, Error> {
+        if range.start > range.end || range.end > len {
+            return Err(Error::OutOfBoundsSubtree {
+                start: range.start,
+                end: range.end,
+                len,
+            });
+        }
+        let packing_depth = opt_packing_depth::<T>().unwrap_or(0);
+        let mut tree = tree;
+        let mut depth = depth;
+        let Range { mut start, mut end } = range;
+
+        // Descend for as long as the range lies entirely within one child.
+        while let Tree::Node { left, right, .. } = &**tree {
+            if depth == 0 || start == end {
+                break;
+            }
+            let half = 1 << (depth - 1 + packing_depth);
+            if end <= half {
+                tree = left;
+            } else if start >= half {
+                tree = right;
+                start -= half;
+                end -= half;
+            } else {
+                break;
+            }
+            depth -= 1;
+        }
+
+        Ok(Self {
+            tree: tree.clone(),
+            depth,
+            packing_depth,
+            start,
+            len: end - start,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            self.tree
+                .get_recursive(self.start + index, self.depth, self.packing_depth)
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.iter_at(0)
+    }
+
+    /// Iterate over the elements starting from `index`.
+    pub fn iter_from(&self, index: usize) -> Result<Iter<'_, T>, Error> {
+        if index > self.len {
+            return Err(Error::OutOfBoundsIterFrom {
+                index,
+                len: self.len,
+            });
+        }
+        Ok(self.iter_at(index))
+    }
+
+    fn iter_at(&self, index: usize) -> Iter<'_, T> {
+        Iter::from_index(
+            self.start + index,
+            &self.tree,
+            self.depth,
+            Length(self.start + self.len),
+        )
+    }
+
+    /// Create a view of `range` within this view.
+    pub fn subtree(&self, range: Range<usize>) -> Result<Self, Error> {
+        if range.start > range.end || range.end > self.len {
+            return Err(Error::OutOfBoundsSubtree {
+                start: range.start,
+                end: range.end,
+                len: self.len,
+            });
+        }
+        Self::new(
+            &self.tree,
+            self.depth,
+            self.start + self.len,
+            self.start + range.start..self.start + range.end,
+        )
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Value> Index<usize> for SubtreeView<T> {
+    type Output = T;
+
+    /// Return the element at `index`, panicking if it is out of bounds.
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            )
+        })
+    }
+}
+
+impl<'a, T: Value> IntoIterator for &'a SubtreeView<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::interface_iter::InterfaceIter;
+use crate::{UpdateMap, Value};
+use std::collections::VecDeque;
+
+/// Iterator over overlapping windows of `k` consecutive elements of a list or vector.
+///
+/// Windows are read through a single iterator, so each element is fetched from the tree once
+/// rather than `k` times. Elements can't be borrowed as a slice when they span several leaves,
+/// so each window is yielded as a `Vec` of references.
+#[derive(Debug)]
+pub struct Windows<'a, T: Value, U: UpdateMap<T>> {
+    iter: InterfaceIter<'a, T, U>,
+    window: VecDeque<&'a T>,
+    size: usize,
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> Windows<'a, T, U> {
+    pub(crate) fn new(iter: InterfaceIter<'a, T, U>, size: usize) -> Self {
+        assert!(size != 0, "window size must be non-zero");
+        Self {
+            iter,
+            window: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> Iterator for Windows<'a, T, U> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().copied().collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let available = self.window.len() + self.iter.len();
+        let remaining = if self.window.len() == self.size {
+            available - self.size
+        } else {
+            (available + 1).saturating_sub(self.size)
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Value, U: UpdateMap<T>> ExactSizeIterator for Windows<'a, T, U> {}
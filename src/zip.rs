@@ -0,0 +1,147 @@
+use crate::iter::Iter;
+use crate::utils::{opt_packing_depth, Length};
+use crate::{Tree, Value};
+use std::iter::Zip;
+use std::slice::Iter as SliceIter;
+use tree_hash::Hash256;
+
+/// Iterator over the elements of two lists of the same length in lockstep.
+///
+/// Created by `List::iter_zip`. Pairs of subtrees which are shared or have equal cached hashes
+/// are skipped without being visited, so only the elements in changed regions are yielded.
+/// Packed leaves are yielded in full, so some of the pairs yielded may be equal.
+#[derive(Debug)]
+pub struct ZipIter<'a, T: Value> {
+    /// Pairs of subtrees still to be visited, with the index of their first element and depth.
+    stack: Vec<(&'a Tree<T>, &'a Tree<T>, usize, usize)>,
+    /// Remaining pairs of values from the current pair of packed leaves.
+    packed: Zip<SliceIter<'a, T>, SliceIter<'a, T>>,
+    /// Index of the next value in `packed`.
+    packed_index: usize,
+    packing_depth: usize,
+    len: usize,
+}
+
+impl<'a, T: Value> ZipIter<'a, T> {
+    pub(crate) fn new(left: &'a Tree<T>, right: &'a Tree<T>, depth: usize, len: usize) -> Self {
+        Self {
+            stack: vec![(left, right, 0, depth)],
+            packed: [].iter().zip([].iter()),
+            packed_index: 0,
+            packing_depth: opt_packing_depth::<T>().unwrap_or(0),
+            len,
+        }
+    }
+}
+
+impl<'a, T: Value> Iterator for ZipIter<'a, T> {
+    type Item = (usize, &'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((left, right)) = self.packed.next() {
+                let index = self.packed_index;
+                self.packed_index += 1;
+                if index < self.len {
+                    return Some((index, left, right));
+                }
+            }
+
+            let (left, right, prefix, depth) = self.stack.pop()?;
+            if prefix >= self.len || known_equal(left, right) {
+                continue;
+            }
+            match (left, right) {
+                (Tree::Leaf(left), Tree::Leaf(right)) => {
+                    return Some((prefix, &left.value, &right.value));
+                }
+                (Tree::PackedLeaf(left), Tree::PackedLeaf(right)) => {
+                    self.packed = left.values.iter().zip(right.values.iter());
+                    self.packed_index = prefix;
+                }
+                (
+                    Tree::Node {
+                        left: left_left,
+                        right: left_right,
+                        ..
+                    },
+                    Tree::Node {
+                        left: right_left,
+                        right: right_right,
+                        ..
+                    },
+                ) if depth > 0 => {
+                    let right_prefix = prefix | (1 << (depth - 1 + self.packing_depth));
+                    self.stack
+                        .push((left_right, right_right, right_prefix, depth - 1));
+                    self.stack.push((left_left, right_left, prefix, depth - 1));
+                }
+                // Trees of the same length have the same shape, and their zero subtrees only
+                // contain elements beyond the length.
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Iterator over the elements of a list which differ from a base list.
+///
+/// Created by `List::iter_changed`. Elements beyond the length of the base list are always
+/// yielded, while elements of the base list beyond the length of the new list are ignored.
+#[derive(Debug)]
+pub struct ChangedIter<'a, T: Value> {
+    zip: ZipIter<'a, T>,
+    /// Iterator over the elements added after the end of the base list.
+    added: Iter<'a, T>,
+    added_index: usize,
+}
+
+impl<'a, T: Value> ChangedIter<'a, T> {
+    pub(crate) fn new(
+        base: &'a Tree<T>,
+        base_len: usize,
+        new: &'a Tree<T>,
+        new_len: usize,
+        depth: usize,
+    ) -> Self {
+        Self {
+            zip: ZipIter::new(base, new, depth, std::cmp::min(base_len, new_len)),
+            added: Iter::from_index(base_len, new, depth, Length(new_len)),
+            added_index: base_len,
+        }
+    }
+}
+
+impl<'a, T: Value> Iterator for ChangedIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, base_value, new_value) in self.zip.by_ref() {
+            if base_value != new_value {
+                return Some((index, new_value));
+            }
+        }
+        let value = self.added.next()?;
+        let index = self.added_index;
+        self.added_index += 1;
+        Some((index, value))
+    }
+}
+
+/// Check whether two subtrees are known to be equal without traversing them.
+fn known_equal<T: Value>(left: &Tree<T>, right: &Tree<T>) -> bool {
+    if std::ptr::eq(left, right) {
+        return true;
+    }
+    let (left_hash, right_hash) = (cached_hash(left), cached_hash(right));
+    !left_hash.is_zero() && left_hash == right_hash
+}
+
+fn cached_hash<T: Value>(tree: &Tree<T>) -> Hash256 {
+    match tree {
+        Tree::Leaf(leaf) => *leaf.hash.read(),
+        Tree::PackedLeaf(leaf) => *leaf.hash.read(),
+        Tree::Node { hash, .. } => *hash.read(),
+        Tree::Zero(_) => Hash256::zero(),
+    }
+}
@@ -0,0 +1,73 @@
+//! Kept out of the unit tests, which would otherwise record into the global sink while it is
+//! installed and throw off the exact counts checked here.
+use milhouse::metrics::{clear_metrics_sink, set_metrics_sink};
+use milhouse::{List, MetricsSink};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+#[derive(Default)]
+struct Counters {
+    hashes: AtomicUsize,
+    nodes: AtomicUsize,
+    update_map_sizes: AtomicUsize,
+    packed_leaf_rewrites: AtomicUsize,
+}
+
+impl MetricsSink for Counters {
+    fn hashes_computed(&self, count: usize) {
+        self.hashes.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn nodes_allocated(&self, count: usize) {
+        self.nodes.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn update_map_size(&self, len: usize) {
+        self.update_map_sizes.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn packed_leaf_rewritten(&self) {
+        self.packed_leaf_rewrites.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn metrics() {
+    let counters = Arc::new(Counters::default());
+    set_metrics_sink(counters.clone());
+
+    let mut list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+    assert!(counters.nodes.load(Ordering::Relaxed) >= list.mem_usage().nodes);
+    // Hashes are only computed when first needed, so building a list computes none.
+    assert_eq!(counters.hashes.load(Ordering::Relaxed), 0);
+
+    // 1024 packed values fill 256 leaves, with 255 internal nodes above them.
+    list.tree_hash_root();
+    assert_eq!(counters.hashes.load(Ordering::Relaxed), 255);
+
+    // Two updates to adjacent leaves of a shared tree copy both leaves and share every ancestor.
+    let base = list.clone();
+    *list.get_mut(0).unwrap() = 100;
+    *list.get_mut(5).unwrap() = 100;
+    list.apply_updates().unwrap();
+    assert_eq!(counters.update_map_sizes.load(Ordering::Relaxed), 2);
+    assert_eq!(counters.packed_leaf_rewrites.load(Ordering::Relaxed), 2);
+    list.tree_hash_root();
+    assert_eq!(counters.hashes.load(Ordering::Relaxed), 255 + 8);
+
+    // The copied path is owned by `list` alone, so updating it again rewrites the leaf in place
+    // and allocates nothing.
+    let nodes = counters.nodes.load(Ordering::Relaxed);
+    *list.get_mut(1).unwrap() = 100;
+    list.apply_updates().unwrap();
+    assert_eq!(counters.packed_leaf_rewrites.load(Ordering::Relaxed), 3);
+    assert_eq!(counters.nodes.load(Ordering::Relaxed), nodes);
+    drop(base);
+
+    clear_metrics_sink();
+    let nodes = counters.nodes.load(Ordering::Relaxed);
+    drop(List::<u64, U1024>::new((0..1024).collect()).unwrap());
+    assert_eq!(counters.nodes.load(Ordering::Relaxed), nodes);
+}
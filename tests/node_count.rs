@@ -0,0 +1,40 @@
+//! The node count is global, so these tests run in their own process rather than alongside the
+//! unit tests.
+#![cfg(feature = "node_count")]
+
+use milhouse::node_count::live_nodes;
+use milhouse::{List, Vector};
+use typenum::{U1024, U16};
+
+#[test]
+fn nodes_are_freed() {
+    let before = live_nodes();
+
+    let list = List::<u64, U1024>::new((0..1000).collect()).unwrap();
+    assert_eq!(live_nodes() - before, list.mem_usage().nodes);
+
+    let mut states = vec![list];
+    for i in 0..10 {
+        let mut state = states.last().unwrap().clone();
+        *state.get_mut(i * 100).unwrap() = 0;
+        state.push(i as u64).unwrap();
+        state.apply_updates().unwrap();
+        state
+            .tree_hash_root_with::<milhouse::Sha256Hasher>()
+            .unwrap();
+        states.push(state);
+    }
+    let sharing = List::count_unique_nodes(&states.iter().collect::<Vec<_>>());
+    assert_eq!(live_nodes() - before, sharing.total_nodes());
+
+    // Consuming a list moves values out of its nodes, which must still be freed.
+    let values = states.pop().unwrap().into_iter().collect::<Vec<_>>();
+    assert_eq!(values.len(), 1010);
+    drop(states);
+    assert_eq!(live_nodes(), before);
+
+    let vector = Vector::<u64, U16>::new(vec![1; 16]).unwrap();
+    assert!(live_nodes() > before);
+    drop(vector);
+    assert_eq!(live_nodes(), before);
+}
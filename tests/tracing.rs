@@ -0,0 +1,120 @@
+//! Spans recorded with the `tracing` feature, using a minimal subscriber which keeps the fields
+//! of every span.
+#![cfg(feature = "tracing")]
+use milhouse::List;
+use ssz::{Decode, Encode};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tree_hash::TreeHash;
+use typenum::U1024;
+
+type Fields = HashMap<&'static str, u64>;
+
+#[derive(Default)]
+struct Spans {
+    next_id: AtomicU64,
+    open: Mutex<HashMap<u64, (&'static str, Fields)>>,
+    closed: Mutex<Vec<(&'static str, Fields)>>,
+}
+
+impl Spans {
+    /// Return the fields of the spans named `name` closed since the last call, in order.
+    fn take(&self, name: &str) -> Vec<Fields> {
+        let mut closed = self.closed.lock().unwrap();
+        let (matching, rest) = closed.drain(..).partition(|(n, _)| *n == name);
+        *closed = rest;
+        matching.into_iter().map(|(_, fields)| fields).collect()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name(), value);
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn Debug) {}
+}
+
+#[derive(Clone)]
+struct Recorder(Arc<Spans>);
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut fields = Fields::new();
+        span.record(&mut FieldVisitor(&mut fields));
+        let name = span.metadata().name();
+        self.0.open.lock().unwrap().insert(id, (name, fields));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some((_, fields)) = self.0.open.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+
+    fn try_close(&self, span: Id) -> bool {
+        if let Some(closed) = self.0.open.lock().unwrap().remove(&span.into_u64()) {
+            self.0.closed.lock().unwrap().push(closed);
+        }
+        true
+    }
+}
+
+#[test]
+fn spans() {
+    let spans = Arc::new(Spans::default());
+    tracing::subscriber::with_default(Recorder(spans.clone()), || {
+        let mut list = List::<u64, U1024>::new((0..1024).collect()).unwrap();
+
+        // 1024 packed values fill 256 leaves, with 255 internal nodes above them.
+        list.tree_hash_root();
+        let tree_hash = spans.take("tree_hash");
+        assert_eq!(tree_hash.len(), 1);
+        assert_eq!(tree_hash[0]["depth"], 8);
+        assert_eq!(tree_hash[0]["nodes_rewritten"], 256 + 255);
+
+        // Two adjacent leaves of a unique tree are modified in place, along with the 8 nodes
+        // above them.
+        *list.get_mut(0).unwrap() = 100;
+        *list.get_mut(5).unwrap() = 100;
+        list.apply_updates().unwrap();
+        let apply = spans.take("apply_updates");
+        assert_eq!(apply.len(), 1);
+        assert_eq!(apply[0]["elements"], 2);
+        assert_eq!(apply[0]["nodes_rewritten"], 2 + 8);
+
+        let bytes = list.as_ssz_bytes();
+        let mut decoded = List::<u64, U1024>::from_ssz_bytes(&bytes).unwrap();
+        let decode = spans.take("decode_list");
+        assert_eq!(decode.len(), 1);
+        assert_eq!(decode[0]["elements"], 1024);
+        assert!(decode[0]["nodes_rewritten"] >= 256 + 255);
+
+        decoded.rebase_on(&list).unwrap();
+        let rebase = spans.take("rebase");
+        assert_eq!(rebase.len(), 1);
+        assert_eq!(rebase[0]["elements"], 1024);
+        assert!(rebase[0].contains_key("nodes_rewritten"));
+    });
+}